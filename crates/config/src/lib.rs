@@ -28,6 +28,14 @@
 //! enable_mdns = true
 //! enable_manual = true
 //! discovery_timeout_secs = 10
+//! mdns_service_name = "_honeylink._tcp"
+//! mdns_interfaces = ["eth0"]
+//! mdns_enable_ipv6 = true
+//! mdns_subtype = "_gaming"
+//!
+//! [keychain]
+//! backend = "auto"
+//! file_path = "/var/lib/honeylink/keychain.json"
 //!
 //! [logging]
 //! level = "info"
@@ -46,6 +54,7 @@
 //! export HONEYLINK_TRANSPORT_LISTEN_ADDRESS="127.0.0.1:6000"
 //! export HONEYLINK_LOGGING_LEVEL="debug"
 //! export HONEYLINK_TELEMETRY_ENABLED="true"
+//! export HONEYLINK_KEYCHAIN_BACKEND="file"
 //! ```
 
 use serde::{Deserialize, Serialize};
@@ -92,6 +101,8 @@ pub struct Config {
     pub qos: QosConfig,
     /// Discovery mechanism configuration
     pub discovery: DiscoveryConfig,
+    /// Credential storage configuration
+    pub keychain: KeychainConfig,
     /// Logging configuration
     pub logging: LoggingConfig,
     /// Telemetry configuration (optional)
@@ -150,8 +161,49 @@ pub struct DiscoveryConfig {
     pub enable_manual: bool,
     /// Discovery timeout in seconds
     pub discovery_timeout_secs: u64,
-    /// Service name for mDNS advertisement
+    /// Service name for mDNS advertisement (e.g., "_honeylink._tcp")
     pub mdns_service_name: String,
+    /// Interfaces to announce on (e.g., ["eth0", "wlan0"]; empty = all non-loopback)
+    pub mdns_interfaces: Vec<String>,
+    /// Announce and browse over IPv6 in addition to IPv4
+    pub mdns_enable_ipv6: bool,
+    /// DNS-SD subtype to announce (e.g., "gaming" or "_gaming" ->
+    /// "_gaming._sub._honeylink._tcp"; the leading '_' is optional)
+    pub mdns_subtype: Option<String>,
+    /// DNS-SD subtype to browse for, same format (None = all HoneyLink devices)
+    pub mdns_browse_subtype: Option<String>,
+}
+
+/// Credential storage backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeychainBackend {
+    /// OS keychain, falling back to the encrypted file when it is unavailable
+    Auto,
+    /// OS keychain only (Windows Credential Manager, macOS Keychain, Secret Service)
+    System,
+    /// Encrypted file only (headless Linux, containers, CI)
+    File,
+}
+
+/// Credential storage configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeychainConfig {
+    /// Backend selection (auto, system, file)
+    pub backend: KeychainBackend,
+    /// Keychain service name
+    pub service: String,
+    /// Keychain namespace (username) within the service
+    pub namespace: String,
+    /// Encrypted keychain file (None = platform data directory)
+    pub file_path: Option<String>,
+    /// Environment variable holding the file keychain passphrase
+    pub passphrase_env: String,
+    /// File holding the wrapping secret (takes precedence over the passphrase)
+    ///
+    /// Without a secret file or passphrase, the machine ID is used.
+    pub secret_file: Option<String>,
 }
 
 /// Logging configuration
@@ -195,6 +247,7 @@ impl Default for Config {
             transport: TransportConfig::default(),
             qos: QosConfig::default(),
             discovery: DiscoveryConfig::default(),
+            keychain: KeychainConfig::default(),
             logging: LoggingConfig::default(),
             telemetry: TelemetryConfig::default(),
         }
@@ -234,6 +287,23 @@ impl Default for DiscoveryConfig {
             enable_manual: true,
             discovery_timeout_secs: 10,
             mdns_service_name: "_honeylink._tcp".to_string(),
+            mdns_interfaces: Vec::new(), // All interfaces
+            mdns_enable_ipv6: true,
+            mdns_subtype: None,
+            mdns_browse_subtype: None,
+        }
+    }
+}
+
+impl Default for KeychainConfig {
+    fn default() -> Self {
+        Self {
+            backend: KeychainBackend::Auto,
+            service: "honeylink".to_string(),
+            namespace: "default".to_string(),
+            file_path: None,
+            passphrase_env: "HONEYLINK_KEYCHAIN_PASSPHRASE".to_string(),
+            secret_file: None,
         }
     }
 }
@@ -347,6 +417,20 @@ impl Config {
             config.transport.listen_address = address;
         }
 
+        if let Ok(backend) = std::env::var("HONEYLINK_KEYCHAIN_BACKEND") {
+            config.keychain.backend = match backend.to_lowercase().as_str() {
+                "auto" => KeychainBackend::Auto,
+                "system" => KeychainBackend::System,
+                "file" => KeychainBackend::File,
+                _ => {
+                    return Err(ConfigError::ValidationError(format!(
+                        "HONEYLINK_KEYCHAIN_BACKEND must be auto, system or file, got \"{}\"",
+                        backend
+                    )))
+                }
+            };
+        }
+
         Ok(config)
     }
 
@@ -375,6 +459,38 @@ impl Config {
             )));
         }
 
+        // Validate mDNS service name ("_<service>._tcp" or "_<service>._udp")
+        let service_name = self.discovery.mdns_service_name.trim_end_matches('.');
+        let service_name = service_name.strip_suffix(".local").unwrap_or(service_name);
+        let valid_service = service_name.starts_with('_')
+            && (service_name.ends_with("._tcp") || service_name.ends_with("._udp"));
+        if !valid_service {
+            return Err(ConfigError::ValidationError(format!(
+                "discovery.mdns_service_name must be \"_<service>._tcp\" or \"_<service>._udp\", got \"{}\"",
+                self.discovery.mdns_service_name
+            )));
+        }
+
+        for subtype in [&self.discovery.mdns_subtype, &self.discovery.mdns_browse_subtype]
+            .into_iter()
+            .flatten()
+        {
+            // The leading '_' is optional and added by discovery when missing
+            let label = subtype.strip_prefix('_').unwrap_or(subtype);
+            if label.is_empty() || label.len() > 62 || label.contains('.') || label.starts_with('_') {
+                return Err(ConfigError::ValidationError(format!(
+                    "discovery mDNS subtype must be a single DNS label (optionally prefixed with '_'), got \"{}\"",
+                    subtype
+                )));
+            }
+        }
+
+        if self.keychain.service.is_empty() || self.keychain.namespace.is_empty() {
+            return Err(ConfigError::ValidationError(
+                "keychain.service and keychain.namespace must be non-empty".to_string(),
+            ));
+        }
+
         // Validate telemetry sampling ratio
         if self.telemetry.trace_sampling_ratio < 0.0 || self.telemetry.trace_sampling_ratio > 1.0
         {
@@ -463,6 +579,47 @@ level = "debug"
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_validation_mdns_names() {
+        let mut config = Config::default();
+        config.discovery.mdns_service_name = "_honeylink._udp.local.".to_string();
+        config.discovery.mdns_subtype = Some("_gaming".to_string());
+        assert!(config.validate().is_ok());
+
+        config.discovery.mdns_subtype = Some("gaming".to_string());
+        assert!(config.validate().is_ok());
+
+        for invalid in ["_", "", "_gaming._sub", "__gaming"] {
+            config.discovery.mdns_subtype = Some(invalid.to_string());
+            assert!(config.validate().is_err(), "{:?} accepted", invalid);
+        }
+
+        config.discovery.mdns_subtype = None;
+        config.discovery.mdns_service_name = "honeylink".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_keychain_section() {
+        let toml_content = r#"
+[keychain]
+backend = "file"
+file_path = "/var/lib/honeylink/keychain.json"
+"#;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(toml_content.as_bytes()).unwrap();
+
+        let config = Config::load_from_file(temp_file.path()).unwrap();
+        assert_eq!(config.keychain.backend, KeychainBackend::File);
+        assert_eq!(
+            config.keychain.file_path.as_deref(),
+            Some("/var/lib/honeylink/keychain.json")
+        );
+        assert_eq!(config.keychain.service, "honeylink");
+        assert_eq!(Config::default().keychain.backend, KeychainBackend::Auto);
+    }
+
     #[test]
     fn test_save_and_load_roundtrip() {
         let config = Config::default();
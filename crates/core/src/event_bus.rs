@@ -0,0 +1,613 @@
+//! Shared, topic-filtered event bus
+//!
+//! One `EventBus` is shared by all modules: the session orchestrator, policy
+//! engine and transport publish typed `Event`s, and cross-cutting consumers
+//! (telemetry, UI) subscribe to the topics they care about.
+//!
+//! - Buffering is bounded; a subscriber that falls behind gets
+//!   `RecvError::Lagged` with the number of events it missed.
+//! - Every event is wrapped in an `EventEnvelope` with a sequence number.
+//! - An optional `EventJournal` persists envelopes before delivery so they
+//!   can be replayed after a restart. Journal writes run on a dedicated
+//!   writer thread, so `publish` never blocks on disk I/O.
+//!
+//! Transport publishes `StreamCreated` from
+//! `TransportManager::open_session_stream`, and the physical adapter
+//! `AdapterRegistry` publishes `LinkStateChanged`. Telemetry's
+//! `TransportEvent` (FEC, WFQ and power details) stays telemetry-internal;
+//! only its link switches are bridged here via `to_core_events`.
+
+use crate::error::{Error, Result};
+use crate::events::{Event, Topic};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use tokio::sync::broadcast;
+
+/// Default number of events buffered per subscriber
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// Default number of events a `FileEventJournal` keeps after compaction
+pub const DEFAULT_RETAINED_EVENTS: usize = 65_536;
+
+/// An event with its bus sequence number and publish time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    /// Monotonic sequence number, starting at 1
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    pub event: Event,
+}
+
+/// Subscription receive errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RecvError {
+    /// The subscriber fell behind and `missed` events were dropped
+    #[error("Subscriber lagged behind by {missed} events")]
+    Lagged { missed: u64 },
+
+    /// The bus was dropped
+    #[error("Event bus closed")]
+    Closed,
+}
+
+/// Persistent record of published events
+pub trait EventJournal: Send + Sync {
+    /// Persist an envelope
+    fn append(&self, envelope: &EventEnvelope) -> Result<()>;
+
+    /// Envelopes with a sequence number greater than `sequence`, in order
+    fn read_after(&self, sequence: u64) -> Result<Vec<EventEnvelope>>;
+
+    /// Highest sequence number persisted (0 if empty)
+    fn last_sequence(&self) -> Result<u64> {
+        Ok(self.read_after(0)?.last().map_or(0, |e| e.sequence))
+    }
+}
+
+/// Event bus shared across modules
+pub struct EventBus {
+    sender: broadcast::Sender<EventEnvelope>,
+
+    /// Last assigned sequence number; held while sending or queueing for the
+    /// journal writer so subscribers and the journal see the same order
+    sequence: Mutex<u64>,
+
+    journal: Option<Arc<dyn EventJournal>>,
+
+    /// Background writer persisting and then delivering journaled events
+    writer: Option<JournalWriter>,
+
+    /// Events dropped across all lagging subscribers
+    lagged: Arc<AtomicU64>,
+}
+
+impl EventBus {
+    /// Create new event bus with default capacity
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_EVENT_CAPACITY)
+    }
+
+    /// Create event bus buffering up to `capacity` events per subscriber
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            sequence: Mutex::new(0),
+            journal: None,
+            writer: None,
+            lagged: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Persist every event to `journal`, continuing its sequence numbers
+    ///
+    /// Starts the writer thread that appends events to the journal and
+    /// delivers each one only after it has been persisted.
+    pub fn with_journal(mut self, journal: impl EventJournal + 'static) -> Result<Self> {
+        *self.sequence.get_mut().map_err(poisoned)? = journal.last_sequence()?;
+        let journal: Arc<dyn EventJournal> = Arc::new(journal);
+        self.writer = Some(JournalWriter::spawn(
+            Arc::clone(&journal),
+            self.sender.clone(),
+        )?);
+        self.journal = Some(journal);
+        Ok(self)
+    }
+
+    /// Publish an event to the subscribers of its topic
+    ///
+    /// Publishing without subscribers is not an error. With a journal the
+    /// event is queued for the writer thread and delivered once persisted.
+    ///
+    /// # Returns
+    /// The number of subscribers the event is delivered to (with a journal,
+    /// the subscribers at the time of publishing)
+    ///
+    /// # Errors
+    /// Returns an error if the journal writer has failed; no further events
+    /// are persisted or delivered after a failed append.
+    pub fn publish(&self, event: Event) -> Result<usize> {
+        let mut sequence = self.sequence.lock().map_err(poisoned)?;
+        let envelope = EventEnvelope {
+            sequence: *sequence + 1,
+            timestamp: Utc::now(),
+            event,
+        };
+
+        let delivered = match &self.writer {
+            Some(writer) => {
+                writer.enqueue(envelope)?;
+                self.sender.receiver_count()
+            }
+            None => self.sender.send(envelope).unwrap_or(0),
+        };
+        *sequence += 1;
+
+        Ok(delivered)
+    }
+
+    /// Subscribe to the given topics
+    pub fn subscribe(&self, topics: impl IntoIterator<Item = Topic>) -> Subscription {
+        Subscription {
+            receiver: self.sender.subscribe(),
+            topics: topics.into_iter().collect(),
+            lagged: Arc::clone(&self.lagged),
+        }
+    }
+
+    /// Subscribe to every topic
+    pub fn subscribe_all(&self) -> Subscription {
+        self.subscribe(Topic::ALL)
+    }
+
+    /// Journaled events published after `sequence` (empty without a journal)
+    ///
+    /// Events still queued for the writer thread are not included.
+    pub fn replay(&self, sequence: u64) -> Result<Vec<EventEnvelope>> {
+        match &self.journal {
+            Some(journal) => journal.read_after(sequence),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get current subscriber count
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Total events dropped by lagging subscribers
+    pub fn lagged_total(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writer thread that persists envelopes in publish order, then delivers them
+struct JournalWriter {
+    queue: Option<mpsc::Sender<EventEnvelope>>,
+    handle: Option<JoinHandle<()>>,
+
+    /// Error of the append that stopped the writer
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl JournalWriter {
+    fn spawn(
+        journal: Arc<dyn EventJournal>,
+        sender: broadcast::Sender<EventEnvelope>,
+    ) -> Result<Self> {
+        let (queue, pending) = mpsc::channel::<EventEnvelope>();
+        let failure = Arc::new(Mutex::new(None));
+        let writer_failure = Arc::clone(&failure);
+
+        let handle = std::thread::Builder::new()
+            .name("event-journal".to_string())
+            .spawn(move || {
+                for envelope in pending {
+                    if let Err(e) = journal.append(&envelope) {
+                        if let Ok(mut failure) = writer_failure.lock() {
+                            *failure = Some(e.to_string());
+                        }
+                        return;
+                    }
+                    let _ = sender.send(envelope);
+                }
+            })
+            .map_err(|e| Error::Internal(format!("Event journal writer: {}", e)))?;
+
+        Ok(Self {
+            queue: Some(queue),
+            handle: Some(handle),
+            failure,
+        })
+    }
+
+    fn enqueue(&self, envelope: EventEnvelope) -> Result<()> {
+        let sent = self
+            .queue
+            .as_ref()
+            .is_some_and(|queue| queue.send(envelope).is_ok());
+        if sent {
+            return Ok(());
+        }
+
+        let failure = self.failure.lock().map_err(poisoned)?.clone();
+        Err(Error::Internal(format!(
+            "Event journal writer stopped: {}",
+            failure.unwrap_or_else(|| "unknown error".to_string())
+        )))
+    }
+}
+
+impl Drop for JournalWriter {
+    /// Persist and deliver the events still queued before the bus goes away
+    fn drop(&mut self) {
+        self.queue.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Receiver for the events of selected topics
+pub struct Subscription {
+    receiver: broadcast::Receiver<EventEnvelope>,
+    topics: Vec<Topic>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl Subscription {
+    /// Topics this subscription receives
+    pub fn topics(&self) -> &[Topic] {
+        &self.topics
+    }
+
+    /// Wait for the next event on a subscribed topic
+    ///
+    /// After `RecvError::Lagged` the subscription continues with the oldest
+    /// event still buffered. Lag counts events of every topic.
+    pub async fn recv(&mut self) -> std::result::Result<EventEnvelope, RecvError> {
+        loop {
+            let received = self.receiver.recv().await;
+            if let Some(result) = self.filter(received) {
+                return result;
+            }
+        }
+    }
+
+    /// Next buffered event on a subscribed topic, if any
+    pub fn try_recv(&mut self) -> std::result::Result<Option<EventEnvelope>, RecvError> {
+        loop {
+            let received = match self.receiver.try_recv() {
+                Ok(envelope) => Ok(envelope),
+                Err(broadcast::error::TryRecvError::Empty) => return Ok(None),
+                Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                    Err(broadcast::error::RecvError::Lagged(missed))
+                }
+                Err(broadcast::error::TryRecvError::Closed) => {
+                    Err(broadcast::error::RecvError::Closed)
+                }
+            };
+            if let Some(result) = self.filter(received) {
+                return result.map(Some);
+            }
+        }
+    }
+
+    /// Map a raw receive result, or `None` for events of other topics
+    fn filter(
+        &self,
+        received: std::result::Result<EventEnvelope, broadcast::error::RecvError>,
+    ) -> Option<std::result::Result<EventEnvelope, RecvError>> {
+        match received {
+            Ok(envelope) if self.topics.contains(&envelope.event.topic()) => Some(Ok(envelope)),
+            Ok(_) => None,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                self.lagged.fetch_add(missed, Ordering::Relaxed);
+                Some(Err(RecvError::Lagged { missed }))
+            }
+            Err(broadcast::error::RecvError::Closed) => Some(Err(RecvError::Closed)),
+        }
+    }
+}
+
+/// Journal appending one JSON envelope per line to a local file
+///
+/// Each append is synced before the event is delivered. A torn final line
+/// left by a crash is dropped when the journal is reopened.
+///
+/// Once the file holds twice the retained event count it is compacted to the
+/// newest retained events (`DEFAULT_RETAINED_EVENTS` unless set with
+/// `with_retained_events`), so `read_after` only covers that window.
+pub struct FileEventJournal {
+    path: PathBuf,
+    file: Mutex<JournalFile>,
+    retained: usize,
+}
+
+/// Open journal file and the number of events it holds
+struct JournalFile {
+    file: File,
+    events: usize,
+}
+
+impl FileEventJournal {
+    /// Open or create the journal at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        let file = open_append(&path)?;
+
+        // Drop a torn final line so new appends start on a line boundary
+        let contents = std::fs::read(&path).map_err(|e| io_error(&path, e))?;
+        let complete = contents
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |pos| pos + 1);
+        if complete < contents.len() {
+            file.set_len(complete as u64)
+                .map_err(|e| io_error(&path, e))?;
+        }
+        let events = contents[..complete]
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.trim_ascii().is_empty())
+            .count();
+
+        Ok(Self {
+            path,
+            file: Mutex::new(JournalFile { file, events }),
+            retained: DEFAULT_RETAINED_EVENTS,
+        })
+    }
+
+    /// Keep at least the newest `retained` events when compacting (minimum 1)
+    pub fn with_retained_events(mut self, retained: usize) -> Self {
+        self.retained = retained.max(1);
+        self
+    }
+
+    /// Path of the journal file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Rewrite the journal with only the newest `retained` events
+    ///
+    /// The new file is written and synced next to the journal, then renamed
+    /// over it, so a crash leaves either the old or the compacted journal.
+    fn compact(&self, journal: &mut JournalFile) -> Result<()> {
+        let envelopes = self.read_after(0)?;
+        let keep = &envelopes[envelopes.len().saturating_sub(self.retained)..];
+
+        let mut contents = Vec::new();
+        for envelope in keep {
+            serde_json::to_writer(&mut contents, envelope)
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            contents.push(b'\n');
+        }
+
+        let tmp_path = self.path.with_extension("compact.tmp");
+        let mut tmp = File::create(&tmp_path).map_err(|e| io_error(&tmp_path, e))?;
+        tmp.write_all(&contents)
+            .and_then(|_| tmp.sync_all())
+            .map_err(|e| io_error(&tmp_path, e))?;
+        std::fs::rename(&tmp_path, &self.path).map_err(|e| io_error(&self.path, e))?;
+
+        journal.file = open_append(&self.path)?;
+        journal.events = keep.len();
+        Ok(())
+    }
+}
+
+impl EventJournal for FileEventJournal {
+    fn append(&self, envelope: &EventEnvelope) -> Result<()> {
+        let mut line =
+            serde_json::to_vec(envelope).map_err(|e| Error::Serialization(e.to_string()))?;
+        line.push(b'\n');
+
+        let mut journal = self.file.lock().map_err(poisoned)?;
+        journal
+            .file
+            .write_all(&line)
+            .and_then(|_| journal.file.sync_data())
+            .map_err(|e| io_error(&self.path, e))?;
+        journal.events += 1;
+
+        if journal.events >= self.retained.saturating_mul(2) {
+            self.compact(&mut journal)?;
+        }
+        Ok(())
+    }
+
+    fn read_after(&self, sequence: u64) -> Result<Vec<EventEnvelope>> {
+        let file = File::open(&self.path).map_err(|e| io_error(&self.path, e))?;
+        let lines: Vec<String> = BufReader::new(file)
+            .lines()
+            .collect::<std::io::Result<_>>()
+            .map_err(|e| io_error(&self.path, e))?;
+
+        let mut envelopes = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<EventEnvelope>(line) {
+                Ok(envelope) if envelope.sequence > sequence => envelopes.push(envelope),
+                Ok(_) => {}
+                // Torn write from a crash mid-append
+                Err(_) if index + 1 == lines.len() => {}
+                Err(e) => {
+                    return Err(Error::Serialization(format!(
+                        "{} line {}: {}",
+                        self.path.display(),
+                        index + 1,
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(envelopes)
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| io_error(path, e))
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> Error {
+    Error::Internal(format!("{}: {}", path.display(), error))
+}
+
+fn poisoned<T>(_: std::sync::PoisonError<T>) -> Error {
+    Error::Internal("Event bus lock poisoned".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{LinkState, PolicyChangeKind, SessionPhase};
+    use crate::types::SessionId;
+
+    fn link_down() -> Event {
+        Event::LinkStateChanged {
+            adapter: "wifi6e".to_string(),
+            state: LinkState::Down,
+        }
+    }
+
+    fn policy_applied() -> Event {
+        Event::PolicyUpdated {
+            policy_id: "pol_1".to_string(),
+            device_id: Some("DEV-B".to_string()),
+            stream_id: 1,
+            change: PolicyChangeKind::Applied,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_topic_filtering() {
+        let bus = EventBus::new();
+        let mut policy = bus.subscribe([Topic::Policy]);
+        let mut all = bus.subscribe_all();
+
+        assert_eq!(bus.publish(link_down()).unwrap(), 2);
+        assert_eq!(bus.publish(policy_applied()).unwrap(), 2);
+
+        let received = policy.recv().await.unwrap();
+        assert_eq!(received.sequence, 2);
+        assert_eq!(received.event, policy_applied());
+        assert!(policy.try_recv().unwrap().is_none());
+
+        assert_eq!(all.recv().await.unwrap().event.topic(), Topic::Link);
+        assert_eq!(all.recv().await.unwrap().event.topic(), Topic::Policy);
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers() {
+        let bus = EventBus::new();
+        assert_eq!(bus.publish(link_down()).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_lag_reported() {
+        let bus = EventBus::with_capacity(2);
+        let mut slow = bus.subscribe_all();
+
+        for _ in 0..5 {
+            bus.publish(link_down()).unwrap();
+        }
+
+        assert_eq!(slow.recv().await, Err(RecvError::Lagged { missed: 3 }));
+        assert_eq!(bus.lagged_total(), 3);
+        assert_eq!(slow.recv().await.unwrap().sequence, 4);
+    }
+
+    #[tokio::test]
+    async fn test_journal_replay_across_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let session_id = SessionId::new();
+
+        {
+            let bus = EventBus::new()
+                .with_journal(FileEventJournal::open(&path).unwrap())
+                .unwrap();
+            bus.publish(Event::SessionStateChanged {
+                session_id,
+                from: SessionPhase::Paired,
+                to: SessionPhase::Active,
+            })
+            .unwrap();
+            bus.publish(policy_applied()).unwrap();
+        }
+
+        // Simulate a torn write
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"sequence\":3,\"timest").unwrap();
+
+        let bus = EventBus::new()
+            .with_journal(FileEventJournal::open(&path).unwrap())
+            .unwrap();
+        let replayed = bus.replay(0).unwrap();
+        assert_eq!(replayed.len(), 2);
+        assert_eq!(replayed[1].event, policy_applied());
+        assert_eq!(bus.replay(1).unwrap().len(), 1);
+
+        let mut subscriber = bus.subscribe_all();
+        bus.publish(link_down()).unwrap();
+        assert_eq!(subscriber.recv().await.unwrap().sequence, 3);
+    }
+
+    #[test]
+    fn test_journal_compaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+
+        {
+            let journal = FileEventJournal::open(&path)
+                .unwrap()
+                .with_retained_events(3);
+            let bus = EventBus::new().with_journal(journal).unwrap();
+            for _ in 0..7 {
+                bus.publish(link_down()).unwrap();
+            }
+        }
+
+        // Compacted to 3 events at the 6th append, then one more appended
+        let journal = FileEventJournal::open(&path)
+            .unwrap()
+            .with_retained_events(3);
+        let sequences: Vec<u64> = journal
+            .read_after(0)
+            .unwrap()
+            .iter()
+            .map(|e| e.sequence)
+            .collect();
+        assert_eq!(sequences, vec![4, 5, 6, 7]);
+
+        // Sequence numbers continue after the compacted prefix
+        let bus = EventBus::new().with_journal(journal).unwrap();
+        bus.publish(link_down()).unwrap();
+        drop(bus);
+        let journal = FileEventJournal::open(&path).unwrap();
+        assert_eq!(journal.last_sequence().unwrap(), 8);
+    }
+}
@@ -1,21 +1,89 @@
 //! Event definitions for the shared event bus
+//!
+//! Every event belongs to a `Topic`; subscribers of `EventBus` choose the
+//! topics they receive.
 
-use crate::types::{DeviceId, SessionId, StreamId};
+use crate::types::{SessionId, StreamId};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Event category used for subscription filtering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    Session,
+    Policy,
+    Stream,
+    Link,
+}
+
+impl Topic {
+    /// Every topic
+    pub const ALL: [Topic; 4] = [Topic::Session, Topic::Policy, Topic::Stream, Topic::Link];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Session => "session",
+            Self::Policy => "policy",
+            Self::Stream => "stream",
+            Self::Link => "link",
+        }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Session lifecycle state, as driven by the session orchestrator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPhase {
+    Pending,
+    Paired,
+    Active,
+    Suspended,
+    Closed,
+}
+
+/// How an applied QoS policy changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyChangeKind {
+    Applied,
+    RolledBack,
+    Invalidated,
+}
+
+/// State of a physical link adapter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    Up,
+    Degraded,
+    Down,
+}
 
 /// Events emitted by various modules
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Event {
     /// Session state changed
     SessionStateChanged {
         session_id: SessionId,
-        old_state: String,
-        new_state: String,
+        from: SessionPhase,
+        to: SessionPhase,
     },
 
-    /// Policy updated
-    PolicyUpdated { device_id: DeviceId, version: String },
+    /// QoS policy applied, rolled back or invalidated
+    PolicyUpdated {
+        policy_id: String,
+        device_id: Option<String>,
+        stream_id: u8,
+        change: PolicyChangeKind,
+    },
 
     /// Stream created
     StreamCreated {
@@ -24,5 +92,27 @@ pub enum Event {
     },
 
     /// Link state changed
-    LinkStateChanged { adapter: String, state: String },
+    LinkStateChanged { adapter: String, state: LinkState },
+}
+
+impl Event {
+    /// Topic the event is published under
+    pub fn topic(&self) -> Topic {
+        match self {
+            Self::SessionStateChanged { .. } => Topic::Session,
+            Self::PolicyUpdated { .. } => Topic::Policy,
+            Self::StreamCreated { .. } => Topic::Stream,
+            Self::LinkStateChanged { .. } => Topic::Link,
+        }
+    }
+
+    /// Event type as string
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::SessionStateChanged { .. } => "session.state_changed",
+            Self::PolicyUpdated { .. } => "policy.updated",
+            Self::StreamCreated { .. } => "stream.created",
+            Self::LinkStateChanged { .. } => "link.state_changed",
+        }
+    }
 }
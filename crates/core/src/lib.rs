@@ -7,11 +7,17 @@
 //! - `types`: Core type definitions (DeviceId, SessionId, etc.)
 //! - `traits`: Common traits for all modules
 //! - `error`: Unified error types
-//! - `events`: Event definitions for the event bus
+//! - `events`: Typed event definitions and topics
+//! - `event_bus`: Shared topic-filtered event bus with optional journal
 
 pub mod error;
+pub mod event_bus;
 pub mod events;
 pub mod traits;
 pub mod types;
 
 pub use error::{Error, Result};
+pub use event_bus::{
+    EventBus, EventEnvelope, EventJournal, FileEventJournal, RecvError, Subscription,
+};
+pub use events::{Event, Topic};
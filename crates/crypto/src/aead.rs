@@ -130,7 +130,38 @@ impl ChaCha20Poly1305Cipher {
         // Generate a random nonce
         let mut nonce_bytes = [0u8; NONCE_SIZE];
         rand::thread_rng().fill(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = self.encrypt_with_nonce(&nonce_bytes, plaintext, aad)?;
+
+        Ok((nonce_bytes, ciphertext))
+    }
+
+    /// Encrypts plaintext under a caller-supplied nonce.
+    ///
+    /// Used by protocols that derive nonces from message counters (e.g. the
+    /// Noise handshake and secure channel transport messages).
+    ///
+    /// # Security
+    /// The caller must guarantee that a nonce is never reused with the same
+    /// key; reuse breaks both confidentiality and integrity.
+    ///
+    /// # Errors
+    /// Returns an error if plaintext exceeds MAX_PLAINTEXT_SIZE.
+    pub fn encrypt_with_nonce(
+        &self,
+        nonce: &[u8; NONCE_SIZE],
+        plaintext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>> {
+        if plaintext.len() > MAX_PLAINTEXT_SIZE {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Plaintext too large: {} bytes (max {} bytes)",
+                plaintext.len(),
+                MAX_PLAINTEXT_SIZE
+            )));
+        }
+
+        let nonce = Nonce::from_slice(nonce);
 
         // Create cipher instance
         let cipher = ChaCha20Poly1305::new(self.key.as_bytes().into());
@@ -142,11 +173,9 @@ impl ChaCha20Poly1305Cipher {
         };
 
         // Encrypt and authenticate
-        let ciphertext = cipher
+        cipher
             .encrypt(nonce, payload)
-            .map_err(|e| honeylink_core::Error::Crypto(format!("Encryption failed: {}", e)))?;
-
-        Ok((nonce_bytes, ciphertext))
+            .map_err(|e| honeylink_core::Error::Crypto(format!("Encryption failed: {}", e)))
     }
 
     /// Decrypts ciphertext with optional associated data.
@@ -217,7 +246,34 @@ impl ChaCha20Poly1305Cipher {
         // Generate a random nonce
         let mut nonce_bytes = [0u8; NONCE_SIZE];
         rand::thread_rng().fill(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        self.encrypt_in_place_with_nonce(&nonce_bytes, buffer, aad)?;
+
+        Ok(nonce_bytes)
+    }
+
+    /// Encrypts data in-place under a caller-supplied nonce.
+    ///
+    /// Used by packet protection, where nonces are derived from sequence
+    /// numbers instead of being transmitted.
+    ///
+    /// # Security
+    /// The caller must guarantee that a nonce is never reused with the same key.
+    pub fn encrypt_in_place_with_nonce(
+        &self,
+        nonce: &[u8; NONCE_SIZE],
+        buffer: &mut dyn chacha20poly1305::aead::Buffer,
+        aad: &[u8],
+    ) -> Result<()> {
+        if buffer.len() > MAX_PLAINTEXT_SIZE {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Plaintext too large: {} bytes (max {} bytes)",
+                buffer.len(),
+                MAX_PLAINTEXT_SIZE
+            )));
+        }
+
+        let nonce = Nonce::from_slice(nonce);
 
         // Create cipher instance
         let cipher = ChaCha20Poly1305::new(self.key.as_bytes().into());
@@ -225,9 +281,7 @@ impl ChaCha20Poly1305Cipher {
         // Encrypt in-place
         cipher
             .encrypt_in_place(nonce, aad, buffer)
-            .map_err(|e| honeylink_core::Error::Crypto(format!("In-place encryption failed: {}", e)))?;
-
-        Ok(nonce_bytes)
+            .map_err(|e| honeylink_core::Error::Crypto(format!("In-place encryption failed: {}", e)))
     }
 
     /// Decrypts data in-place (overwrites the buffer).
@@ -0,0 +1,1007 @@
+//! Device certificates and organization trust chains
+//!
+//! A device certificate binds a device's Ed25519 public key to its `DeviceId`,
+//! a validity window and a set of capability flags. Certificates are signed by
+//! an organization root key (or by an intermediate certificate holding the
+//! `ISSUE` capability) using `crate::signing`.
+//!
+//! # Certificate Format
+//! ```text
+//! version (1) | serial (u64) | not_before (i64) | not_after (i64) |
+//! capabilities (u32) | subject key (32) | issuer key (32) |
+//! device ID length (1) | device ID | signature (64)
+//! ```
+//!
+//! All integers are big-endian. The signature covers a domain-separation
+//! prefix followed by every byte before the signature.
+//!
+//! # Transport Binding
+//! The Noise static key of a device is derived from its Ed25519 key
+//! (`noise_identity`), so a peer's handshake key can be checked against its
+//! certificate without an extra signature (`DeviceCertificate::noise_public_key`).
+//!
+//! # Revocation
+//! Roots publish signed, monotonically numbered `RevocationList`s. A
+//! `TrustStore` only accepts lists newer than the one it holds, so a stale
+//! list cannot un-revoke a device. Intermediates holding `ISSUE` can publish
+//! lists for the certificates they issued
+//! (`TrustStore::apply_delegated_revocations`).
+//!
+//! # Example
+//! ```
+//! use honeylink_core::types::DeviceId;
+//! use honeylink_crypto::certificate::{
+//!     CertificateChain, CertificateIssuer, Capabilities, TrustStore,
+//! };
+//! use ed25519_dalek::SigningKey;
+//! use std::time::Duration;
+//!
+//! let root = CertificateIssuer::new(SigningKey::from_bytes(&[1u8; 32]));
+//! let device_key = SigningKey::from_bytes(&[2u8; 32]);
+//!
+//! let certificate = root.issue(
+//!     &DeviceId::new("sensor-0042".to_string()).unwrap(),
+//!     &device_key.verifying_key(),
+//!     Capabilities::CONNECT,
+//!     Duration::from_secs(365 * 24 * 3600),
+//! );
+//!
+//! let trust = TrustStore::new().with_root(root.verifying_key());
+//! let verified = trust.verify_now(&CertificateChain::new(certificate.clone())).unwrap();
+//! assert_eq!(verified.device_id.as_str(), "sensor-0042");
+//!
+//! // Revoke it
+//! let list = root.revoke(None, [certificate.serial()]).unwrap();
+//! trust.apply_revocations(&list).unwrap();
+//! assert!(trust.verify_now(&CertificateChain::new(certificate)).is_err());
+//! ```
+
+use crate::key_agreement::{PublicKey, SecretKey};
+use crate::key_derivation::{DeriveContext, KeyDerivation};
+use crate::signing;
+use chrono::Utc;
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use honeylink_core::types::DeviceId;
+use honeylink_core::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Current certificate format version
+pub const CERTIFICATE_VERSION: u8 = 1;
+
+/// Maximum number of certificates in a chain (leaf plus intermediates)
+pub const MAX_CHAIN_DEPTH: usize = 4;
+
+/// Domain separation prefix for certificate signatures
+const CERTIFICATE_CONTEXT: &[u8] = b"HoneyLink-v1|DeviceCertificate|";
+
+/// Domain separation prefix for revocation list signatures
+const REVOCATION_CONTEXT: &[u8] = b"HoneyLink-v1|RevocationList|";
+
+/// HKDF label deriving the certificate authority seed from a root key
+const AUTHORITY_LABEL: &str = "CertificateAuthority";
+
+/// Length of the fixed-size part of an encoded certificate (before the device ID)
+const FIXED_LEN: usize = 1 + 8 + 8 + 8 + 4 + 32 + 32 + 1;
+
+/// Length of an Ed25519 signature
+const SIGNATURE_LEN: usize = 64;
+
+/// Capability flags granted by a certificate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// May establish sessions with fleet peers
+    pub const CONNECT: Self = Self(1 << 0);
+    /// May relay traffic for other devices
+    pub const RELAY: Self = Self(1 << 1);
+    /// May publish policy updates
+    pub const PUBLISH_POLICY: Self = Self(1 << 2);
+    /// May issue certificates (intermediate issuer)
+    pub const ISSUE: Self = Self(1 << 3);
+
+    const NAMES: [(&'static str, Self); 4] = [
+        ("connect", Self::CONNECT),
+        ("relay", Self::RELAY),
+        ("publish-policy", Self::PUBLISH_POLICY),
+        ("issue", Self::ISSUE),
+    ];
+
+    /// No capabilities
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Every known capability
+    pub const fn all() -> Self {
+        Self(Self::CONNECT.0 | Self::RELAY.0 | Self::PUBLISH_POLICY.0 | Self::ISSUE.0)
+    }
+
+    /// Raw flag bits
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Creates capabilities from raw bits (unknown bits are kept)
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns true if all flags in `other` are set
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Union of both flag sets
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Parses a comma-separated list (e.g. "connect,relay")
+    pub fn parse(names: &str) -> Result<Self> {
+        names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(Self::empty(), |caps, name| {
+                Self::NAMES
+                    .iter()
+                    .find(|(known, _)| known.eq_ignore_ascii_case(name))
+                    .map(|(_, flag)| caps.union(*flag))
+                    .ok_or_else(|| {
+                        honeylink_core::Error::Crypto(format!("Unknown capability: {}", name))
+                    })
+            })
+    }
+
+    /// Names of the known capabilities that are set
+    pub fn names(&self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|(_, flag)| self.contains(*flag))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+impl std::ops::BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+/// Root-signed device credential
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCertificate {
+    serial: u64,
+    subject: DeviceId,
+    subject_key: VerifyingKey,
+    issuer_key: VerifyingKey,
+    not_before: i64,
+    not_after: i64,
+    capabilities: Capabilities,
+    signature: Signature,
+}
+
+impl DeviceCertificate {
+    /// Serial number (unique per issuer, used for revocation)
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+
+    /// Device the certificate was issued to
+    pub fn subject(&self) -> &DeviceId {
+        &self.subject
+    }
+
+    /// Device Ed25519 public key
+    pub fn subject_key(&self) -> &VerifyingKey {
+        &self.subject_key
+    }
+
+    /// Public key of the issuer that signed this certificate
+    pub fn issuer_key(&self) -> &VerifyingKey {
+        &self.issuer_key
+    }
+
+    /// Start of the validity window (Unix epoch seconds)
+    pub fn not_before(&self) -> i64 {
+        self.not_before
+    }
+
+    /// End of the validity window (Unix epoch seconds)
+    pub fn not_after(&self) -> i64 {
+        self.not_after
+    }
+
+    /// Capabilities granted to the device
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Returns true if `now` lies inside the validity window
+    pub fn is_valid_at(&self, now: i64) -> bool {
+        self.not_before <= now && now <= self.not_after
+    }
+
+    /// X25519 key the device uses as its Noise static key (see `noise_identity`)
+    pub fn noise_public_key(&self) -> PublicKey {
+        PublicKey::from(self.subject_key.to_montgomery().to_bytes())
+    }
+
+    /// Encodes the certificate in its compact binary form
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.signed_bytes();
+        bytes.extend_from_slice(&self.signature.to_bytes());
+        bytes
+    }
+
+    /// Decodes a certificate (the signature is not verified here)
+    ///
+    /// # Errors
+    /// Returns an error for truncated input, trailing bytes, an unsupported
+    /// version or invalid keys.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < FIXED_LEN + SIGNATURE_LEN {
+            return Err(invalid("certificate is truncated"));
+        }
+        if bytes[0] != CERTIFICATE_VERSION {
+            return Err(invalid(&format!("unsupported version {}", bytes[0])));
+        }
+
+        let id_len = bytes[FIXED_LEN - 1] as usize;
+        if bytes.len() != FIXED_LEN + id_len + SIGNATURE_LEN {
+            return Err(invalid("length does not match device ID length"));
+        }
+
+        let subject = std::str::from_utf8(&bytes[FIXED_LEN..FIXED_LEN + id_len])
+            .map_err(|_| invalid("device ID is not UTF-8"))
+            .and_then(|id| DeviceId::new(id.to_string()).map_err(|e| invalid(&e)))?;
+
+        let signature = Signature::from_bytes(
+            bytes[FIXED_LEN + id_len..]
+                .try_into()
+                .map_err(|_| invalid("bad signature length"))?,
+        );
+
+        Ok(Self {
+            serial: u64::from_be_bytes(array(&bytes[1..9])),
+            not_before: i64::from_be_bytes(array(&bytes[9..17])),
+            not_after: i64::from_be_bytes(array(&bytes[17..25])),
+            capabilities: Capabilities::from_bits(u32::from_be_bytes(array(&bytes[25..29]))),
+            subject_key: verifying_key(&bytes[29..61])?,
+            issuer_key: verifying_key(&bytes[61..93])?,
+            subject,
+            signature,
+        })
+    }
+
+    /// Verifies the signature against the embedded issuer key
+    pub fn verify_signature(&self) -> Result<()> {
+        signing::verify(&self.issuer_key, &self.signed_message(), &self.signature)
+    }
+
+    /// Encoded fields covered by the signature
+    fn signed_bytes(&self) -> Vec<u8> {
+        let id = self.subject.as_str().as_bytes();
+        let mut bytes = Vec::with_capacity(FIXED_LEN + id.len() + SIGNATURE_LEN);
+        bytes.push(CERTIFICATE_VERSION);
+        bytes.extend_from_slice(&self.serial.to_be_bytes());
+        bytes.extend_from_slice(&self.not_before.to_be_bytes());
+        bytes.extend_from_slice(&self.not_after.to_be_bytes());
+        bytes.extend_from_slice(&self.capabilities.bits().to_be_bytes());
+        bytes.extend_from_slice(self.subject_key.as_bytes());
+        bytes.extend_from_slice(self.issuer_key.as_bytes());
+        // DeviceId is limited to 64 characters
+        bytes.push(id.len() as u8);
+        bytes.extend_from_slice(id);
+        bytes
+    }
+
+    fn signed_message(&self) -> Vec<u8> {
+        [CERTIFICATE_CONTEXT, &self.signed_bytes()].concat()
+    }
+}
+
+/// Leaf certificate followed by zero or more intermediate certificates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateChain {
+    certificates: Vec<DeviceCertificate>,
+}
+
+impl CertificateChain {
+    /// Creates a chain with a single (root-signed) leaf certificate
+    pub fn new(leaf: DeviceCertificate) -> Self {
+        Self {
+            certificates: vec![leaf],
+        }
+    }
+
+    /// Appends the certificate of the issuer of the last certificate
+    pub fn with_intermediate(mut self, intermediate: DeviceCertificate) -> Self {
+        self.certificates.push(intermediate);
+        self
+    }
+
+    /// The device's own certificate
+    pub fn leaf(&self) -> &DeviceCertificate {
+        &self.certificates[0]
+    }
+
+    /// All certificates, leaf first
+    pub fn certificates(&self) -> &[DeviceCertificate] {
+        &self.certificates
+    }
+
+    /// Encodes the chain: count (1) followed by length-prefixed (u16) certificates
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.certificates.len() as u8];
+        for certificate in &self.certificates {
+            let encoded = certificate.to_bytes();
+            bytes.extend_from_slice(&(encoded.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(&encoded);
+        }
+        bytes
+    }
+
+    /// Decodes a chain produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (&count, mut rest) = bytes
+            .split_first()
+            .ok_or_else(|| invalid("empty certificate chain"))?;
+        if count == 0 || count as usize > MAX_CHAIN_DEPTH {
+            return Err(invalid(&format!("chain length {} out of range", count)));
+        }
+
+        let mut certificates = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            if rest.len() < 2 {
+                return Err(invalid("certificate chain is truncated"));
+            }
+            let len = u16::from_be_bytes([rest[0], rest[1]]) as usize;
+            if rest.len() < 2 + len {
+                return Err(invalid("certificate chain is truncated"));
+            }
+            certificates.push(DeviceCertificate::from_bytes(&rest[2..2 + len])?);
+            rest = &rest[2 + len..];
+        }
+        if !rest.is_empty() {
+            return Err(invalid("trailing bytes after certificate chain"));
+        }
+
+        Ok(Self { certificates })
+    }
+}
+
+/// Signs certificates and revocation lists with an issuer key
+pub struct CertificateIssuer {
+    signing_key: SigningKey,
+}
+
+impl CertificateIssuer {
+    /// Creates an issuer from an organization root (or intermediate) key
+    pub fn new(signing_key: SigningKey) -> Self {
+        Self { signing_key }
+    }
+
+    /// Creates the organization issuer for a HKDF root key
+    ///
+    /// The Ed25519 seed is derived from `root_key` under a dedicated label,
+    /// so the signing key never equals key material the hierarchy hands out
+    /// for other purposes.
+    pub fn from_root_key(root_key: &[u8]) -> Result<Self> {
+        let seed = KeyDerivation::derive_with_context(
+            root_key,
+            &DeriveContext::custom(AUTHORITY_LABEL, Vec::new()),
+            32,
+        )?;
+        Ok(Self::new(SigningKey::from_bytes(&array(&seed))))
+    }
+
+    /// Public key devices configure as a trust root
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Issues a certificate valid from now for `validity`
+    pub fn issue(
+        &self,
+        subject: &DeviceId,
+        subject_key: &VerifyingKey,
+        capabilities: Capabilities,
+        validity: Duration,
+    ) -> DeviceCertificate {
+        let now = Utc::now().timestamp();
+        self.issue_with_window(
+            subject,
+            subject_key,
+            capabilities,
+            now,
+            now.saturating_add(i64::try_from(validity.as_secs()).unwrap_or(i64::MAX)),
+        )
+    }
+
+    /// Issues a certificate with an explicit validity window (Unix epoch seconds)
+    pub fn issue_with_window(
+        &self,
+        subject: &DeviceId,
+        subject_key: &VerifyingKey,
+        capabilities: Capabilities,
+        not_before: i64,
+        not_after: i64,
+    ) -> DeviceCertificate {
+        let mut certificate = DeviceCertificate {
+            serial: rand::random(),
+            subject: subject.clone(),
+            subject_key: *subject_key,
+            issuer_key: self.verifying_key(),
+            not_before,
+            not_after,
+            capabilities,
+            signature: Signature::from_bytes(&[0u8; SIGNATURE_LEN]),
+        };
+        certificate.signature = signing::sign(&self.signing_key, &certificate.signed_message());
+        certificate
+    }
+
+    /// Signs a revocation list extending `previous` with `serials`
+    ///
+    /// The new list carries every serial of `previous` and the next sequence
+    /// number, so trust stores holding the previous list accept it.
+    ///
+    /// # Errors
+    /// Returns an error if `previous` was signed by a different issuer.
+    pub fn revoke(
+        &self,
+        previous: Option<&RevocationList>,
+        serials: impl IntoIterator<Item = u64>,
+    ) -> Result<RevocationList> {
+        let issuer = self.verifying_key().to_bytes();
+        let (sequence, mut revoked) = match previous {
+            Some(list) if list.issuer != issuer => {
+                return Err(honeylink_core::Error::Crypto(
+                    "Revocation list belongs to a different issuer".to_string(),
+                ))
+            }
+            Some(list) => (list.sequence + 1, list.serials.clone()),
+            None => (1, BTreeSet::new()),
+        };
+        revoked.extend(serials);
+
+        let mut list = RevocationList {
+            issuer,
+            sequence,
+            issued_at: Utc::now().timestamp(),
+            serials: revoked,
+            signature: Vec::new(),
+        };
+        list.signature = signing::sign(&self.signing_key, &list.signed_message())
+            .to_bytes()
+            .to_vec();
+        Ok(list)
+    }
+}
+
+impl std::fmt::Debug for CertificateIssuer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CertificateIssuer")
+            .field("verifying_key", &self.verifying_key())
+            .field("signing_key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Signed list of revoked certificate serials for one issuer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationList {
+    issuer: [u8; 32],
+    sequence: u64,
+    issued_at: i64,
+    serials: BTreeSet<u64>,
+    #[serde(with = "serde_bytes")]
+    signature: Vec<u8>,
+}
+
+impl RevocationList {
+    /// Public key of the root (or intermediate) that signed the list
+    pub fn issuer_key(&self) -> Result<VerifyingKey> {
+        verifying_key(&self.issuer)
+    }
+
+    /// Monotonic list number (higher replaces lower)
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Time the list was signed (Unix epoch seconds)
+    pub fn issued_at(&self) -> i64 {
+        self.issued_at
+    }
+
+    /// Revoked serial numbers
+    pub fn serials(&self) -> &BTreeSet<u64> {
+        &self.serials
+    }
+
+    /// Returns true if `serial` is revoked
+    pub fn contains(&self, serial: u64) -> bool {
+        self.serials.contains(&serial)
+    }
+
+    /// Verifies the signature against the embedded issuer key
+    pub fn verify_signature(&self) -> Result<()> {
+        let signature = Signature::from_slice(&self.signature).map_err(|e| {
+            honeylink_core::Error::Crypto(format!("Invalid revocation list signature: {}", e))
+        })?;
+        signing::verify(&self.issuer_key()?, &self.signed_message(), &signature)
+    }
+
+    fn signed_message(&self) -> Vec<u8> {
+        let mut message = REVOCATION_CONTEXT.to_vec();
+        message.extend_from_slice(&self.issuer);
+        message.extend_from_slice(&self.sequence.to_be_bytes());
+        message.extend_from_slice(&self.issued_at.to_be_bytes());
+        message.extend_from_slice(&(self.serials.len() as u64).to_be_bytes());
+        for serial in &self.serials {
+            message.extend_from_slice(&serial.to_be_bytes());
+        }
+        message
+    }
+}
+
+/// Identity established by a successfully verified certificate chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedDevice {
+    /// Device ID from the leaf certificate
+    pub device_id: DeviceId,
+    /// Device Ed25519 public key
+    pub device_key: VerifyingKey,
+    /// Capabilities granted to the device
+    pub capabilities: Capabilities,
+    /// Trust root the chain ends in
+    pub root: VerifyingKey,
+    /// Leaf certificate serial
+    pub serial: u64,
+    /// Earliest expiry of any certificate in the chain (Unix epoch seconds)
+    pub not_after: i64,
+}
+
+/// Trusted organization roots and the current revocation list of each issuer
+///
+/// Revocation lists can be applied through a shared reference, so one store
+/// can be shared (e.g. in an `Arc`) between connection handlers and the
+/// task receiving revocation updates.
+#[derive(Debug, Default)]
+pub struct TrustStore {
+    roots: HashMap<[u8; 32], VerifyingKey>,
+    revocations: RwLock<HashMap<[u8; 32], RevocationList>>,
+}
+
+impl TrustStore {
+    /// Creates a store with no trusted roots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a trusted organization root key
+    pub fn with_root(mut self, root: VerifyingKey) -> Self {
+        self.roots.insert(root.to_bytes(), root);
+        self
+    }
+
+    /// Trusted root keys
+    pub fn roots(&self) -> impl Iterator<Item = &VerifyingKey> {
+        self.roots.values()
+    }
+
+    /// Installs a revocation list signed by a trusted root
+    ///
+    /// # Returns
+    /// `true` if the list replaced an older one, `false` if it was not newer
+    /// than the list already held
+    ///
+    /// # Errors
+    /// Returns an error if the issuer is not trusted or the signature is invalid.
+    pub fn apply_revocations(&self, list: &RevocationList) -> Result<bool> {
+        if !self.roots.contains_key(&list.issuer) {
+            return Err(honeylink_core::Error::Crypto(
+                "Revocation list issuer is not a trusted root".to_string(),
+            ));
+        }
+        list.verify_signature()?;
+        self.install_revocations(list)
+    }
+
+    /// Installs a revocation list signed by an intermediate
+    ///
+    /// `issuer` must verify now and its leaf must be the intermediate that
+    /// signed `list` and hold the `ISSUE` capability. The list then revokes
+    /// certificates issued by that intermediate.
+    ///
+    /// # Returns
+    /// `true` if the list replaced an older one, `false` if it was not newer
+    /// than the list already held
+    ///
+    /// # Errors
+    /// Returns an error if the issuer chain is rejected, does not match the
+    /// list or the signature is invalid.
+    pub fn apply_delegated_revocations(
+        &self,
+        list: &RevocationList,
+        issuer: &CertificateChain,
+    ) -> Result<bool> {
+        let verified = self.verify_now(issuer)?;
+        if verified.device_key.to_bytes() != list.issuer {
+            return Err(rejected("revocation list was not signed by the chain leaf"));
+        }
+        if !verified.capabilities.contains(Capabilities::ISSUE) {
+            return Err(rejected("revocation list issuer cannot issue"));
+        }
+        list.verify_signature()?;
+        self.install_revocations(list)
+    }
+
+    fn install_revocations(&self, list: &RevocationList) -> Result<bool> {
+        let mut revocations = self
+            .revocations
+            .write()
+            .map_err(|_| honeylink_core::Error::Crypto("Revocation lock poisoned".to_string()))?;
+        match revocations.get(&list.issuer) {
+            Some(current) if current.sequence >= list.sequence => Ok(false),
+            _ => {
+                revocations.insert(list.issuer, list.clone());
+                Ok(true)
+            }
+        }
+    }
+
+    /// Current revocation list published by `issuer` (a root or intermediate), if any
+    pub fn revocations(&self, issuer: &VerifyingKey) -> Option<RevocationList> {
+        self.revocations
+            .read()
+            .ok()
+            .and_then(|revocations| revocations.get(issuer.as_bytes()).cloned())
+    }
+
+    /// Verifies `chain` at the current time
+    pub fn verify_now(&self, chain: &CertificateChain) -> Result<VerifiedDevice> {
+        self.verify(chain, Utc::now().timestamp())
+    }
+
+    /// Verifies `chain` at `now` (Unix epoch seconds)
+    ///
+    /// Every certificate must be inside its validity window, signed by the
+    /// next certificate in the chain (or a trusted root for the last one)
+    /// and revoked neither by the root nor by its issuing intermediate.
+    /// Intermediates need the `ISSUE` capability and can only grant
+    /// capabilities they hold themselves.
+    pub fn verify(&self, chain: &CertificateChain, now: i64) -> Result<VerifiedDevice> {
+        let certificates = chain.certificates();
+        if certificates.is_empty() || certificates.len() > MAX_CHAIN_DEPTH {
+            return Err(rejected("chain length out of range"));
+        }
+
+        let last = &certificates[certificates.len() - 1];
+        let root = *self
+            .roots
+            .get(last.issuer_key.as_bytes())
+            .ok_or_else(|| rejected("chain does not end in a trusted root"))?;
+
+        let revocations = self
+            .revocations
+            .read()
+            .map_err(|_| honeylink_core::Error::Crypto("Revocation lock poisoned".to_string()))?;
+        let revoked = revocations.get(root.as_bytes());
+
+        for (index, certificate) in certificates.iter().enumerate() {
+            // Roots revoke anything in their chains, intermediates only
+            // what they issued
+            let revoked_by_issuer = certificates
+                .get(index + 1)
+                .and_then(|issuer| revocations.get(issuer.subject_key.as_bytes()));
+            if !certificate.is_valid_at(now) {
+                return Err(rejected(&format!(
+                    "certificate {} is outside its validity window",
+                    certificate.serial
+                )));
+            }
+            if revoked
+                .into_iter()
+                .chain(revoked_by_issuer)
+                .any(|list| list.contains(certificate.serial))
+            {
+                return Err(rejected(&format!(
+                    "certificate {} is revoked",
+                    certificate.serial
+                )));
+            }
+
+            if let Some(issuer) = certificates.get(index + 1) {
+                if issuer.subject_key != certificate.issuer_key {
+                    return Err(rejected("chain is not linked by issuer keys"));
+                }
+                if !issuer.capabilities.contains(Capabilities::ISSUE) {
+                    return Err(rejected(&format!(
+                        "certificate {} may not issue certificates",
+                        issuer.serial
+                    )));
+                }
+                if !issuer.capabilities.contains(certificate.capabilities) {
+                    return Err(rejected("certificate exceeds its issuer's capabilities"));
+                }
+            }
+
+            certificate.verify_signature()?;
+        }
+
+        let leaf = chain.leaf();
+        Ok(VerifiedDevice {
+            device_id: leaf.subject.clone(),
+            device_key: leaf.subject_key,
+            capabilities: leaf.capabilities,
+            root,
+            serial: leaf.serial,
+            not_after: certificates.iter().map(|c| c.not_after).min().unwrap_or(0),
+        })
+    }
+}
+
+/// Noise static key for a device identified by `signing_key`
+///
+/// Uses the birationally equivalent X25519 scalar of the Ed25519 key, so the
+/// resulting public key equals `DeviceCertificate::noise_public_key()`.
+///
+/// # Security
+/// Reusing one key for Ed25519 signatures and X25519 key agreement is sound
+/// for this combination (see <https://eprint.iacr.org/2021/509>), but the
+/// device key must then be protected like a long-term DH key as well.
+pub fn noise_identity(signing_key: &SigningKey) -> Result<SecretKey> {
+    SecretKey::from_bytes(&zeroize::Zeroizing::new(signing_key.to_scalar_bytes())[..])
+}
+
+fn invalid(reason: &str) -> honeylink_core::Error {
+    honeylink_core::Error::Crypto(format!("Invalid certificate: {}", reason))
+}
+
+fn rejected(reason: &str) -> honeylink_core::Error {
+    honeylink_core::Error::Crypto(format!("Certificate chain rejected: {}", reason))
+}
+
+fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(bytes);
+    array
+}
+
+fn verifying_key(bytes: &[u8]) -> Result<VerifyingKey> {
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| invalid("public key must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| invalid(&format!("bad public key: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device_id(id: &str) -> DeviceId {
+        DeviceId::new(id.to_string()).unwrap()
+    }
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let root = CertificateIssuer::new(key(1));
+        let certificate = root.issue(
+            &device_id("phone-01"),
+            &key(2).verifying_key(),
+            Capabilities::CONNECT | Capabilities::RELAY,
+            Duration::from_secs(30 * 24 * 3600),
+        );
+
+        let decoded = DeviceCertificate::from_bytes(&certificate.to_bytes()).unwrap();
+        assert_eq!(decoded, certificate);
+        decoded.verify_signature().unwrap();
+
+        let chain = CertificateChain::new(certificate);
+        assert_eq!(
+            CertificateChain::from_bytes(&chain.to_bytes()).unwrap(),
+            chain
+        );
+
+        // Any modification breaks the signature
+        let mut tampered = chain.leaf().to_bytes();
+        tampered[28] ^= Capabilities::ISSUE.bits() as u8;
+        let tampered = DeviceCertificate::from_bytes(&tampered).unwrap();
+        assert!(tampered.capabilities().contains(Capabilities::ISSUE));
+        assert!(tampered.verify_signature().is_err());
+    }
+
+    #[test]
+    fn test_chain_validation() {
+        let root = CertificateIssuer::new(key(1));
+        let intermediate_key = key(3);
+        let now = 1_700_000_000;
+        let trust = TrustStore::new().with_root(root.verifying_key());
+
+        let intermediate_cert = root.issue_with_window(
+            &device_id("site-gateway"),
+            &intermediate_key.verifying_key(),
+            Capabilities::ISSUE | Capabilities::CONNECT,
+            now - 10,
+            now + 1000,
+        );
+        let intermediate = CertificateIssuer::new(intermediate_key);
+        let leaf = intermediate.issue_with_window(
+            &device_id("sensor-01"),
+            &key(4).verifying_key(),
+            Capabilities::CONNECT,
+            now - 10,
+            now + 100,
+        );
+
+        let chain = CertificateChain::new(leaf).with_intermediate(intermediate_cert.clone());
+        let verified = trust.verify(&chain, now).unwrap();
+        assert_eq!(verified.device_id, device_id("sensor-01"));
+        assert_eq!(verified.root, root.verifying_key());
+        assert_eq!(verified.not_after, now + 100);
+
+        // Expired, untrusted root, missing intermediate
+        assert!(trust.verify(&chain, now + 101).is_err());
+        assert!(TrustStore::new()
+            .with_root(key(9).verifying_key())
+            .verify(&chain, now)
+            .is_err());
+        assert!(trust
+            .verify(&CertificateChain::new(chain.leaf().clone()), now)
+            .is_err());
+
+        // Intermediates cannot grant capabilities they lack
+        let escalated = intermediate.issue_with_window(
+            &device_id("sensor-02"),
+            &key(5).verifying_key(),
+            Capabilities::PUBLISH_POLICY,
+            now - 10,
+            now + 100,
+        );
+        let chain = CertificateChain::new(escalated).with_intermediate(intermediate_cert);
+        assert!(trust.verify(&chain, now).is_err());
+    }
+
+    #[test]
+    fn test_revocation_lists() {
+        let root = CertificateIssuer::new(key(1));
+        let trust = TrustStore::new().with_root(root.verifying_key());
+        let certificate = root.issue(
+            &device_id("laptop-7"),
+            &key(2).verifying_key(),
+            Capabilities::CONNECT,
+            Duration::from_secs(3600),
+        );
+        let chain = CertificateChain::new(certificate.clone());
+
+        let first = root.revoke(None, [certificate.serial()]).unwrap();
+        let second = root.revoke(Some(&first), [42]).unwrap();
+        assert_eq!(second.sequence(), 2);
+        assert!(second.contains(certificate.serial()) && second.contains(42));
+
+        assert!(trust.apply_revocations(&second).unwrap());
+        assert!(trust.verify_now(&chain).is_err());
+
+        // Older lists are ignored
+        assert!(!trust.apply_revocations(&first).unwrap());
+        assert_eq!(
+            trust.revocations(&root.verifying_key()).unwrap().sequence(),
+            2
+        );
+
+        // Lists from untrusted or forged issuers are refused
+        let other = CertificateIssuer::new(key(8)).revoke(None, [1]).unwrap();
+        assert!(trust.apply_revocations(&other).is_err());
+        let mut forged = second.clone();
+        forged.sequence = 3;
+        forged.serials.clear();
+        assert!(trust.apply_revocations(&forged).is_err());
+
+        // Lists survive JSON transport
+        let json = serde_json::to_string(&second).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RevocationList>(&json).unwrap(),
+            second
+        );
+    }
+
+    #[test]
+    fn test_delegated_revocation_lists() {
+        let root = CertificateIssuer::new(key(1));
+        let trust = TrustStore::new().with_root(root.verifying_key());
+        let intermediate_key = key(3);
+        let intermediate_cert = root.issue(
+            &device_id("site-gateway"),
+            &intermediate_key.verifying_key(),
+            Capabilities::ISSUE | Capabilities::CONNECT,
+            Duration::from_secs(3600),
+        );
+        let intermediate_chain = CertificateChain::new(intermediate_cert.clone());
+        let intermediate = CertificateIssuer::new(intermediate_key);
+        let leaf = intermediate.issue(
+            &device_id("sensor-01"),
+            &key(4).verifying_key(),
+            Capabilities::CONNECT,
+            Duration::from_secs(3600),
+        );
+        let chain = CertificateChain::new(leaf.clone()).with_intermediate(intermediate_cert);
+        trust.verify_now(&chain).unwrap();
+
+        // Intermediate lists need the intermediate's chain
+        let list = intermediate.revoke(None, [leaf.serial()]).unwrap();
+        assert!(trust.apply_revocations(&list).is_err());
+        assert!(trust
+            .apply_delegated_revocations(&list, &intermediate_chain)
+            .unwrap());
+        assert!(trust.verify_now(&chain).is_err());
+        assert_eq!(
+            trust
+                .revocations(&intermediate.verifying_key())
+                .unwrap()
+                .sequence(),
+            1
+        );
+
+        // A chain for a different key, or one without ISSUE, is refused
+        let device = root.issue(
+            &device_id("laptop-7"),
+            &key(2).verifying_key(),
+            Capabilities::CONNECT,
+            Duration::from_secs(3600),
+        );
+        assert!(trust
+            .apply_delegated_revocations(&list, &CertificateChain::new(device.clone()))
+            .is_err());
+        let device_list = CertificateIssuer::new(key(2)).revoke(None, [7]).unwrap();
+        assert!(trust
+            .apply_delegated_revocations(&device_list, &CertificateChain::new(device))
+            .is_err());
+    }
+
+    #[test]
+    fn test_issuer_from_root_key_uses_derived_seed() {
+        let root_key = [7u8; 32];
+        let issuer = CertificateIssuer::from_root_key(&root_key).unwrap();
+        assert_eq!(
+            issuer.verifying_key(),
+            CertificateIssuer::from_root_key(&root_key)
+                .unwrap()
+                .verifying_key()
+        );
+        assert_ne!(
+            issuer.verifying_key(),
+            SigningKey::from_bytes(&root_key).verifying_key()
+        );
+    }
+
+    #[test]
+    fn test_noise_identity_matches_certificate() {
+        let device_key = key(6);
+        let certificate = CertificateIssuer::new(key(1)).issue(
+            &device_id("tv-livingroom"),
+            &device_key.verifying_key(),
+            Capabilities::CONNECT,
+            Duration::from_secs(24 * 3600),
+        );
+
+        let identity = noise_identity(&device_key).unwrap();
+        assert_eq!(
+            identity.public_key().as_bytes(),
+            certificate.noise_public_key().as_bytes()
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! Passphrase-encrypted local key store.
+//!
+//! Stores all keys in a single file, encrypted with ChaCha20-Poly1305 under a
+//! key derived from a passphrase with Argon2id. Intended for serverless
+//! deployments where no Vault server or OS keychain is available.
+//!
+//! # File Format
+//! ```json
+//! {
+//!   "format": 1,
+//!   "kdf": { "algorithm": "argon2id", "salt": "...", "memory_kib": 19456, "iterations": 2, "parallelism": 1 },
+//!   "nonce": "...",
+//!   "ciphertext": "..."
+//! }
+//! ```
+//!
+//! The ciphertext holds the JSON-encoded key records. The whole file is
+//! re-encrypted with a fresh nonce on every write and replaced atomically
+//! (temporary file fsynced before the rename, directory fsynced after it).
+//!
+//! The KDF parameters in the header are checked against a floor before the
+//! key is derived, so an attacker with write access cannot downgrade the file
+//! to a cheap Argon2 setting and speed up passphrase guessing.
+//!
+//! # Example
+//! ```no_run
+//! use honeylink_crypto::file_key_store::FileKeyStore;
+//! use honeylink_crypto::lifecycle::KeyLifecycle;
+//! use std::sync::Arc;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let store = FileKeyStore::open("/var/lib/honeylink/keys.json", "correct horse battery staple")?;
+//! let lifecycle = KeyLifecycle::new(Arc::new(store));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use crate::key_management::KeyScope;
+use crate::key_store::{
+    encode_keys, storage_key, validate_key_name, EncodedKey, KeyMaterial, KeyStore, KeyStoreError,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use async_trait::async_trait;
+use base64::Engine as _;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+/// Current file format version.
+const FORMAT_VERSION: u32 = 1;
+
+/// Associated data binding the ciphertext to this file format.
+const FILE_AAD: &[u8] = b"honeylink-file-key-store-v1";
+
+/// Salt length for Argon2id (bytes).
+const SALT_SIZE: usize = 16;
+
+/// Argon2id parameters used when creating a new key store.
+///
+/// Existing files use the parameters recorded in their header, as long as
+/// they meet the floor passed to `FileKeyStore::open_with_params`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of iterations
+    pub iterations: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /// Cheap parameters so tests stay fast in debug builds. Never use them
+    /// for real data.
+    #[cfg(any(test, feature = "test-util"))]
+    pub const TESTING: KdfParams = KdfParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    /// Whether every cost parameter is at least as high as in `floor`.
+    pub fn meets(&self, floor: &KdfParams) -> bool {
+        self.memory_kib >= floor.memory_kib
+            && self.iterations >= floor.iterations
+            && self.parallelism >= floor.parallelism
+    }
+}
+
+impl Default for KdfParams {
+    /// OWASP recommended Argon2id baseline (19 MiB, 2 iterations, 1 lane).
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// KDF section of the file header.
+#[derive(Serialize, Deserialize)]
+struct KdfHeader {
+    algorithm: String,
+    salt: String,
+    #[serde(flatten)]
+    params: KdfParams,
+}
+
+/// On-disk representation.
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    format: u32,
+    kdf: KdfHeader,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Passphrase-encrypted file key store.
+pub struct FileKeyStore {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305Cipher,
+    salt: [u8; SALT_SIZE],
+    params: KdfParams,
+    keys: Mutex<BTreeMap<String, KeyMaterial>>,
+}
+
+impl FileKeyStore {
+    /// Opens the key store at `path`, creating it if it does not exist.
+    ///
+    /// # Errors
+    /// - `KeyStoreError::Decryption`: Wrong passphrase or corrupted file
+    /// - `KeyStoreError::InvalidFormat`: Unsupported file format, or KDF
+    ///   parameters weaker than `KdfParams::default()`
+    /// - `KeyStoreError::Io`: File cannot be read or created
+    pub fn open(path: impl AsRef<Path>, passphrase: &str) -> Result<Self, KeyStoreError> {
+        Self::open_with_params(path, passphrase, KdfParams::default())
+    }
+
+    /// Opens the key store, using `params` if a new file has to be created.
+    ///
+    /// `params` is also the minimum accepted from an existing file's header;
+    /// weaker parameters are rejected with `KeyStoreError::InvalidFormat`
+    /// before any key derivation.
+    pub fn open_with_params(
+        path: impl AsRef<Path>,
+        passphrase: &str,
+        params: KdfParams,
+    ) -> Result<Self, KeyStoreError> {
+        let path = path.as_ref().to_path_buf();
+
+        if !path.exists() {
+            let mut salt = [0u8; SALT_SIZE];
+            rand::thread_rng().fill_bytes(&mut salt);
+
+            let store = Self {
+                cipher: derive_cipher(passphrase, &salt, params)?,
+                path,
+                salt,
+                params,
+                keys: Mutex::new(BTreeMap::new()),
+            };
+            store.save(&BTreeMap::new())?;
+            return Ok(store);
+        }
+
+        let file: EncryptedFile = serde_json::from_slice(&std::fs::read(&path)?)?;
+        if file.format != FORMAT_VERSION {
+            return Err(KeyStoreError::InvalidFormat(format!(
+                "Unsupported key store format {}",
+                file.format
+            )));
+        }
+        if file.kdf.algorithm != "argon2id" {
+            return Err(KeyStoreError::InvalidFormat(format!(
+                "Unsupported KDF {}",
+                file.kdf.algorithm
+            )));
+        }
+        if !file.kdf.params.meets(&params) {
+            return Err(KeyStoreError::InvalidFormat(format!(
+                "KDF parameters {:?} are below the required minimum {:?}",
+                file.kdf.params, params
+            )));
+        }
+
+        let salt: [u8; SALT_SIZE] = decode_b64(&file.kdf.salt)?
+            .try_into()
+            .map_err(|_| KeyStoreError::InvalidFormat("Invalid salt length".to_string()))?;
+        let cipher = derive_cipher(passphrase, &salt, file.kdf.params)?;
+
+        let nonce = decode_b64(&file.nonce)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(KeyStoreError::InvalidFormat(
+                "Invalid nonce length".to_string(),
+            ));
+        }
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(&nonce, &decode_b64(&file.ciphertext)?, FILE_AAD)
+                .map_err(|_| KeyStoreError::Decryption)?,
+        );
+
+        let records: BTreeMap<String, EncodedKey> = serde_json::from_slice(&plaintext)?;
+        let keys = records
+            .iter()
+            .map(|(key, record)| Ok((key.clone(), record.decode()?)))
+            .collect::<Result<BTreeMap<_, _>, KeyStoreError>>()?;
+
+        Ok(Self {
+            path,
+            cipher,
+            salt,
+            params: file.kdf.params,
+            keys: Mutex::new(keys),
+        })
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Encrypts `keys` and atomically replaces the backing file.
+    fn save(&self, keys: &BTreeMap<String, KeyMaterial>) -> Result<(), KeyStoreError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(&encode_keys(keys.values()))?);
+        let (nonce, ciphertext) = self
+            .cipher
+            .encrypt(&plaintext, FILE_AAD)
+            .map_err(|e| KeyStoreError::Backend(e.to_string()))?;
+
+        let file = EncryptedFile {
+            format: FORMAT_VERSION,
+            kdf: KdfHeader {
+                algorithm: "argon2id".to_string(),
+                salt: encode_b64(&self.salt),
+                params: self.params,
+            },
+            nonce: encode_b64(&nonce),
+            ciphertext: encode_b64(&ciphertext),
+        };
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let mut tmp = std::fs::File::create(&tmp_path)?;
+            restrict_permissions(&tmp_path)?;
+            tmp.write_all(&serde_json::to_vec_pretty(&file)?)?;
+            tmp.sync_all()?;
+        }
+        std::fs::rename(&tmp_path, &self.path)?;
+        sync_parent_dir(&self.path)?;
+
+        Ok(())
+    }
+
+    /// Applies `update` to the key map and persists the result.
+    ///
+    /// The in-memory map is only changed if the write succeeds.
+    fn update(
+        &self,
+        update: impl FnOnce(&mut BTreeMap<String, KeyMaterial>),
+    ) -> Result<(), KeyStoreError> {
+        let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        let mut updated = keys.clone();
+        update(&mut updated);
+        self.save(&updated)?;
+        *keys = updated;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyStore for FileKeyStore {
+    fn backend(&self) -> &'static str {
+        "file"
+    }
+
+    async fn put(&self, material: KeyMaterial) -> Result<(), KeyStoreError> {
+        validate_key_name(&material.metadata.name)?;
+
+        let key = storage_key(material.metadata.scope, &material.metadata.name);
+        self.update(|keys| {
+            keys.insert(key, material);
+        })
+    }
+
+    async fn get(&self, scope: KeyScope, name: &str) -> Result<KeyMaterial, KeyStoreError> {
+        self.keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&storage_key(scope, name))
+            .cloned()
+            .ok_or_else(|| KeyStoreError::NotFound {
+                scope,
+                name: name.to_string(),
+            })
+    }
+
+    async fn list(&self, scope: KeyScope) -> Result<Vec<String>, KeyStoreError> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter(|material| material.metadata.scope == scope)
+            .map(|material| material.metadata.name.clone())
+            .collect())
+    }
+
+    async fn delete(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError> {
+        let key = storage_key(scope, name);
+        if !self
+            .keys
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(&key)
+        {
+            return Ok(());
+        }
+
+        self.update(|keys| {
+            keys.remove(&key);
+        })
+    }
+}
+
+impl std::fmt::Debug for FileKeyStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileKeyStore")
+            .field("path", &self.path)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+/// Derives a 32-byte wrapping key from a passphrase or other secret with Argon2id.
+///
+/// Also used by `honeylink-keychain`'s file-backed keychain.
+pub fn derive_passphrase_key(
+    secret: &[u8],
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, KeyStoreError> {
+    let argon_params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| KeyStoreError::InvalidFormat(format!("Invalid KDF parameters: {}", e)))?;
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+        .hash_password_into(secret, salt, key.as_mut())
+        .map_err(|e| KeyStoreError::Backend(format!("Key derivation failed: {}", e)))?;
+
+    Ok(key)
+}
+
+/// Derives the file encryption key from the passphrase.
+fn derive_cipher(
+    passphrase: &str,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<ChaCha20Poly1305Cipher, KeyStoreError> {
+    let key = derive_passphrase_key(passphrase.as_bytes(), salt, params)?;
+    ChaCha20Poly1305Cipher::new(key.as_ref()).map_err(|e| KeyStoreError::Backend(e.to_string()))
+}
+
+fn encode_b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+fn decode_b64(data: &str) -> Result<Vec<u8>, KeyStoreError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| KeyStoreError::InvalidFormat(e.to_string()))
+}
+
+/// Restricts the key store file to the owner (no-op on non-Unix platforms).
+fn restrict_permissions(path: &Path) -> Result<(), KeyStoreError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// Flushes the directory entry of a renamed file (no-op on non-Unix platforms).
+pub(crate) fn sync_parent_dir(path: &Path) -> Result<(), KeyStoreError> {
+    #[cfg(unix)]
+    {
+        let parent = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_file_store_persists_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file-key-store.json");
+
+        let store =
+            FileKeyStore::open_with_params(&path, "passphrase", KdfParams::TESTING).unwrap();
+        store
+            .put(KeyMaterial::new(
+                KeyScope::DeviceMaster,
+                "device-1",
+                vec![5u8; 32],
+                Duration::from_secs(3600),
+                "test",
+            ))
+            .await
+            .unwrap();
+        drop(store);
+
+        // Key data must not appear in plaintext on disk
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("device-1"));
+
+        let reopened =
+            FileKeyStore::open_with_params(&path, "passphrase", KdfParams::TESTING).unwrap();
+        let key = reopened
+            .get(KeyScope::DeviceMaster, "device-1")
+            .await
+            .unwrap();
+        assert_eq!(key.data, vec![5u8; 32]);
+        assert_eq!(
+            reopened.list(KeyScope::DeviceMaster).await.unwrap(),
+            vec!["device-1"]
+        );
+
+        reopened
+            .delete(KeyScope::DeviceMaster, "device-1")
+            .await
+            .unwrap();
+        let reopened =
+            FileKeyStore::open_with_params(&path, "passphrase", KdfParams::TESTING).unwrap();
+        assert!(reopened
+            .list(KeyScope::DeviceMaster)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_file_store_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file-key-store-wrong.json");
+        FileKeyStore::open_with_params(&path, "right", KdfParams::TESTING).unwrap();
+
+        assert!(matches!(
+            FileKeyStore::open_with_params(&path, "wrong", KdfParams::TESTING),
+            Err(KeyStoreError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn test_file_store_rejects_kdf_downgrade() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file-key-store-downgrade.json");
+        FileKeyStore::open_with_params(&path, "passphrase", KdfParams::TESTING).unwrap();
+
+        // A file weaker than the caller's floor is refused
+        assert!(matches!(
+            FileKeyStore::open(&path, "passphrase"),
+            Err(KeyStoreError::InvalidFormat(_))
+        ));
+
+        // Rewriting the header to cheaper parameters is refused too
+        let mut file: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        file["kdf"]["memory_kib"] = 8.into();
+        std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+        assert!(matches!(
+            FileKeyStore::open_with_params(&path, "passphrase", KdfParams::TESTING),
+            Err(KeyStoreError::InvalidFormat(_))
+        ));
+    }
+}
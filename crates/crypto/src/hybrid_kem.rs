@@ -0,0 +1,306 @@
+//! Hybrid post-quantum key encapsulation (X25519 + ML-KEM-768)
+//!
+//! X25519 alone is vulnerable to "harvest now, decrypt later": recorded
+//! traffic can be decrypted once a large quantum computer exists. The hybrid
+//! KEM runs X25519 and ML-KEM-768 (FIPS 203) side by side and combines both
+//! shared secrets through HKDF-SHA512, so the result stays secret as long as
+//! either algorithm is unbroken.
+//!
+//! Only available with the `pq-hybrid` feature. ML-KEM comes from the
+//! RustCrypto `ml-kem` crate (pure Rust, no C/C++).
+//!
+//! # Combiner
+//! ```text
+//! shared = HKDF-SHA512(
+//!     ikm  = ss_mlkem || ss_x25519,
+//!     info = "HoneyLink-v1|Custom|HybridKem|X25519-MLKEM768|" || ct_x25519 || pk_x25519,
+//! )
+//! ```
+//!
+//! The X25519 ciphertext and public key are bound into the output (as in
+//! X-Wing), since X25519 on its own is not a binding KEM.
+//!
+//! # Example
+//! ```
+//! use honeylink_crypto::hybrid_kem::{HybridKem, HybridPublicKey};
+//!
+//! let (secret, public) = HybridKem::generate_keypair();
+//!
+//! // Sender encapsulates to the receiver's public key
+//! let public = HybridPublicKey::from_bytes(&public.to_bytes()).unwrap();
+//! let (ciphertext, sender_shared) = HybridKem::encapsulate(&public).unwrap();
+//!
+//! // Receiver recovers the same secret
+//! let receiver_shared = HybridKem::decapsulate(&secret, &ciphertext).unwrap();
+//! assert_eq!(sender_shared.as_bytes(), receiver_shared.as_bytes());
+//! ```
+
+use crate::key_agreement::{KeyAgreement, PublicKey, SecretKey, SharedSecret};
+use crate::key_derivation::{DeriveContext, KeyDerivation};
+use honeylink_core::Result;
+use ml_kem::kem::{Decapsulate, DecapsulationKey, Encapsulate, EncapsulationKey};
+use ml_kem::{Encoded, EncodedSizeUser, KemCore, MlKem768, MlKem768Params};
+use zeroize::Zeroizing;
+
+/// ML-KEM-768 encapsulation key size
+pub const MLKEM_PUBLIC_KEY_SIZE: usize = 1184;
+
+/// ML-KEM-768 ciphertext size
+pub const MLKEM_CIPHERTEXT_SIZE: usize = 1088;
+
+/// Hybrid public key size (X25519 key followed by ML-KEM key)
+pub const HYBRID_PUBLIC_KEY_SIZE: usize = 32 + MLKEM_PUBLIC_KEY_SIZE;
+
+/// Hybrid ciphertext size (X25519 ephemeral key followed by ML-KEM ciphertext)
+pub const HYBRID_CIPHERTEXT_SIZE: usize = 32 + MLKEM_CIPHERTEXT_SIZE;
+
+/// ML-KEM-768 decapsulation key (zeroized on drop)
+pub(crate) type MlKemSecretKey = DecapsulationKey<MlKem768Params>;
+
+/// Secret half of a hybrid key pair
+pub struct HybridSecretKey {
+    x25519: SecretKey,
+    mlkem: MlKemSecretKey,
+    /// Kept for the combiner, which binds the X25519 public key
+    x25519_public: PublicKey,
+}
+
+impl std::fmt::Debug for HybridSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HybridSecretKey")
+            .field("x25519", &"[REDACTED]")
+            .field("mlkem", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Public half of a hybrid key pair
+#[derive(Clone)]
+pub struct HybridPublicKey {
+    x25519: PublicKey,
+    mlkem: EncapsulationKey<MlKem768Params>,
+}
+
+impl HybridPublicKey {
+    /// X25519 component
+    pub fn x25519(&self) -> &PublicKey {
+        &self.x25519
+    }
+
+    /// Serializes to `HYBRID_PUBLIC_KEY_SIZE` bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HYBRID_PUBLIC_KEY_SIZE);
+        bytes.extend_from_slice(self.x25519.as_bytes());
+        bytes.extend_from_slice(&self.mlkem.as_bytes());
+        bytes
+    }
+
+    /// Parses a key produced by `to_bytes`
+    ///
+    /// # Errors
+    /// Returns an error if the input is not `HYBRID_PUBLIC_KEY_SIZE` bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != HYBRID_PUBLIC_KEY_SIZE {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Invalid hybrid public key length: expected {} bytes, got {}",
+                HYBRID_PUBLIC_KEY_SIZE,
+                bytes.len()
+            )));
+        }
+        let (x25519, mlkem) = bytes.split_at(32);
+        Ok(Self {
+            x25519: KeyAgreement::deserialize_public_key(x25519)?,
+            mlkem: mlkem_public_key(mlkem)?,
+        })
+    }
+}
+
+impl std::fmt::Debug for HybridPublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HybridPublicKey")
+            .field("x25519", self.x25519.as_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+/// X25519 + ML-KEM-768 hybrid KEM operations
+pub struct HybridKem;
+
+impl HybridKem {
+    /// Generates a hybrid key pair using OS randomness
+    pub fn generate_keypair() -> (HybridSecretKey, HybridPublicKey) {
+        let (x25519, x25519_public) = KeyAgreement::generate_keypair();
+        let (mlkem, mlkem_public) = MlKem768::generate(&mut rand::rngs::OsRng);
+
+        (
+            HybridSecretKey {
+                x25519,
+                mlkem,
+                x25519_public,
+            },
+            HybridPublicKey {
+                x25519: x25519_public,
+                mlkem: mlkem_public,
+            },
+        )
+    }
+
+    /// Encapsulates a fresh shared secret to `public`
+    ///
+    /// # Returns
+    /// `(ciphertext, shared_secret)`; the ciphertext is
+    /// `HYBRID_CIPHERTEXT_SIZE` bytes and must be sent to the key owner.
+    pub fn encapsulate(public: &HybridPublicKey) -> Result<(Vec<u8>, SharedSecret)> {
+        let (ephemeral, ephemeral_public) = KeyAgreement::generate_keypair();
+        let x25519_shared = KeyAgreement::derive_shared_secret(&ephemeral, &public.x25519)?;
+        let (mlkem_ciphertext, mlkem_shared) = mlkem_encapsulate_to(&public.mlkem)?;
+
+        let mut ciphertext = Vec::with_capacity(HYBRID_CIPHERTEXT_SIZE);
+        ciphertext.extend_from_slice(ephemeral_public.as_bytes());
+        ciphertext.extend_from_slice(&mlkem_ciphertext);
+
+        let shared = combine(
+            &mlkem_shared,
+            x25519_shared.as_bytes(),
+            &ephemeral_public,
+            &public.x25519,
+        )?;
+        Ok((ciphertext, shared))
+    }
+
+    /// Recovers the shared secret from a ciphertext produced by `encapsulate`
+    ///
+    /// # Errors
+    /// Returns an error for malformed ciphertexts or a low-order X25519 key.
+    /// A tampered ML-KEM ciphertext does not fail here (implicit rejection)
+    /// but yields an unrelated secret.
+    pub fn decapsulate(secret: &HybridSecretKey, ciphertext: &[u8]) -> Result<SharedSecret> {
+        if ciphertext.len() != HYBRID_CIPHERTEXT_SIZE {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Invalid hybrid ciphertext length: expected {} bytes, got {}",
+                HYBRID_CIPHERTEXT_SIZE,
+                ciphertext.len()
+            )));
+        }
+        let (ephemeral_public, mlkem_ciphertext) = ciphertext.split_at(32);
+        let ephemeral_public = KeyAgreement::deserialize_public_key(ephemeral_public)?;
+
+        let x25519_shared = KeyAgreement::derive_shared_secret(&secret.x25519, &ephemeral_public)?;
+        let mlkem_shared = mlkem_decapsulate(&secret.mlkem, mlkem_ciphertext)?;
+
+        combine(
+            &mlkem_shared,
+            x25519_shared.as_bytes(),
+            &ephemeral_public,
+            &secret.x25519_public,
+        )
+    }
+}
+
+/// Generates an ML-KEM-768 key pair: (decapsulation key, encoded public key)
+pub(crate) fn mlkem_generate() -> (MlKemSecretKey, Vec<u8>) {
+    let (secret, public) = MlKem768::generate(&mut rand::rngs::OsRng);
+    (secret, public.as_bytes().to_vec())
+}
+
+/// Encapsulates to an encoded ML-KEM-768 public key
+pub(crate) fn mlkem_encapsulate(public: &[u8]) -> Result<(Vec<u8>, Zeroizing<[u8; 32]>)> {
+    mlkem_encapsulate_to(&mlkem_public_key(public)?)
+}
+
+/// Decapsulates an encoded ML-KEM-768 ciphertext
+pub(crate) fn mlkem_decapsulate(
+    secret: &MlKemSecretKey,
+    ciphertext: &[u8],
+) -> Result<Zeroizing<[u8; 32]>> {
+    let ciphertext = ml_kem::Ciphertext::<MlKem768>::try_from(ciphertext)
+        .map_err(|_| kem_error("Invalid ML-KEM ciphertext length"))?;
+    let shared = secret
+        .decapsulate(&ciphertext)
+        .map_err(|_| kem_error("ML-KEM decapsulation failed"))?;
+    Ok(Zeroizing::new(shared.into()))
+}
+
+fn mlkem_public_key(bytes: &[u8]) -> Result<EncapsulationKey<MlKem768Params>> {
+    let encoded = Encoded::<EncapsulationKey<MlKem768Params>>::try_from(bytes)
+        .map_err(|_| kem_error("Invalid ML-KEM public key length"))?;
+    Ok(EncapsulationKey::from_bytes(&encoded))
+}
+
+fn mlkem_encapsulate_to(
+    public: &EncapsulationKey<MlKem768Params>,
+) -> Result<(Vec<u8>, Zeroizing<[u8; 32]>)> {
+    let (ciphertext, shared) = public
+        .encapsulate(&mut rand::rngs::OsRng)
+        .map_err(|_| kem_error("ML-KEM encapsulation failed"))?;
+    Ok((ciphertext.to_vec(), Zeroizing::new(shared.into())))
+}
+
+/// Combines both shared secrets through HKDF-SHA512
+fn combine(
+    mlkem_shared: &[u8; 32],
+    x25519_shared: &[u8; 32],
+    x25519_ciphertext: &PublicKey,
+    x25519_public: &PublicKey,
+) -> Result<SharedSecret> {
+    let mut ikm = Zeroizing::new([0u8; 64]);
+    ikm[..32].copy_from_slice(mlkem_shared);
+    ikm[32..].copy_from_slice(x25519_shared);
+
+    let mut info = Vec::with_capacity(64);
+    info.extend_from_slice(x25519_ciphertext.as_bytes());
+    info.extend_from_slice(x25519_public.as_bytes());
+    let context = DeriveContext::custom("HybridKem|X25519-MLKEM768|", info);
+
+    let output = KeyDerivation::derive_with_context(ikm.as_slice(), &context, 32)?;
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&output);
+    Ok(SharedSecret::from_bytes(bytes))
+}
+
+fn kem_error(message: &str) -> honeylink_core::Error {
+    honeylink_core::Error::Crypto(format!("Hybrid KEM: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes_match_fips_203() {
+        let (_, public) = HybridKem::generate_keypair();
+        assert_eq!(public.to_bytes().len(), HYBRID_PUBLIC_KEY_SIZE);
+
+        let (ciphertext, _) = HybridKem::encapsulate(&public).unwrap();
+        assert_eq!(ciphertext.len(), HYBRID_CIPHERTEXT_SIZE);
+    }
+
+    #[test]
+    fn test_both_components_contribute() {
+        let (secret, public) = HybridKem::generate_keypair();
+        let (ciphertext, shared) = HybridKem::encapsulate(&public).unwrap();
+
+        // Tampering with either half changes the result
+        for index in [0, 32 + 100] {
+            let mut tampered = ciphertext.clone();
+            tampered[index] ^= 0x01;
+            // A tampered X25519 key may also be rejected outright
+            if let Ok(other) = HybridKem::decapsulate(&secret, &tampered) {
+                assert_ne!(other.as_bytes(), shared.as_bytes());
+            }
+        }
+
+        // A different key pair recovers nothing useful
+        let (other_secret, _) = HybridKem::generate_keypair();
+        let other = HybridKem::decapsulate(&other_secret, &ciphertext).unwrap();
+        assert_ne!(other.as_bytes(), shared.as_bytes());
+    }
+
+    #[test]
+    fn test_invalid_lengths_rejected() {
+        let (secret, _) = HybridKem::generate_keypair();
+        assert!(HybridPublicKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(HybridKem::decapsulate(&secret, &[0u8; 64]).is_err());
+        assert!(mlkem_encapsulate(&[0u8; 10]).is_err());
+    }
+}
@@ -30,7 +30,7 @@ use curve25519_dalek::montgomery::MontgomeryPoint;
 use curve25519_dalek::scalar::Scalar;
 use honeylink_core::Result;
 use rand::RngCore;
-use x25519_dalek::PublicKey;
+pub use x25519_dalek::PublicKey;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// A zeroizing wrapper for X25519 static secret keys.
@@ -39,7 +39,7 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 ///
 /// Note: x25519-dalek 2.0 removed StaticSecret. We use curve25519-dalek's
 /// Scalar directly for reusable keys.
-#[derive(Zeroize, ZeroizeOnDrop)]
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct SecretKey {
     scalar: Scalar,
 }
@@ -100,6 +100,12 @@ pub struct SharedSecret {
 }
 
 impl SharedSecret {
+    /// Wraps secret bytes produced by another key exchange (e.g. `hybrid_kem`).
+    #[cfg_attr(not(feature = "pq-hybrid"), allow(dead_code))]
+    pub(crate) fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self { bytes }
+    }
+
     /// Returns the shared secret as a byte slice.
     ///
     /// # Security
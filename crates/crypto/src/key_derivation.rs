@@ -51,6 +51,12 @@ pub enum DeriveContext {
         session_id: String,
         stream_id: String,
     },
+    /// Session traffic secret ratchet (see `ratchet` module)
+    Traffic {
+        session_id: String,
+        label: String,
+        epoch: u64,
+    },
     /// Custom derivation (use with caution)
     Custom { label: String, info: Vec<u8> },
 }
@@ -79,6 +85,15 @@ impl DeriveContext {
         }
     }
 
+    /// Creates a traffic secret ratchet derivation context.
+    pub fn traffic(session_id: impl Into<String>, label: impl Into<String>, epoch: u64) -> Self {
+        Self::Traffic {
+            session_id: session_id.into(),
+            label: label.into(),
+            epoch,
+        }
+    }
+
     /// Creates a custom derivation context.
     pub fn custom(label: impl Into<String>, info: impl Into<Vec<u8>>) -> Self {
         Self::Custom {
@@ -110,6 +125,13 @@ impl DeriveContext {
             } => {
                 format!("{}|Stream|{}|{}", prefix, session_id, stream_id).into_bytes()
             }
+            Self::Traffic {
+                session_id,
+                label,
+                epoch,
+            } => {
+                format!("{}|Traffic|{}|{}|{}", prefix, session_id, label, epoch).into_bytes()
+            }
             Self::Custom { label, info } => {
                 let mut encoded = format!("{}|Custom|{}", prefix, label).into_bytes();
                 encoded.extend_from_slice(info);
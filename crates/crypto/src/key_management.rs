@@ -1,6 +1,7 @@
 //! Key hierarchy management
 
 use honeylink_core::Result;
+use std::time::Duration;
 use zeroize::Zeroizing;
 
 /// Key scopes in the hierarchy
@@ -10,12 +11,48 @@ use zeroize::Zeroizing;
 /// - DeviceMaster: Device identification (90 day rotation)
 /// - Session: Per-session encryption (24 hour lifetime)
 /// - Stream: Per-stream keys (connection lifetime)
+///
+/// Stored (non-derived) keys additionally use:
+/// - Service: Service-level master keys (Session Orchestrator, Policy Engine, etc.)
+/// - Profile: Profile data encryption keys
+/// - Telemetry: Telemetry data encryption keys
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum KeyScope {
     Root,
     DeviceMaster,
     Session,
     Stream,
+    Service,
+    Profile,
+    Telemetry,
+}
+
+impl KeyScope {
+    /// Storage path prefix for keys in this scope (e.g. "k_service")
+    pub fn storage_path(&self) -> &'static str {
+        match self {
+            KeyScope::Root => "k_root",
+            KeyScope::DeviceMaster => "k_device_master",
+            KeyScope::Session => "k_session",
+            KeyScope::Stream => "k_stream",
+            KeyScope::Service => "k_service",
+            KeyScope::Profile => "k_profile",
+            KeyScope::Telemetry => "k_telemetry",
+        }
+    }
+
+    /// Recommended rotation interval for stored keys in this scope
+    pub fn rotation_interval(&self) -> Duration {
+        match self {
+            KeyScope::Root => Duration::from_secs(5 * 365 * 24 * 3600), // 5 years
+            KeyScope::Service => Duration::from_secs(365 * 24 * 3600),   // 1 year
+            KeyScope::DeviceMaster | KeyScope::Profile | KeyScope::Telemetry => {
+                Duration::from_secs(90 * 24 * 3600) // 90 days
+            }
+            KeyScope::Session => Duration::from_secs(24 * 3600), // 24 hours
+            KeyScope::Stream => Duration::from_secs(3600),       // 1 hour
+        }
+    }
 }
 
 /// Hierarchical key management
@@ -59,6 +96,9 @@ fn scope_to_string(scope: KeyScope) -> &'static str {
         KeyScope::DeviceMaster => "device-master",
         KeyScope::Session => "session",
         KeyScope::Stream => "stream",
+        KeyScope::Service => "service",
+        KeyScope::Profile => "profile",
+        KeyScope::Telemetry => "telemetry",
     }
 }
 
@@ -0,0 +1,460 @@
+//! Pluggable key storage backends.
+//!
+//! `KeyStore` is the storage interface used by `KeyLifecycle` and
+//! `RotationScheduler`, so key generation, rotation and revocation work the
+//! same way regardless of where keys live:
+//!
+//! - `MemoryKeyStore`: In-process storage (tests, ephemeral nodes)
+//! - `FileKeyStore`: Passphrase-encrypted local file (Argon2id + ChaCha20-Poly1305)
+//! - `KeychainKeyStore`: OS keychain or encrypted file keychain (in `honeylink-keychain`)
+//! - `VaultClient`: HashiCorp Vault KV v2 (`vault` feature)
+//!
+//! # Example
+//! ```
+//! use honeylink_crypto::key_management::KeyScope;
+//! use honeylink_crypto::key_store::{KeyMaterial, KeyStore, MemoryKeyStore};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), honeylink_crypto::key_store::KeyStoreError> {
+//! let store = MemoryKeyStore::new();
+//! let material = KeyMaterial::new(
+//!     KeyScope::DeviceMaster,
+//!     "device-12345",
+//!     vec![0u8; 32],
+//!     Duration::from_secs(90 * 24 * 3600),
+//!     "development",
+//! );
+//! store.put(material).await?;
+//!
+//! let key = store.get(KeyScope::DeviceMaster, "device-12345").await?;
+//! assert_eq!(key.metadata.version, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::key_management::KeyScope;
+use async_trait::async_trait;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::RwLock;
+use std::time::Duration;
+use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Errors that can occur in key storage backends.
+#[derive(Debug, Error)]
+pub enum KeyStoreError {
+    #[error("Key not found: scope={scope:?}, name={name}")]
+    NotFound { scope: KeyScope, name: String },
+
+    #[error("Invalid key name: {0}")]
+    InvalidName(String),
+
+    #[error("Invalid key format: {0}")]
+    InvalidFormat(String),
+
+    #[error("Decryption failed (wrong passphrase or corrupted key store)")]
+    Decryption,
+
+    #[error("Key store backend error: {0}")]
+    Backend(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Metadata for a stored key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyMetadata {
+    /// Key scope
+    pub scope: KeyScope,
+    /// Key identifier (e.g., "session-orchestrator", "device-12345")
+    pub name: String,
+    /// Key version (increments on rotation)
+    pub version: u32,
+    /// Creation timestamp (RFC 3339)
+    pub created_at: String,
+    /// Expiration timestamp (RFC 3339)
+    pub expires_at: String,
+    /// Algorithm used (e.g., "X25519", "ChaCha20-Poly1305"), or
+    /// `UNSPECIFIED_ALGORITHM` if the caller did not set one
+    pub algorithm: String,
+    /// Environment (dev, staging, production)
+    pub environment: String,
+}
+
+/// Algorithm label for key material stored without `with_algorithm()`.
+pub const UNSPECIFIED_ALGORITHM: &str = "unspecified";
+
+impl KeyMetadata {
+    /// Creates version 1 metadata valid for `ttl` from now.
+    ///
+    /// The algorithm is `UNSPECIFIED_ALGORITHM`; set it with `with_algorithm()`.
+    pub fn new(scope: KeyScope, name: &str, ttl: Duration, environment: &str) -> Self {
+        let now = chrono::Utc::now();
+        let expires_at = now + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+
+        Self {
+            scope,
+            name: name.to_string(),
+            version: 1,
+            created_at: now.to_rfc3339(),
+            expires_at: expires_at.to_rfc3339(),
+            algorithm: UNSPECIFIED_ALGORITHM.to_string(),
+            environment: environment.to_string(),
+        }
+    }
+
+    /// Returns metadata for the next version, valid for `ttl` from now.
+    pub fn next_version(&self, ttl: Duration) -> Self {
+        Self {
+            version: self.version + 1,
+            ..Self::new(self.scope, &self.name, ttl, &self.environment)
+        }
+        .with_algorithm(&self.algorithm)
+    }
+
+    /// Sets the algorithm label.
+    pub fn with_algorithm(mut self, algorithm: &str) -> Self {
+        self.algorithm = algorithm.to_string();
+        self
+    }
+
+    /// Age of the key, or `None` if `created_at` is not valid RFC 3339.
+    pub fn age(&self) -> Option<chrono::Duration> {
+        let created_at = chrono::DateTime::parse_from_rfc3339(&self.created_at).ok()?;
+        Some(chrono::Utc::now().signed_duration_since(created_at))
+    }
+
+    /// Checks whether the key has expired (unparseable timestamps count as expired).
+    pub fn is_expired(&self) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.expires_at)
+            .map(|expires_at| expires_at < chrono::Utc::now())
+            .unwrap_or(true)
+    }
+}
+
+/// A key material wrapper that automatically zeroizes on drop.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct KeyMaterial {
+    #[zeroize(skip)]
+    pub metadata: KeyMetadata,
+    pub data: Vec<u8>,
+}
+
+impl KeyMaterial {
+    /// Creates version 1 key material valid for `ttl` from now.
+    pub fn new(
+        scope: KeyScope,
+        name: &str,
+        data: Vec<u8>,
+        ttl: Duration,
+        environment: &str,
+    ) -> Self {
+        Self {
+            metadata: KeyMetadata::new(scope, name, ttl, environment),
+            data,
+        }
+    }
+
+    /// Sets the algorithm label in the metadata.
+    pub fn with_algorithm(mut self, algorithm: &str) -> Self {
+        self.metadata.algorithm = algorithm.to_string();
+        self
+    }
+}
+
+impl std::fmt::Debug for KeyMaterial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyMaterial")
+            .field("metadata", &self.metadata)
+            .field("data", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Serialized key record (base64 key data + metadata).
+///
+/// Shared by the Vault, keychain and file backends so records can be moved
+/// between them unchanged.
+#[derive(Serialize, Deserialize)]
+pub struct EncodedKey {
+    /// Base64-encoded key material
+    pub key_data: String,
+    /// Key metadata
+    pub metadata: KeyMetadata,
+}
+
+impl EncodedKey {
+    /// Encodes key material.
+    pub fn encode(material: &KeyMaterial) -> Self {
+        Self {
+            key_data: base64::engine::general_purpose::STANDARD.encode(&material.data),
+            metadata: material.metadata.clone(),
+        }
+    }
+
+    /// Decodes the record back into key material.
+    pub fn decode(&self) -> Result<KeyMaterial, KeyStoreError> {
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&self.key_data)
+            .map_err(|e| KeyStoreError::InvalidFormat(e.to_string()))?;
+
+        Ok(KeyMaterial {
+            metadata: self.metadata.clone(),
+            data,
+        })
+    }
+}
+
+impl Drop for EncodedKey {
+    fn drop(&mut self) {
+        self.key_data.zeroize();
+    }
+}
+
+/// Storage backend for long-lived keys.
+///
+/// Keys are addressed by `(scope, name)`. `get()` returns the latest version
+/// without checking expiration; callers such as `KeyLifecycle` decide how to
+/// treat expired keys.
+#[async_trait]
+pub trait KeyStore: Send + Sync {
+    /// Backend name for logs and audit events (e.g. "memory", "vault").
+    fn backend(&self) -> &'static str;
+
+    /// Stores key material, replacing any existing version.
+    async fn put(&self, material: KeyMaterial) -> Result<(), KeyStoreError>;
+
+    /// Retrieves the latest version of a key.
+    async fn get(&self, scope: KeyScope, name: &str) -> Result<KeyMaterial, KeyStoreError>;
+
+    /// Lists key names in a scope.
+    async fn list(&self, scope: KeyScope) -> Result<Vec<String>, KeyStoreError>;
+
+    /// Deletes a key. Deleting a missing key is not an error.
+    ///
+    /// Backends with version history (Vault) may keep the data recoverable;
+    /// use `destroy()` for permanent removal.
+    async fn delete(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError>;
+
+    /// Permanently removes a key and all its versions.
+    async fn destroy(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError> {
+        self.delete(scope, name).await
+    }
+
+    /// Checks that the backend is reachable and usable.
+    async fn health_check(&self) -> Result<(), KeyStoreError> {
+        Ok(())
+    }
+
+    /// Replaces a key with a new version and returns the new version number.
+    ///
+    /// There is no grace period: `get()` returns the new version immediately.
+    /// Memory, file and keychain stores overwrite the previous version; Vault
+    /// keeps it in the KV v2 version history only. Callers that must accept
+    /// the old key during a transition have to keep it themselves (see
+    /// `rotation::KeyRotationManager`).
+    async fn rotate(
+        &self,
+        scope: KeyScope,
+        name: &str,
+        new_key_data: Vec<u8>,
+        ttl: Duration,
+    ) -> Result<u32, KeyStoreError> {
+        let current = self.get(scope, name).await?;
+        let metadata = current.metadata.next_version(ttl);
+        let version = metadata.version;
+
+        self.put(KeyMaterial {
+            metadata,
+            data: new_key_data,
+        })
+        .await?;
+
+        Ok(version)
+    }
+}
+
+/// Validates a key name for use as a storage path component.
+///
+/// Names must start with an ASCII alphanumeric character and contain only
+/// ASCII alphanumerics, '-', '_' and '.'.
+pub fn validate_key_name(name: &str) -> Result<(), KeyStoreError> {
+    let valid = name.len() <= 128
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(KeyStoreError::InvalidName(name.to_string()))
+    }
+}
+
+/// Storage key for a `(scope, name)` pair, e.g. "k_service/session-orchestrator".
+pub fn storage_key(scope: KeyScope, name: &str) -> String {
+    format!("{}/{}", scope.storage_path(), name)
+}
+
+/// In-memory key store.
+///
+/// Keys are lost when the store is dropped. Suitable for tests and for nodes
+/// that re-derive their keys on every start.
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    keys: RwLock<HashMap<(KeyScope, String), KeyMaterial>>,
+}
+
+impl MemoryKeyStore {
+    /// Creates an empty in-memory key store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl KeyStore for MemoryKeyStore {
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn put(&self, material: KeyMaterial) -> Result<(), KeyStoreError> {
+        validate_key_name(&material.metadata.name)?;
+
+        let key = (material.metadata.scope, material.metadata.name.clone());
+        self.keys
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, material);
+        Ok(())
+    }
+
+    async fn get(&self, scope: KeyScope, name: &str) -> Result<KeyMaterial, KeyStoreError> {
+        self.keys
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(scope, name.to_string()))
+            .cloned()
+            .ok_or_else(|| KeyStoreError::NotFound {
+                scope,
+                name: name.to_string(),
+            })
+    }
+
+    async fn list(&self, scope: KeyScope) -> Result<Vec<String>, KeyStoreError> {
+        let mut names: Vec<String> = self
+            .keys
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .filter(|(key_scope, _)| *key_scope == scope)
+            .map(|(_, name)| name.clone())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    async fn delete(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError> {
+        self.keys
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(scope, name.to_string()));
+        Ok(())
+    }
+}
+
+/// Serializes a set of keys into the shared record format, ordered by storage key.
+pub(crate) fn encode_keys<'a>(
+    keys: impl Iterator<Item = &'a KeyMaterial>,
+) -> BTreeMap<String, EncodedKey> {
+    keys.map(|material| {
+        (
+            storage_key(material.metadata.scope, &material.metadata.name),
+            EncodedKey::encode(material),
+        )
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material(name: &str) -> KeyMaterial {
+        KeyMaterial::new(
+            KeyScope::Service,
+            name,
+            vec![7u8; 32],
+            Duration::from_secs(3600),
+            "test",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_roundtrip_and_rotate() {
+        let store = MemoryKeyStore::new();
+        store.put(material("svc-b")).await.unwrap();
+        store.put(material("svc-a")).await.unwrap();
+
+        assert_eq!(
+            store.list(KeyScope::Service).await.unwrap(),
+            vec!["svc-a", "svc-b"]
+        );
+        assert!(store.list(KeyScope::Profile).await.unwrap().is_empty());
+
+        let version = store
+            .rotate(
+                KeyScope::Service,
+                "svc-a",
+                vec![9u8; 32],
+                Duration::from_secs(60),
+            )
+            .await
+            .unwrap();
+        assert_eq!(version, 2);
+
+        let rotated = store.get(KeyScope::Service, "svc-a").await.unwrap();
+        assert_eq!(rotated.data, vec![9u8; 32]);
+        assert_eq!(rotated.metadata.version, 2);
+        assert!(!rotated.metadata.is_expired());
+
+        store.delete(KeyScope::Service, "svc-a").await.unwrap();
+        assert!(matches!(
+            store.get(KeyScope::Service, "svc-a").await,
+            Err(KeyStoreError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_key_name() {
+        assert!(validate_key_name("device-12345").is_ok());
+        assert!(validate_key_name("svc_1.v2").is_ok());
+        assert!(validate_key_name("").is_err());
+        assert!(validate_key_name(".index").is_err());
+        assert!(validate_key_name("../etc").is_err());
+        assert!(validate_key_name("a/b").is_err());
+    }
+
+    #[test]
+    fn test_encoded_key_roundtrip() {
+        let original = material("svc");
+        let json = serde_json::to_string(&EncodedKey::encode(&original)).unwrap();
+        let decoded = serde_json::from_str::<EncodedKey>(&json)
+            .unwrap()
+            .decode()
+            .unwrap();
+
+        assert_eq!(decoded.data, original.data);
+        assert_eq!(decoded.metadata, original.metadata);
+    }
+}
@@ -5,10 +5,19 @@
 //! ## Features
 //!
 //! - X25519 key agreement
+//! - Hybrid X25519 + ML-KEM-768 key encapsulation (`pq-hybrid` feature)
+//! - Noise XX handshake with device identity keys
 //! - ChaCha20-Poly1305 AEAD encryption
+//! - Sequence-number packet protection with replay window
 //! - HKDF-SHA512 key derivation
 //! - Ed25519 signatures
+//! - Root-signed device certificate chains with revocation lists
 //! - Hierarchical key management (k_root → k_service → k_session → k_stream)
+//! - Shamir k-of-n backup of the root key
+//! - Session traffic-key ratchet with AEAD usage limits
+//! - Pluggable key storage (`key-store` feature: in-memory and encrypted file,
+//!   plus the `vault` backend; the keychain backend lives in `honeylink-keychain`)
+//! - Encrypted rotation state files (`key-store` feature)
 //!
 //! ## Security
 //!
@@ -17,36 +26,73 @@
 //! - Key lifecycle management
 
 pub mod aead;
+pub mod certificate;
 pub mod key_agreement;
 pub mod key_derivation;
 pub mod key_management;
+pub mod noise;
+pub mod packet_protection;
 pub mod pop_token;
+pub mod ratchet;
 pub mod rotation;
+pub mod shamir;
 pub mod signing;
 pub mod telemetry;
 
-#[cfg(feature = "vault")]
+#[cfg(feature = "key-store")]
+pub mod key_store;
+
+#[cfg(feature = "key-store")]
+pub mod file_key_store;
+
+#[cfg(feature = "key-store")]
 pub mod rotation_scheduler;
 
+#[cfg(feature = "key-store")]
+pub mod rotation_store;
+
 #[cfg(feature = "vault")]
 pub mod vault;
 
-#[cfg(feature = "vault")]
+#[cfg(feature = "pq-hybrid")]
+pub mod hybrid_kem;
+
+#[cfg(feature = "key-store")]
 pub mod lifecycle;
 
 pub use aead::{ChaCha20Poly1305Cipher, EncryptionKey, MAX_PLAINTEXT_SIZE, NONCE_SIZE, TAG_SIZE};
+pub use certificate::{
+    Capabilities, CertificateChain, CertificateIssuer, DeviceCertificate, RevocationList, TrustStore,
+    VerifiedDevice,
+};
 pub use key_agreement::{KeyAgreement, SecretKey, SharedSecret};
 pub use key_derivation::{DeriveContext, KeyDerivation};
 pub use key_management::{KeyHierarchy, KeyScope};
+pub use noise::{HandshakeRole, KemMode, KemPolicy, NoiseHandshake, TransportKeys};
+pub use packet_protection::{PacketOpener, PacketProtection, PacketSealer, ReplayWindow};
 pub use pop_token::{PopClaims, PopToken, PopTokenGenerator, MAX_TOKEN_TTL_SECONDS};
+pub use ratchet::{KeyUpdate, RatchetPolicy, RatchetRole, SealedRecord, TrafficRatchet};
 pub use rotation::{KeyRotationManager, RotationPolicy, RotationStatus, VersionedKey, KeyVersion};
+pub use shamir::{combine_shares, split_secret, Share};
 pub use telemetry::CryptoTelemetry;
 
-#[cfg(feature = "vault")]
-pub use vault::{VaultClient, VaultError, KeyMaterial, KeyMetadata};
+#[cfg(feature = "key-store")]
+pub use key_store::{KeyMaterial, KeyMetadata, KeyStore, KeyStoreError, MemoryKeyStore};
 
-#[cfg(feature = "vault")]
-pub use lifecycle::{VaultKeyLifecycle, LifecycleError};
+#[cfg(feature = "key-store")]
+pub use file_key_store::{derive_passphrase_key, FileKeyStore, KdfParams};
+
+#[cfg(feature = "pq-hybrid")]
+pub use hybrid_kem::{HybridKem, HybridPublicKey, HybridSecretKey};
 
 #[cfg(feature = "vault")]
+pub use vault::{VaultClient, VaultError};
+
+#[cfg(feature = "key-store")]
+pub use lifecycle::{KeyLifecycle, VaultKeyLifecycle, LifecycleError};
+
+#[cfg(feature = "key-store")]
+pub use rotation_store::{RotationStateStore, SealingKey};
+
+#[cfg(feature = "key-store")]
 pub use rotation_scheduler::{RotationScheduler, RotationTrigger, RotationEvent, SchedulerConfig, SchedulerError};
@@ -1,16 +1,17 @@
-//! Key lifecycle management with Vault integration.
+//! Key lifecycle management on top of a pluggable `KeyStore`.
 //!
-//! This module extends the existing key generation functionality with Vault storage,
-//! implementing rotation, revocation, and grace period management according to
-//! spec/security/key-management.md.
+//! This module extends the existing key generation functionality with persistent
+//! storage, implementing rotation and revocation according to
+//! spec/security/key-management.md. Any `KeyStore` backend can be used (Vault,
+//! OS keychain, encrypted file, in-memory), so serverless deployments do not
+//! need a Vault server to rotate keys.
 
-#[cfg(feature = "vault")]
-use crate::vault::{KeyScope as VaultKeyScope, KeyMaterial, VaultClient, VaultError};
 use crate::key_management::KeyScope;
-use crate::rotation::{KeyRotationManager, RotationPolicy};
-use std::time::Duration;
+use crate::key_store::{KeyMaterial, KeyStore, KeyStoreError};
+#[cfg(feature = "vault")]
+use crate::vault::{VaultClient, VaultError};
+use std::sync::Arc;
 use thiserror::Error;
-use zeroize::Zeroizing;
 
 /// Errors that can occur during key lifecycle operations.
 #[derive(Debug, Error)]
@@ -30,56 +31,68 @@ pub enum LifecycleError {
     #[error("Invalid key scope: {0}")]
     InvalidScope(String),
 
+    #[error("Key expired: scope={scope:?}, name={name}")]
+    Expired { scope: KeyScope, name: String },
+
+    #[error("Key store error: {0}")]
+    Store(#[from] KeyStoreError),
+
     #[cfg(feature = "vault")]
     #[error("Vault error: {0}")]
     VaultError(#[from] VaultError),
 }
 
-/// Maps KeyScope to VaultKeyScope.
-#[cfg(feature = "vault")]
-fn to_vault_scope(scope: KeyScope) -> Result<VaultKeyScope, LifecycleError> {
-    match scope {
-        KeyScope::Root => Ok(VaultKeyScope::Root),
-        KeyScope::DeviceMaster => Ok(VaultKeyScope::Service),
-        KeyScope::Session => Ok(VaultKeyScope::Service),
-        KeyScope::Stream => Ok(VaultKeyScope::Service),
-    }
+/// Key lifecycle manager over any `KeyStore` backend.
+pub struct KeyLifecycle {
+    store: Arc<dyn KeyStore>,
+    environment: String,
 }
 
-/// Key lifecycle manager integrating Vault storage.
-#[cfg(feature = "vault")]
-pub struct VaultKeyLifecycle {
-    vault: VaultClient,
-    rotation_manager: KeyRotationManager,
-}
+/// Former name of `KeyLifecycle`, from when Vault was the only backend.
+pub type VaultKeyLifecycle = KeyLifecycle;
 
-#[cfg(feature = "vault")]
-impl VaultKeyLifecycle {
-    /// Creates a new lifecycle manager from environment variables.
+impl KeyLifecycle {
+    /// Creates a new lifecycle manager on top of a key store.
+    ///
+    /// The environment label defaults to `HONEYLINK_ENV` (or "development").
+    pub fn new(store: Arc<dyn KeyStore>) -> Self {
+        Self {
+            store,
+            environment: std::env::var("HONEYLINK_ENV")
+                .unwrap_or_else(|_| "development".to_string()),
+        }
+    }
+
+    /// Creates a new lifecycle manager backed by Vault, configured from environment variables.
+    #[cfg(feature = "vault")]
     pub fn from_env() -> Result<Self, LifecycleError> {
         let vault = VaultClient::from_env()
             .map_err(|e| LifecycleError::Vault(e.to_string()))?;
 
-        let rotation_manager = KeyRotationManager::new();
-
-        Ok(Self {
-            vault,
-            rotation_manager,
-        })
+        Ok(Self::with_vault(vault))
     }
 
     /// Creates a new lifecycle manager with explicit Vault client.
-    pub fn new(vault: VaultClient) -> Self {
-        Self {
-            vault,
-            rotation_manager: KeyRotationManager::new(),
-        }
+    #[cfg(feature = "vault")]
+    pub fn with_vault(vault: VaultClient) -> Self {
+        Self::new(Arc::new(vault))
+    }
+
+    /// Sets the environment label recorded in key metadata.
+    pub fn with_environment(mut self, environment: impl Into<String>) -> Self {
+        self.environment = environment.into();
+        self
+    }
+
+    /// Returns the underlying key store.
+    pub fn store(&self) -> &Arc<dyn KeyStore> {
+        &self.store
     }
 
-    /// Generates a new key and stores it in Vault.
+    /// Generates a new key and stores it.
     ///
     /// # Arguments
-    /// - `scope`: Key scope (Root, DeviceMaster, Session, Stream)
+    /// - `scope`: Key scope (Root, DeviceMaster, Session, Stream, ...)
     /// - `name`: Key identifier
     /// - `key_data`: Key material (will be zeroized)
     ///
@@ -92,18 +105,19 @@ impl VaultKeyLifecycle {
         name: &str,
         key_data: Vec<u8>,
     ) -> Result<(), LifecycleError> {
-        let vault_scope = to_vault_scope(scope)?;
-        let ttl = vault_scope.rotation_interval();
-
-        self.vault
-            .store_key(vault_scope, name, key_data, ttl)
-            .await
-            .map_err(|e| LifecycleError::Vault(e.to_string()))?;
-
+        let material = KeyMaterial::new(
+            scope,
+            name,
+            key_data,
+            scope.rotation_interval(),
+            &self.environment,
+        );
+
+        self.store.put(material).await?;
         Ok(())
     }
 
-    /// Retrieves a key from Vault.
+    /// Retrieves a key.
     ///
     /// # Arguments
     /// - `scope`: Key scope
@@ -111,26 +125,32 @@ impl VaultKeyLifecycle {
     ///
     /// # Returns
     /// - `Ok(KeyMaterial)`: Key material with metadata
+    /// - `Err(LifecycleError::Expired)`: Key has expired
     /// - `Err(LifecycleError)`: Key not found or retrieval failed
     pub async fn retrieve(
         &self,
         scope: KeyScope,
         name: &str,
     ) -> Result<KeyMaterial, LifecycleError> {
-        let vault_scope = to_vault_scope(scope)?;
+        let material = self.store.get(scope, name).await?;
 
-        self.vault
-            .retrieve_key(vault_scope, name)
-            .await
-            .map_err(|e| LifecycleError::Vault(e.to_string()))
+        if material.metadata.is_expired() {
+            return Err(LifecycleError::Expired {
+                scope,
+                name: name.to_string(),
+            });
+        }
+
+        Ok(material)
     }
 
     /// Rotates a key by generating a new version.
     ///
     /// # Process
     /// 1. Generate new key material
-    /// 2. Store in Vault with incremented version
-    /// 3. Old version remains accessible for grace period
+    /// 2. Store with incremented version
+    /// 3. The old version is no longer returned by `retrieve` (see
+    ///    `KeyStore::rotate`; there is no grace period at this layer)
     ///
     /// # Arguments
     /// - `scope`: Key scope
@@ -146,27 +166,23 @@ impl VaultKeyLifecycle {
         name: &str,
         new_key_data: Vec<u8>,
     ) -> Result<u32, LifecycleError> {
-        let vault_scope = to_vault_scope(scope)?;
-
-        self.vault
-            .rotate_key(vault_scope, name, new_key_data)
+        self.store
+            .rotate(scope, name, new_key_data, scope.rotation_interval())
             .await
             .map_err(|e| LifecycleError::Rotation(e.to_string()))
     }
 
-    /// Revokes a key (soft delete).
+    /// Revokes a key.
     ///
-    /// The key is marked as deleted but remains accessible for recovery.
-    /// For permanent deletion, use `destroy`.
+    /// On Vault the key is soft-deleted and remains recoverable; other backends
+    /// remove it. For permanent deletion, use `destroy`.
     ///
     /// # Arguments
     /// - `scope`: Key scope
     /// - `name`: Key identifier
     pub async fn revoke(&self, scope: KeyScope, name: &str) -> Result<(), LifecycleError> {
-        let vault_scope = to_vault_scope(scope)?;
-
-        self.vault
-            .delete_key(vault_scope, name)
+        self.store
+            .delete(scope, name)
             .await
             .map_err(|e| LifecycleError::Revocation(e.to_string()))
     }
@@ -179,29 +195,16 @@ impl VaultKeyLifecycle {
     /// # Arguments
     /// - `scope`: Key scope
     /// - `name`: Key identifier
-    /// - `versions`: Version numbers to destroy
-    pub async fn destroy(
-        &self,
-        scope: KeyScope,
-        name: &str,
-        versions: Vec<u64>,
-    ) -> Result<(), LifecycleError> {
-        let vault_scope = to_vault_scope(scope)?;
-
-        self.vault
-            .destroy_key(vault_scope, name, versions)
+    pub async fn destroy(&self, scope: KeyScope, name: &str) -> Result<(), LifecycleError> {
+        self.store
+            .destroy(scope, name)
             .await
             .map_err(|e| LifecycleError::Revocation(e.to_string()))
     }
 
     /// Lists all keys in a scope.
     pub async fn list(&self, scope: KeyScope) -> Result<Vec<String>, LifecycleError> {
-        let vault_scope = to_vault_scope(scope)?;
-
-        self.vault
-            .list_keys(vault_scope)
-            .await
-            .map_err(|e| LifecycleError::Vault(e.to_string()))
+        Ok(self.store.list(scope).await?)
     }
 
     /// Checks if a key needs rotation based on policy.
@@ -211,43 +214,92 @@ impl VaultKeyLifecycle {
     /// - `name`: Key identifier
     ///
     /// # Returns
-    /// - `Ok(true)`: Key should be rotated
+    /// - `Ok(true)`: Key should be rotated (including expired keys)
     /// - `Ok(false)`: Key is still valid
     pub async fn should_rotate(
         &self,
         scope: KeyScope,
         name: &str,
     ) -> Result<bool, LifecycleError> {
-        let material = self.retrieve(scope, name).await?;
-
-        // Parse created_at timestamp
-        let created_at = chrono::DateTime::parse_from_rfc3339(&material.metadata.created_at)
-            .map_err(|e| LifecycleError::Vault(format!("Invalid timestamp: {}", e)))?;
+        let material = self.store.get(scope, name).await?;
 
-        let age = chrono::Utc::now().signed_duration_since(created_at);
-        let rotation_interval = to_vault_scope(scope)?.rotation_interval();
+        let age = material
+            .metadata
+            .age()
+            .ok_or_else(|| LifecycleError::Rotation("Invalid created_at timestamp".to_string()))?;
 
-        Ok(age.num_seconds() as u64 >= rotation_interval.as_secs())
+        Ok(material.metadata.is_expired()
+            || age.num_seconds() as u64 >= scope.rotation_interval().as_secs())
     }
 
-    /// Health check for Vault connectivity.
+    /// Health check for key store connectivity.
     pub async fn health_check(&self) -> Result<(), LifecycleError> {
-        self.vault
-            .health_check()
-            .await
-            .map_err(|e| LifecycleError::Vault(e.to_string()))
+        Ok(self.store.health_check().await?)
     }
 }
 
 #[cfg(test)]
-#[cfg(feature = "vault")]
 mod tests {
     use super::*;
+    use crate::key_store::MemoryKeyStore;
+
+    #[tokio::test]
+    async fn test_lifecycle_on_memory_store() {
+        let lifecycle = KeyLifecycle::new(Arc::new(MemoryKeyStore::new())).with_environment("test");
+        let scope = KeyScope::DeviceMaster;
+        let name = "test-device";
+
+        lifecycle
+            .generate_and_store(scope, name, vec![1u8; 32])
+            .await
+            .expect("Failed to generate and store");
+        assert!(!lifecycle.should_rotate(scope, name).await.unwrap());
+
+        let new_version = lifecycle
+            .rotate(scope, name, vec![2u8; 32])
+            .await
+            .expect("Failed to rotate");
+        assert_eq!(new_version, 2);
+
+        let retrieved = lifecycle.retrieve(scope, name).await.expect("Failed to retrieve");
+        assert_eq!(retrieved.data, vec![2u8; 32]);
+        assert_eq!(retrieved.metadata.version, 2);
+        assert_eq!(retrieved.metadata.environment, "test");
+        assert_eq!(lifecycle.list(scope).await.unwrap(), vec![name]);
+
+        lifecycle.revoke(scope, name).await.expect("Failed to revoke");
+        assert!(matches!(
+            lifecycle.retrieve(scope, name).await,
+            Err(LifecycleError::Store(KeyStoreError::NotFound { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_expired_key_is_rejected_and_due_for_rotation() {
+        let store = Arc::new(MemoryKeyStore::new());
+        let mut material = KeyMaterial::new(
+            KeyScope::Session,
+            "old-session",
+            vec![3u8; 32],
+            std::time::Duration::from_secs(60),
+            "test",
+        );
+        material.metadata.expires_at = (chrono::Utc::now() - chrono::Duration::seconds(1)).to_rfc3339();
+        store.put(material).await.unwrap();
+
+        let lifecycle = KeyLifecycle::new(store);
+        assert!(matches!(
+            lifecycle.retrieve(KeyScope::Session, "old-session").await,
+            Err(LifecycleError::Expired { .. })
+        ));
+        assert!(lifecycle.should_rotate(KeyScope::Session, "old-session").await.unwrap());
+    }
 
     #[tokio::test]
     #[ignore] // Requires Vault dev server
+    #[cfg(feature = "vault")]
     async fn test_lifecycle_roundtrip() {
-        let lifecycle = VaultKeyLifecycle::from_env()
+        let lifecycle = KeyLifecycle::from_env()
             .expect("Failed to create lifecycle manager");
 
         let key_data = vec![42u8; 32];
@@ -277,8 +329,9 @@ mod tests {
 
     #[tokio::test]
     #[ignore] // Requires Vault dev server
+    #[cfg(feature = "vault")]
     async fn test_rotation() {
-        let lifecycle = VaultKeyLifecycle::from_env()
+        let lifecycle = KeyLifecycle::from_env()
             .expect("Failed to create lifecycle manager");
 
         let original_key = vec![1u8; 32];
@@ -310,6 +363,6 @@ mod tests {
         assert_eq!(retrieved.metadata.version, 2);
 
         // Cleanup
-        lifecycle.destroy(scope, name, vec![1, 2]).await.ok();
+        lifecycle.destroy(scope, name).await.ok();
     }
 }
@@ -0,0 +1,786 @@
+//! Noise XX handshake
+//!
+//! Implements `Noise_XX_25519_ChaChaPoly_SHA512` from the Noise Protocol
+//! Framework (revision 34) on top of the crate's X25519, HKDF-SHA512 and
+//! ChaCha20-Poly1305 primitives. Both peers authenticate with static X25519
+//! device identity keys, which are transmitted encrypted, so neither identity
+//! is visible to a passive observer.
+//!
+//! # Message Pattern
+//! ```text
+//! -> e
+//! <- e, ee, s, es
+//! -> s, se
+//! ```
+//!
+//! Each message may carry a payload (encrypted from the second message on).
+//! After the third message, `into_transport()` splits the handshake into one
+//! key per direction for the transport phase.
+//!
+//! # Key Exchange Negotiation
+//! The first message starts with the initiator's offer (a bitmask of
+//! `KemMode`s) and the second with the responder's selection. With the
+//! `pq-hybrid` feature the hybrid mode adds an ML-KEM-768 exchange in the
+//! style of Noise HFS:
+//!
+//! ```text
+//! -> e, e1
+//! <- e, ee, ekem1, s, es
+//! -> s, se
+//! ```
+//!
+//! `e1` is an ephemeral ML-KEM encapsulation key, and `ekem1` the encrypted
+//! ciphertext encapsulated to it. The ML-KEM secret is mixed into the
+//! chaining key after the X25519 `ee` result, so the transport keys depend
+//! on both. Offer and selection are mixed into the handshake hash: an
+//! attacker who strips the hybrid offer or rewrites the selection changes
+//! the transcript, and the second message fails to authenticate.
+//!
+//! # Example
+//! ```
+//! use honeylink_crypto::key_agreement::KeyAgreement;
+//! use honeylink_crypto::noise::{HandshakeRole, NoiseHandshake};
+//!
+//! let (alice_static, _) = KeyAgreement::generate_keypair();
+//! let (bob_static, bob_public) = KeyAgreement::generate_keypair();
+//!
+//! let mut alice = NoiseHandshake::new(HandshakeRole::Initiator, alice_static, b"honeylink");
+//! let mut bob = NoiseHandshake::new(HandshakeRole::Responder, bob_static, b"honeylink");
+//!
+//! let msg1 = alice.write_message(b"").unwrap();
+//! bob.read_message(&msg1).unwrap();
+//! let msg2 = bob.write_message(b"DEV-BOB").unwrap();
+//! assert_eq!(alice.read_message(&msg2).unwrap(), b"DEV-BOB");
+//! let msg3 = alice.write_message(b"DEV-ALICE").unwrap();
+//! assert_eq!(bob.read_message(&msg3).unwrap(), b"DEV-ALICE");
+//!
+//! let alice_keys = alice.into_transport().unwrap();
+//! let bob_keys = bob.into_transport().unwrap();
+//! assert_eq!(alice_keys.remote_static.as_bytes(), bob_public.as_bytes());
+//! assert_eq!(alice_keys.send_key.as_slice(), bob_keys.recv_key.as_slice());
+//! ```
+
+use crate::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE, TAG_SIZE};
+#[cfg(feature = "pq-hybrid")]
+use crate::hybrid_kem::{self, MlKemSecretKey};
+use crate::key_agreement::{KeyAgreement, PublicKey, SecretKey};
+use crate::key_derivation::KeyDerivation;
+use honeylink_core::Result;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+/// Noise protocol name (also the initial handshake hash)
+pub const PROTOCOL_NAME: &[u8] = b"Noise_XX_25519_ChaChaPoly_SHA512";
+
+/// Maximum Noise message size
+pub const MAX_MESSAGE_SIZE: usize = 65535;
+
+/// SHA-512 output length
+const HASH_LEN: usize = 64;
+
+/// X25519 public key length
+const DH_LEN: usize = 32;
+
+/// ML-KEM-768 encapsulation key length (`e1`, present whenever hybrid is offered)
+const KEM_PUBLIC_KEY_LEN: usize = 1184;
+
+/// Offer and selection bit for `KemMode::Classic`
+const KEM_CLASSIC: u8 = 0x01;
+
+/// Offer and selection bit for `KemMode::Hybrid`
+const KEM_HYBRID: u8 = 0x02;
+
+/// New chaining key and cipher key produced by `SymmetricState::hkdf2`
+type ChainedKeys = (Zeroizing<[u8; HASH_LEN]>, Zeroizing<[u8; 32]>);
+
+/// Side of the handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandshakeRole {
+    /// Peer that sends the first message
+    Initiator,
+    /// Peer that answers the first message
+    Responder,
+}
+
+/// Key exchange used by a handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KemMode {
+    /// X25519 only
+    Classic,
+    /// X25519 combined with ML-KEM-768 (`pq-hybrid` feature)
+    Hybrid,
+}
+
+impl KemMode {
+    fn bit(self) -> u8 {
+        match self {
+            Self::Classic => KEM_CLASSIC,
+            Self::Hybrid => KEM_HYBRID,
+        }
+    }
+
+    fn from_bit(bit: u8) -> Option<Self> {
+        match bit {
+            KEM_CLASSIC => Some(Self::Classic),
+            KEM_HYBRID => Some(Self::Hybrid),
+            _ => None,
+        }
+    }
+}
+
+/// Key exchange modes a peer offers (initiator) or accepts (responder)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KemPolicy {
+    /// X25519 only
+    Classic,
+    /// Hybrid when the peer supports it, classic otherwise
+    #[cfg(feature = "pq-hybrid")]
+    PreferHybrid,
+    /// Hybrid only; peers without `pq-hybrid` are rejected
+    #[cfg(feature = "pq-hybrid")]
+    RequireHybrid,
+}
+
+impl Default for KemPolicy {
+    /// `PreferHybrid` with the `pq-hybrid` feature, `Classic` otherwise
+    fn default() -> Self {
+        #[cfg(feature = "pq-hybrid")]
+        {
+            Self::PreferHybrid
+        }
+        #[cfg(not(feature = "pq-hybrid"))]
+        {
+            Self::Classic
+        }
+    }
+}
+
+impl KemPolicy {
+    /// Bitmask of accepted modes
+    fn accepted(self) -> u8 {
+        match self {
+            Self::Classic => KEM_CLASSIC,
+            #[cfg(feature = "pq-hybrid")]
+            Self::PreferHybrid => KEM_CLASSIC | KEM_HYBRID,
+            #[cfg(feature = "pq-hybrid")]
+            Self::RequireHybrid => KEM_HYBRID,
+        }
+    }
+
+    /// Strongest mode in both the peer's offer and this policy
+    fn select(self, offer: u8) -> Result<KemMode> {
+        let common = self.accepted() & offer;
+        if common & KEM_HYBRID != 0 {
+            Ok(KemMode::Hybrid)
+        } else if common & KEM_CLASSIC != 0 {
+            Ok(KemMode::Classic)
+        } else {
+            Err(handshake_error("No common key exchange mode"))
+        }
+    }
+}
+
+/// Transport keys produced by a completed handshake
+pub struct TransportKeys {
+    /// Key for messages sent by this peer
+    pub send_key: Zeroizing<[u8; 32]>,
+    /// Key for messages received from the peer
+    pub recv_key: Zeroizing<[u8; 32]>,
+    /// Handshake hash (unique per session, usable for channel binding)
+    pub handshake_hash: [u8; HASH_LEN],
+    /// Authenticated static key of the peer
+    pub remote_static: PublicKey,
+    /// Negotiated key exchange
+    pub kem_mode: KemMode,
+}
+
+impl std::fmt::Debug for TransportKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransportKeys")
+            .field("send_key", &"[REDACTED]")
+            .field("recv_key", &"[REDACTED]")
+            .field("remote_static", &self.remote_static.as_bytes())
+            .field("kem_mode", &self.kem_mode)
+            .finish()
+    }
+}
+
+/// Handshake cipher state (key and message counter)
+struct CipherState {
+    cipher: Option<ChaCha20Poly1305Cipher>,
+    n: u64,
+}
+
+impl CipherState {
+    fn nonce(&self) -> [u8; NONCE_SIZE] {
+        // Noise: 32 bits of zeros followed by the little-endian counter
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce[4..].copy_from_slice(&self.n.to_le_bytes());
+        nonce
+    }
+
+    fn encrypt(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => {
+                let ciphertext = cipher.encrypt_with_nonce(&self.nonce(), plaintext, ad)?;
+                self.n += 1;
+                Ok(ciphertext)
+            }
+            None => Ok(plaintext.to_vec()),
+        }
+    }
+
+    fn decrypt(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => {
+                let plaintext = cipher.decrypt(&self.nonce(), ciphertext, ad)?;
+                self.n += 1;
+                Ok(plaintext)
+            }
+            None => Ok(ciphertext.to_vec()),
+        }
+    }
+}
+
+/// Chaining key, handshake hash and current cipher
+struct SymmetricState {
+    ck: Zeroizing<[u8; HASH_LEN]>,
+    h: [u8; HASH_LEN],
+    cipher: CipherState,
+}
+
+impl SymmetricState {
+    fn new(prologue: &[u8]) -> Self {
+        // Protocol name is shorter than HASH_LEN, so it is zero-padded
+        let mut h = [0u8; HASH_LEN];
+        h[..PROTOCOL_NAME.len()].copy_from_slice(PROTOCOL_NAME);
+
+        let mut state = Self {
+            ck: Zeroizing::new(h),
+            h,
+            cipher: CipherState { cipher: None, n: 0 },
+        };
+        state.mix_hash(prologue);
+        state
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        let mut hasher = Sha512::new();
+        hasher.update(self.h);
+        hasher.update(data);
+        self.h.copy_from_slice(&hasher.finalize());
+    }
+
+    /// Noise HKDF with two outputs (HKDF-SHA512 with the chaining key as salt)
+    fn hkdf2(&self, ikm: &[u8]) -> Result<ChainedKeys> {
+        let output = KeyDerivation::derive(ikm, Some(self.ck.as_slice()), b"", 2 * HASH_LEN)?;
+
+        let mut first = Zeroizing::new([0u8; HASH_LEN]);
+        first.copy_from_slice(&output[..HASH_LEN]);
+        // Cipher keys are truncated to 32 bytes (HASH_LEN is 64)
+        let mut second = Zeroizing::new([0u8; 32]);
+        second.copy_from_slice(&output[HASH_LEN..HASH_LEN + 32]);
+        Ok((first, second))
+    }
+
+    fn mix_key(&mut self, ikm: &[u8]) -> Result<()> {
+        let (ck, key) = self.hkdf2(ikm)?;
+        self.ck = ck;
+        self.cipher = CipherState {
+            cipher: Some(ChaCha20Poly1305Cipher::new(key.as_slice())?),
+            n: 0,
+        };
+        Ok(())
+    }
+
+    fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = self.cipher.encrypt(&self.h, plaintext)?;
+        self.mix_hash(&ciphertext);
+        Ok(ciphertext)
+    }
+
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let plaintext = self.cipher.decrypt(&self.h, ciphertext)?;
+        self.mix_hash(ciphertext);
+        Ok(plaintext)
+    }
+
+    fn has_key(&self) -> bool {
+        self.cipher.cipher.is_some()
+    }
+}
+
+/// Noise XX handshake state machine
+pub struct NoiseHandshake {
+    role: HandshakeRole,
+    symmetric: SymmetricState,
+    s: SecretKey,
+    e: Option<SecretKey>,
+    rs: Option<PublicKey>,
+    re: Option<PublicKey>,
+    /// Local ephemeral ML-KEM key (initiator, hybrid offered)
+    #[cfg(feature = "pq-hybrid")]
+    e1: Option<MlKemSecretKey>,
+    /// Remote ephemeral ML-KEM key (responder, hybrid offered)
+    #[cfg(feature = "pq-hybrid")]
+    re1: Option<Vec<u8>>,
+    kem_policy: KemPolicy,
+    /// Modes offered in the first message
+    offer: u8,
+    kem_mode: Option<KemMode>,
+    /// Number of messages processed (the handshake is complete after 3)
+    step: usize,
+}
+
+impl NoiseHandshake {
+    /// Starts a handshake with the local static (device identity) key
+    ///
+    /// The prologue is mixed into the handshake hash; both peers must use
+    /// the same prologue or the handshake fails.
+    pub fn new(role: HandshakeRole, local_static: SecretKey, prologue: &[u8]) -> Self {
+        Self {
+            role,
+            symmetric: SymmetricState::new(prologue),
+            s: local_static,
+            e: None,
+            rs: None,
+            re: None,
+            #[cfg(feature = "pq-hybrid")]
+            e1: None,
+            #[cfg(feature = "pq-hybrid")]
+            re1: None,
+            kem_policy: KemPolicy::default(),
+            offer: 0,
+            kem_mode: None,
+            step: 0,
+        }
+    }
+
+    /// Sets the key exchange modes to offer or accept
+    ///
+    /// Must be called before the first message.
+    pub fn with_kem_policy(mut self, policy: KemPolicy) -> Self {
+        self.kem_policy = policy;
+        self
+    }
+
+    /// Role of this peer
+    pub fn role(&self) -> HandshakeRole {
+        self.role
+    }
+
+    /// Returns true once all three messages have been processed
+    pub fn is_finished(&self) -> bool {
+        self.step == 3
+    }
+
+    /// Returns true if the next step is writing a message
+    pub fn is_my_turn(&self) -> bool {
+        match self.role {
+            HandshakeRole::Initiator => self.step == 0 || self.step == 2,
+            HandshakeRole::Responder => self.step == 1,
+        }
+    }
+
+    /// Negotiated key exchange (known once the responder has selected it)
+    pub fn kem_mode(&self) -> Option<KemMode> {
+        self.kem_mode
+    }
+
+    /// Static key of the peer (known after the second message)
+    pub fn remote_static(&self) -> Option<PublicKey> {
+        self.rs
+    }
+
+    /// Current handshake hash
+    pub fn handshake_hash(&self) -> [u8; HASH_LEN] {
+        self.symmetric.h
+    }
+
+    /// Writes the next handshake message carrying `payload`
+    ///
+    /// # Errors
+    /// Returns an error if it is the peer's turn or the message would exceed
+    /// `MAX_MESSAGE_SIZE`.
+    pub fn write_message(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+        if self.is_finished() || !self.is_my_turn() {
+            return Err(handshake_error("Unexpected write (not our turn)"));
+        }
+
+        let mut message = Vec::new();
+        match self.step {
+            // -> e, [e1]
+            0 => {
+                self.offer = self.kem_policy.accepted();
+                message.push(self.offer);
+                self.symmetric.mix_hash(&[self.offer]);
+                message.extend_from_slice(self.write_ephemeral().as_bytes());
+
+                #[cfg(feature = "pq-hybrid")]
+                if self.offer & KEM_HYBRID != 0 {
+                    let (e1, e1_public) = hybrid_kem::mlkem_generate();
+                    self.symmetric.mix_hash(&e1_public);
+                    message.extend(e1_public);
+                    self.e1 = Some(e1);
+                }
+            }
+            // <- e, ee, [ekem1], s, es
+            1 => {
+                let mode = self
+                    .kem_mode
+                    .ok_or_else(|| handshake_error("Key exchange mode not negotiated"))?;
+                message.push(mode.bit());
+                self.symmetric.mix_hash(&[mode.bit()]);
+
+                message.extend_from_slice(self.write_ephemeral().as_bytes());
+                mix_dh(
+                    &mut self.symmetric,
+                    required(&self.e, "Local ephemeral")?,
+                    required(&self.re, "Remote ephemeral")?,
+                )?;
+
+                #[cfg(feature = "pq-hybrid")]
+                if mode == KemMode::Hybrid {
+                    let (ciphertext, shared) =
+                        hybrid_kem::mlkem_encapsulate(required(&self.re1, "Remote KEM")?)?;
+                    message.extend(self.symmetric.encrypt_and_hash(&ciphertext)?);
+                    self.symmetric.mix_key(shared.as_slice())?;
+                }
+
+                let s_pub = self.s.public_key();
+                message.extend(self.symmetric.encrypt_and_hash(s_pub.as_bytes())?);
+                mix_dh(
+                    &mut self.symmetric,
+                    &self.s,
+                    required(&self.re, "Remote ephemeral")?,
+                )?;
+            }
+            // -> s, se
+            _ => {
+                let s_pub = self.s.public_key();
+                message.extend(self.symmetric.encrypt_and_hash(s_pub.as_bytes())?);
+                mix_dh(
+                    &mut self.symmetric,
+                    &self.s,
+                    required(&self.re, "Remote ephemeral")?,
+                )?;
+            }
+        }
+
+        message.extend(self.symmetric.encrypt_and_hash(payload)?);
+        if message.len() > MAX_MESSAGE_SIZE {
+            return Err(handshake_error("Handshake message too large"));
+        }
+
+        self.step += 1;
+        Ok(message)
+    }
+
+    /// Reads the next handshake message from the peer and returns its payload
+    ///
+    /// # Errors
+    /// Returns an error if it is our turn, the message is malformed, or
+    /// authentication fails (wrong prologue, tampering, invalid keys).
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>> {
+        if self.is_finished() || self.is_my_turn() {
+            return Err(handshake_error("Unexpected read (not peer's turn)"));
+        }
+        if message.len() > MAX_MESSAGE_SIZE {
+            return Err(handshake_error("Handshake message too large"));
+        }
+
+        let encrypted_static_len = DH_LEN + TAG_SIZE;
+        let payload = match self.step {
+            // -> e, [e1]
+            0 => {
+                let (&offer, rest) = split_byte(message)?;
+                self.offer = offer;
+                self.symmetric.mix_hash(&[offer]);
+                let mut rest = self.read_ephemeral(rest)?;
+
+                // Mixed even when unsupported, so both transcripts match
+                if offer & KEM_HYBRID != 0 {
+                    if rest.len() < KEM_PUBLIC_KEY_LEN {
+                        return Err(handshake_error("Handshake message truncated"));
+                    }
+                    let (re1, remainder) = rest.split_at(KEM_PUBLIC_KEY_LEN);
+                    self.symmetric.mix_hash(re1);
+                    #[cfg(feature = "pq-hybrid")]
+                    {
+                        self.re1 = Some(re1.to_vec());
+                    }
+                    rest = remainder;
+                }
+
+                self.kem_mode = Some(self.kem_policy.select(offer)?);
+                self.symmetric.decrypt_and_hash(rest)?
+            }
+            // <- e, ee, [ekem1], s, es
+            1 => {
+                let (&selected, rest) = split_byte(message)?;
+                let mode = KemMode::from_bit(selected)
+                    .filter(|mode| self.offer & mode.bit() != 0)
+                    .ok_or_else(|| handshake_error("Peer selected a mode that was not offered"))?;
+                self.symmetric.mix_hash(&[selected]);
+                self.kem_mode = Some(mode);
+
+                let rest = self.read_ephemeral(rest)?;
+                mix_dh(
+                    &mut self.symmetric,
+                    required(&self.e, "Local ephemeral")?,
+                    required(&self.re, "Remote ephemeral")?,
+                )?;
+
+                #[cfg(feature = "pq-hybrid")]
+                let rest = if mode == KemMode::Hybrid {
+                    let encrypted_len = hybrid_kem::MLKEM_CIPHERTEXT_SIZE + TAG_SIZE;
+                    if rest.len() < encrypted_len {
+                        return Err(handshake_error("Handshake message truncated"));
+                    }
+                    let (ciphertext, rest) = rest.split_at(encrypted_len);
+                    let ciphertext = self.symmetric.decrypt_and_hash(ciphertext)?;
+                    let shared = hybrid_kem::mlkem_decapsulate(
+                        required(&self.e1, "Local KEM")?,
+                        &ciphertext,
+                    )?;
+                    self.symmetric.mix_key(shared.as_slice())?;
+                    rest
+                } else {
+                    rest
+                };
+
+                if rest.len() < encrypted_static_len {
+                    return Err(handshake_error("Handshake message truncated"));
+                }
+                let (encrypted_static, rest) = rest.split_at(encrypted_static_len);
+                let rs = self.read_static(encrypted_static)?;
+                mix_dh(
+                    &mut self.symmetric,
+                    required(&self.e, "Local ephemeral")?,
+                    &rs,
+                )?;
+                self.symmetric.decrypt_and_hash(rest)?
+            }
+            // -> s, se
+            _ => {
+                if message.len() < encrypted_static_len {
+                    return Err(handshake_error("Handshake message truncated"));
+                }
+                let (encrypted_static, rest) = message.split_at(encrypted_static_len);
+                let rs = self.read_static(encrypted_static)?;
+                mix_dh(
+                    &mut self.symmetric,
+                    required(&self.e, "Local ephemeral")?,
+                    &rs,
+                )?;
+                self.symmetric.decrypt_and_hash(rest)?
+            }
+        };
+
+        self.step += 1;
+        Ok(payload)
+    }
+
+    /// Splits the completed handshake into transport keys
+    ///
+    /// The ephemeral keys are zeroized when the handshake is dropped.
+    pub fn into_transport(self) -> Result<TransportKeys> {
+        if !self.is_finished() || !self.symmetric.has_key() {
+            return Err(handshake_error("Handshake not finished"));
+        }
+        let remote_static = self
+            .rs
+            .ok_or_else(|| handshake_error("Remote static key missing"))?;
+        let kem_mode = self
+            .kem_mode
+            .ok_or_else(|| handshake_error("Key exchange mode not negotiated"))?;
+
+        let output =
+            KeyDerivation::derive(b"", Some(self.symmetric.ck.as_slice()), b"", 2 * HASH_LEN)?;
+        let mut initiator_key = Zeroizing::new([0u8; 32]);
+        initiator_key.copy_from_slice(&output[..32]);
+        let mut responder_key = Zeroizing::new([0u8; 32]);
+        responder_key.copy_from_slice(&output[HASH_LEN..HASH_LEN + 32]);
+
+        let (send_key, recv_key) = match self.role {
+            HandshakeRole::Initiator => (initiator_key, responder_key),
+            HandshakeRole::Responder => (responder_key, initiator_key),
+        };
+
+        Ok(TransportKeys {
+            send_key,
+            recv_key,
+            handshake_hash: self.symmetric.h,
+            remote_static,
+            kem_mode,
+        })
+    }
+
+    fn write_ephemeral(&mut self) -> PublicKey {
+        let (secret, public) = KeyAgreement::generate_keypair();
+        self.e = Some(secret);
+        self.symmetric.mix_hash(public.as_bytes());
+        public
+    }
+
+    fn read_ephemeral<'a>(&mut self, message: &'a [u8]) -> Result<&'a [u8]> {
+        if message.len() < DH_LEN {
+            return Err(handshake_error("Handshake message truncated"));
+        }
+        let (re, rest) = message.split_at(DH_LEN);
+        let re = KeyAgreement::deserialize_public_key(re)?;
+        self.symmetric.mix_hash(re.as_bytes());
+        self.re = Some(re);
+        Ok(rest)
+    }
+
+    fn read_static(&mut self, encrypted_static: &[u8]) -> Result<PublicKey> {
+        let rs = self.symmetric.decrypt_and_hash(encrypted_static)?;
+        let rs = KeyAgreement::deserialize_public_key(&rs)?;
+        self.rs = Some(rs);
+        Ok(rs)
+    }
+}
+
+/// Mixes the X25519 result of `secret` and `public` into the chaining key
+fn mix_dh(symmetric: &mut SymmetricState, secret: &SecretKey, public: &PublicKey) -> Result<()> {
+    let shared = KeyAgreement::derive_shared_secret(secret, public)?;
+    symmetric.mix_key(shared.as_bytes())
+}
+
+/// Splits off the leading negotiation byte
+fn split_byte(message: &[u8]) -> Result<(&u8, &[u8])> {
+    message
+        .split_first()
+        .ok_or_else(|| handshake_error("Handshake message truncated"))
+}
+
+fn required<'a, T>(value: &'a Option<T>, what: &str) -> Result<&'a T> {
+    value
+        .as_ref()
+        .ok_or_else(|| handshake_error(&format!("{} key missing", what)))
+}
+
+fn handshake_error(message: &str) -> honeylink_core::Error {
+    honeylink_core::Error::Crypto(format!("Noise handshake failed: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handshake(
+        prologue_a: &[u8],
+        prologue_b: &[u8],
+    ) -> Result<(TransportKeys, TransportKeys, PublicKey, PublicKey)> {
+        let (a_static, a_public) = KeyAgreement::generate_keypair();
+        let (b_static, b_public) = KeyAgreement::generate_keypair();
+        let mut a = NoiseHandshake::new(HandshakeRole::Initiator, a_static, prologue_a);
+        let mut b = NoiseHandshake::new(HandshakeRole::Responder, b_static, prologue_b);
+
+        b.read_message(&a.write_message(b"")?)?;
+        a.read_message(&b.write_message(b"")?)?;
+        b.read_message(&a.write_message(b"")?)?;
+
+        Ok((a.into_transport()?, b.into_transport()?, a_public, b_public))
+    }
+
+    fn negotiate(initiator: KemPolicy, responder: KemPolicy) -> Result<(KemMode, KemMode)> {
+        let (a_static, _) = KeyAgreement::generate_keypair();
+        let (b_static, _) = KeyAgreement::generate_keypair();
+        let mut a =
+            NoiseHandshake::new(HandshakeRole::Initiator, a_static, b"").with_kem_policy(initiator);
+        let mut b =
+            NoiseHandshake::new(HandshakeRole::Responder, b_static, b"").with_kem_policy(responder);
+
+        b.read_message(&a.write_message(b"")?)?;
+        a.read_message(&b.write_message(b"")?)?;
+        b.read_message(&a.write_message(b"")?)?;
+
+        let (a, b) = (a.into_transport()?, b.into_transport()?);
+        assert_eq!(a.send_key.as_slice(), b.recv_key.as_slice());
+        Ok((a.kem_mode, b.kem_mode))
+    }
+
+    #[test]
+    fn test_handshake_agrees_on_keys_and_identities() {
+        let (a, b, a_public, b_public) = handshake(b"p", b"p").unwrap();
+
+        assert_eq!(a.send_key.as_slice(), b.recv_key.as_slice());
+        assert_eq!(a.recv_key.as_slice(), b.send_key.as_slice());
+        assert_ne!(a.send_key.as_slice(), a.recv_key.as_slice());
+        assert_eq!(a.handshake_hash, b.handshake_hash);
+        assert_eq!(a.remote_static.as_bytes(), b_public.as_bytes());
+        assert_eq!(b.remote_static.as_bytes(), a_public.as_bytes());
+    }
+
+    #[test]
+    fn test_prologue_mismatch_fails() {
+        assert!(handshake(b"honeylink-v1", b"honeylink-v2").is_err());
+    }
+
+    #[test]
+    fn test_tampered_message_and_turn_order() {
+        let (a_static, _) = KeyAgreement::generate_keypair();
+        let (b_static, _) = KeyAgreement::generate_keypair();
+        let mut a = NoiseHandshake::new(HandshakeRole::Initiator, a_static, b"");
+        let mut b = NoiseHandshake::new(HandshakeRole::Responder, b_static, b"");
+
+        assert!(b.write_message(b"").is_err());
+        b.read_message(&a.write_message(b"").unwrap()).unwrap();
+
+        let mut msg2 = b.write_message(b"payload").unwrap();
+        let last = msg2.len() - 1;
+        msg2[last] ^= 0x01;
+        assert!(a.read_message(&msg2).is_err());
+        assert!(a.into_transport().is_err());
+    }
+
+    #[test]
+    fn test_classic_negotiation() {
+        assert_eq!(
+            negotiate(KemPolicy::Classic, KemPolicy::default()).unwrap(),
+            (KemMode::Classic, KemMode::Classic)
+        );
+        assert_eq!(
+            negotiate(KemPolicy::default(), KemPolicy::Classic).unwrap(),
+            (KemMode::Classic, KemMode::Classic)
+        );
+    }
+
+    #[cfg(feature = "pq-hybrid")]
+    #[test]
+    fn test_hybrid_negotiation() {
+        assert_eq!(KEM_PUBLIC_KEY_LEN, hybrid_kem::MLKEM_PUBLIC_KEY_SIZE);
+        assert_eq!(
+            negotiate(KemPolicy::PreferHybrid, KemPolicy::PreferHybrid).unwrap(),
+            (KemMode::Hybrid, KemMode::Hybrid)
+        );
+        assert_eq!(
+            negotiate(KemPolicy::RequireHybrid, KemPolicy::PreferHybrid).unwrap(),
+            (KemMode::Hybrid, KemMode::Hybrid)
+        );
+        assert!(negotiate(KemPolicy::Classic, KemPolicy::RequireHybrid).is_err());
+        assert!(negotiate(KemPolicy::RequireHybrid, KemPolicy::Classic).is_err());
+    }
+
+    #[cfg(feature = "pq-hybrid")]
+    #[test]
+    fn test_stripped_hybrid_offer_detected() {
+        let (a_static, _) = KeyAgreement::generate_keypair();
+        let (b_static, _) = KeyAgreement::generate_keypair();
+        let mut a = NoiseHandshake::new(HandshakeRole::Initiator, a_static, b"");
+        let mut b = NoiseHandshake::new(HandshakeRole::Responder, b_static, b"");
+
+        // Attacker rewrites the offer to classic and drops e1
+        let msg1 = a.write_message(b"").unwrap();
+        let mut downgraded = vec![KEM_CLASSIC];
+        downgraded.extend_from_slice(&msg1[1..1 + DH_LEN]);
+        downgraded.extend_from_slice(&msg1[1 + DH_LEN + KEM_PUBLIC_KEY_LEN..]);
+        b.read_message(&downgraded).unwrap();
+        assert_eq!(b.kem_mode(), Some(KemMode::Classic));
+
+        // The initiator's transcript still contains the hybrid offer
+        assert!(a.read_message(&b.write_message(b"").unwrap()).is_err());
+    }
+}
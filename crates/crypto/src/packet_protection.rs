@@ -0,0 +1,343 @@
+//! Sequence-number packet protection
+//!
+//! `ChaCha20Poly1305Cipher::encrypt` draws a random nonce per call and
+//! returns it separately, which costs 12 bytes per packet and provides no
+//! replay protection. For datagram and physical-adapter paths, packets are
+//! instead keyed by a 64-bit sequence number:
+//!
+//! - **Nonce**: Static 96-bit IV XOR the left-padded big-endian sequence
+//!   number (TLS 1.3, RFC 8446 Section 5.3), so only the sequence number
+//!   travels on the wire and nonces never repeat under one key
+//! - **AAD**: Caller-supplied header bytes, authenticated but not encrypted
+//! - **Replay protection**: `PacketOpener` tracks a sliding window of
+//!   `REPLAY_WINDOW_SIZE` sequence numbers and rejects duplicates and packets
+//!   older than the window
+//! - **In-place**: Payloads are encrypted in their own buffer; only the
+//!   16-byte tag is appended
+//!
+//! # Example
+//! ```
+//! use honeylink_crypto::packet_protection::{PacketOpener, PacketProtection, PacketSealer};
+//!
+//! let secret = [3u8; 32];
+//! let mut sealer = PacketSealer::new(PacketProtection::derive(&secret, "a-to-b").unwrap());
+//! let mut opener = PacketOpener::new(PacketProtection::derive(&secret, "a-to-b").unwrap());
+//!
+//! let header = b"stream-7";
+//! let mut payload = b"sensor reading".to_vec();
+//! let seq = sealer.seal(header, &mut payload).unwrap();
+//! let mut replayed = payload.clone();
+//!
+//! opener.open(seq, header, &mut payload).unwrap();
+//! assert_eq!(payload, b"sensor reading");
+//!
+//! // The same packet is rejected the second time
+//! assert!(opener.open(seq, header, &mut replayed).is_err());
+//! ```
+
+use crate::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use crate::key_derivation::{DeriveContext, KeyDerivation};
+use crate::ratchet::INTEGRITY_LIMIT;
+use honeylink_core::Result;
+
+/// Number of sequence numbers tracked behind the highest one received
+pub const REPLAY_WINDOW_SIZE: u64 = 64;
+
+/// Key and static IV for sequence-number nonces
+pub struct PacketProtection {
+    cipher: ChaCha20Poly1305Cipher,
+    iv: [u8; NONCE_SIZE],
+}
+
+impl PacketProtection {
+    /// Creates packet protection from an explicit key and IV
+    ///
+    /// # Errors
+    /// Returns an error if the key is not exactly 32 bytes.
+    pub fn new(key: &[u8], iv: [u8; NONCE_SIZE]) -> Result<Self> {
+        Ok(Self {
+            cipher: ChaCha20Poly1305Cipher::new(key)?,
+            iv,
+        })
+    }
+
+    /// Derives key and IV from a traffic secret
+    ///
+    /// Each direction must use its own `label` (e.g. "a-to-b" / "b-to-a"),
+    /// otherwise both peers would produce the same nonces.
+    pub fn derive(secret: &[u8], label: &str) -> Result<Self> {
+        let context = DeriveContext::custom(format!("PacketProtection|{}", label), Vec::new());
+        let material = KeyDerivation::derive_with_context(secret, &context, 32 + NONCE_SIZE)?;
+
+        let mut iv = [0u8; NONCE_SIZE];
+        iv.copy_from_slice(&material[32..]);
+        Self::new(&material[..32], iv)
+    }
+
+    /// Nonce for `seq`: IV XOR the left-padded big-endian sequence number
+    pub fn nonce(&self, seq: u64) -> [u8; NONCE_SIZE] {
+        let mut nonce = self.iv;
+        for (byte, seq_byte) in nonce[NONCE_SIZE - 8..].iter_mut().zip(seq.to_be_bytes()) {
+            *byte ^= seq_byte;
+        }
+        nonce
+    }
+
+    /// Encrypts `buffer` in place and appends the authentication tag
+    ///
+    /// # Security
+    /// A sequence number must never be sealed twice under the same key;
+    /// use `PacketSealer` to allocate them.
+    pub fn seal_in_place(&self, seq: u64, header: &[u8], buffer: &mut Vec<u8>) -> Result<()> {
+        self.cipher
+            .encrypt_in_place_with_nonce(&self.nonce(seq), buffer, header)
+    }
+
+    /// Authenticates and decrypts `buffer` in place, removing the tag
+    ///
+    /// Does not check for replays; use `PacketOpener` for that.
+    pub fn open_in_place(&self, seq: u64, header: &[u8], buffer: &mut Vec<u8>) -> Result<()> {
+        self.cipher
+            .decrypt_in_place(&self.nonce(seq), buffer, header)
+    }
+}
+
+impl std::fmt::Debug for PacketProtection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PacketProtection")
+            .field("key", &"[REDACTED]")
+            .field("iv", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Sending side: allocates sequence numbers and seals packets
+#[derive(Debug)]
+pub struct PacketSealer {
+    protection: PacketProtection,
+    next_seq: u64,
+}
+
+impl PacketSealer {
+    /// Creates a sealer starting at sequence number 0
+    pub fn new(protection: PacketProtection) -> Self {
+        Self {
+            protection,
+            next_seq: 0,
+        }
+    }
+
+    /// Sequence number the next packet will use
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Seals `buffer` in place under the next sequence number
+    ///
+    /// # Returns
+    /// The sequence number, which must be sent alongside the packet
+    ///
+    /// # Errors
+    /// Returns an error once the sequence space is exhausted (rekey first).
+    pub fn seal(&mut self, header: &[u8], buffer: &mut Vec<u8>) -> Result<u64> {
+        let seq = self.next_seq;
+        let next_seq = seq.checked_add(1).ok_or_else(|| {
+            honeylink_core::Error::Crypto("Packet sequence numbers exhausted".to_string())
+        })?;
+
+        self.protection.seal_in_place(seq, header, buffer)?;
+        self.next_seq = next_seq;
+        Ok(seq)
+    }
+}
+
+/// Receiving side: opens packets and rejects replays
+#[derive(Debug)]
+pub struct PacketOpener {
+    protection: PacketProtection,
+    window: ReplayWindow,
+    failures: u64,
+}
+
+impl PacketOpener {
+    /// Creates an opener with an empty replay window
+    pub fn new(protection: PacketProtection) -> Self {
+        Self {
+            protection,
+            window: ReplayWindow::new(),
+            failures: 0,
+        }
+    }
+
+    /// Replay window state
+    pub fn window(&self) -> &ReplayWindow {
+        &self.window
+    }
+
+    /// Authenticates and decrypts `buffer` in place
+    ///
+    /// The sequence number is recorded only after successful authentication,
+    /// so forged packets cannot advance the window.
+    ///
+    /// # Errors
+    /// Returns an error for replayed or too old sequence numbers, failed
+    /// authentication, or once `INTEGRITY_LIMIT` failures were seen.
+    pub fn open(&mut self, seq: u64, header: &[u8], buffer: &mut Vec<u8>) -> Result<()> {
+        if self.failures >= INTEGRITY_LIMIT {
+            return Err(honeylink_core::Error::Crypto(
+                "AEAD integrity limit reached; rekey required".to_string(),
+            ));
+        }
+        if !self.window.check(seq) {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Replayed or stale packet (sequence number {})",
+                seq
+            )));
+        }
+
+        if let Err(e) = self.protection.open_in_place(seq, header, buffer) {
+            self.failures += 1;
+            return Err(e);
+        }
+
+        self.window.mark(seq);
+        Ok(())
+    }
+}
+
+/// Sliding window of received sequence numbers
+#[derive(Debug, Clone, Default)]
+pub struct ReplayWindow {
+    /// Highest sequence number accepted so far
+    highest: Option<u64>,
+    /// Bit i set = sequence number `highest - i` was received
+    bitmap: u64,
+}
+
+impl ReplayWindow {
+    /// Creates an empty window
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Highest sequence number accepted so far
+    pub fn highest(&self) -> Option<u64> {
+        self.highest
+    }
+
+    /// Returns true if `seq` has not been seen and is inside the window
+    pub fn check(&self, seq: u64) -> bool {
+        match self.highest {
+            None => true,
+            Some(highest) if seq > highest => true,
+            Some(highest) => {
+                let offset = highest - seq;
+                offset < REPLAY_WINDOW_SIZE && self.bitmap & (1 << offset) == 0
+            }
+        }
+    }
+
+    /// Records `seq` as received (call only after authentication)
+    pub fn mark(&mut self, seq: u64) {
+        match self.highest {
+            Some(highest) if seq <= highest => {
+                let offset = highest - seq;
+                if offset < REPLAY_WINDOW_SIZE {
+                    self.bitmap |= 1 << offset;
+                }
+            }
+            Some(highest) => {
+                let shift = seq - highest;
+                self.bitmap = if shift >= REPLAY_WINDOW_SIZE {
+                    0
+                } else {
+                    self.bitmap << shift
+                };
+                self.bitmap |= 1;
+                self.highest = Some(seq);
+            }
+            None => {
+                self.bitmap = 1;
+                self.highest = Some(seq);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair() -> (PacketSealer, PacketOpener) {
+        let secret = [0x11u8; 32];
+        (
+            PacketSealer::new(PacketProtection::derive(&secret, "test").unwrap()),
+            PacketOpener::new(PacketProtection::derive(&secret, "test").unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_nonce_construction() {
+        let protection = PacketProtection::new(&[0u8; 32], [0xAA; NONCE_SIZE]).unwrap();
+
+        assert_eq!(protection.nonce(0), [0xAA; NONCE_SIZE]);
+        let nonce = protection.nonce(0x0102);
+        assert_eq!(&nonce[..10], &[0xAA; 10]);
+        assert_eq!(&nonce[10..], &[0xAA ^ 0x01, 0xAA ^ 0x02]);
+        assert_ne!(protection.nonce(1), protection.nonce(2));
+    }
+
+    #[test]
+    fn test_reordered_packets_and_replays() {
+        let (mut sealer, mut opener) = pair();
+
+        let mut packets = Vec::new();
+        for i in 0..3u8 {
+            let mut buffer = vec![i; 10];
+            let seq = sealer.seal(b"hdr", &mut buffer).unwrap();
+            assert_eq!(buffer.len(), 10 + crate::aead::TAG_SIZE);
+            packets.push((seq, buffer));
+        }
+
+        // Out of order delivery is fine
+        for &index in &[2usize, 0, 1] {
+            let (seq, mut buffer) = packets[index].clone();
+            opener.open(seq, b"hdr", &mut buffer).unwrap();
+            assert_eq!(buffer, vec![index as u8; 10]);
+        }
+
+        // Duplicates are rejected
+        let (seq, mut buffer) = packets[1].clone();
+        assert!(opener.open(seq, b"hdr", &mut buffer).is_err());
+    }
+
+    #[test]
+    fn test_header_and_sequence_are_authenticated() {
+        let (mut sealer, mut opener) = pair();
+        let mut buffer = b"payload".to_vec();
+        let seq = sealer.seal(b"header-a", &mut buffer).unwrap();
+
+        assert!(opener.open(seq, b"header-b", &mut buffer.clone()).is_err());
+        assert!(opener
+            .open(seq + 1, b"header-a", &mut buffer.clone())
+            .is_err());
+
+        // Forged packets do not consume the sequence number
+        opener.open(seq, b"header-a", &mut buffer).unwrap();
+        assert_eq!(buffer, b"payload");
+    }
+
+    #[test]
+    fn test_replay_window_slides() {
+        let mut window = ReplayWindow::new();
+        window.mark(100);
+        assert!(!window.check(100));
+        assert!(window.check(99));
+        assert!(!window.check(100 - REPLAY_WINDOW_SIZE));
+
+        window.mark(100 + REPLAY_WINDOW_SIZE);
+        assert!(!window.check(100));
+        assert!(window.check(101));
+        assert_eq!(window.highest(), Some(100 + REPLAY_WINDOW_SIZE));
+    }
+}
@@ -0,0 +1,658 @@
+//! Session traffic-key ratchet
+//!
+//! Sessions live for hours, but `KeyDerivation::derive_session_key` yields a
+//! single static key per session. `TrafficRatchet` derives per-direction
+//! traffic keys from the session key and advances them with a one-way
+//! HKDF-SHA512 chain, giving forward secrecy within a session: once a key
+//! update is applied, the previous traffic secret is zeroized and cannot be
+//! recomputed from the current one.
+//!
+//! # Key Schedule
+//! ```text
+//! Session Key
+//!   ↓ HKDF-SHA512 ("initiator" / "responder")
+//! Traffic Secret (epoch 0)  →  Traffic Key (epoch 0)
+//!   ↓ HKDF-SHA512 ("update")
+//! Traffic Secret (epoch 1)  →  Traffic Key (epoch 1)
+//!   ↓ ...
+//! ```
+//!
+//! # Key Updates
+//! The sender advances its sending key every `max_messages` messages,
+//! `max_bytes` bytes or `max_age` (see `RatchetPolicy`), by sending a
+//! `KeyUpdate` record sealed under the old key. The peer applies it to its
+//! receiving key. The previous receiving key is kept only until the first
+//! record under the new epoch authenticates, to tolerate in-flight records;
+//! it is only bound by the AEAD safety limits, since the sender updated
+//! precisely because the old key reached the policy.
+//!
+//! The policy is enforced, not advisory: `seal()` refuses to encrypt once the
+//! sending key reached a limit, and `open()` refuses records under a receiving
+//! key that is past the same limits (the key age with `RECV_AGE_TOLERANCE`
+//! for clock skew between the peers' key installation). Both peers must use
+//! the same policy.
+//!
+//! # AEAD Safety Limits
+//! ChaCha20-Poly1305 with random 96-bit nonces is limited to
+//! `CONFIDENTIALITY_LIMIT` encryptions per key (nonce collision probability
+//! ≤ 2^-32) and `INTEGRITY_LIMIT` failed decryptions per key (RFC 9001
+//! Section 6.6). Each traffic key counts its invocations and refuses further
+//! use once a limit is reached.
+//!
+//! # Example
+//! ```
+//! use honeylink_crypto::ratchet::{RatchetRole, TrafficRatchet};
+//!
+//! let session_key = [7u8; 32];
+//! let mut alice = TrafficRatchet::new(&session_key, "session-abc", RatchetRole::Initiator).unwrap();
+//! let mut bob = TrafficRatchet::new(&session_key, "session-abc", RatchetRole::Responder).unwrap();
+//!
+//! let record = alice.seal(b"hello", b"stream-1").unwrap();
+//! assert_eq!(bob.open(&record, b"stream-1").unwrap(), b"hello");
+//!
+//! // Advance Alice's sending key; Bob applies the update
+//! let update = alice.initiate_update(b"stream-1").unwrap();
+//! bob.apply_update(&update, b"stream-1").unwrap();
+//! assert_eq!(alice.send_epoch(), bob.recv_epoch());
+//! ```
+
+use crate::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use crate::key_derivation::{DeriveContext, KeyDerivation};
+use crate::key_management::{KeyHierarchy, KeyScope};
+use honeylink_core::Result;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// Maximum encryptions per traffic key (random nonces, collision probability ≤ 2^-32)
+pub const CONFIDENTIALITY_LIMIT: u64 = 1 << 32;
+
+/// Maximum failed decryptions per traffic key (ChaCha20-Poly1305, RFC 9001)
+pub const INTEGRITY_LIMIT: u64 = 1 << 36;
+
+/// Extra key age accepted on the receiving side before records are refused
+///
+/// Covers the delay between the sender installing a key and the receiver
+/// applying the update.
+pub const RECV_AGE_TOLERANCE: Duration = Duration::from_secs(60);
+
+/// Associated data prefix of key update records
+const KEY_UPDATE_AAD: &[u8] = b"honeylink-traffic-key-update";
+
+/// Associated data of a key update record: `KEY_UPDATE_AAD || aad`
+fn key_update_aad(aad: &[u8]) -> Vec<u8> {
+    let mut update_aad = Vec::with_capacity(KEY_UPDATE_AAD.len() + aad.len());
+    update_aad.extend_from_slice(KEY_UPDATE_AAD);
+    update_aad.extend_from_slice(aad);
+    update_aad
+}
+
+/// Side of the session, which selects the sending and receiving chains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RatchetRole {
+    /// Peer that opened the session
+    Initiator,
+    /// Peer that accepted the session
+    Responder,
+}
+
+impl RatchetRole {
+    fn send_label(&self) -> &'static str {
+        match self {
+            RatchetRole::Initiator => "initiator",
+            RatchetRole::Responder => "responder",
+        }
+    }
+
+    fn recv_label(&self) -> &'static str {
+        match self {
+            RatchetRole::Initiator => "responder",
+            RatchetRole::Responder => "initiator",
+        }
+    }
+}
+
+/// When the sending traffic key must be advanced
+///
+/// Whichever limit is reached first triggers `needs_update()`; from then on
+/// `seal()` fails until `initiate_update()` is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatchetPolicy {
+    /// Messages sealed under one key
+    pub max_messages: u64,
+    /// Plaintext bytes sealed under one key
+    pub max_bytes: u64,
+    /// Lifetime of one key
+    pub max_age: Duration,
+}
+
+impl Default for RatchetPolicy {
+    /// 2^20 messages, 1 GiB or 1 hour per traffic key
+    fn default() -> Self {
+        Self {
+            max_messages: 1 << 20,
+            max_bytes: 1 << 30,
+            max_age: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl RatchetPolicy {
+    /// Sets the message limit (capped at `CONFIDENTIALITY_LIMIT`)
+    pub fn with_max_messages(mut self, max_messages: u64) -> Self {
+        self.max_messages = max_messages.min(CONFIDENTIALITY_LIMIT);
+        self
+    }
+
+    /// Sets the byte limit
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Sets the key lifetime
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+}
+
+/// Key update announced by a peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyUpdate {
+    /// Epoch of the new sending key
+    pub epoch: u64,
+}
+
+/// Record sealed under a traffic key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedRecord {
+    /// Epoch of the traffic key used
+    pub epoch: u64,
+    /// Random AEAD nonce
+    pub nonce: [u8; NONCE_SIZE],
+    /// Ciphertext with authentication tag
+    pub ciphertext: Vec<u8>,
+}
+
+/// Traffic key for one epoch with its usage counters
+struct TrafficKey {
+    epoch: u64,
+    secret: Zeroizing<Vec<u8>>,
+    cipher: ChaCha20Poly1305Cipher,
+    messages: u64,
+    bytes: u64,
+    failures: u64,
+    installed_at: Instant,
+}
+
+impl TrafficKey {
+    fn new(secret: Zeroizing<Vec<u8>>, session_id: &str, epoch: u64) -> Result<Self> {
+        let key = KeyDerivation::derive_with_context(
+            &secret,
+            &DeriveContext::traffic(session_id, "key", epoch),
+            32,
+        )?;
+
+        Ok(Self {
+            epoch,
+            secret,
+            cipher: ChaCha20Poly1305Cipher::new(&key)?,
+            messages: 0,
+            bytes: 0,
+            failures: 0,
+            installed_at: Instant::now(),
+        })
+    }
+
+    /// Whether the key is still within `policy`, allowing up to `max_age`
+    fn within(&self, policy: &RatchetPolicy, max_age: Duration) -> bool {
+        self.messages < policy.max_messages
+            && self.bytes < policy.max_bytes
+            && self.installed_at.elapsed() < max_age
+    }
+
+    /// Initial key of a direction, derived from the session key
+    fn initial(session_key: &[u8], session_id: &str, label: &str) -> Result<Self> {
+        let secret = KeyDerivation::derive_with_context(
+            session_key,
+            &DeriveContext::traffic(session_id, label, 0),
+            32,
+        )?;
+        Self::new(secret, session_id, 0)
+    }
+
+    /// Key of the next epoch (the old secret cannot be recovered from it)
+    fn next(&self, session_id: &str) -> Result<Self> {
+        let epoch = self.epoch.checked_add(1).ok_or_else(|| {
+            honeylink_core::Error::Crypto("Traffic key epoch exhausted".to_string())
+        })?;
+        let secret = KeyDerivation::derive_with_context(
+            &self.secret,
+            &DeriveContext::traffic(session_id, "update", epoch),
+            32,
+        )?;
+        Self::new(secret, session_id, epoch)
+    }
+
+    fn seal(&mut self, plaintext: &[u8], aad: &[u8]) -> Result<SealedRecord> {
+        if self.messages >= CONFIDENTIALITY_LIMIT {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "AEAD usage limit reached for traffic key epoch {}; key update required",
+                self.epoch
+            )));
+        }
+
+        let (nonce, ciphertext) = self.cipher.encrypt(plaintext, aad)?;
+        self.messages += 1;
+        self.bytes = self.bytes.saturating_add(plaintext.len() as u64);
+
+        Ok(SealedRecord {
+            epoch: self.epoch,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    fn open(&mut self, record: &SealedRecord, aad: &[u8]) -> Result<Vec<u8>> {
+        if self.failures >= INTEGRITY_LIMIT {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "AEAD integrity limit reached for traffic key epoch {}",
+                self.epoch
+            )));
+        }
+
+        match self.cipher.decrypt(&record.nonce, &record.ciphertext, aad) {
+            Ok(plaintext) => {
+                self.messages += 1;
+                self.bytes = self.bytes.saturating_add(plaintext.len() as u64);
+                Ok(plaintext)
+            }
+            Err(e) => {
+                self.failures += 1;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Per-session symmetric ratchet over sending and receiving traffic keys
+pub struct TrafficRatchet {
+    session_id: String,
+    policy: RatchetPolicy,
+    send: TrafficKey,
+    recv: TrafficKey,
+    /// Previous receiving key, kept until a record under `recv` authenticates
+    previous_recv: Option<TrafficKey>,
+}
+
+impl TrafficRatchet {
+    /// Creates a ratchet from a session key
+    ///
+    /// Both peers must use the same session key and session ID with
+    /// opposite roles.
+    pub fn new(session_key: &[u8], session_id: &str, role: RatchetRole) -> Result<Self> {
+        Ok(Self {
+            session_id: session_id.to_string(),
+            policy: RatchetPolicy::default(),
+            send: TrafficKey::initial(session_key, session_id, role.send_label())?,
+            recv: TrafficKey::initial(session_key, session_id, role.recv_label())?,
+            previous_recv: None,
+        })
+    }
+
+    /// Creates a ratchet from per-direction secrets of a completed handshake
+    ///
+    /// For handshakes that already split keys by direction (e.g. Noise
+    /// `TransportKeys`): the peer passes the same two secrets swapped.
+    pub fn from_split_secrets(
+        send_secret: &[u8],
+        recv_secret: &[u8],
+        session_id: &str,
+    ) -> Result<Self> {
+        Ok(Self {
+            session_id: session_id.to_string(),
+            policy: RatchetPolicy::default(),
+            send: TrafficKey::initial(send_secret, session_id, "split")?,
+            recv: TrafficKey::initial(recv_secret, session_id, "split")?,
+            previous_recv: None,
+        })
+    }
+
+    /// Creates a ratchet from the session-scope key of a key hierarchy
+    pub fn from_hierarchy(
+        hierarchy: &KeyHierarchy,
+        session_id: &str,
+        role: RatchetRole,
+    ) -> Result<Self> {
+        let session_key = hierarchy.derive(KeyScope::Session, session_id.as_bytes())?;
+        Self::new(&session_key, session_id, role)
+    }
+
+    /// Sets the key update policy
+    pub fn with_policy(mut self, policy: RatchetPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Session this ratchet belongs to
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Key update policy
+    pub fn policy(&self) -> &RatchetPolicy {
+        &self.policy
+    }
+
+    /// Epoch of the current sending key
+    pub fn send_epoch(&self) -> u64 {
+        self.send.epoch
+    }
+
+    /// Epoch of the current receiving key
+    pub fn recv_epoch(&self) -> u64 {
+        self.recv.epoch
+    }
+
+    /// Returns true if the sending key reached a policy limit
+    ///
+    /// Callers must send `initiate_update()` before sealing further records.
+    pub fn needs_update(&self) -> bool {
+        !self.send.within(&self.policy, self.policy.max_age)
+    }
+
+    /// Encrypts a record under the current sending key
+    ///
+    /// # Errors
+    /// Returns an error once the key reached a `RatchetPolicy` limit (see
+    /// `needs_update()`) or `CONFIDENTIALITY_LIMIT` (the last invocation is
+    /// reserved for the key update record).
+    pub fn seal(&mut self, plaintext: &[u8], aad: &[u8]) -> Result<SealedRecord> {
+        if self.needs_update() {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Ratchet policy limit reached for traffic key epoch {}; key update required",
+                self.send.epoch
+            )));
+        }
+        if self.send.messages + 1 >= CONFIDENTIALITY_LIMIT {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "AEAD usage limit reached for traffic key epoch {}; key update required",
+                self.send.epoch
+            )));
+        }
+        self.send.seal(plaintext, aad)
+    }
+
+    /// Decrypts a record under the receiving key of its epoch
+    ///
+    /// Records of the previous epoch are accepted until the first record of
+    /// the current epoch authenticates; the previous key is then zeroized.
+    /// They are not checked against the `RatchetPolicy`, which that key
+    /// usually reached already, only against the AEAD safety limits.
+    ///
+    /// # Errors
+    /// Returns an error for unknown epochs, failed authentication, keys past
+    /// the `RatchetPolicy` (the peer skipped a key update), or once the key
+    /// reached `INTEGRITY_LIMIT`.
+    pub fn open(&mut self, record: &SealedRecord, aad: &[u8]) -> Result<Vec<u8>> {
+        let policy = self.policy;
+        let exceeded = |epoch: u64| {
+            honeylink_core::Error::Crypto(format!(
+                "Peer exceeded the ratchet policy for traffic key epoch {}",
+                epoch
+            ))
+        };
+
+        if record.epoch == self.recv.epoch {
+            if !self
+                .recv
+                .within(&policy, policy.max_age.saturating_add(RECV_AGE_TOLERANCE))
+            {
+                return Err(exceeded(record.epoch));
+            }
+            let plaintext = self.recv.open(record, aad)?;
+            self.previous_recv = None;
+            return Ok(plaintext);
+        }
+
+        match self.previous_recv.as_mut() {
+            Some(previous) if previous.epoch == record.epoch => previous.open(record, aad),
+            _ => Err(honeylink_core::Error::Crypto(format!(
+                "No traffic key for epoch {} (current epoch {})",
+                record.epoch, self.recv.epoch
+            ))),
+        }
+    }
+
+    /// Advances the sending key and returns the key update record for the peer
+    ///
+    /// The record is sealed under the old key with `aad` (e.g. the record
+    /// header of the caller's framing); the old sending key is zeroized.
+    pub fn initiate_update(&mut self, aad: &[u8]) -> Result<SealedRecord> {
+        let next = self.send.next(&self.session_id)?;
+        let update = KeyUpdate { epoch: next.epoch };
+        let record = self
+            .send
+            .seal(&update.epoch.to_be_bytes(), &key_update_aad(aad))?;
+
+        self.send = next;
+        Ok(record)
+    }
+
+    /// Applies a key update record from the peer to the receiving key
+    ///
+    /// # Errors
+    /// Returns an error if the record does not authenticate under the current
+    /// receiving key and `aad`, or does not announce the next epoch.
+    pub fn apply_update(&mut self, record: &SealedRecord, aad: &[u8]) -> Result<KeyUpdate> {
+        if record.epoch != self.recv.epoch {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Key update sealed under epoch {}, expected {}",
+                record.epoch, self.recv.epoch
+            )));
+        }
+
+        let payload = self.recv.open(record, &key_update_aad(aad))?;
+        let epoch: [u8; 8] = payload
+            .as_slice()
+            .try_into()
+            .map_err(|_| honeylink_core::Error::Crypto("Malformed key update".to_string()))?;
+        let update = KeyUpdate {
+            epoch: u64::from_be_bytes(epoch),
+        };
+
+        let next = self.recv.next(&self.session_id)?;
+        if update.epoch != next.epoch {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Key update announces epoch {}, expected {}",
+                update.epoch, next.epoch
+            )));
+        }
+
+        // Replacing drops (and zeroizes) any older previous key
+        self.previous_recv = Some(std::mem::replace(&mut self.recv, next));
+        Ok(update)
+    }
+}
+
+impl std::fmt::Debug for TrafficRatchet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrafficRatchet")
+            .field("session_id", &self.session_id)
+            .field("policy", &self.policy)
+            .field("send_epoch", &self.send.epoch)
+            .field("recv_epoch", &self.recv.epoch)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair() -> (TrafficRatchet, TrafficRatchet) {
+        let session_key = [0x42u8; 32];
+        (
+            TrafficRatchet::new(&session_key, "session-1", RatchetRole::Initiator).unwrap(),
+            TrafficRatchet::new(&session_key, "session-1", RatchetRole::Responder).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_directions_use_distinct_keys() {
+        let (mut alice, mut bob) = pair();
+
+        let record = alice.seal(b"ping", b"aad").unwrap();
+        assert_eq!(bob.open(&record, b"aad").unwrap(), b"ping");
+
+        // A record cannot be reflected back to its sender
+        assert!(alice.open(&record, b"aad").is_err());
+
+        let reply = bob.seal(b"pong", b"aad").unwrap();
+        assert_eq!(alice.open(&reply, b"aad").unwrap(), b"pong");
+    }
+
+    #[test]
+    fn test_key_update() {
+        let (mut alice, mut bob) = pair();
+        let in_flight = alice.seal(b"old epoch", b"").unwrap();
+        let old_secret = alice.send.secret.clone();
+
+        let update = alice.initiate_update(b"").unwrap();
+        assert_eq!(alice.send_epoch(), 1);
+        assert_ne!(alice.send.secret.as_slice(), old_secret.as_slice());
+
+        // The update is bound to its associated data
+        assert!(bob.apply_update(&update, b"other").is_err());
+        assert_eq!(bob.recv_epoch(), 0);
+
+        assert_eq!(
+            bob.apply_update(&update, b"").unwrap(),
+            KeyUpdate { epoch: 1 }
+        );
+        assert_eq!(bob.recv_epoch(), 1);
+
+        // Records sealed before the update are still accepted...
+        assert_eq!(bob.open(&in_flight, b"").unwrap(), b"old epoch");
+
+        // ...until the first record of the new epoch arrives
+        let record = alice.seal(b"new epoch", b"").unwrap();
+        assert_eq!(record.epoch, 1);
+        assert_eq!(bob.open(&record, b"").unwrap(), b"new epoch");
+        assert!(bob.open(&in_flight, b"").is_err());
+
+        // Replaying the update does not advance the epoch again
+        assert!(bob.apply_update(&update, b"").is_err());
+        assert_eq!(bob.recv_epoch(), 1);
+    }
+
+    #[test]
+    fn test_policy_and_usage_limits() {
+        let (alice, _) = pair();
+        let mut alice = alice.with_policy(RatchetPolicy::default().with_max_messages(2));
+
+        alice.seal(b"1", b"").unwrap();
+        assert!(!alice.needs_update());
+        alice.seal(b"2", b"").unwrap();
+        assert!(alice.needs_update());
+
+        // The policy is enforced: no more records until the key is updated
+        assert!(alice.seal(b"3", b"").is_err());
+        alice.initiate_update(b"").unwrap();
+        assert!(!alice.needs_update());
+        alice.seal(b"3", b"").unwrap();
+
+        // Hard AEAD limit applies regardless of policy, but a key update
+        // can always be sent
+        alice.policy = RatchetPolicy::default().with_max_messages(CONFIDENTIALITY_LIMIT);
+        alice.send.messages = CONFIDENTIALITY_LIMIT - 1;
+        assert!(alice.seal(b"3", b"").is_err());
+        alice.initiate_update(b"").unwrap();
+        alice.seal(b"3", b"").unwrap();
+    }
+
+    #[test]
+    fn test_receiver_enforces_policy() {
+        let policy = RatchetPolicy::default()
+            .with_max_messages(2)
+            .with_max_bytes(1024);
+        let (alice, bob) = pair();
+        let mut alice = alice.with_policy(RatchetPolicy::default());
+        let mut bob = bob.with_policy(policy);
+
+        // A sender ignoring the policy is cut off by the receiver
+        for _ in 0..2 {
+            let record = alice.seal(b"x", b"").unwrap();
+            bob.open(&record, b"").unwrap();
+        }
+        let record = alice.seal(b"x", b"").unwrap();
+        assert!(bob.open(&record, b"").is_err());
+
+        // After a key update the new epoch is accepted again
+        let update = alice.initiate_update(b"").unwrap();
+        bob.apply_update(&update, b"").unwrap();
+        let record = alice.seal(&[0u8; 2048], b"").unwrap();
+        bob.open(&record, b"").unwrap();
+
+        // Byte limit: 2048 bytes already exceed 1024
+        let record = alice.seal(b"x", b"").unwrap();
+        assert!(bob.open(&record, b"").is_err());
+
+        // Age limit
+        let (mut alice, bob) = pair();
+        let mut bob = bob.with_policy(RatchetPolicy::default().with_max_age(Duration::ZERO));
+        bob.recv.installed_at = Instant::now() - RECV_AGE_TOLERANCE - Duration::from_secs(1);
+        let record = alice.seal(b"late", b"").unwrap();
+        assert!(bob.open(&record, b"").is_err());
+    }
+
+    #[test]
+    fn test_in_flight_record_after_update_at_limit() {
+        let policy = RatchetPolicy::default().with_max_messages(2);
+        let (alice, bob) = pair();
+        let mut alice = alice.with_policy(policy);
+        let mut bob = bob.with_policy(policy);
+
+        let first = alice.seal(b"1", b"").unwrap();
+        let second = alice.seal(b"2", b"").unwrap();
+        assert!(alice.needs_update());
+        let update = alice.initiate_update(b"").unwrap();
+
+        // The update overtakes the last record of the exhausted key
+        bob.open(&first, b"").unwrap();
+        bob.apply_update(&update, b"").unwrap();
+        assert_eq!(bob.open(&second, b"").unwrap(), b"2");
+
+        let record = alice.seal(b"3", b"").unwrap();
+        assert_eq!(bob.open(&record, b"").unwrap(), b"3");
+    }
+
+    #[test]
+    fn test_split_secrets_match_peer() {
+        let (a, b) = ([1u8; 32], [2u8; 32]);
+        let mut alice = TrafficRatchet::from_split_secrets(&a, &b, "noise-1").unwrap();
+        let mut bob = TrafficRatchet::from_split_secrets(&b, &a, "noise-1").unwrap();
+
+        let record = alice.seal(b"to bob", b"").unwrap();
+        assert_eq!(bob.open(&record, b"").unwrap(), b"to bob");
+        assert!(alice.open(&record, b"").is_err());
+
+        let reply = bob.seal(b"to alice", b"").unwrap();
+        assert_eq!(alice.open(&reply, b"").unwrap(), b"to alice");
+    }
+
+    #[test]
+    fn test_from_hierarchy_matches_peer() {
+        let hierarchy = KeyHierarchy::from_bytes([9u8; 32]);
+        let mut alice =
+            TrafficRatchet::from_hierarchy(&hierarchy, "session-2", RatchetRole::Initiator)
+                .unwrap();
+        let mut bob =
+            TrafficRatchet::from_hierarchy(&hierarchy, "session-2", RatchetRole::Responder)
+                .unwrap();
+
+        let record = alice.seal(b"data", b"").unwrap();
+        assert_eq!(bob.open(&record, b"").unwrap(), b"data");
+    }
+}
@@ -6,16 +6,21 @@
 //! # Features
 //! - Scheduled rotation (cron-based)
 //! - Emergency rotation (on-demand, high priority)
-//! - Grace period management (1-hour overlap)
 //! - Audit logging integration
 //! - Telemetry events emission
+//! - Works on any `KeyStore` backend (Vault, OS keychain, encrypted file, in-memory)
 //!
 //! # Example
 //! ```no_run
 //! use honeylink_crypto::rotation_scheduler::{RotationScheduler, RotationTrigger};
 //!
+//! use honeylink_crypto::file_key_store::FileKeyStore;
+//! use honeylink_crypto::rotation_scheduler::SchedulerConfig;
+//! use std::sync::Arc;
+//!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-//! let scheduler = RotationScheduler::from_env().await?;
+//! let store = FileKeyStore::open("keys.json", "passphrase")?;
+//! let scheduler = RotationScheduler::new(Arc::new(store), SchedulerConfig::default());
 //!
 //! // Start automatic rotation (90-day interval for device master keys)
 //! scheduler.start_background_rotation().await?;
@@ -26,8 +31,11 @@
 //! # }
 //! ```
 
-use crate::vault::{KeyScope, VaultClient, VaultError};
-use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use crate::key_management::KeyScope;
+use crate::key_store::{KeyStore, KeyStoreError};
+#[cfg(feature = "vault")]
+use crate::vault::VaultClient;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -38,8 +46,8 @@ use tokio::time::{interval, Duration};
 /// Errors that can occur during rotation scheduling.
 #[derive(Debug, Error)]
 pub enum SchedulerError {
-    #[error("Vault error: {0}")]
-    Vault(#[from] VaultError),
+    #[error("Key store error: {0}")]
+    Store(#[from] KeyStoreError),
 
     #[error("Rotation failed for key {key_name}: {reason}")]
     RotationFailed { key_name: String, reason: String },
@@ -85,7 +93,7 @@ pub struct RotationEvent {
     pub event_id: String,
     /// Timestamp (RFC 3339)
     pub timestamp: String,
-    /// Key scope (Root, Service, Profile, Telemetry)
+    /// Key scope (Root, DeviceMaster, Service, Profile, Telemetry, ...)
     pub scope: String,
     /// Key name
     pub key_name: String,
@@ -133,7 +141,10 @@ impl RotationEvent {
 pub struct SchedulerConfig {
     /// Rotation interval for each scope (in seconds)
     pub intervals: HashMap<KeyScope, Duration>,
-    /// Grace period after rotation (old key remains valid)
+    /// Grace period after rotation, for consumers that keep the old key
+    ///
+    /// Informational: stores return only the new version after rotation (see
+    /// `KeyStore::rotate`).
     pub grace_period: Duration,
     /// Emergency rotation timeout (30 minutes)
     pub emergency_timeout: Duration,
@@ -145,6 +156,7 @@ impl Default for SchedulerConfig {
     fn default() -> Self {
         let mut intervals = HashMap::new();
         intervals.insert(KeyScope::Root, Duration::from_secs(365 * 24 * 3600)); // 1 year
+        intervals.insert(KeyScope::DeviceMaster, Duration::from_secs(90 * 24 * 3600)); // 90 days
         intervals.insert(KeyScope::Service, Duration::from_secs(90 * 24 * 3600)); // 90 days
         intervals.insert(KeyScope::Profile, Duration::from_secs(90 * 24 * 3600)); // 90 days
         intervals.insert(KeyScope::Telemetry, Duration::from_secs(90 * 24 * 3600)); // 90 days
@@ -160,7 +172,7 @@ impl Default for SchedulerConfig {
 
 /// Automatic key rotation scheduler.
 pub struct RotationScheduler {
-    vault: VaultClient,
+    store: Arc<dyn KeyStore>,
     config: SchedulerConfig,
     running: Arc<RwLock<bool>>,
     /// Rotation history for auditing
@@ -168,18 +180,19 @@ pub struct RotationScheduler {
 }
 
 impl RotationScheduler {
-    /// Creates a new scheduler from environment variables.
+    /// Creates a new Vault-backed scheduler from environment variables.
+    #[cfg(feature = "vault")]
     pub async fn from_env() -> Result<Self, SchedulerError> {
         let vault = VaultClient::from_env()
             .map_err(|e| SchedulerError::Configuration(e.to_string()))?;
 
-        Ok(Self::new(vault, SchedulerConfig::default()))
+        Ok(Self::new(Arc::new(vault), SchedulerConfig::default()))
     }
 
-    /// Creates a new scheduler with custom configuration.
-    pub fn new(vault: VaultClient, config: SchedulerConfig) -> Self {
+    /// Creates a new scheduler over a key store with custom configuration.
+    pub fn new(store: Arc<dyn KeyStore>, config: SchedulerConfig) -> Self {
         Self {
-            vault,
+            store,
             config,
             running: Arc::new(RwLock::new(false)),
             history: Arc::new(RwLock::new(Vec::new())),
@@ -202,7 +215,7 @@ impl RotationScheduler {
         *running = true;
         drop(running);
 
-        let vault = self.vault.clone();
+        let store = self.store.clone();
         let config = self.config.clone();
         let running = self.running.clone();
         let history = self.history.clone();
@@ -221,7 +234,7 @@ impl RotationScheduler {
                 // Check each scope for keys needing rotation
                 for (scope, rotation_interval) in &config.intervals {
                     if let Err(e) = Self::check_and_rotate_scope(
-                        &vault,
+                        store.as_ref(),
                         *scope,
                         *rotation_interval,
                         &history,
@@ -245,23 +258,25 @@ impl RotationScheduler {
 
     /// Checks and rotates keys in a scope if they exceed the rotation interval.
     async fn check_and_rotate_scope(
-        vault: &VaultClient,
+        store: &dyn KeyStore,
         scope: KeyScope,
         rotation_interval: Duration,
         history: &Arc<RwLock<Vec<RotationEvent>>>,
     ) -> Result<(), SchedulerError> {
-        let key_names = vault.list_keys(scope).await?;
+        let key_names = store.list(scope).await?;
 
         for key_name in key_names {
-            let key_material = vault.retrieve_key(scope, &key_name).await?;
+            let key_material = store.get(scope, &key_name).await?;
 
-            // Check if rotation is needed
-            let created_at = chrono::DateTime::parse_from_rfc3339(&key_material.metadata.created_at)
-                .map_err(|e| SchedulerError::Configuration(format!("Invalid timestamp: {}", e)))?;
+            // Check if rotation is needed (expired keys are always rotated)
+            let age = key_material
+                .metadata
+                .age()
+                .ok_or_else(|| SchedulerError::Configuration("Invalid timestamp".to_string()))?;
 
-            let age = Utc::now().signed_duration_since(created_at);
-
-            if age.num_seconds() as u64 >= rotation_interval.as_secs() {
+            if key_material.metadata.is_expired()
+                || age.num_seconds() as u64 >= rotation_interval.as_secs()
+            {
                 // Rotate the key
                 println!(
                     "Rotating key {} in scope {:?} (age: {} days)",
@@ -270,8 +285,14 @@ impl RotationScheduler {
                     age.num_days()
                 );
 
-                match Self::perform_rotation(vault, scope, &key_name, RotationTrigger::Scheduled)
-                    .await
+                match Self::perform_rotation(
+                    store,
+                    scope,
+                    &key_name,
+                    rotation_interval,
+                    RotationTrigger::Scheduled,
+                )
+                .await
                 {
                     Ok(event) => {
                         history.write().await.push(event);
@@ -297,9 +318,10 @@ impl RotationScheduler {
 
     /// Performs key rotation with timing and event logging.
     async fn perform_rotation(
-        vault: &VaultClient,
+        store: &dyn KeyStore,
         scope: KeyScope,
         key_name: &str,
+        ttl: Duration,
         trigger: RotationTrigger,
     ) -> Result<RotationEvent, SchedulerError> {
         let start = std::time::Instant::now();
@@ -308,9 +330,9 @@ impl RotationScheduler {
         let mut new_key_data = vec![0u8; 32];
         rand::Rng::fill(&mut rand::thread_rng(), &mut new_key_data[..]);
 
-        // Rotate in Vault
-        let new_version = vault
-            .rotate_key(scope, key_name, new_key_data)
+        // Rotate in the key store
+        let new_version = store
+            .rotate(scope, key_name, new_key_data, ttl)
             .await
             .map_err(|e| SchedulerError::RotationFailed {
                 key_name: key_name.to_string(),
@@ -319,14 +341,18 @@ impl RotationScheduler {
 
         let duration = start.elapsed();
 
-        Ok(RotationEvent::new(
+        let mut event = RotationEvent::new(
             scope,
             key_name.to_string(),
             trigger,
             duration.as_secs(),
             true,
             None,
-        ))
+        );
+        event.old_key_id = Some(format!("{}:v{}", key_name, new_version - 1));
+        event.new_key_id = format!("{}:v{}", key_name, new_version);
+
+        Ok(event)
     }
 
     /// Triggers emergency rotation for a specific key (30-minute target).
@@ -350,8 +376,15 @@ impl RotationScheduler {
 
         // Determine scope (assume Service for simplicity; in production, this should be configurable)
         let scope = KeyScope::Service;
+        let ttl = self
+            .config
+            .intervals
+            .get(&scope)
+            .copied()
+            .unwrap_or_else(|| scope.rotation_interval());
 
-        let event = Self::perform_rotation(&self.vault, scope, key_name, trigger).await?;
+        let event =
+            Self::perform_rotation(self.store.as_ref(), scope, key_name, ttl, trigger).await?;
 
         let duration = start.elapsed();
 
@@ -385,15 +418,6 @@ impl RotationScheduler {
     }
 }
 
-// Implement Clone for VaultClient (needed for tokio::spawn)
-impl Clone for VaultClient {
-    fn clone(&self) -> Self {
-        // This is a simplified clone for scheduler use
-        // In production, you'd want to properly clone the underlying HTTP client
-        VaultClient::from_env().expect("Failed to clone VaultClient")
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,4 +458,44 @@ mod tests {
         assert!(event.success);
         assert!(event.error.is_none());
     }
+
+    #[tokio::test]
+    async fn test_rotation_on_memory_store() {
+        use crate::key_store::{KeyMaterial, MemoryKeyStore};
+
+        let store = Arc::new(MemoryKeyStore::new());
+        for (scope, name) in [(KeyScope::Service, "svc"), (KeyScope::DeviceMaster, "device-1")] {
+            store
+                .put(KeyMaterial::new(scope, name, vec![0u8; 32], Duration::from_secs(3600), "test"))
+                .await
+                .unwrap();
+        }
+
+        let scheduler = RotationScheduler::new(store.clone(), SchedulerConfig::default());
+        scheduler
+            .rotate_emergency("svc", RotationTrigger::Compromised)
+            .await
+            .unwrap();
+
+        let rotated = store.get(KeyScope::Service, "svc").await.unwrap();
+        assert_eq!(rotated.metadata.version, 2);
+        assert_ne!(rotated.data, vec![0u8; 32]);
+
+        let history = scheduler.get_history().await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].new_key_id, "svc:v2");
+
+        // Zero interval: every key in the scope is due
+        let history = Arc::new(RwLock::new(Vec::new()));
+        RotationScheduler::check_and_rotate_scope(
+            store.as_ref(),
+            KeyScope::DeviceMaster,
+            Duration::ZERO,
+            &history,
+        )
+        .await
+        .unwrap();
+        assert_eq!(history.read().await.len(), 1);
+        assert_eq!(store.get(KeyScope::DeviceMaster, "device-1").await.unwrap().metadata.version, 2);
+    }
 }
@@ -0,0 +1,527 @@
+//! Encrypted, versioned persistence for `KeyRotationManager` state.
+//!
+//! Rotation state contains raw key material, so it is never written in
+//! plaintext. The state is sealed with ChaCha20-Poly1305 under either a
+//! passphrase-derived key (Argon2id) or a 32-byte key held elsewhere, e.g. in
+//! the OS keychain (`honeylink_keychain::sealing_key_from_keychain`).
+//!
+//! # File Format
+//! ```json
+//! {
+//!   "format": 1,
+//!   "generation": 7,
+//!   "kdf": { "algorithm": "argon2id", "salt": "...", "memory_kib": 19456, "iterations": 2, "parallelism": 1 },
+//!   "nonce": "...",
+//!   "ciphertext": "..."
+//! }
+//! ```
+//!
+//! `kdf.algorithm` is `"raw"` (without salt or parameters) when sealed under
+//! a key. The header is bound to the ciphertext as associated data, so
+//! tampering with any field, including the `generation` counter that
+//! increases with every save, fails decryption.
+//!
+//! `generation` only orders the saves of one file: replacing the whole file
+//! with an older sealed copy (e.g. from a backup) still loads, because the
+//! store keeps no record of the last generation it saw. Detecting such a
+//! rollback needs a counter held outside the file.
+//!
+//! # Migration
+//! Format 0 is the plaintext JSON written by earlier `honeylink-keygen`
+//! versions. Only `migrate()` reads it (and re-writes it in the current
+//! format); every other operation rejects it with `InvalidFormat`, so a
+//! replaced file cannot smuggle in unauthenticated key material.
+//!
+//! # Example
+//! ```no_run
+//! use honeylink_crypto::key_management::{KeyHierarchy, KeyScope};
+//! use honeylink_crypto::rotation_store::{RotationStateStore, SealingKey};
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let store = RotationStateStore::new(
+//!     "/var/lib/honeylink/rotation.json",
+//!     SealingKey::passphrase("correct horse battery staple"),
+//! );
+//!
+//! let hierarchy = KeyHierarchy::from_bytes([7u8; 32]);
+//! let now = chrono::Utc::now().timestamp();
+//! let version = store.rotate(&hierarchy, KeyScope::Session, now)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use crate::file_key_store::{derive_passphrase_key, sync_parent_dir, KdfParams};
+use crate::key_management::{KeyHierarchy, KeyScope};
+use crate::key_store::KeyStoreError;
+use crate::rotation::{KeyRotationManager, KeyVersion};
+use base64::Engine as _;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// Current rotation state format version
+pub const ROTATION_STATE_FORMAT: u32 = 1;
+
+/// Salt length for Argon2id (bytes)
+const SALT_SIZE: usize = 16;
+
+/// Key that seals the rotation state
+#[derive(Clone)]
+pub enum SealingKey {
+    /// Passphrase stretched with Argon2id (salt stored in the file)
+    Passphrase(Zeroizing<String>),
+    /// 32-byte key used directly (e.g. from the OS keychain)
+    Key(Zeroizing<[u8; 32]>),
+}
+
+impl SealingKey {
+    /// Creates a passphrase sealing key
+    pub fn passphrase(passphrase: impl Into<String>) -> Self {
+        Self::Passphrase(Zeroizing::new(passphrase.into()))
+    }
+
+    /// Creates a raw sealing key
+    ///
+    /// # Errors
+    /// Returns an error if `key` is not exactly 32 bytes.
+    pub fn from_bytes(key: &[u8]) -> Result<Self, KeyStoreError> {
+        let key: [u8; 32] = key.try_into().map_err(|_| {
+            KeyStoreError::InvalidFormat(format!("Sealing key must be 32 bytes, got {}", key.len()))
+        })?;
+        Ok(Self::Key(Zeroizing::new(key)))
+    }
+}
+
+impl std::fmt::Debug for SealingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passphrase(_) => f.write_str("SealingKey::Passphrase([REDACTED])"),
+            Self::Key(_) => f.write_str("SealingKey::Key([REDACTED])"),
+        }
+    }
+}
+
+/// KDF section of the file header
+#[derive(Serialize, Deserialize)]
+struct KdfHeader {
+    algorithm: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(flatten)]
+    params: Option<KdfParams>,
+}
+
+/// On-disk representation
+#[derive(Serialize, Deserialize)]
+struct SealedState {
+    format: u32,
+    generation: u64,
+    kdf: KdfHeader,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl SealedState {
+    /// Associated data covering every header field
+    fn aad(&self) -> Vec<u8> {
+        format!(
+            "honeylink-rotation-state|{}|{}|{}|{}|{}",
+            self.format,
+            self.generation,
+            self.kdf.algorithm,
+            self.kdf.salt.as_deref().unwrap_or(""),
+            self.kdf
+                .params
+                .map(|p| format!("{}/{}/{}", p.memory_kib, p.iterations, p.parallelism))
+                .unwrap_or_default()
+        )
+        .into_bytes()
+    }
+}
+
+/// Encrypted file holding `KeyRotationManager` state
+pub struct RotationStateStore {
+    path: PathBuf,
+    sealing_key: SealingKey,
+    params: KdfParams,
+}
+
+impl RotationStateStore {
+    /// Creates a store for the state file at `path`
+    ///
+    /// Nothing is read or written until the first operation.
+    pub fn new(path: impl AsRef<Path>, sealing_key: SealingKey) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            sealing_key,
+            params: KdfParams::default(),
+        }
+    }
+
+    /// Sets the Argon2id parameters used for passphrase-sealed saves
+    pub fn with_kdf_params(mut self, params: KdfParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Path of the state file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the state file exists
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Loads the rotation state
+    ///
+    /// # Errors
+    /// - `KeyStoreError::Decryption`: Wrong key or tampered file
+    /// - `KeyStoreError::InvalidFormat`: Unknown format version or KDF, or
+    ///   legacy plaintext state that has not been migrated
+    /// - `KeyStoreError::Io`: File cannot be read
+    pub fn load(&self) -> Result<KeyRotationManager, KeyStoreError> {
+        let _lock = self.lock(false)?;
+        Ok(self.read(false)?.0)
+    }
+
+    /// Encrypts and atomically replaces the stored state
+    pub fn save(&self, manager: &KeyRotationManager) -> Result<(), KeyStoreError> {
+        let _lock = self.lock(true)?;
+        let generation = if self.path.exists() {
+            self.read(false)?.1
+        } else {
+            0
+        };
+        self.write(manager, generation + 1)
+    }
+
+    /// Loads the state (or starts from `KeyRotationManager::new()`), applies
+    /// `update` and saves the result while holding the file lock
+    ///
+    /// Nothing is written if `update` fails.
+    pub fn update<T>(
+        &self,
+        update: impl FnOnce(&mut KeyRotationManager) -> Result<T, KeyStoreError>,
+    ) -> Result<T, KeyStoreError> {
+        let _lock = self.lock(true)?;
+        let (mut manager, generation, _) = if self.path.exists() {
+            self.read(false)?
+        } else {
+            (KeyRotationManager::new(), 0, ROTATION_STATE_FORMAT)
+        };
+
+        let result = update(&mut manager)?;
+        self.write(&manager, generation + 1)?;
+        Ok(result)
+    }
+
+    /// Derives and activates a new key version for `scope`
+    pub fn rotate(
+        &self,
+        hierarchy: &KeyHierarchy,
+        scope: KeyScope,
+        now: i64,
+    ) -> Result<KeyVersion, KeyStoreError> {
+        self.update(|manager| {
+            manager
+                .rotate(hierarchy, scope, now)
+                .map_err(|e| KeyStoreError::Backend(e.to_string()))
+        })
+    }
+
+    /// Adds externally generated key material as a new version for `scope`
+    pub fn add_key_version(
+        &self,
+        scope: KeyScope,
+        key_material: [u8; 32],
+        now: i64,
+    ) -> Result<KeyVersion, KeyStoreError> {
+        self.update(|manager| {
+            manager
+                .add_key_version(scope, key_material, now)
+                .map_err(|e| KeyStoreError::Backend(e.to_string()))
+        })
+    }
+
+    /// Re-writes an older format in the current format
+    ///
+    /// This is the only operation accepting legacy plaintext (format 0)
+    /// state; run it once, deliberately, after upgrading.
+    ///
+    /// # Returns
+    /// The format version found on disk before migration
+    pub fn migrate(&self) -> Result<u32, KeyStoreError> {
+        let _lock = self.lock(true)?;
+        let (manager, generation, format) = self.read(true)?;
+        if format != ROTATION_STATE_FORMAT {
+            self.write(&manager, generation + 1)?;
+        }
+        Ok(format)
+    }
+
+    /// Reads and decrypts the file: (state, generation, format)
+    ///
+    /// Legacy plaintext state is only accepted with `allow_legacy`.
+    fn read(&self, allow_legacy: bool) -> Result<(KeyRotationManager, u64, u32), KeyStoreError> {
+        let raw = Zeroizing::new(std::fs::read(&self.path)?);
+        let value: serde_json::Value = serde_json::from_slice(&raw)?;
+
+        // Format 0: legacy plaintext `KeyRotationManager` JSON
+        if value.get("format").is_none() {
+            if !allow_legacy {
+                return Err(KeyStoreError::InvalidFormat(
+                    "Plaintext rotation state (format 0) must be migrated first".to_string(),
+                ));
+            }
+            return Ok((serde_json::from_value(value)?, 0, 0));
+        }
+
+        let sealed: SealedState = serde_json::from_value(value)?;
+        if sealed.format != ROTATION_STATE_FORMAT {
+            return Err(KeyStoreError::InvalidFormat(format!(
+                "Unsupported rotation state format {}",
+                sealed.format
+            )));
+        }
+
+        let cipher = self.cipher(&sealed.kdf)?;
+        let nonce = decode_b64(&sealed.nonce)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(KeyStoreError::InvalidFormat(
+                "Invalid nonce length".to_string(),
+            ));
+        }
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(&nonce, &decode_b64(&sealed.ciphertext)?, &sealed.aad())
+                .map_err(|_| KeyStoreError::Decryption)?,
+        );
+
+        Ok((
+            serde_json::from_slice(&plaintext)?,
+            sealed.generation,
+            sealed.format,
+        ))
+    }
+
+    /// Seals `manager` and atomically replaces the file
+    fn write(&self, manager: &KeyRotationManager, generation: u64) -> Result<(), KeyStoreError> {
+        let kdf = match &self.sealing_key {
+            SealingKey::Passphrase(_) => {
+                let mut salt = [0u8; SALT_SIZE];
+                rand::thread_rng().fill_bytes(&mut salt);
+                KdfHeader {
+                    algorithm: "argon2id".to_string(),
+                    salt: Some(encode_b64(&salt)),
+                    params: Some(self.params),
+                }
+            }
+            SealingKey::Key(_) => KdfHeader {
+                algorithm: "raw".to_string(),
+                salt: None,
+                params: None,
+            },
+        };
+
+        let mut sealed = SealedState {
+            format: ROTATION_STATE_FORMAT,
+            generation,
+            kdf,
+            nonce: String::new(),
+            ciphertext: String::new(),
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(manager)?);
+        let (nonce, ciphertext) = self
+            .cipher(&sealed.kdf)?
+            .encrypt(&plaintext, &sealed.aad())
+            .map_err(|e| KeyStoreError::Backend(e.to_string()))?;
+        sealed.nonce = encode_b64(&nonce);
+        sealed.ciphertext = encode_b64(&ciphertext);
+
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let file = File::create(&tmp_path)?;
+            restrict_permissions(&tmp_path)?;
+            serde_json::to_writer_pretty(&file, &sealed)?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp_path, &self.path)?;
+        sync_parent_dir(&self.path)?;
+
+        Ok(())
+    }
+
+    /// Cipher for the KDF recorded in `kdf`
+    fn cipher(&self, kdf: &KdfHeader) -> Result<ChaCha20Poly1305Cipher, KeyStoreError> {
+        let key = match (&self.sealing_key, kdf.algorithm.as_str()) {
+            (SealingKey::Passphrase(passphrase), "argon2id") => {
+                let salt = decode_b64(kdf.salt.as_deref().unwrap_or_default())?;
+                let params = kdf.params.ok_or_else(|| {
+                    KeyStoreError::InvalidFormat("Missing KDF parameters".to_string())
+                })?;
+                derive_passphrase_key(passphrase.as_bytes(), &salt, params)?
+            }
+            (SealingKey::Key(key), "raw") => key.clone(),
+            (_, "argon2id" | "raw") => return Err(KeyStoreError::Decryption),
+            (_, other) => {
+                return Err(KeyStoreError::InvalidFormat(format!(
+                    "Unsupported KDF {}",
+                    other
+                )))
+            }
+        };
+        ChaCha20Poly1305Cipher::new(key.as_ref()).map_err(|e| KeyStoreError::Backend(e.to_string()))
+    }
+
+    /// Takes the advisory lock file next to the state file
+    fn lock(&self, exclusive: bool) -> Result<File, KeyStoreError> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut lock_path = self.path.clone().into_os_string();
+        lock_path.push(".lock");
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+        if exclusive {
+            file.lock()?;
+        } else {
+            file.lock_shared()?;
+        }
+        Ok(file)
+    }
+}
+
+impl std::fmt::Debug for RotationStateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotationStateStore")
+            .field("path", &self.path)
+            .field("sealing_key", &self.sealing_key)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+fn encode_b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+fn decode_b64(data: &str) -> Result<Vec<u8>, KeyStoreError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| KeyStoreError::InvalidFormat(e.to_string()))
+}
+
+/// Restricts the state file to the owner (no-op on non-Unix platforms)
+fn restrict_permissions(path: &Path) -> Result<(), KeyStoreError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_roundtrip_and_wrong_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rotation-state.json");
+        let store = RotationStateStore::new(&path, SealingKey::passphrase("secret"))
+            .with_kdf_params(KdfParams::TESTING);
+
+        let hierarchy = KeyHierarchy::from_bytes([3u8; 32]);
+        assert_eq!(
+            store.rotate(&hierarchy, KeyScope::Session, 1_000).unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .add_key_version(KeyScope::Root, [9u8; 32], 1_000)
+                .unwrap(),
+            1
+        );
+
+        // Key material never hits the disk in plaintext
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("key_material"));
+
+        let manager = store.load().unwrap();
+        assert_eq!(
+            manager
+                .get_active_key(KeyScope::Root, 1_000)
+                .unwrap()
+                .key_material,
+            [9u8; 32]
+        );
+
+        let wrong = RotationStateStore::new(&path, SealingKey::passphrase("wrong"));
+        assert!(matches!(wrong.load(), Err(KeyStoreError::Decryption)));
+        let raw_key = RotationStateStore::new(&path, SealingKey::from_bytes(&[1u8; 32]).unwrap());
+        assert!(matches!(raw_key.load(), Err(KeyStoreError::Decryption)));
+    }
+
+    #[test]
+    fn test_header_tampering_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rotation-state-tamper.json");
+        let store = RotationStateStore::new(&path, SealingKey::from_bytes(&[5u8; 32]).unwrap());
+        store
+            .add_key_version(KeyScope::Session, [1u8; 32], 0)
+            .unwrap();
+        store
+            .add_key_version(KeyScope::Session, [2u8; 32], 10)
+            .unwrap();
+
+        let mut sealed: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(sealed["generation"], 2);
+        sealed["generation"] = 1.into();
+        std::fs::write(&path, serde_json::to_vec(&sealed).unwrap()).unwrap();
+
+        assert!(matches!(store.load(), Err(KeyStoreError::Decryption)));
+    }
+
+    #[test]
+    fn test_migrates_legacy_plaintext() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rotation-state-legacy.json");
+        let mut legacy = KeyRotationManager::new();
+        legacy
+            .add_key_version(KeyScope::Root, [4u8; 32], 0)
+            .unwrap();
+        std::fs::write(&path, serde_json::to_vec_pretty(&legacy).unwrap()).unwrap();
+
+        let store = RotationStateStore::new(&path, SealingKey::passphrase("secret"))
+            .with_kdf_params(KdfParams::TESTING);
+        assert!(matches!(store.load(), Err(KeyStoreError::InvalidFormat(_))));
+        assert!(matches!(
+            store.add_key_version(KeyScope::Root, [5u8; 32], 0),
+            Err(KeyStoreError::InvalidFormat(_))
+        ));
+        assert_eq!(store.migrate().unwrap(), 0);
+        assert_eq!(store.migrate().unwrap(), ROTATION_STATE_FORMAT);
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("\"format\": 1"));
+        assert!(store
+            .load()
+            .unwrap()
+            .get_active_key(KeyScope::Root, 0)
+            .is_some());
+    }
+}
@@ -0,0 +1,402 @@
+//! Shamir secret sharing for root key backup
+//!
+//! Splits a secret (typically the 32-byte organization root key) into `n`
+//! shares so that any `k` of them reconstruct it, while `k - 1` shares reveal
+//! nothing about it. Arithmetic is done byte-wise in GF(2^8) with the AES
+//! polynomial (x^8 + x^4 + x^3 + x + 1) and without secret-dependent branches
+//! or table lookups.
+//!
+//! # Share Encoding
+//! ```text
+//! version (1) | threshold (1) | index (1) | share bytes | checksum (4)
+//! ```
+//!
+//! The checksum (truncated SHA-256) catches transcription errors. Shares can
+//! be exported as URL-safe base64 or as uppercase base32, which fits the QR
+//! code alphanumeric mode.
+//!
+//! # Example
+//! ```
+//! use honeylink_crypto::shamir::{combine_shares, fingerprint, split_secret, Share};
+//!
+//! let root_key = [7u8; 32];
+//! let shares = split_secret(&root_key, 2, 3).unwrap();
+//!
+//! // Hand out shares as text, later recover from any two
+//! let texts: Vec<String> = shares.iter().map(|s| s.to_base32()).collect();
+//! let recovered: Vec<Share> = texts[1..].iter().map(|t| Share::parse(t).unwrap()).collect();
+//!
+//! let secret = combine_shares(&recovered).unwrap();
+//! assert_eq!(fingerprint(&secret), fingerprint(&root_key));
+//! ```
+
+use base64::Engine;
+use honeylink_core::Result;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Current share encoding version
+pub const SHARE_VERSION: u8 = 1;
+
+/// Length of the share checksum
+const CHECKSUM_LEN: usize = 4;
+
+/// RFC 4648 base32 alphabet
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// One share of a split secret
+///
+/// Zeroized on drop.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Share {
+    threshold: u8,
+    index: u8,
+    data: Vec<u8>,
+}
+
+impl Share {
+    /// Number of shares needed to recover the secret
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Share number (x coordinate, 1-255)
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Encodes the share with version, header and checksum
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(Vec::with_capacity(3 + self.data.len() + CHECKSUM_LEN));
+        bytes.extend_from_slice(&[SHARE_VERSION, self.threshold, self.index]);
+        bytes.extend_from_slice(&self.data);
+        let checksum = Sha256::digest(&bytes[..]);
+        bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+        bytes
+    }
+
+    /// Decodes a share produced by `to_bytes`
+    ///
+    /// # Errors
+    /// Returns an error for truncated input, an unknown version, invalid
+    /// header values or a checksum mismatch.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 3 + 1 + CHECKSUM_LEN {
+            return Err(invalid_share("share is truncated"));
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if Sha256::digest(body)[..CHECKSUM_LEN] != *checksum {
+            return Err(invalid_share("checksum mismatch"));
+        }
+        if body[0] != SHARE_VERSION {
+            return Err(invalid_share(&format!("unsupported version {}", body[0])));
+        }
+        if body[1] < 2 || body[2] == 0 {
+            return Err(invalid_share(
+                "threshold must be at least 2 and index non-zero",
+            ));
+        }
+
+        Ok(Self {
+            threshold: body[1],
+            index: body[2],
+            data: body[3..].to_vec(),
+        })
+    }
+
+    /// URL-safe base64 text form
+    pub fn to_base64(&self) -> String {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&self.to_bytes()[..])
+    }
+
+    /// Uppercase base32 text form (QR alphanumeric mode friendly)
+    pub fn to_base32(&self) -> String {
+        base32_encode(&self.to_bytes())
+    }
+
+    /// Parses either text form
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        if let Some(bytes) = base32_decode(text) {
+            if let Ok(share) = Self::from_bytes(&Zeroizing::new(bytes)) {
+                return Ok(share);
+            }
+        }
+
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(text)
+            .map(Zeroizing::new)
+            .map_err(|_| invalid_share("not valid base32 or base64"))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl std::fmt::Debug for Share {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Share")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .field("data", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Splits `secret` into `shares` shares, any `threshold` of which recover it
+///
+/// # Errors
+/// Returns an error if the secret is empty, `threshold` is below 2 or greater
+/// than `shares`. A threshold of 1 would make every share a copy of the
+/// secret.
+pub fn split_secret(secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<Share>> {
+    if secret.is_empty() {
+        return Err(honeylink_core::Error::Crypto(
+            "Cannot split an empty secret".to_string(),
+        ));
+    }
+    if threshold < 2 || threshold > shares {
+        return Err(honeylink_core::Error::Crypto(format!(
+            "Invalid Shamir parameters: {}-of-{}",
+            threshold, shares
+        )));
+    }
+
+    // coefficients[0] is the secret byte, the rest are random
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+    let mut output: Vec<Share> = (1..=shares)
+        .map(|index| Share {
+            threshold,
+            index,
+            data: Vec::with_capacity(secret.len()),
+        })
+        .collect();
+
+    for &byte in secret {
+        coefficients[0] = byte;
+        rand::rngs::OsRng.fill_bytes(&mut coefficients[1..]);
+        for share in &mut output {
+            share.data.push(evaluate(&coefficients, share.index));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Reconstructs the secret from at least `threshold` shares
+///
+/// Only the first `threshold` shares are used. A wrong share yields a wrong
+/// secret rather than an error, so compare the result against a stored
+/// `fingerprint` (see `combine_verified`).
+///
+/// # Errors
+/// Returns an error if too few shares are given or the shares are
+/// inconsistent (different thresholds or lengths, duplicate indices).
+pub fn combine_shares(shares: &[Share]) -> Result<Zeroizing<Vec<u8>>> {
+    let first = shares
+        .first()
+        .ok_or_else(|| honeylink_core::Error::Crypto("No shares given".to_string()))?;
+    let threshold = first.threshold as usize;
+    if shares.len() < threshold {
+        return Err(honeylink_core::Error::Crypto(format!(
+            "Need {} shares, got {}",
+            threshold,
+            shares.len()
+        )));
+    }
+
+    let shares = &shares[..threshold];
+    for (i, share) in shares.iter().enumerate() {
+        if share.threshold != first.threshold || share.data.len() != first.data.len() {
+            return Err(honeylink_core::Error::Crypto(
+                "Shares belong to different secrets".to_string(),
+            ));
+        }
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(honeylink_core::Error::Crypto(format!(
+                "Duplicate share {}",
+                share.index
+            )));
+        }
+    }
+
+    // Lagrange basis polynomials evaluated at x = 0
+    let basis: Vec<u8> = shares
+        .iter()
+        .map(|share| {
+            shares
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1u8, |acc, other| {
+                    gf_mul(acc, gf_div(other.index, other.index ^ share.index))
+                })
+        })
+        .collect();
+
+    let secret = (0..first.data.len())
+        .map(|position| {
+            shares.iter().zip(&basis).fold(0u8, |acc, (share, &l)| {
+                acc ^ gf_mul(share.data[position], l)
+            })
+        })
+        .collect();
+
+    Ok(Zeroizing::new(secret))
+}
+
+/// Reconstructs the secret and checks it against `expected_fingerprint`
+pub fn combine_verified(
+    shares: &[Share],
+    expected_fingerprint: &str,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let secret = combine_shares(shares)?;
+    if fingerprint(&secret) != expected_fingerprint.trim() {
+        return Err(honeylink_core::Error::Crypto(
+            "Recovered secret does not match fingerprint (wrong or corrupted share)".to_string(),
+        ));
+    }
+    Ok(secret)
+}
+
+/// Public fingerprint of a secret: `SHA256:` followed by base64 of its hash
+///
+/// Safe to store next to the shares; it identifies but does not reveal the key.
+pub fn fingerprint(secret: &[u8]) -> String {
+    let digest = Sha256::digest([b"HoneyLink-v1|Fingerprint|".as_slice(), secret].concat());
+    format!(
+        "SHA256:{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest)
+    )
+}
+
+/// Evaluates the polynomial at `x` (Horner's method)
+fn evaluate(coefficients: &[u8], x: u8) -> u8 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0u8, |acc, &coefficient| gf_mul(acc, x) ^ coefficient)
+}
+
+/// Multiplication in GF(2^8) without secret-dependent branches
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// Division in GF(2^8) (`b` must be non-zero): a * b^254
+fn gf_div(a: u8, b: u8) -> u8 {
+    let mut inverse = 1u8;
+    let mut power = b;
+    // 254 = 0b11111110
+    for _ in 0..7 {
+        power = gf_mul(power, power);
+        inverse = gf_mul(inverse, power);
+    }
+    gf_mul(a, inverse)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            text.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        text.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    text
+}
+
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 5 / 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&a| a == c)? as u16;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+fn invalid_share(reason: &str) -> honeylink_core::Error {
+    honeylink_core::Error::Crypto(format!("Invalid share: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_arithmetic() {
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1); // FIPS-197 example
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(gf_div(1, a), a), 1);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_subset_recovers() {
+        let secret: Vec<u8> = (0..32).collect();
+        let shares = split_secret(&secret, 3, 5).unwrap();
+        let print = fingerprint(&secret);
+
+        for a in 0..5 {
+            for b in (a + 1)..5 {
+                for c in (b + 1)..5 {
+                    let subset = [shares[c].clone(), shares[a].clone(), shares[b].clone()];
+                    assert_eq!(&combine_verified(&subset, &print).unwrap()[..], &secret[..]);
+                }
+            }
+        }
+
+        // Too few shares, duplicates and invalid parameters are rejected
+        assert!(combine_shares(&shares[..2]).is_err());
+        assert!(
+            combine_shares(&[shares[0].clone(), shares[0].clone(), shares[1].clone()]).is_err()
+        );
+        assert!(split_secret(&secret, 4, 3).is_err());
+        assert!(split_secret(&secret, 1, 3).is_err());
+        assert!(split_secret(&secret, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_wrong_share_fails_fingerprint() {
+        let secret = [0xA5u8; 32];
+        let mut shares = split_secret(&secret, 2, 3).unwrap();
+        shares[1].data[0] ^= 1;
+
+        assert!(combine_verified(&shares, &fingerprint(&secret)).is_err());
+    }
+
+    #[test]
+    fn test_text_encodings() {
+        let shares = split_secret(&[42u8; 32], 2, 2).unwrap();
+        let share = &shares[0];
+
+        assert_eq!(&Share::parse(&share.to_base64()).unwrap(), share);
+        let qr = share.to_base32();
+        assert!(qr.bytes().all(|c| BASE32_ALPHABET.contains(&c)));
+        assert_eq!(&Share::parse(&qr).unwrap(), share);
+
+        // Transcription errors are caught by the checksum
+        let mut typo = qr.into_bytes();
+        typo[10] = if typo[10] == b'A' { b'B' } else { b'A' };
+        assert!(Share::parse(std::str::from_utf8(&typo).unwrap()).is_err());
+    }
+}
@@ -15,6 +15,13 @@ pub fn verify(verifying_key: &VerifyingKey, message: &[u8], signature: &Signatur
         .map_err(|e| honeylink_core::Error::Crypto(format!("Signature verification failed: {}", e)))
 }
 
+/// Fingerprint of a public key for out-of-band comparison
+///
+/// Same `SHA256:<base64url>` format as `shamir::fingerprint`.
+pub fn fingerprint(verifying_key: &VerifyingKey) -> String {
+    crate::shamir::fingerprint(verifying_key.as_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,4 +39,14 @@ mod tests {
         let signature = sign(&signing_key, message);
         assert!(verify(&verifying_key, message, &signature).is_ok());
     }
+
+    #[test]
+    fn test_fingerprint_is_stable_per_key() {
+        let a = SigningKey::from_bytes(&[1u8; 32]).verifying_key();
+        let b = SigningKey::from_bytes(&[2u8; 32]).verifying_key();
+
+        assert!(fingerprint(&a).starts_with("SHA256:"));
+        assert_eq!(fingerprint(&a), fingerprint(&a));
+        assert_ne!(fingerprint(&a), fingerprint(&b));
+    }
 }
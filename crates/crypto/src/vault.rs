@@ -24,7 +24,7 @@
 //! client.store_key(
 //!     KeyScope::Service,
 //!     "session-orchestrator",
-//!     &[0u8; 32],
+//!     vec![0u8; 32],
 //!     std::time::Duration::from_secs(90 * 24 * 3600), // 90 days
 //! ).await?;
 //!
@@ -34,12 +34,13 @@
 //! # }
 //! ```
 
-use serde::{Deserialize, Serialize};
+use crate::key_store::{EncodedKey, KeyStore, KeyStoreError};
+use async_trait::async_trait;
 use std::time::Duration;
 use thiserror::Error;
 use vaultrs::client::{VaultClient as VrsClient, VaultClientSettingsBuilder};
 use vaultrs::kv2;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::Zeroize;
 
 /// Errors that can occur during Vault operations.
 #[derive(Debug, Error)]
@@ -72,90 +73,19 @@ pub enum VaultError {
     Serialization(#[from] serde_json::Error),
 }
 
-/// Key scope in the HoneyLink hierarchy.
-///
-/// Maps to Vault paths:
-/// - Root -> honeylink/k_root
-/// - Service -> honeylink/k_service
-/// - Profile -> honeylink/k_profile
-/// - Telemetry -> honeylink/k_telemetry
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum KeyScope {
-    /// Root trust anchor (HSM-backed, rarely accessed)
-    Root,
-    /// Service-level master keys (Session Orchestrator, Policy Engine, etc.)
-    Service,
-    /// Profile data encryption keys
-    Profile,
-    /// Telemetry data encryption keys
-    Telemetry,
-}
+pub use crate::key_management::KeyScope;
+pub use crate::key_store::{KeyMaterial, KeyMetadata};
 
 impl KeyScope {
     /// Returns the Vault path prefix for this scope.
+    ///
+    /// Maps to Vault paths under the KV mount, e.g.
+    /// Root -> honeylink/k_root, Service -> honeylink/k_service.
     pub fn vault_path(&self) -> &'static str {
-        match self {
-            KeyScope::Root => "k_root",
-            KeyScope::Service => "k_service",
-            KeyScope::Profile => "k_profile",
-            KeyScope::Telemetry => "k_telemetry",
-        }
-    }
-
-    /// Returns the recommended rotation interval for keys in this scope.
-    pub fn rotation_interval(&self) -> Duration {
-        match self {
-            KeyScope::Root => Duration::from_secs(5 * 365 * 24 * 3600), // 5 years
-            KeyScope::Service => Duration::from_secs(365 * 24 * 3600),   // 1 year
-            KeyScope::Profile => Duration::from_secs(90 * 24 * 3600),    // 90 days
-            KeyScope::Telemetry => Duration::from_secs(90 * 24 * 3600),  // 90 days
-        }
+        self.storage_path()
     }
 }
 
-/// Metadata for a stored key.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KeyMetadata {
-    /// Key scope (root, service, profile, telemetry)
-    pub scope: KeyScope,
-    /// Key identifier (e.g., "session-orchestrator", "device-12345")
-    pub name: String,
-    /// Key version (increments on rotation)
-    pub version: u32,
-    /// Creation timestamp (RFC 3339)
-    pub created_at: String,
-    /// Expiration timestamp (RFC 3339)
-    pub expires_at: String,
-    /// Algorithm used (e.g., "X25519", "ChaCha20-Poly1305")
-    pub algorithm: String,
-    /// Environment (dev, staging, production)
-    pub environment: String,
-}
-
-/// A key material wrapper that automatically zeroizes on drop.
-#[derive(Clone, Zeroize, ZeroizeOnDrop)]
-pub struct KeyMaterial {
-    #[zeroize(skip)]
-    pub metadata: KeyMetadata,
-    pub data: Vec<u8>,
-}
-
-impl std::fmt::Debug for KeyMaterial {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("KeyMaterial")
-            .field("metadata", &self.metadata)
-            .field("data", &"[REDACTED]")
-            .finish()
-    }
-}
-
-/// Vault secret format (internal representation).
-#[derive(Debug, Serialize, Deserialize)]
-struct VaultSecret {
-    key_data: String, // Base64-encoded key material
-    metadata: KeyMetadata,
-}
-
 /// HoneyLink Vault client for key management.
 ///
 /// Implements the key hierarchy defined in spec/security/encryption.md.
@@ -210,7 +140,7 @@ impl VaultClient {
         settings_builder.token(token);
 
         if let Some(ns) = namespace {
-            settings_builder.namespace(ns);
+            settings_builder.namespace(Some(ns.to_string()));
         }
 
         let settings = settings_builder
@@ -246,38 +176,23 @@ impl VaultClient {
         mut key_data: Vec<u8>,
         ttl: Duration,
     ) -> Result<(), VaultError> {
-        let now = chrono::Utc::now();
-        let expires_at = now + chrono::Duration::from_std(ttl).unwrap();
-
-        let metadata = KeyMetadata {
-            scope,
-            name: name.to_string(),
-            version: 1, // Will be incremented on rotation
-            created_at: now.to_rfc3339(),
-            expires_at: expires_at.to_rfc3339(),
-            algorithm: "X25519".to_string(),
-            environment: self.environment.clone(),
-        };
-
-        // Encode key data as base64
-        let encoded_key = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &key_data);
+        let material = KeyMaterial::new(scope, name, key_data.clone(), ttl, &self.environment);
 
         // Zeroize original key material
         key_data.zeroize();
 
-        let secret = VaultSecret {
-            key_data: encoded_key,
-            metadata,
-        };
+        self.write_material(&material).await
+    }
 
-        let path = format!("{}/{}", scope.vault_path(), name);
+    /// Writes key material (with its metadata) to the key's Vault path.
+    async fn write_material(&self, material: &KeyMaterial) -> Result<(), VaultError> {
+        let path = format!("{}/{}", material.metadata.scope.vault_path(), material.metadata.name);
 
         kv2::set(
             &self.client,
             &self.mount,
             &path,
-            &serde_json::to_value(&secret)
-                .map_err(|e| VaultError::Serialization(e))?,
+            &serde_json::to_value(EncodedKey::encode(material))?,
         )
         .await
         .map_err(|e| VaultError::ApiError(format!("Failed to store key: {}", e)))?;
@@ -285,6 +200,28 @@ impl VaultClient {
         Ok(())
     }
 
+    /// Reads the latest key version without checking expiration.
+    async fn read_material(&self, scope: KeyScope, name: &str) -> Result<KeyMaterial, VaultError> {
+        let path = format!("{}/{}", scope.vault_path(), name);
+
+        let secret: EncodedKey = kv2::read(&self.client, &self.mount, &path)
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("404") {
+                    VaultError::KeyNotFound {
+                        scope,
+                        name: name.to_string(),
+                    }
+                } else {
+                    VaultError::ApiError(format!("Failed to retrieve key: {}", e))
+                }
+            })?;
+
+        secret
+            .decode()
+            .map_err(|e| VaultError::InvalidKeyFormat(e.to_string()))
+    }
+
     /// Retrieves a key from Vault.
     ///
     /// # Arguments
@@ -304,40 +241,20 @@ impl VaultClient {
         scope: KeyScope,
         name: &str,
     ) -> Result<KeyMaterial, VaultError> {
-        let path = format!("{}/{}", scope.vault_path(), name);
-
-        let secret: VaultSecret = kv2::read(&self.client, &self.mount, &path)
-            .await
-            .map_err(|e| {
-                if e.to_string().contains("404") {
-                    VaultError::KeyNotFound {
-                        scope,
-                        name: name.to_string(),
-                    }
-                } else {
-                    VaultError::ApiError(format!("Failed to retrieve key: {}", e))
-                }
-            })?;
+        let material = self.read_material(scope, name).await?;
 
         // Check expiration
-        let expires_at = chrono::DateTime::parse_from_rfc3339(&secret.metadata.expires_at)
+        chrono::DateTime::parse_from_rfc3339(&material.metadata.expires_at)
             .map_err(|e| VaultError::InvalidKeyFormat(e.to_string()))?;
 
-        if expires_at < chrono::Utc::now() {
+        if material.metadata.is_expired() {
             return Err(VaultError::KeyExpired {
                 scope,
                 name: name.to_string(),
             });
         }
 
-        // Decode key data
-        let key_bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &secret.key_data)
-            .map_err(|e| VaultError::InvalidKeyFormat(e.to_string()))?;
-
-        Ok(KeyMaterial {
-            metadata: secret.metadata,
-            data: key_bytes,
-        })
+        Ok(material)
     }
 
     /// Lists all keys in a given scope.
@@ -384,7 +301,7 @@ impl VaultClient {
     ) -> Result<(), VaultError> {
         let path = format!("{}/{}", scope.vault_path(), name);
 
-        kv2::destroy(&self.client, &self.mount, &path, versions)
+        kv2::destroy_versions(&self.client, &self.mount, &path, versions)
             .await
             .map_err(|e| VaultError::ApiError(format!("Failed to destroy key: {}", e)))?;
 
@@ -397,7 +314,8 @@ impl VaultClient {
     /// 1. Retrieve current key
     /// 2. Generate new key material (caller responsibility)
     /// 3. Store new version with incremented version number
-    /// 4. Old version remains accessible for grace period
+    /// 4. Old version is kept in the KV v2 version history (not returned by
+    ///    `retrieve_key`)
     pub async fn rotate_key(
         &self,
         scope: KeyScope,
@@ -406,13 +324,15 @@ impl VaultClient {
     ) -> Result<u32, VaultError> {
         // Retrieve current key to get version
         let current = self.retrieve_key(scope, name).await?;
-        let new_version = current.metadata.version + 1;
+        let material = KeyMaterial {
+            metadata: current.metadata.next_version(scope.rotation_interval()),
+            data: new_key_data,
+        };
 
         // Store new version
-        let ttl = scope.rotation_interval();
-        self.store_key(scope, name, new_key_data, ttl).await?;
+        self.write_material(&material).await?;
 
-        Ok(new_version)
+        Ok(material.metadata.version)
     }
 
     /// Health check for Vault connectivity.
@@ -429,8 +349,56 @@ impl VaultClient {
     }
 }
 
-// Required to satisfy the vault feature
-use base64::Engine as _;
+impl From<VaultError> for KeyStoreError {
+    fn from(e: VaultError) -> Self {
+        match e {
+            VaultError::KeyNotFound { scope, name } => KeyStoreError::NotFound { scope, name },
+            VaultError::InvalidKeyFormat(msg) => KeyStoreError::InvalidFormat(msg),
+            e => KeyStoreError::Backend(e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl KeyStore for VaultClient {
+    fn backend(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn put(&self, material: KeyMaterial) -> Result<(), KeyStoreError> {
+        crate::key_store::validate_key_name(&material.metadata.name)?;
+        Ok(self.write_material(&material).await?)
+    }
+
+    async fn get(&self, scope: KeyScope, name: &str) -> Result<KeyMaterial, KeyStoreError> {
+        Ok(self.read_material(scope, name).await?)
+    }
+
+    async fn list(&self, scope: KeyScope) -> Result<Vec<String>, KeyStoreError> {
+        match self.list_keys(scope).await {
+            Ok(keys) => Ok(keys),
+            // Vault answers 404 for an empty scope
+            Err(VaultError::ApiError(msg)) if msg.contains("404") => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError> {
+        Ok(self.delete_key(scope, name).await?)
+    }
+
+    async fn destroy(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError> {
+        let path = format!("{}/{}", scope.vault_path(), name);
+
+        kv2::delete_metadata(&self.client, &self.mount, &path)
+            .await
+            .map_err(|e| KeyStoreError::Backend(format!("Failed to destroy key: {}", e)))
+    }
+
+    async fn health_check(&self) -> Result<(), KeyStoreError> {
+        Ok(VaultClient::health_check(self).await?)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -346,7 +346,7 @@ async fn test_lifecycle_rotation() {
 
     // Cleanup
     lifecycle
-        .destroy(CryptoKeyScope::Session, key_name, vec![1, 2])
+        .destroy(CryptoKeyScope::Session, key_name)
         .await
         .ok();
 }
@@ -429,7 +429,10 @@ async fn test_key_metadata() {
     assert_eq!(retrieved.metadata.scope, KeyScope::Service);
     assert_eq!(retrieved.metadata.name, key_name);
     assert_eq!(retrieved.metadata.version, 1);
-    assert_eq!(retrieved.metadata.algorithm, "X25519");
+    assert_eq!(
+        retrieved.metadata.algorithm,
+        honeylink_crypto::key_store::UNSPECIFIED_ALGORITHM
+    );
     assert_eq!(retrieved.metadata.environment, "development");
 
     // Verify timestamps are valid RFC3339
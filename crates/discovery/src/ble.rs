@@ -2,44 +2,68 @@
 //!
 //! Provides BLE Peripheral (advertising) and Central (scanning) functionality
 //! for device discovery in scenarios where mDNS is not available (e.g., mobile networks).
-
-use crate::error::Result;
+//!
+//! All radio access goes through a `BleRadio` backend: `BtleplugRadio` for real
+//! adapters (`btleplug` feature) or `SimulatedRadio` for tests without hardware.
+//! No backend is selected by default: advertising and scanning fail until one
+//! is attached with `BleDiscovery::with_radio()`.
+
+use crate::capabilities::DeviceCapabilities;
+use crate::error::{DiscoveryError, Result};
+use crate::gatt::{
+    GattDeviceInfo, GattPairingState, PairingState, DEVICE_INFO_CHAR_UUID, HONEYLINK_SERVICE_UUID,
+    PAIRING_STATE_CHAR_UUID,
+};
+use crate::radio::{BleAdvertisement, BleRadio, GattServer, ScanResult};
 use crate::types::{DeviceInfo, DeviceType, DiscoveryEvent};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
-use tracing::info;
-
-// BLE UUIDs for HoneyLink
-// Service UUID: 0000FE00-0000-1000-8000-00805F9B34FB (HoneyLink P2P Service)
-#[allow(dead_code)]
-const HONEYLINK_SERVICE_UUID: &str = "0000FE00-0000-1000-8000-00805F9B34FB";
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
-// Characteristic UUIDs
-// Device Info: 0000FE01-0000-1000-8000-00805F9B34FB
-#[allow(dead_code)]
-const DEVICE_INFO_CHAR_UUID: &str = "0000FE01-0000-1000-8000-00805F9B34FB";
+/// Prefix for device IDs of peers found over BLE
+///
+/// The full device ID is only exchanged after pairing; until then BLE peers
+/// are identified by the hex-encoded short ID from the Device Info characteristic.
+pub const BLE_DEVICE_ID_PREFIX: &str = "BLE-";
 
 /// BLE Discovery implementation
 pub struct BleDiscovery {
     /// Own device information
     device_id: String,
     device_name: String,
-    #[allow(dead_code)]
     device_type: DeviceType,
 
+    /// Capabilities exposed via the Device Info characteristic
+    capabilities: DeviceCapabilities,
+
+    /// Radio backend (hardware or simulated), if one was attached
+    radio: Option<Arc<dyn BleRadio>>,
+
+    /// Local GATT table served while advertising
+    gatt: Arc<GattServer>,
+
     /// Event sender
-    #[allow(dead_code)]
     event_tx: mpsc::Sender<DiscoveryEvent>,
 
     /// Running state
     running: Arc<Mutex<bool>>,
 
+    /// Background task processing scan results
+    scan_task: Option<JoinHandle<()>>,
+
     /// Discovered devices (device_id -> DeviceInfo)
     discovered_devices: Arc<Mutex<std::collections::HashMap<String, DeviceInfo>>>,
+
+    /// Radio addresses of discovered devices (device_id -> address)
+    peer_addresses: Arc<Mutex<std::collections::HashMap<String, String>>>,
 }
 
 impl BleDiscovery {
     /// Create new BLE discovery service
+    ///
+    /// No radio is attached: call `with_radio()` before advertising or
+    /// scanning, otherwise they return `DiscoveryError::BleError`.
     pub fn new(
         device_id: &str,
         device_name: &str,
@@ -47,70 +71,148 @@ impl BleDiscovery {
         event_tx: mpsc::Sender<DiscoveryEvent>,
     ) -> Result<Self> {
         let device_type = DeviceType::from_str(device_type);
+        let gatt = GattServer::honeylink(
+            GattDeviceInfo::new(device_id, device_type).to_bytes(),
+            GattPairingState::new_with_random_nonce(PairingState::Idle).to_bytes(),
+        );
 
         Ok(Self {
             device_id: device_id.to_string(),
             device_name: device_name.to_string(),
             device_type,
+            capabilities: DeviceCapabilities::default(),
+            radio: None,
+            gatt: Arc::new(gatt),
             event_tx,
             running: Arc::new(Mutex::new(false)),
+            scan_task: None,
             discovered_devices: Arc::new(Mutex::new(std::collections::HashMap::new())),
+            peer_addresses: Arc::new(Mutex::new(std::collections::HashMap::new())),
+        })
+    }
+
+    /// Set radio backend (e.g. `BtleplugRadio` or a `SimulatedRadio`)
+    pub fn with_radio(mut self, radio: Arc<dyn BleRadio>) -> Self {
+        self.radio = Some(radio);
+        self
+    }
+
+    /// Set capabilities to advertise
+    pub fn with_capabilities(mut self, capabilities: DeviceCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Whether a radio backend is attached
+    pub fn has_radio(&self) -> bool {
+        self.radio.is_some()
+    }
+
+    /// Attached radio backend, or an error if none was configured
+    fn radio(&self) -> Result<&Arc<dyn BleRadio>> {
+        self.radio.as_ref().ok_or_else(|| {
+            warn!(device_id = %self.device_id, "BLE requested but no radio backend is configured");
+            DiscoveryError::BleError(
+                "no BLE radio configured; attach one with BleDiscovery::with_radio()".to_string(),
+            )
         })
     }
 
+    /// Value of the GATT Device Info characteristic for this device
+    ///
+    /// Capabilities are packed into the 20-byte value (see `GattDeviceInfo`).
+    pub fn gatt_device_info(&self) -> GattDeviceInfo {
+        GattDeviceInfo::new(&self.device_id, self.device_type)
+            .with_capabilities(self.capabilities.clone())
+    }
+
     /// Start BLE advertising (Peripheral mode)
     ///
-    /// Advertises device as HoneyLink service with device info in advertisement data
+    /// Advertises the HoneyLink service UUID with the device name as local name,
+    /// and serves the Device Info and Pairing State characteristics.
     pub async fn start_advertising(&mut self) -> Result<()> {
+        let radio = self.radio()?.clone();
         info!(
             device_id = %self.device_id,
             device_name = %self.device_name,
             "Starting BLE advertising"
         );
 
-        *self.running.lock().await = true;
+        self.gatt
+            .set_value(
+                DEVICE_INFO_CHAR_UUID,
+                self.gatt_device_info().to_bytes(),
+                false,
+            )
+            .await;
+
+        let advertisement =
+            BleAdvertisement::new(HONEYLINK_SERVICE_UUID).with_local_name(&self.device_name);
+        radio
+            .start_advertising(advertisement, self.gatt.clone())
+            .await?;
 
-        // TODO: Implement BLE advertising using btleplug
-        // - Create peripheral
-        // - Set advertisement data (service UUID + device info)
-        // - Start advertising
+        *self.running.lock().await = true;
 
-        info!("BLE advertising started (placeholder)");
+        info!(address = %radio.address(), "BLE advertising started");
         Ok(())
     }
 
     /// Start BLE scanning (Central mode)
     ///
-    /// Scans for nearby HoneyLink devices advertising the service UUID
+    /// Scans for nearby HoneyLink devices advertising the service UUID. The
+    /// Device Info characteristic of each new peer is read once and a
+    /// `DeviceFound` event is sent; later advertisements only refresh RSSI.
     pub async fn start_scanning(&mut self) -> Result<()> {
+        let radio = self.radio()?.clone();
         info!("Starting BLE scanning for HoneyLink devices");
 
-        *self.running.lock().await = true;
+        let mut scan_rx = radio.start_scan(HONEYLINK_SERVICE_UUID).await?;
+
+        let devices = self.discovered_devices.clone();
+        let peers = self.peer_addresses.clone();
+        let event_tx = self.event_tx.clone();
 
-        // TODO: Implement BLE scanning using btleplug
-        // - Create central
-        // - Filter by HoneyLink service UUID
-        // - Handle scan results
-        // - Read device info characteristic
-        // - Measure RSSI
-        // - Send DeviceFound events
+        let task = tokio::spawn(async move {
+            while let Some(result) = scan_rx.recv().await {
+                handle_scan_result(radio.as_ref(), result, &devices, &peers, &event_tx).await;
+            }
+        });
+
+        if let Some(previous) = self.scan_task.replace(task) {
+            previous.abort();
+        }
+
+        *self.running.lock().await = true;
 
-        info!("BLE scanning started (placeholder)");
+        info!("BLE scanning started");
         Ok(())
     }
 
+    /// Stop BLE scanning (advertising continues)
+    pub async fn stop_scanning(&mut self) -> Result<()> {
+        if let Some(task) = self.scan_task.take() {
+            task.abort();
+        }
+        match &self.radio {
+            Some(radio) => radio.stop_scan().await,
+            None => Ok(()),
+        }
+    }
+
     /// Stop BLE discovery (graceful shutdown)
     pub async fn stop(&mut self) -> Result<()> {
         info!(device_id = %self.device_id, "Stopping BLE discovery");
 
         *self.running.lock().await = false;
 
-        // TODO: Implement shutdown
-        // - Stop advertising
-        // - Stop scanning
-        // - Cleanup resources
+        self.stop_scanning().await?;
+        if let Some(radio) = &self.radio {
+            radio.stop_advertising().await?;
+        }
 
         self.discovered_devices.lock().await.clear();
+        self.peer_addresses.lock().await.clear();
 
         info!("BLE discovery stopped");
         Ok(())
@@ -125,6 +227,132 @@ impl BleDiscovery {
             .cloned()
             .collect()
     }
+
+    /// Current value of the local Pairing State characteristic
+    ///
+    /// Reflects writes made by remote centrals.
+    pub async fn pairing_state(&self) -> Result<GattPairingState> {
+        let value = self.gatt.read(PAIRING_STATE_CHAR_UUID).await?;
+        GattPairingState::from_bytes(&value).map_err(DiscoveryError::InvalidDeviceInfo)
+    }
+
+    /// Update the local Pairing State characteristic
+    pub async fn set_pairing_state(&self, state: &GattPairingState) {
+        self.gatt
+            .set_value(PAIRING_STATE_CHAR_UUID, state.to_bytes(), true)
+            .await;
+    }
+
+    /// Read a discovered peer's Pairing State characteristic
+    pub async fn read_peer_pairing_state(&self, device_id: &str) -> Result<GattPairingState> {
+        let address = self.peer_address(device_id).await?;
+        let value = self
+            .radio()?
+            .read_characteristic(&address, PAIRING_STATE_CHAR_UUID)
+            .await?;
+        GattPairingState::from_bytes(&value).map_err(DiscoveryError::InvalidDeviceInfo)
+    }
+
+    /// Write a discovered peer's Pairing State characteristic
+    pub async fn write_peer_pairing_state(
+        &self,
+        device_id: &str,
+        state: &GattPairingState,
+    ) -> Result<()> {
+        let address = self.peer_address(device_id).await?;
+        self.radio()?
+            .write_characteristic(&address, PAIRING_STATE_CHAR_UUID, &state.to_bytes())
+            .await
+    }
+
+    async fn peer_address(&self, device_id: &str) -> Result<String> {
+        self.peer_addresses
+            .lock()
+            .await
+            .get(device_id)
+            .cloned()
+            .ok_or_else(|| DiscoveryError::BleError(format!("Unknown BLE peer {}", device_id)))
+    }
+}
+
+/// Device ID assigned to a BLE peer before pairing
+fn ble_device_id(info: &GattDeviceInfo) -> String {
+    let hex: String = info
+        .device_id_short
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("{}{}", BLE_DEVICE_ID_PREFIX, hex)
+}
+
+/// BLE device ID under which a peer with full ID `device_id` is reported
+pub fn ble_device_id_for(device_id: &str) -> String {
+    ble_device_id(&GattDeviceInfo::new(device_id, DeviceType::Unknown))
+}
+
+/// Process one advertisement received while scanning
+async fn handle_scan_result(
+    radio: &dyn BleRadio,
+    result: ScanResult,
+    devices: &Mutex<std::collections::HashMap<String, DeviceInfo>>,
+    peers: &Mutex<std::collections::HashMap<String, String>>,
+    event_tx: &mpsc::Sender<DiscoveryEvent>,
+) {
+    // Known peer: refresh signal strength only
+    let known = peers
+        .lock()
+        .await
+        .iter()
+        .find(|(_, address)| **address == result.address)
+        .map(|(device_id, _)| device_id.clone());
+    if let Some(device_id) = known {
+        if let Some(device) = devices.lock().await.get_mut(&device_id) {
+            device.rssi = result.rssi.or(device.rssi);
+        }
+        return;
+    }
+
+    // New peer: read Device Info (retried on the next advertisement if lost)
+    let value = match radio
+        .read_characteristic(&result.address, DEVICE_INFO_CHAR_UUID)
+        .await
+    {
+        Ok(value) => value,
+        Err(e) => {
+            debug!(address = %result.address, error = %e, "BLE Device Info read failed");
+            return;
+        }
+    };
+
+    let info = match GattDeviceInfo::from_bytes(&value) {
+        Ok(info) => info,
+        Err(e) => {
+            warn!(address = %result.address, error = %e, "Invalid BLE Device Info");
+            return;
+        }
+    };
+
+    let device_id = ble_device_id(&info);
+    let device_name = result
+        .advertisement
+        .local_name
+        .clone()
+        .unwrap_or_else(|| device_id.clone());
+    let mut device = DeviceInfo::new(device_id.clone(), device_name, info.device_type)
+        .with_capabilities(info.capabilities);
+    device.rssi = result.rssi;
+
+    info!(
+        device_id = %device_id,
+        address = %result.address,
+        rssi = ?result.rssi,
+        "BLE device found"
+    );
+
+    peers.lock().await.insert(device_id.clone(), result.address);
+    devices.lock().await.insert(device_id, device.clone());
+
+    let _ = event_tx.send(DiscoveryEvent::DeviceFound(device)).await;
 }
 
 // DiscoveryProtocol trait implementation for BleDiscovery
@@ -151,8 +379,7 @@ impl DiscoveryProtocol for BleDiscovery {
     }
 
     async fn stop_browsing(&mut self) -> Result<()> {
-        // Scanning is stopped via running flag in stop()
-        Ok(())
+        self.stop_scanning().await
     }
 
     async fn get_devices(&self) -> HashMap<String, DeviceInfo> {
@@ -167,18 +394,42 @@ impl DiscoveryProtocol for BleDiscovery {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sim_radio::SimulatedMedium;
 
     #[tokio::test]
     async fn test_ble_creation() {
         let (tx, _rx) = mpsc::channel(10);
         let ble = BleDiscovery::new("DEV-TEST-BLE-001", "Test BLE Device", "mobile", tx);
         assert!(ble.is_ok());
+        assert!(!ble.unwrap().has_radio());
+    }
+
+    #[tokio::test]
+    async fn test_ble_without_radio_fails() {
+        let (tx, _rx) = mpsc::channel(10);
+        let mut ble = BleDiscovery::new("DEV-TEST-BLE-004", "Test Device", "mobile", tx).unwrap();
+
+        assert!(matches!(
+            ble.start_advertising().await,
+            Err(DiscoveryError::BleError(_))
+        ));
+        assert!(ble.start_scanning().await.is_err());
+        assert!(!*ble.running.lock().await);
+        ble.stop().await.unwrap();
+    }
+
+    #[test]
+    fn test_ble_device_id_for_matches_advertised_id() {
+        let info = GattDeviceInfo::new("DEV-ALIAS", DeviceType::Desktop);
+        assert_eq!(ble_device_id_for("DEV-ALIAS"), ble_device_id(&info));
     }
 
     #[tokio::test]
     async fn test_ble_lifecycle() {
         let (tx, _rx) = mpsc::channel(10);
-        let mut ble = BleDiscovery::new("DEV-TEST-BLE-002", "Test Device", "mobile", tx).unwrap();
+        let mut ble = BleDiscovery::new("DEV-TEST-BLE-002", "Test Device", "mobile", tx)
+            .unwrap()
+            .with_radio(Arc::new(SimulatedMedium::new().radio("SIM-LIFECYCLE")));
 
         // Start advertising
         ble.start_advertising().await.unwrap();
@@ -189,6 +440,140 @@ mod tests {
         assert!(!*ble.running.lock().await);
     }
 
+    #[tokio::test]
+    async fn test_gatt_device_info_carries_capabilities() {
+        use crate::capabilities::{Service, TransportCapability};
+
+        let (tx, _rx) = mpsc::channel(10);
+        let caps = DeviceCapabilities::new()
+            .with_service(Service::MediaStream)
+            .with_transport(TransportCapability::WebRtc);
+        let ble = BleDiscovery::new("DEV-TEST-BLE-003", "Test Device", "mobile", tx)
+            .unwrap()
+            .with_capabilities(caps.clone());
+
+        let decoded = GattDeviceInfo::from_bytes(&ble.gatt_device_info().to_bytes()).unwrap();
+        assert_eq!(decoded.device_type, DeviceType::Mobile);
+        assert_eq!(decoded.capabilities, caps);
+    }
+
+    fn simulated_pair(
+        medium: &SimulatedMedium,
+    ) -> (BleDiscovery, BleDiscovery, mpsc::Receiver<DiscoveryEvent>) {
+        let (tx_a, _rx_a) = mpsc::channel(10);
+        let (tx_b, rx_b) = mpsc::channel(10);
+
+        let peripheral = BleDiscovery::new("DEV-BLE-PERIPH", "Sensor", "iot", tx_a)
+            .unwrap()
+            .with_radio(Arc::new(medium.radio("SIM-PERIPH")));
+        let central = BleDiscovery::new("DEV-BLE-CENTRAL", "Phone", "mobile", tx_b)
+            .unwrap()
+            .with_radio(Arc::new(medium.radio("SIM-CENTRAL")));
+
+        (peripheral, central, rx_b)
+    }
+
+    async fn next_found(rx: &mut mpsc::Receiver<DiscoveryEvent>) -> DeviceInfo {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .expect("no BLE discovery event")
+            .unwrap();
+        match event {
+            DiscoveryEvent::DeviceFound(device) => device,
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulated_discovery_emits_device_found() {
+        use crate::capabilities::Service;
+        use crate::sim_radio::LinkConditions;
+
+        let medium = SimulatedMedium::with_seed(1)
+            .with_advertising_interval(std::time::Duration::from_millis(10));
+        medium.set_link("SIM-PERIPH", "SIM-CENTRAL", LinkConditions::new(-67, 0.0));
+        let (peripheral, mut central, mut rx) = simulated_pair(&medium);
+        let mut peripheral = peripheral
+            .with_capabilities(DeviceCapabilities::new().with_service(Service::Telemetry));
+
+        peripheral.start_advertising().await.unwrap();
+        central.start_scanning().await.unwrap();
+
+        let device = next_found(&mut rx).await;
+        let expected_info = peripheral.gatt_device_info();
+        assert!(expected_info.matches_device_id("DEV-BLE-PERIPH"));
+        assert_eq!(device.device_id, ble_device_id(&expected_info));
+        assert_eq!(device.device_name, "Sensor");
+        assert_eq!(device.device_type, DeviceType::Iot);
+        assert_eq!(device.rssi, Some(-67));
+        assert!(device.capabilities.supports_service(Service::Telemetry));
+
+        // Repeated advertisements do not produce duplicate events
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(rx.try_recv().is_err());
+        assert_eq!(central.get_discovered_devices().await.len(), 1);
+
+        central.stop().await.unwrap();
+        peripheral.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_simulated_discovery_with_packet_loss() {
+        use crate::sim_radio::LinkConditions;
+
+        let medium = SimulatedMedium::with_seed(9)
+            .with_advertising_interval(std::time::Duration::from_millis(5))
+            .with_default_link(LinkConditions::new(-90, 0.6));
+        let (mut peripheral, mut central, mut rx) = simulated_pair(&medium);
+
+        peripheral.start_advertising().await.unwrap();
+        central.start_scanning().await.unwrap();
+
+        let device = next_found(&mut rx).await;
+        assert_eq!(device.rssi, Some(-90));
+    }
+
+    #[tokio::test]
+    async fn test_pairing_state_gatt_write() {
+        let medium = SimulatedMedium::with_seed(3)
+            .with_advertising_interval(std::time::Duration::from_millis(10));
+        let (mut peripheral, mut central, mut rx) = simulated_pair(&medium);
+
+        peripheral.start_advertising().await.unwrap();
+        central.start_scanning().await.unwrap();
+        let device = next_found(&mut rx).await;
+
+        assert_eq!(
+            central
+                .read_peer_pairing_state(&device.device_id)
+                .await
+                .unwrap()
+                .state,
+            PairingState::Idle
+        );
+
+        let request = GattPairingState::new(PairingState::Discovering, [7u8; 16]);
+        central
+            .write_peer_pairing_state(&device.device_id, &request)
+            .await
+            .unwrap();
+        assert_eq!(peripheral.pairing_state().await.unwrap(), request);
+
+        // Device Info is read-only
+        let address = central.peer_address(&device.device_id).await.unwrap();
+        assert!(central
+            .radio()
+            .unwrap()
+            .write_characteristic(&address, DEVICE_INFO_CHAR_UUID, &[0u8; 20])
+            .await
+            .is_err());
+
+        assert!(central
+            .read_peer_pairing_state("BLE-unknown")
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_service_uuid() {
         // Verify UUIDs are valid format
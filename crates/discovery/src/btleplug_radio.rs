@@ -0,0 +1,237 @@
+//! btleplug-backed BLE radio (real hardware)
+//!
+//! Enabled with the `btleplug` feature. btleplug only implements the Central
+//! role, so this backend scans and accesses peer GATT servers but cannot
+//! advertise; `start_advertising()` returns `DiscoveryError::BleError`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn example() -> honeylink_discovery::Result<()> {
+//! use honeylink_discovery::{BleDiscovery, BtleplugRadio};
+//! use std::sync::Arc;
+//! use tokio::sync::mpsc;
+//!
+//! let (tx, _rx) = mpsc::channel(100);
+//! let mut ble = BleDiscovery::new("DEV-001", "My Phone", "mobile", tx)?
+//!     .with_radio(Arc::new(BtleplugRadio::new().await?));
+//! ble.start_scanning().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{DiscoveryError, Result};
+use crate::radio::{BleAdvertisement, BleRadio, GattServer, ScanResult};
+use async_trait::async_trait;
+use btleplug::api::{Central, CentralEvent, Manager as _, Peripheral as _, ScanFilter, WriteType};
+use btleplug::platform::{Adapter, Manager, Peripheral};
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::debug;
+use uuid::Uuid;
+
+/// BLE radio backed by the first system Bluetooth adapter
+pub struct BtleplugRadio {
+    adapter: Adapter,
+    address: String,
+    scan_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl BtleplugRadio {
+    /// Open the first available Bluetooth adapter
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscoveryError::BleError` if the platform Bluetooth stack is
+    /// unavailable or no adapter is present.
+    pub async fn new() -> Result<Self> {
+        let manager = Manager::new().await.map_err(ble_error)?;
+        let adapter = manager
+            .adapters()
+            .await
+            .map_err(ble_error)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| DiscoveryError::BleError("No Bluetooth adapter found".to_string()))?;
+        let address = adapter.adapter_info().await.map_err(ble_error)?;
+
+        Ok(Self {
+            adapter,
+            address,
+            scan_task: Mutex::new(None),
+        })
+    }
+
+    /// Find a known peripheral by its address and make sure it is connected
+    async fn connected_peripheral(&self, address: &str) -> Result<Peripheral> {
+        let mut found = None;
+        for peripheral in self.adapter.peripherals().await.map_err(ble_error)? {
+            if peripheral
+                .address()
+                .to_string()
+                .eq_ignore_ascii_case(address)
+            {
+                found = Some(peripheral);
+                break;
+            }
+        }
+        let peripheral =
+            found.ok_or_else(|| DiscoveryError::BleError(format!("Peer {} not found", address)))?;
+
+        if !peripheral.is_connected().await.map_err(ble_error)? {
+            peripheral.connect().await.map_err(ble_error)?;
+        }
+        if peripheral.characteristics().is_empty() {
+            peripheral.discover_services().await.map_err(ble_error)?;
+        }
+
+        Ok(peripheral)
+    }
+}
+
+#[async_trait]
+impl BleRadio for BtleplugRadio {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn start_advertising(
+        &self,
+        _advertisement: BleAdvertisement,
+        _gatt: Arc<GattServer>,
+    ) -> Result<()> {
+        Err(DiscoveryError::BleError(
+            "btleplug backend does not support the Peripheral role".to_string(),
+        ))
+    }
+
+    async fn stop_advertising(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn start_scan(&self, service_uuid: &str) -> Result<mpsc::Receiver<ScanResult>> {
+        let service = parse_uuid(service_uuid)?;
+        let mut events = self.adapter.events().await.map_err(ble_error)?;
+        self.adapter
+            .start_scan(ScanFilter {
+                services: vec![service],
+            })
+            .await
+            .map_err(ble_error)?;
+
+        let (tx, rx) = mpsc::channel(64);
+        let adapter = self.adapter.clone();
+
+        let task = tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                let id = match event {
+                    CentralEvent::DeviceDiscovered(id) | CentralEvent::DeviceUpdated(id) => id,
+                    _ => continue,
+                };
+
+                let properties = match adapter.peripheral(&id).await {
+                    Ok(peripheral) => match peripheral.properties().await {
+                        Ok(Some(properties)) => properties,
+                        _ => continue,
+                    },
+                    Err(e) => {
+                        debug!(error = %e, "Failed to look up scanned peripheral");
+                        continue;
+                    }
+                };
+
+                // Some platforms ignore the scan filter
+                if !properties.services.contains(&service) {
+                    continue;
+                }
+
+                let result = ScanResult {
+                    address: properties.address.to_string(),
+                    advertisement: BleAdvertisement {
+                        local_name: properties.local_name,
+                        service_uuids: properties
+                            .services
+                            .iter()
+                            .map(|uuid| uuid.to_string().to_ascii_uppercase())
+                            .collect(),
+                    },
+                    rssi: properties.rssi,
+                };
+
+                if tx.send(result).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        if let Some(previous) = self
+            .scan_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(task)
+        {
+            previous.abort();
+        }
+
+        Ok(rx)
+    }
+
+    async fn stop_scan(&self) -> Result<()> {
+        if let Some(task) = self
+            .scan_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            task.abort();
+        }
+        self.adapter.stop_scan().await.map_err(ble_error)
+    }
+
+    async fn read_characteristic(&self, address: &str, char_uuid: &str) -> Result<Vec<u8>> {
+        let uuid = parse_uuid(char_uuid)?;
+        let peripheral = self.connected_peripheral(address).await?;
+        let characteristic = peripheral
+            .characteristics()
+            .into_iter()
+            .find(|c| c.uuid == uuid)
+            .ok_or_else(|| {
+                DiscoveryError::BleError(format!("Characteristic {} not found", char_uuid))
+            })?;
+
+        peripheral.read(&characteristic).await.map_err(ble_error)
+    }
+
+    async fn write_characteristic(
+        &self,
+        address: &str,
+        char_uuid: &str,
+        value: &[u8],
+    ) -> Result<()> {
+        let uuid = parse_uuid(char_uuid)?;
+        let peripheral = self.connected_peripheral(address).await?;
+        let characteristic = peripheral
+            .characteristics()
+            .into_iter()
+            .find(|c| c.uuid == uuid)
+            .ok_or_else(|| {
+                DiscoveryError::BleError(format!("Characteristic {} not found", char_uuid))
+            })?;
+
+        peripheral
+            .write(&characteristic, value, WriteType::WithResponse)
+            .await
+            .map_err(ble_error)
+    }
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid> {
+    Uuid::parse_str(value)
+        .map_err(|e| DiscoveryError::BleError(format!("Invalid UUID {}: {}", value, e)))
+}
+
+fn ble_error(e: btleplug::Error) -> DiscoveryError {
+    DiscoveryError::BleError(e.to_string())
+}
@@ -0,0 +1,515 @@
+//! Device capability advertisement
+//!
+//! Describes what a device can do so peers can decide whether it is worth
+//! connecting to before any session is established: exposed services, supported
+//! transports, protocol version range and supported policy presets.
+//!
+//! # Wire Formats
+//!
+//! - **mDNS TXT**: human-readable comma-separated lists
+//!   (`services=file_transfer,media_stream`, `transports=quic,datagram`,
+//!   `proto=1-1`, `presets=gaming_input`)
+//! - **BLE GATT**: packed bitmasks inside the 20-byte Device Info value
+//!   (see `gatt::GattDeviceInfo`)
+//!
+//! Devices that do not advertise capabilities (older builds) parse as
+//! `DeviceCapabilities::default()`: no services, no transports, protocol v1.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Current HoneyLink discovery/session protocol version
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// TXT record key for advertised services
+pub const TXT_KEY_SERVICES: &str = "services";
+
+/// TXT record key for supported transports
+pub const TXT_KEY_TRANSPORTS: &str = "transports";
+
+/// TXT record key for protocol version range (`min-max`)
+pub const TXT_KEY_PROTOCOL: &str = "proto";
+
+/// TXT record key for supported policy presets
+pub const TXT_KEY_PRESETS: &str = "presets";
+
+/// Application services a device exposes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Service {
+    /// Accepts file transfers
+    FileTransfer,
+    /// Accepts audio/video media streams
+    MediaStream,
+    /// Accepts chat/control messages
+    Messaging,
+    /// Accepts remote input (game controllers, keyboards)
+    RemoteInput,
+    /// Publishes sensor telemetry
+    Telemetry,
+}
+
+impl Service {
+    /// All known services (in bit order)
+    pub const ALL: [Service; 5] = [
+        Self::FileTransfer,
+        Self::MediaStream,
+        Self::Messaging,
+        Self::RemoteInput,
+        Self::Telemetry,
+    ];
+
+    /// Convert to TXT record token
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::FileTransfer => "file_transfer",
+            Self::MediaStream => "media_stream",
+            Self::Messaging => "messaging",
+            Self::RemoteInput => "remote_input",
+            Self::Telemetry => "telemetry",
+        }
+    }
+
+    /// Parse TXT record token (unknown tokens return None)
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// Bit position in the GATT services bitmask
+    fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+}
+
+/// Transports a device can accept connections on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportCapability {
+    /// QUIC streams
+    Quic,
+    /// WebRTC data channels
+    WebRtc,
+    /// Unreliable datagrams (QUIC DATAGRAM extension)
+    Datagram,
+}
+
+impl TransportCapability {
+    /// All known transports (in bit order)
+    pub const ALL: [TransportCapability; 3] = [Self::Quic, Self::WebRtc, Self::Datagram];
+
+    /// Convert to TXT record token
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Quic => "quic",
+            Self::WebRtc => "webrtc",
+            Self::Datagram => "datagram",
+        }
+    }
+
+    /// Parse TXT record token (unknown tokens return None)
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// Bit position in the GATT transports bitmask
+    fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+/// Policy presets a device can run (mirrors the policy engine presets)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyPreset {
+    /// `prof_iot_lowpower_v2`
+    IotLowPower,
+    /// `prof_arvr_spatial_v1`
+    ArVrSpatial,
+    /// `prof_media_8k_v1`
+    Media8k,
+    /// `prof_gaming_input_v1`
+    GamingInput,
+}
+
+impl PolicyPreset {
+    /// All known presets (in bit order)
+    pub const ALL: [PolicyPreset; 4] = [
+        Self::IotLowPower,
+        Self::ArVrSpatial,
+        Self::Media8k,
+        Self::GamingInput,
+    ];
+
+    /// Convert to TXT record token
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IotLowPower => "iot_lowpower",
+            Self::ArVrSpatial => "arvr_spatial",
+            Self::Media8k => "media_8k",
+            Self::GamingInput => "gaming_input",
+        }
+    }
+
+    /// Parse TXT record token (unknown tokens return None)
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// Bit position in the GATT presets bitmask
+    fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+}
+
+/// Capabilities advertised by a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceCapabilities {
+    /// Application services exposed by the device
+    pub services: BTreeSet<Service>,
+
+    /// Transports the device accepts
+    pub transports: BTreeSet<TransportCapability>,
+
+    /// Lowest supported protocol version
+    pub min_protocol_version: u8,
+
+    /// Highest supported protocol version
+    pub max_protocol_version: u8,
+
+    /// Policy presets the device can run
+    pub policy_presets: BTreeSet<PolicyPreset>,
+}
+
+impl Default for DeviceCapabilities {
+    fn default() -> Self {
+        Self {
+            services: BTreeSet::new(),
+            transports: BTreeSet::new(),
+            min_protocol_version: PROTOCOL_VERSION,
+            max_protocol_version: PROTOCOL_VERSION,
+            policy_presets: BTreeSet::new(),
+        }
+    }
+}
+
+impl DeviceCapabilities {
+    /// Create empty capabilities (current protocol version only)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an exposed service
+    pub fn with_service(mut self, service: Service) -> Self {
+        self.services.insert(service);
+        self
+    }
+
+    /// Add a supported transport
+    pub fn with_transport(mut self, transport: TransportCapability) -> Self {
+        self.transports.insert(transport);
+        self
+    }
+
+    /// Add a supported policy preset
+    pub fn with_policy_preset(mut self, preset: PolicyPreset) -> Self {
+        self.policy_presets.insert(preset);
+        self
+    }
+
+    /// Set supported protocol version range (inclusive)
+    pub fn with_protocol_versions(mut self, min: u8, max: u8) -> Self {
+        self.min_protocol_version = min.min(max);
+        self.max_protocol_version = min.max(max);
+        self
+    }
+
+    /// Check if the device exposes a service
+    pub fn supports_service(&self, service: Service) -> bool {
+        self.services.contains(&service)
+    }
+
+    /// Check if the device accepts a transport
+    pub fn supports_transport(&self, transport: TransportCapability) -> bool {
+        self.transports.contains(&transport)
+    }
+
+    /// Check if the device can run a policy preset
+    pub fn supports_policy_preset(&self, preset: PolicyPreset) -> bool {
+        self.policy_presets.contains(&preset)
+    }
+
+    /// Check if a protocol version is within the supported range
+    pub fn supports_protocol_version(&self, version: u8) -> bool {
+        (self.min_protocol_version..=self.max_protocol_version).contains(&version)
+    }
+
+    /// Highest protocol version both sides support, if any
+    pub fn negotiate_protocol_version(&self, other: &DeviceCapabilities) -> Option<u8> {
+        let max = self.max_protocol_version.min(other.max_protocol_version);
+        let min = self.min_protocol_version.max(other.min_protocol_version);
+        (min <= max).then_some(max)
+    }
+
+    /// Encode as mDNS TXT properties
+    pub fn to_txt_properties(&self) -> Vec<(String, String)> {
+        vec![
+            (
+                TXT_KEY_SERVICES.to_string(),
+                join_tokens(self.services.iter().map(|s| s.as_str())),
+            ),
+            (
+                TXT_KEY_TRANSPORTS.to_string(),
+                join_tokens(self.transports.iter().map(|t| t.as_str())),
+            ),
+            (
+                TXT_KEY_PROTOCOL.to_string(),
+                format!("{}-{}", self.min_protocol_version, self.max_protocol_version),
+            ),
+            (
+                TXT_KEY_PRESETS.to_string(),
+                join_tokens(self.policy_presets.iter().map(|p| p.as_str())),
+            ),
+        ]
+    }
+
+    /// Decode from mDNS TXT properties
+    ///
+    /// `get` looks up a TXT key. Missing keys and unknown tokens are ignored,
+    /// so records from older or newer peers always parse.
+    pub fn from_txt_properties<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Self {
+        let mut caps = Self::default();
+
+        if let Some(value) = get(TXT_KEY_SERVICES) {
+            caps.services = split_tokens(value).filter_map(Service::parse).collect();
+        }
+        if let Some(value) = get(TXT_KEY_TRANSPORTS) {
+            caps.transports = split_tokens(value)
+                .filter_map(TransportCapability::parse)
+                .collect();
+        }
+        if let Some((min, max)) = get(TXT_KEY_PROTOCOL).and_then(parse_version_range) {
+            caps = caps.with_protocol_versions(min, max);
+        }
+        if let Some(value) = get(TXT_KEY_PRESETS) {
+            caps.policy_presets = split_tokens(value).filter_map(PolicyPreset::parse).collect();
+        }
+
+        caps
+    }
+
+    /// Services as GATT bitmask
+    pub(crate) fn services_bits(&self) -> u16 {
+        self.services.iter().fold(0, |acc, s| acc | s.bit())
+    }
+
+    /// Transports as GATT bitmask
+    pub(crate) fn transports_bits(&self) -> u8 {
+        self.transports.iter().fold(0, |acc, t| acc | t.bit())
+    }
+
+    /// Policy presets as GATT bitmask
+    pub(crate) fn presets_bits(&self) -> u16 {
+        self.policy_presets.iter().fold(0, |acc, p| acc | p.bit())
+    }
+
+    /// Rebuild from GATT bitmasks (unknown bits are ignored)
+    pub(crate) fn from_bits(
+        services: u16,
+        transports: u8,
+        min_protocol_version: u8,
+        max_protocol_version: u8,
+        presets: u16,
+    ) -> Self {
+        let mut caps = Self {
+            services: Service::ALL
+                .into_iter()
+                .filter(|s| services & s.bit() != 0)
+                .collect(),
+            transports: TransportCapability::ALL
+                .into_iter()
+                .filter(|t| transports & t.bit() != 0)
+                .collect(),
+            policy_presets: PolicyPreset::ALL
+                .into_iter()
+                .filter(|p| presets & p.bit() != 0)
+                .collect(),
+            ..Self::default()
+        };
+
+        // Zero version bytes come from pre-capability encoders: keep the default
+        if min_protocol_version != 0 || max_protocol_version != 0 {
+            caps = caps.with_protocol_versions(min_protocol_version, max_protocol_version);
+        }
+
+        caps
+    }
+}
+
+/// Capability requirements used to filter discovered devices
+///
+/// An empty filter matches every device. Each non-empty requirement must be
+/// satisfied in full (all listed services, all listed transports, ...).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapabilityFilter {
+    /// Required services
+    pub services: BTreeSet<Service>,
+
+    /// Required transports
+    pub transports: BTreeSet<TransportCapability>,
+
+    /// Required policy presets
+    pub policy_presets: BTreeSet<PolicyPreset>,
+
+    /// Protocol version the device must support
+    pub protocol_version: Option<u8>,
+}
+
+impl CapabilityFilter {
+    /// Create a filter that matches every device
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a service
+    pub fn require_service(mut self, service: Service) -> Self {
+        self.services.insert(service);
+        self
+    }
+
+    /// Require a transport
+    pub fn require_transport(mut self, transport: TransportCapability) -> Self {
+        self.transports.insert(transport);
+        self
+    }
+
+    /// Require a policy preset
+    pub fn require_policy_preset(mut self, preset: PolicyPreset) -> Self {
+        self.policy_presets.insert(preset);
+        self
+    }
+
+    /// Require support for a protocol version
+    pub fn require_protocol_version(mut self, version: u8) -> Self {
+        self.protocol_version = Some(version);
+        self
+    }
+
+    /// Check if the given capabilities satisfy this filter
+    pub fn matches(&self, caps: &DeviceCapabilities) -> bool {
+        self.services.is_subset(&caps.services)
+            && self.transports.is_subset(&caps.transports)
+            && self.policy_presets.is_subset(&caps.policy_presets)
+            && self
+                .protocol_version
+                .is_none_or(|v| caps.supports_protocol_version(v))
+    }
+}
+
+/// Join tokens with commas
+fn join_tokens<'a>(tokens: impl Iterator<Item = &'a str>) -> String {
+    tokens.collect::<Vec<_>>().join(",")
+}
+
+/// Split a comma-separated token list (empty tokens skipped)
+fn split_tokens(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|t| !t.is_empty())
+}
+
+/// Parse `min-max` (or a single `v`) version range
+fn parse_version_range(value: &str) -> Option<(u8, u8)> {
+    match value.split_once('-') {
+        Some((min, max)) => Some((min.trim().parse().ok()?, max.trim().parse().ok()?)),
+        None => {
+            let v = value.trim().parse().ok()?;
+            Some((v, v))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn sample() -> DeviceCapabilities {
+        DeviceCapabilities::new()
+            .with_service(Service::FileTransfer)
+            .with_service(Service::MediaStream)
+            .with_transport(TransportCapability::Quic)
+            .with_transport(TransportCapability::Datagram)
+            .with_policy_preset(PolicyPreset::GamingInput)
+            .with_protocol_versions(1, 2)
+    }
+
+    #[test]
+    fn test_txt_roundtrip() {
+        let caps = sample();
+        let txt: HashMap<String, String> = caps.to_txt_properties().into_iter().collect();
+
+        assert_eq!(txt["services"], "file_transfer,media_stream");
+        assert_eq!(txt["transports"], "quic,datagram");
+        assert_eq!(txt["proto"], "1-2");
+        assert_eq!(txt["presets"], "gaming_input");
+
+        let decoded = DeviceCapabilities::from_txt_properties(|k| txt.get(k).map(|v| v.as_str()));
+        assert_eq!(decoded, caps);
+    }
+
+    #[test]
+    fn test_txt_legacy_and_unknown_tokens() {
+        // Record without capability keys (older peer)
+        let legacy = DeviceCapabilities::from_txt_properties(|_| None);
+        assert_eq!(legacy, DeviceCapabilities::default());
+
+        // Unknown tokens from a newer peer are skipped
+        let txt: HashMap<&str, &str> =
+            HashMap::from([("services", "file_transfer,hologram"), ("proto", "garbage")]);
+        let caps = DeviceCapabilities::from_txt_properties(|k| txt.get(k).copied());
+        assert!(caps.supports_service(Service::FileTransfer));
+        assert_eq!(caps.services.len(), 1);
+        assert!(caps.supports_protocol_version(PROTOCOL_VERSION));
+    }
+
+    #[test]
+    fn test_bits_roundtrip() {
+        let caps = sample();
+        let decoded = DeviceCapabilities::from_bits(
+            caps.services_bits(),
+            caps.transports_bits(),
+            caps.min_protocol_version,
+            caps.max_protocol_version,
+            caps.presets_bits(),
+        );
+        assert_eq!(decoded, caps);
+    }
+
+    #[test]
+    fn test_protocol_negotiation() {
+        let a = DeviceCapabilities::new().with_protocol_versions(1, 3);
+        let b = DeviceCapabilities::new().with_protocol_versions(2, 5);
+        let c = DeviceCapabilities::new().with_protocol_versions(4, 5);
+
+        assert_eq!(a.negotiate_protocol_version(&b), Some(3));
+        assert_eq!(a.negotiate_protocol_version(&c), None);
+    }
+
+    #[test]
+    fn test_capability_filter() {
+        let caps = sample();
+
+        assert!(CapabilityFilter::new().matches(&caps));
+        assert!(CapabilityFilter::new()
+            .require_service(Service::FileTransfer)
+            .require_transport(TransportCapability::Quic)
+            .matches(&caps));
+        assert!(!CapabilityFilter::new()
+            .require_service(Service::Telemetry)
+            .matches(&caps));
+        assert!(!CapabilityFilter::new()
+            .require_policy_preset(PolicyPreset::Media8k)
+            .matches(&caps));
+        assert!(!CapabilityFilter::new()
+            .require_protocol_version(3)
+            .matches(&caps));
+    }
+}
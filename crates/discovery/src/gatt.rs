@@ -7,7 +7,7 @@
 //!
 //! - **Service UUID**: `0000FE00-0000-1000-8000-00805F9B34FB`
 //! - **Characteristics**:
-//!   - Device Info (0xFE01): Read-only, contains device_id, device_name, device_type
+//!   - Device Info (0xFE01): Read-only, contains device_id, device_type, capabilities
 //!   - Pairing State (0xFE02): Read/Write, contains pairing status and session info
 //!
 //! # Security
@@ -15,6 +15,7 @@
 //! All characteristics should be accessed only over encrypted BLE connections
 //! (LE Secure Connections with LESC pairing).
 
+use crate::capabilities::DeviceCapabilities;
 use crate::types::DeviceType;
 use serde::{Deserialize, Serialize};
 
@@ -25,7 +26,7 @@ pub const HONEYLINK_SERVICE_UUID: &str = "0000FE00-0000-1000-8000-00805F9B34FB";
 
 /// Device Info Characteristic UUID (Read-only)
 ///
-/// Contains: device_id, device_name, device_type
+/// Contains: device_id, device_type, capabilities
 pub const DEVICE_INFO_CHAR_UUID: &str = "0000FE01-0000-1000-8000-00805F9B34FB";
 
 /// Pairing State Characteristic UUID (Read/Write)
@@ -43,9 +44,17 @@ pub const MAX_GATT_VALUE_SIZE: usize = 20;
 /// Serialized format (binary, little-endian):
 /// - device_id: 8 bytes (truncated SHA256 of full ID)
 /// - device_type: 1 byte (enum)
-/// - reserved: 11 bytes (for future use, zero-filled)
+/// - services: 2 bytes (bitmask)
+/// - transports: 1 byte (bitmask)
+/// - min_protocol_version: 1 byte
+/// - max_protocol_version: 1 byte
+/// - policy_presets: 2 bytes (bitmask)
+/// - reserved: 4 bytes (for future use, zero-filled)
 ///
 /// Total: 20 bytes (fits in single BLE packet)
+///
+/// Values written by pre-capability encoders (zero-filled bytes 9..20) decode
+/// to `DeviceCapabilities::default()`.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GattDeviceInfo {
     /// Truncated device ID (first 8 bytes of SHA256)
@@ -56,9 +65,12 @@ pub struct GattDeviceInfo {
     /// Device type enum
     pub device_type: DeviceType,
 
+    /// Advertised capabilities (packed as bitmasks)
+    pub capabilities: DeviceCapabilities,
+
     /// Reserved for future protocol extensions
     #[serde(skip)]
-    reserved: [u8; 11],
+    reserved: [u8; 4],
 }
 
 impl GattDeviceInfo {
@@ -91,17 +103,35 @@ impl GattDeviceInfo {
         Self {
             device_id_short,
             device_type,
-            reserved: [0u8; 11],
+            capabilities: DeviceCapabilities::default(),
+            reserved: [0u8; 4],
         }
     }
 
+    /// Set advertised capabilities
+    pub fn with_capabilities(mut self, capabilities: DeviceCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Check if this value was produced from the given full device ID
+    pub fn matches_device_id(&self, device_id: &str) -> bool {
+        Self::new(device_id, self.device_type).device_id_short == self.device_id_short
+    }
+
     /// Serialize to binary format for GATT characteristic value
     ///
-    /// Format: [device_id_short(8) | device_type(1) | reserved(11)]
+    /// Format: [device_id_short(8) | device_type(1) | services(2) | transports(1) |
+    /// min_proto(1) | max_proto(1) | presets(2) | reserved(4)]
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(MAX_GATT_VALUE_SIZE);
         bytes.extend_from_slice(&self.device_id_short);
         bytes.push(self.device_type.to_u8());
+        bytes.extend_from_slice(&self.capabilities.services_bits().to_le_bytes());
+        bytes.push(self.capabilities.transports_bits());
+        bytes.push(self.capabilities.min_protocol_version);
+        bytes.push(self.capabilities.max_protocol_version);
+        bytes.extend_from_slice(&self.capabilities.presets_bits().to_le_bytes());
         bytes.extend_from_slice(&self.reserved);
         bytes
     }
@@ -124,14 +154,28 @@ impl GattDeviceInfo {
 
         let device_type = DeviceType::from_u8(data[8]);
 
-        let mut reserved = [0u8; 11];
+        // Capability bytes are optional (truncated or pre-capability values)
+        let capabilities = if data.len() >= 16 {
+            DeviceCapabilities::from_bits(
+                u16::from_le_bytes([data[9], data[10]]),
+                data[11],
+                data[12],
+                data[13],
+                u16::from_le_bytes([data[14], data[15]]),
+            )
+        } else {
+            DeviceCapabilities::default()
+        };
+
+        let mut reserved = [0u8; 4];
         if data.len() >= MAX_GATT_VALUE_SIZE {
-            reserved.copy_from_slice(&data[9..MAX_GATT_VALUE_SIZE]);
+            reserved.copy_from_slice(&data[16..MAX_GATT_VALUE_SIZE]);
         }
 
         Ok(Self {
             device_id_short,
             device_type,
+            capabilities,
             reserved,
         })
     }
@@ -291,6 +335,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_gatt_device_info_capabilities() {
+        use crate::capabilities::{PolicyPreset, Service, TransportCapability};
+
+        let caps = DeviceCapabilities::new()
+            .with_service(Service::FileTransfer)
+            .with_service(Service::MediaStream)
+            .with_transport(TransportCapability::Quic)
+            .with_transport(TransportCapability::WebRtc)
+            .with_policy_preset(PolicyPreset::Media8k)
+            .with_protocol_versions(1, 2);
+        let info =
+            GattDeviceInfo::new("DEV-TEST-001", DeviceType::Mobile).with_capabilities(caps.clone());
+
+        let bytes = info.to_bytes();
+        assert_eq!(bytes.len(), MAX_GATT_VALUE_SIZE);
+
+        let decoded = GattDeviceInfo::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.capabilities, caps);
+        assert!(decoded.matches_device_id("DEV-TEST-001"));
+        assert!(!decoded.matches_device_id("DEV-TEST-002"));
+    }
+
+    #[test]
+    fn test_gatt_device_info_legacy_value() {
+        // Pre-capability encoders zero-filled everything after device_type
+        let mut legacy = vec![0u8; MAX_GATT_VALUE_SIZE];
+        legacy[8] = DeviceType::Desktop.to_u8();
+
+        let decoded = GattDeviceInfo::from_bytes(&legacy).unwrap();
+        assert_eq!(decoded.capabilities, DeviceCapabilities::default());
+    }
+
     #[test]
     fn test_gatt_device_info_invalid_data() {
         let short_data = vec![0u8; 5];
@@ -11,6 +11,14 @@
 //! - **Network resilience**: Re-announce on network changes
 //! - **Mobile support**: BLE discovery for devices without mDNS
 //!
+//! # Features
+//!
+//! - `btleplug` (off by default): hardware BLE backend `BtleplugRadio`. Unlike
+//!   the rest of the crate it is not pure Rust: on Linux it links BlueZ through
+//!   `libdbus-sys` (C library and headers required at build time). Without it,
+//!   BLE needs an application-provided `BleRadio` passed to
+//!   `DiscoveryService::with_ble_radio()`.
+//!
 //! # Examples
 //!
 //! ```no_run
@@ -35,23 +43,39 @@
 //! ```
 
 pub mod ble;
+#[cfg(feature = "btleplug")]
+pub mod btleplug_radio;
+pub mod capabilities;
 pub mod error;
 pub mod gatt;
 pub mod manager;
 pub mod mdns;
 pub mod network_monitor;
 pub mod protocol;
+pub mod radio;
+pub mod sim_radio;
+pub mod subscription;
 pub mod types;
 
 pub use ble::BleDiscovery;
+#[cfg(feature = "btleplug")]
+pub use btleplug_radio::BtleplugRadio;
+pub use capabilities::{
+    CapabilityFilter, DeviceCapabilities, PolicyPreset, Service, TransportCapability,
+    PROTOCOL_VERSION,
+};
 pub use error::{DiscoveryError, Result};
 pub use gatt::{
     GattDeviceInfo, GattPairingState, PairingState, DEVICE_INFO_CHAR_UUID,
     HONEYLINK_SERVICE_UUID, MAX_GATT_VALUE_SIZE, PAIRING_STATE_CHAR_UUID,
 };
+pub use honeylink_config::DiscoveryConfig;
 pub use manager::DiscoveryManager;
 pub use mdns::MdnsDiscovery;
 pub use network_monitor::{NetworkEvent, NetworkMonitor};
+pub use radio::{BleAdvertisement, BleRadio, GattServer, ScanResult};
+pub use sim_radio::{LinkConditions, SimulatedMedium, SimulatedRadio};
+pub use subscription::{DiscoverySubscription, SubscriptionEvent, SubscriptionFilter};
 pub use types::{DeviceInfo, DeviceType, DiscoveryEvent};
 
 use tokio::sync::mpsc;
@@ -107,9 +131,52 @@ impl DiscoveryService {
         Ok(())
     }
 
+    /// Apply mDNS settings from `DiscoveryConfig`
+    ///
+    /// Uses the configured service name, interfaces, IPv6 setting and
+    /// subtypes. Call before `start()`.
+    pub fn with_config(mut self, config: &DiscoveryConfig) -> Self {
+        self.mdns = self.mdns.with_config(config);
+        self
+    }
+
+    /// Set the transport port advertised via mDNS
+    ///
+    /// Pass the port the transport is actually listening on; the transport
+    /// crate's `resolver::advertise_listen_addr()` does this from a listening
+    /// `TransportProtocol`. Call before `start()`.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.mdns = self.mdns.with_port(port);
+        self
+    }
+
+    /// Transport port advertised via mDNS
+    pub fn advertised_port(&self) -> u16 {
+        self.mdns.port()
+    }
+
+    /// Set capabilities advertised via mDNS TXT records and BLE GATT
+    ///
+    /// Call before `start()`.
+    pub fn with_capabilities(mut self, capabilities: DeviceCapabilities) -> Self {
+        self.mdns = self.mdns.with_capabilities(capabilities.clone());
+        self.ble = self.ble.map(|ble| ble.with_capabilities(capabilities));
+        self
+    }
+
+    /// Set the BLE radio backend (e.g. `BtleplugRadio`)
+    ///
+    /// Required before `enable_ble()`; there is no default radio.
+    pub fn with_ble_radio(mut self, radio: std::sync::Arc<dyn BleRadio>) -> Self {
+        self.ble = self.ble.map(|ble| ble.with_radio(radio));
+        self
+    }
+
     /// Enable BLE discovery (mobile device support)
     ///
-    /// Starts BLE advertising and scanning for devices where mDNS is unavailable
+    /// Starts BLE advertising and scanning for devices where mDNS is unavailable.
+    /// Fails with `DiscoveryError::BleError` if no radio was set with
+    /// `with_ble_radio()`.
     pub async fn enable_ble(&mut self) -> Result<()> {
         if let Some(ble) = &mut self.ble {
             ble.start_advertising().await?;
@@ -152,6 +219,21 @@ impl DiscoveryService {
         Ok(devices)
     }
 
+    /// Discover nearby devices that satisfy a capability filter
+    ///
+    /// Same as `discover_devices()`, but only devices whose advertised
+    /// capabilities match `filter` are returned (e.g. peers that accept file
+    /// transfers).
+    pub async fn discover_devices_matching(
+        &mut self,
+        timeout_secs: u64,
+        filter: &CapabilityFilter,
+    ) -> Result<Vec<DeviceInfo>> {
+        let mut devices = self.discover_devices(timeout_secs).await?;
+        devices.retain(|d| filter.matches(&d.capabilities));
+        Ok(devices)
+    }
+
     /// Stop discovery service (graceful shutdown)
     ///
     /// - Unregisters mDNS service
@@ -4,12 +4,15 @@
 //! unified API for device discovery. Handles device deduplication, protocol
 //! selection, and event aggregation.
 
+use crate::ble::{ble_device_id_for, BLE_DEVICE_ID_PREFIX};
+use crate::capabilities::CapabilityFilter;
 use crate::error::Result;
 use crate::protocol::{DiscoveryProtocol, ProtocolStrategy, ProtocolType};
+use crate::subscription::{DeviceMap, DiscoverySubscription, SubscriptionFilter};
 use crate::types::{DeviceInfo, DiscoveryEvent};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tracing::{debug, info, warn};
 
 /// Unified Discovery Manager
@@ -19,13 +22,16 @@ use tracing::{debug, info, warn};
 ///
 /// # Architecture
 /// - Aggregates multiple DiscoveryProtocol implementations
-/// - Deduplicates devices discovered via multiple protocols (by device_id)
-/// - Provides unified event stream for all discovery events
+/// - Deduplicates devices discovered via multiple protocols (by device_id,
+///   matching `BLE-<hex>` short IDs to full device IDs)
+/// - Provides unified event stream for all discovery events, with any number
+///   of subscribers (`subscribe()`)
 /// - Supports protocol selection strategies (prefer mDNS, fallback to BLE, etc.)
 ///
 /// # Thread Safety
 /// - All internal state is protected by `Arc<RwLock>` or `Arc<Mutex>`
-/// - Event channels use mpsc for cross-task communication
+/// - Protocol events arrive over mpsc (`protocol_event_sender()`) and are
+///   fanned out over a broadcast channel that never blocks the protocol tasks
 /// - Protocol implementations must be Send + Sync
 pub struct DiscoveryManager {
     /// Registered discovery protocols (keyed by protocol type)
@@ -35,12 +41,18 @@ pub struct DiscoveryManager {
     ///
     /// Stores deduplicated devices with source protocol tracking.
     /// If same device is discovered via multiple protocols, mDNS takes precedence.
-    devices: Arc<RwLock<HashMap<String, (DeviceInfo, ProtocolType)>>>,
+    devices: DeviceMap,
 
     /// Protocol selection strategy
     strategy: ProtocolStrategy,
 
-    /// Unified event sender
+    /// Event channel buffer size (per protocol input and broadcast)
+    channel_size: usize,
+
+    /// Broadcast sender for subscriptions
+    events: broadcast::Sender<DiscoveryEvent>,
+
+    /// Unified event sender (legacy single-consumer stream)
     event_tx: mpsc::Sender<DiscoveryEvent>,
 
     /// Unified event receiver (for external consumers)
@@ -55,18 +67,23 @@ impl DiscoveryManager {
     ///
     /// # Parameters
     /// - `strategy`: Protocol selection strategy (default: prefer mDNS)
-    /// - `channel_size`: Event channel buffer size (default: 100)
+    /// - `channel_size`: Event channel buffer size (default: 100). Subscribers
+    ///   lagging by more than this many events are resynchronized.
     ///
     /// # Returns
     /// A new DiscoveryManager instance with an empty protocol set.
     /// Call `register_protocol()` to add mDNS, BLE, or other backends.
     pub fn new(strategy: ProtocolStrategy, channel_size: usize) -> Self {
+        let channel_size = channel_size.max(1);
         let (event_tx, event_rx) = mpsc::channel(channel_size);
+        let (events, _) = broadcast::channel(channel_size);
 
         Self {
             protocols: Arc::new(RwLock::new(HashMap::new())),
             devices: Arc::new(RwLock::new(HashMap::new())),
             strategy,
+            channel_size,
+            events,
             event_tx,
             event_rx: Arc::new(Mutex::new(Some(event_rx))),
             running: Arc::new(Mutex::new(false)),
@@ -222,15 +239,120 @@ impl DiscoveryManager {
             .collect()
     }
 
+    /// Get discovered devices whose advertised capabilities match `filter`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use honeylink_discovery::{CapabilityFilter, DiscoveryManager, Service};
+    /// use honeylink_discovery::protocol::ProtocolStrategy;
+    ///
+    /// # async fn example() {
+    /// let manager = DiscoveryManager::new(ProtocolStrategy::default(), 100);
+    /// let filter = CapabilityFilter::new().require_service(Service::FileTransfer);
+    /// let receivers = manager.get_devices_matching(&filter).await;
+    /// # }
+    /// ```
+    pub async fn get_devices_matching(
+        &self,
+        filter: &CapabilityFilter,
+    ) -> HashMap<String, DeviceInfo> {
+        let devices = self.devices.read().await;
+        devices
+            .iter()
+            .filter(|(_, (info, _))| filter.matches(&info.capabilities))
+            .map(|(id, (info, _protocol))| (id.clone(), info.clone()))
+            .collect()
+    }
+
+    /// Look up a single device by ID
+    ///
+    /// Checks the deduplicated device map first, then falls back to querying
+    /// each registered protocol (mDNS entries win over BLE, matching
+    /// `merge_device`). Returns `None` if no protocol currently knows the device.
+    pub async fn get_device(&self, device_id: &str) -> Option<DeviceInfo> {
+        if let Some((info, _protocol)) = self.devices.read().await.get(device_id) {
+            return Some(info.clone());
+        }
+
+        let protocols = self.protocols.read().await;
+        for pt in [ProtocolType::Mdns, ProtocolType::Ble] {
+            if let Some(protocol) = protocols.get(&pt) {
+                if let Some(info) = protocol.get_devices().await.remove(device_id) {
+                    return Some(info);
+                }
+            }
+        }
+
+        None
+    }
+
     /// Get device count
     pub async fn device_count(&self) -> usize {
         self.devices.read().await.len()
     }
 
+    /// Event sender for a protocol backend
+    ///
+    /// Pass the returned sender to the protocol's constructor (e.g.
+    /// `MdnsDiscovery::new`). Its events are deduplicated into the device map
+    /// and published to subscribers. Must be called within a Tokio runtime.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use honeylink_discovery::{DiscoveryManager, MdnsDiscovery};
+    /// use honeylink_discovery::protocol::{ProtocolStrategy, ProtocolType};
+    ///
+    /// #[tokio::main(flavor = "multi_thread")]
+    /// async fn main() {
+    ///     let mut manager = DiscoveryManager::new(ProtocolStrategy::default(), 100);
+    ///     let tx = manager.protocol_event_sender(ProtocolType::Mdns);
+    ///     let mdns = MdnsDiscovery::new("DEV-001", "Test Device", "desktop", tx).unwrap();
+    ///     manager.register_protocol(ProtocolType::Mdns, Box::new(mdns));
+    /// }
+    /// ```
+    pub fn protocol_event_sender(
+        &self,
+        protocol_type: ProtocolType,
+    ) -> mpsc::Sender<DiscoveryEvent> {
+        let (tx, mut rx) = mpsc::channel(self.channel_size);
+        let devices = Arc::clone(&self.devices);
+        let events = self.events.clone();
+        let event_tx = self.event_tx.clone();
+
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                Self::ingest(&devices, &events, &event_tx, protocol_type, event).await;
+            }
+            debug!(protocol = ?protocol_type, "Protocol event stream closed");
+        });
+
+        tx
+    }
+
+    /// Subscribe to discovery events
+    ///
+    /// The subscription first yields `DeviceFound` for every currently known
+    /// device, then incremental events. Any number of subscribers is supported.
+    pub async fn subscribe(&self) -> DiscoverySubscription {
+        self.subscribe_with(SubscriptionFilter::new()).await
+    }
+
+    /// Subscribe to events for devices matching `filter`
+    ///
+    /// `DeviceLost` is delivered for devices the subscriber has seen, including
+    /// devices that stop matching the filter after an update.
+    pub async fn subscribe_with(&self, filter: SubscriptionFilter) -> DiscoverySubscription {
+        // Hold the read lock so no event is published between snapshot and subscribe
+        let devices = self.devices.read().await;
+        let rx = self.events.subscribe();
+        DiscoverySubscription::new(Arc::clone(&self.devices), &devices, rx, filter)
+    }
+
     /// Take the event receiver
     ///
     /// Returns the unified event stream receiver. Can only be called once.
-    /// Subsequent calls return None.
+    /// Subsequent calls return None. Events are dropped while the receiver is
+    /// full; prefer `subscribe()` for multiple consumers.
     pub async fn take_event_receiver(&self) -> Option<mpsc::Receiver<DiscoveryEvent>> {
         self.event_rx.lock().await.take()
     }
@@ -240,18 +362,110 @@ impl DiscoveryManager {
         *self.running.lock().await
     }
 
-    /// Internal: Handle device deduplication
+    /// Internal: Apply a protocol event to the device map and publish it
+    ///
+    /// Events are published while the device map write lock is held, which
+    /// keeps subscription snapshots consistent with the event stream.
+    async fn ingest(
+        devices: &RwLock<HashMap<String, (DeviceInfo, ProtocolType)>>,
+        events: &broadcast::Sender<DiscoveryEvent>,
+        event_tx: &mpsc::Sender<DiscoveryEvent>,
+        source_protocol: ProtocolType,
+        event: DiscoveryEvent,
+    ) {
+        let mut devices = devices.write().await;
+
+        let mut published = Vec::with_capacity(2);
+        match event {
+            DiscoveryEvent::DeviceFound(mut device_info) => {
+                if let Some(device_id) = Self::resolve_ble_alias(&devices, &device_info.device_id) {
+                    // BLE sighting of a device already known by its full ID
+                    device_info.device_id = device_id;
+                } else if let Some((stale, _)) =
+                    devices.remove(&ble_device_id_for(&device_info.device_id))
+                {
+                    // Full ID learned for a device known only over BLE
+                    device_info.rssi = device_info.rssi.or(stale.rssi);
+                    published.push(DiscoveryEvent::DeviceLost(stale.device_id));
+                }
+                published.push(DiscoveryEvent::DeviceFound(Self::merge_into(
+                    &mut devices,
+                    device_info,
+                    source_protocol,
+                )));
+            }
+            DiscoveryEvent::DeviceLost(device_id) => {
+                let device_id = Self::resolve_ble_alias(&devices, &device_id).unwrap_or(device_id);
+                // Only the protocol that provided the entry can remove it
+                match devices.get(&device_id) {
+                    Some((_, protocol)) if *protocol == source_protocol => {
+                        devices.remove(&device_id);
+                        published.push(DiscoveryEvent::DeviceLost(device_id));
+                    }
+                    _ => {}
+                }
+            }
+            DiscoveryEvent::NetworkChanged => published.push(DiscoveryEvent::NetworkChanged),
+        }
+
+        for event in published {
+            // No subscribers is not an error; lagging subscribers resync themselves
+            let _ = events.send(event.clone());
+            // Legacy receiver: never block protocol tasks
+            let _ = event_tx.try_send(event);
+        }
+    }
+
+    /// Full device ID of a known device whose BLE short ID is `device_id`
+    ///
+    /// BLE peers are reported as `BLE-<hex>` until pairing; the hex is derived
+    /// from the full device ID, so a device also seen over mDNS can be matched.
+    fn resolve_ble_alias(
+        devices: &HashMap<String, (DeviceInfo, ProtocolType)>,
+        device_id: &str,
+    ) -> Option<String> {
+        if !device_id.starts_with(BLE_DEVICE_ID_PREFIX) {
+            return None;
+        }
+        devices
+            .keys()
+            .find(|known| {
+                !known.starts_with(BLE_DEVICE_ID_PREFIX) && ble_device_id_for(known) == device_id
+            })
+            .cloned()
+    }
+
+    /// Merge a device discovered outside the registered protocols
+    ///
+    /// Used for manually added peers and tests. Applies the same deduplication
+    /// rules as protocol events and notifies subscribers.
+    pub async fn merge_device(
+        &self,
+        device_info: DeviceInfo,
+        source_protocol: ProtocolType,
+    ) -> Result<()> {
+        Self::ingest(
+            &self.devices,
+            &self.events,
+            &self.event_tx,
+            source_protocol,
+            DiscoveryEvent::DeviceFound(device_info),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Internal: Merge a discovered device into the map, returning the merged entry
     ///
     /// When a device is discovered via multiple protocols:
     /// - Prefer mDNS (faster, more reliable)
     /// - Keep BLE if mDNS is unavailable
-    /// - Update RSSI if BLE provides better signal info
-    async fn merge_device(
-        &self,
+    /// - Keep the last known RSSI (only BLE reports it)
+    fn merge_into(
+        devices: &mut HashMap<String, (DeviceInfo, ProtocolType)>,
         device_info: DeviceInfo,
         source_protocol: ProtocolType,
-    ) -> Result<()> {
-        let mut devices = self.devices.write().await;
+    ) -> DeviceInfo {
         let device_id = device_info.device_id.clone();
 
         match devices.get_mut(&device_id) {
@@ -269,8 +483,10 @@ impl DiscoveryManager {
                         );
                     }
                     (ProtocolType::Ble, ProtocolType::Mdns) => {
-                        // Replace BLE with mDNS (more reliable)
+                        // Replace BLE with mDNS (more reliable), keep BLE RSSI
+                        let rssi = device_info.rssi.or(existing_info.rssi);
                         *existing_info = device_info;
+                        existing_info.rssi = rssi;
                         *existing_protocol = source_protocol;
                         debug!(
                             device_id = %device_id,
@@ -279,7 +495,9 @@ impl DiscoveryManager {
                     }
                     _ => {
                         // Same protocol - update info
+                        let rssi = device_info.rssi.or(existing_info.rssi);
                         *existing_info = device_info;
+                        existing_info.rssi = rssi;
                         debug!(
                             device_id = %device_id,
                             protocol = ?source_protocol,
@@ -287,25 +505,26 @@ impl DiscoveryManager {
                         );
                     }
                 }
+                existing_info.clone()
             }
             None => {
                 // New device - insert
-                devices.insert(device_id.clone(), (device_info, source_protocol));
+                devices.insert(device_id.clone(), (device_info.clone(), source_protocol));
                 debug!(
                     device_id = %device_id,
                     protocol = ?source_protocol,
                     "Added new device"
                 );
+                device_info
             }
         }
-
-        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::subscription::SubscriptionEvent;
     use crate::types::DeviceType;
 
     #[tokio::test]
@@ -363,6 +582,208 @@ mod tests {
         assert_eq!(device.rssi, Some(-50)); // RSSI preserved from BLE
     }
 
+    #[tokio::test]
+    async fn test_ble_alias_deduplicated_against_mdns() {
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
+        let mut subscription = manager.subscribe().await;
+        let alias = ble_device_id_for("DEV-001");
+
+        // Seen over BLE first, before the full ID is known
+        let ble = DeviceInfo::new(&alias, "Phone", DeviceType::Mobile).with_rssi(-60);
+        manager.merge_device(ble.clone(), ProtocolType::Ble).await.unwrap();
+        assert_eq!(found_id(next_event(&mut subscription).await), alias);
+
+        // mDNS reveals the full ID: the alias is replaced
+        let mdns = DeviceInfo::new("DEV-001", "Phone", DeviceType::Mobile).with_port(7843);
+        manager.merge_device(mdns, ProtocolType::Mdns).await.unwrap();
+        assert_eq!(
+            next_event(&mut subscription).await,
+            SubscriptionEvent::Event(DiscoveryEvent::DeviceLost(alias.clone()))
+        );
+        assert_eq!(found_id(next_event(&mut subscription).await), "DEV-001");
+        assert_eq!(manager.device_count().await, 1);
+        assert_eq!(manager.get_device("DEV-001").await.unwrap().rssi, Some(-60));
+
+        // Later BLE sightings update the full entry instead of adding a duplicate
+        let ble_tx = manager.protocol_event_sender(ProtocolType::Ble);
+        ble_tx
+            .send(DiscoveryEvent::DeviceFound(ble.with_rssi(-40)))
+            .await
+            .unwrap();
+        assert_eq!(found_id(next_event(&mut subscription).await), "DEV-001");
+        assert_eq!(manager.device_count().await, 1);
+        assert_eq!(manager.get_device("DEV-001").await.unwrap().rssi, Some(-40));
+
+        // BLE losing the alias does not remove the mDNS entry
+        ble_tx.send(DiscoveryEvent::DeviceLost(alias)).await.unwrap();
+        ble_tx.send(DiscoveryEvent::NetworkChanged).await.unwrap();
+        assert_eq!(
+            next_event(&mut subscription).await,
+            SubscriptionEvent::Event(DiscoveryEvent::NetworkChanged)
+        );
+        assert_eq!(manager.device_count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_device_by_id() {
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
+        assert!(manager.get_device("DEV-001").await.is_none());
+
+        let device = DeviceInfo::new("DEV-001", "Test Device", DeviceType::Desktop)
+            .with_addresses(vec!["192.168.1.100".parse().unwrap()]);
+        manager
+            .merge_device(device.clone(), ProtocolType::Mdns)
+            .await
+            .unwrap();
+
+        let found = manager.get_device("DEV-001").await.unwrap();
+        assert_eq!(found.addresses, device.addresses);
+        assert!(manager.get_device("DEV-002").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_devices_matching_capabilities() {
+        use crate::capabilities::{DeviceCapabilities, Service};
+
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
+        let receiver = DeviceInfo::new("DEV-001", "Receiver", DeviceType::Desktop)
+            .with_capabilities(DeviceCapabilities::new().with_service(Service::FileTransfer));
+        let sensor = DeviceInfo::new("DEV-002", "Sensor", DeviceType::Iot)
+            .with_capabilities(DeviceCapabilities::new().with_service(Service::Telemetry));
+
+        manager.merge_device(receiver, ProtocolType::Mdns).await.unwrap();
+        manager.merge_device(sensor, ProtocolType::Ble).await.unwrap();
+
+        let filter = CapabilityFilter::new().require_service(Service::FileTransfer);
+        let matching = manager.get_devices_matching(&filter).await;
+        assert_eq!(matching.len(), 1);
+        assert!(matching.contains_key("DEV-001"));
+
+        assert_eq!(manager.get_devices_matching(&CapabilityFilter::new()).await.len(), 2);
+    }
+
+    async fn next_event(subscription: &mut DiscoverySubscription) -> SubscriptionEvent {
+        tokio::time::timeout(std::time::Duration::from_secs(5), subscription.recv())
+            .await
+            .expect("no subscription event")
+            .expect("subscription closed")
+    }
+
+    fn found_id(event: SubscriptionEvent) -> String {
+        match event {
+            SubscriptionEvent::Event(DiscoveryEvent::DeviceFound(device)) => device.device_id,
+            other => panic!("expected DeviceFound, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_get_snapshot_then_events() {
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
+        for id in ["DEV-002", "DEV-001"] {
+            let device = DeviceInfo::new(id, "Existing", DeviceType::Desktop);
+            manager.merge_device(device, ProtocolType::Mdns).await.unwrap();
+        }
+
+        let mut ui = manager.subscribe().await;
+        let mut auto_connect = manager.subscribe().await;
+
+        for subscription in [&mut ui, &mut auto_connect] {
+            assert_eq!(found_id(next_event(subscription).await), "DEV-001");
+            assert_eq!(found_id(next_event(subscription).await), "DEV-002");
+        }
+
+        let device = DeviceInfo::new("DEV-003", "New", DeviceType::Mobile);
+        manager.merge_device(device, ProtocolType::Mdns).await.unwrap();
+
+        assert_eq!(found_id(next_event(&mut ui).await), "DEV-003");
+        assert_eq!(found_id(next_event(&mut auto_connect).await), "DEV-003");
+    }
+
+    #[tokio::test]
+    async fn test_subscription_filter_by_type_and_capability() {
+        use crate::capabilities::{DeviceCapabilities, Service};
+
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
+        let filter = SubscriptionFilter::new()
+            .with_device_type(DeviceType::Desktop)
+            .with_capabilities(CapabilityFilter::new().require_service(Service::FileTransfer));
+        let mut subscription = manager.subscribe_with(filter).await;
+
+        let receiver = DeviceInfo::new("DEV-001", "Receiver", DeviceType::Desktop)
+            .with_capabilities(DeviceCapabilities::new().with_service(Service::FileTransfer));
+        let sensor = DeviceInfo::new("DEV-002", "Sensor", DeviceType::Iot)
+            .with_capabilities(DeviceCapabilities::new().with_service(Service::FileTransfer));
+        manager.merge_device(sensor, ProtocolType::Mdns).await.unwrap();
+        manager.merge_device(receiver.clone(), ProtocolType::Mdns).await.unwrap();
+
+        assert_eq!(found_id(next_event(&mut subscription).await), "DEV-001");
+
+        // Receiver stops offering file transfer: reported as lost to this subscriber
+        let receiver = receiver.with_capabilities(DeviceCapabilities::new());
+        manager.merge_device(receiver, ProtocolType::Mdns).await.unwrap();
+        assert_eq!(
+            next_event(&mut subscription).await,
+            SubscriptionEvent::Event(DiscoveryEvent::DeviceLost("DEV-001".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_resyncs() {
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 2);
+        let mut subscription = manager.subscribe().await;
+
+        for i in 1..=5 {
+            let device = DeviceInfo::new(format!("DEV-00{}", i), "Device", DeviceType::Desktop);
+            manager.merge_device(device, ProtocolType::Mdns).await.unwrap();
+        }
+
+        match next_event(&mut subscription).await {
+            SubscriptionEvent::Lagged { missed, devices } => {
+                assert_eq!(missed, 3);
+                assert_eq!(devices.len(), 5);
+            }
+            other => panic!("expected Lagged, got {:?}", other),
+        }
+
+        let device = DeviceInfo::new("DEV-006", "Device", DeviceType::Desktop);
+        manager.merge_device(device, ProtocolType::Mdns).await.unwrap();
+        assert_eq!(found_id(next_event(&mut subscription).await), "DEV-006");
+    }
+
+    #[tokio::test]
+    async fn test_protocol_event_sender() {
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
+        let mut subscription = manager.subscribe().await;
+        let ble_tx = manager.protocol_event_sender(ProtocolType::Ble);
+        let mdns_tx = manager.protocol_event_sender(ProtocolType::Mdns);
+
+        let device = DeviceInfo::new("DEV-001", "Phone", DeviceType::Mobile).with_rssi(-60);
+        ble_tx.send(DiscoveryEvent::DeviceFound(device)).await.unwrap();
+        assert_eq!(found_id(next_event(&mut subscription).await), "DEV-001");
+
+        // mDNS cannot remove an entry provided by BLE
+        mdns_tx
+            .send(DiscoveryEvent::DeviceLost("DEV-001".to_string()))
+            .await
+            .unwrap();
+        mdns_tx.send(DiscoveryEvent::NetworkChanged).await.unwrap();
+        assert_eq!(
+            next_event(&mut subscription).await,
+            SubscriptionEvent::Event(DiscoveryEvent::NetworkChanged)
+        );
+        assert_eq!(manager.device_count().await, 1);
+
+        ble_tx
+            .send(DiscoveryEvent::DeviceLost("DEV-001".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            next_event(&mut subscription).await,
+            SubscriptionEvent::Event(DiscoveryEvent::DeviceLost("DEV-001".to_string()))
+        );
+        assert_eq!(manager.device_count().await, 0);
+    }
+
     #[tokio::test]
     async fn test_event_receiver_take_once() {
         let manager = DiscoveryManager::new(ProtocolStrategy::All, 100);
@@ -1,22 +1,170 @@
 //! mDNS-SD device discovery implementation
 //!
-//! Service: `_honeylink._tcp.local`
-//! TXT Records: device_id, device_name, device_type, version
+//! Service: `_honeylink._tcp.local` (configurable via `DiscoveryConfig::mdns_service_name`)
+//! TXT Records: device_id, device_name, device_type, version, plus capability
+//! keys (services, transports, proto, presets; see `capabilities`)
+//!
+//! Devices are announced with the addresses of all (or the configured)
+//! non-loopback interfaces, IPv4 and IPv6. An optional DNS-SD subtype
+//! (e.g. `_gaming._sub._honeylink._tcp.local.`) lets browsers narrow results.
 
+use crate::capabilities::DeviceCapabilities;
 use crate::error::{DiscoveryError, Result};
 use crate::types::{DeviceInfo, DeviceType, DiscoveryEvent};
-use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use honeylink_config::DiscoveryConfig;
+use mdns_sd::{IfKind, ServiceDaemon, ServiceEvent, ServiceInfo};
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
 use tracing::{debug, error, info, warn};
 
-/// mDNS service type for HoneyLink
-const SERVICE_TYPE: &str = "_honeylink._tcp.local.";
+/// Default mDNS service type for HoneyLink
+pub const DEFAULT_SERVICE_TYPE: &str = "_honeylink._tcp.local.";
 
 /// Default QUIC port
-const DEFAULT_PORT: u16 = 7843;
+pub const DEFAULT_PORT: u16 = 7843;
+
+/// Fully qualify an mDNS service name
+///
+/// `"_honeylink._tcp"`, `"_honeylink._tcp.local"` and `"_honeylink._tcp.local."`
+/// all become `"_honeylink._tcp.local."`.
+pub fn qualified_service_type(service_name: &str) -> String {
+    let name = service_name.trim_end_matches('.');
+    let name = name.strip_suffix(".local").unwrap_or(name);
+    format!("{}.local.", name)
+}
+
+/// DNS-SD subtype label with leading underscore (`"gaming"` -> `"_gaming"`)
+///
+/// The underscore is optional everywhere subtypes are accepted (builder and
+/// `DiscoveryConfig`); `Config::validate()` applies the same rule.
+fn subtype_label(subtype: &str) -> String {
+    if subtype.starts_with('_') {
+        subtype.to_string()
+    } else {
+        format!("_{}", subtype)
+    }
+}
+
+/// Announcement and browsing settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MdnsSettings {
+    /// Fully qualified service type (e.g. "_honeylink._tcp.local.")
+    service_type: String,
+
+    /// Subtype label to announce (e.g. "_gaming")
+    subtype: Option<String>,
+
+    /// Subtype label to browse for
+    browse_subtype: Option<String>,
+
+    /// Interface names to announce on (empty = all)
+    interfaces: Vec<String>,
+
+    /// Include IPv6 interfaces and addresses
+    enable_ipv6: bool,
+
+    /// Advertised transport port
+    port: u16,
+}
+
+impl Default for MdnsSettings {
+    fn default() -> Self {
+        Self {
+            service_type: DEFAULT_SERVICE_TYPE.to_string(),
+            subtype: None,
+            browse_subtype: None,
+            interfaces: Vec::new(),
+            enable_ipv6: true,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+impl MdnsSettings {
+    /// Service type to register (with subtype, if any)
+    fn announce_type(&self) -> String {
+        match &self.subtype {
+            Some(subtype) => format!("{}._sub.{}", subtype, self.service_type),
+            None => self.service_type.clone(),
+        }
+    }
+
+    /// Service type to browse (with subtype, if any)
+    fn browse_type(&self) -> String {
+        match &self.browse_subtype {
+            Some(subtype) => format!("{}._sub.{}", subtype, self.service_type),
+            None => self.service_type.clone(),
+        }
+    }
+
+    /// Pick announced addresses from `(interface name, address)` pairs
+    fn select_addresses(&self, interfaces: &[(String, IpAddr)]) -> Vec<IpAddr> {
+        let mut addresses = Vec::new();
+        for (name, ip) in interfaces {
+            if ip.is_loopback() || (ip.is_ipv6() && !self.enable_ipv6) {
+                continue;
+            }
+            if !self.interfaces.is_empty() && !self.interfaces.contains(name) {
+                continue;
+            }
+            if !addresses.contains(ip) {
+                addresses.push(*ip);
+            }
+        }
+        addresses
+    }
+
+    /// Addresses of the local interfaces to announce
+    ///
+    /// Falls back to the primary local IP (or 127.0.0.1) if no interface matches.
+    fn announce_addresses(&self) -> Vec<IpAddr> {
+        let interfaces: Vec<(String, IpAddr)> = if_addrs::get_if_addrs()
+            .map(|ifaces| {
+                ifaces
+                    .into_iter()
+                    .map(|iface| (iface.name.clone(), iface.ip()))
+                    .collect()
+            })
+            .unwrap_or_else(|e| {
+                warn!("Failed to enumerate interfaces: {}", e);
+                Vec::new()
+            });
+
+        let addresses = self.select_addresses(&interfaces);
+        if !addresses.is_empty() {
+            return addresses;
+        }
+
+        vec![local_ip_address::local_ip().unwrap_or_else(|_| "127.0.0.1".parse().unwrap())]
+    }
+
+    /// Restrict the daemon to the configured interfaces / IP versions
+    fn apply_interface_selection(&self, daemon: &ServiceDaemon) -> Result<()> {
+        if !self.interfaces.is_empty() {
+            let selected: Vec<IfKind> = self
+                .interfaces
+                .iter()
+                .map(|name| IfKind::Name(name.clone()))
+                .collect();
+            daemon.disable_interface(IfKind::All).map_err(|e| {
+                DiscoveryError::MdnsError(format!("Failed to select interfaces: {}", e))
+            })?;
+            daemon.enable_interface(selected).map_err(|e| {
+                DiscoveryError::MdnsError(format!("Failed to select interfaces: {}", e))
+            })?;
+        }
+
+        if !self.enable_ipv6 {
+            daemon.disable_interface(IfKind::IPv6).map_err(|e| {
+                DiscoveryError::MdnsError(format!("Failed to disable IPv6: {}", e))
+            })?;
+        }
+
+        Ok(())
+    }
+}
 
 /// mDNS Discovery implementation
 pub struct MdnsDiscovery {
@@ -25,6 +173,12 @@ pub struct MdnsDiscovery {
     device_name: String,
     device_type: DeviceType,
 
+    /// Capabilities advertised in TXT records
+    capabilities: DeviceCapabilities,
+
+    /// Service type, subtypes, interfaces and port
+    settings: MdnsSettings,
+
     /// mDNS daemon (wrapped in `Arc<Mutex>` for async access)
     daemon: Arc<Mutex<Option<ServiceDaemon>>>,
 
@@ -55,6 +209,8 @@ impl MdnsDiscovery {
             device_id: device_id.to_string(),
             device_name: device_name.to_string(),
             device_type,
+            capabilities: DeviceCapabilities::default(),
+            settings: MdnsSettings::default(),
             daemon: Arc::new(Mutex::new(None)),
             devices: Arc::new(Mutex::new(HashMap::new())),
             event_tx,
@@ -63,13 +219,78 @@ impl MdnsDiscovery {
         })
     }
 
+    /// Set capabilities to advertise
+    ///
+    /// Takes effect on the next `announce()` (or re-announcement after a
+    /// network change).
+    pub fn with_capabilities(mut self, capabilities: DeviceCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Apply service name, interfaces, IPv6 and subtypes from `DiscoveryConfig`
+    pub fn with_config(self, config: &DiscoveryConfig) -> Self {
+        let mut mdns = self
+            .with_service_name(&config.mdns_service_name)
+            .with_interfaces(config.mdns_interfaces.clone())
+            .with_ipv6(config.mdns_enable_ipv6);
+        mdns.settings.subtype = config.mdns_subtype.as_deref().map(subtype_label);
+        mdns.settings.browse_subtype = config.mdns_browse_subtype.as_deref().map(subtype_label);
+        mdns
+    }
+
+    /// Set mDNS service name (e.g. `"_honeylink._udp"`)
+    pub fn with_service_name(mut self, service_name: &str) -> Self {
+        self.settings.service_type = qualified_service_type(service_name);
+        self
+    }
+
+    /// Set advertised transport port (the transport's actual listening port)
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.settings.port = port;
+        self
+    }
+
+    /// Transport port advertised in the mDNS SRV record
+    pub fn port(&self) -> u16 {
+        self.settings.port
+    }
+
+    /// Announce only on the named interfaces (empty = all non-loopback)
+    pub fn with_interfaces(mut self, interfaces: Vec<String>) -> Self {
+        self.settings.interfaces = interfaces;
+        self
+    }
+
+    /// Enable or disable IPv6 announcement and browsing (enabled by default)
+    pub fn with_ipv6(mut self, enable_ipv6: bool) -> Self {
+        self.settings.enable_ipv6 = enable_ipv6;
+        self
+    }
+
+    /// Announce under a DNS-SD subtype (e.g. `"gaming"` or `"_gaming"`)
+    ///
+    /// mdns-sd registers at most one subtype per service instance.
+    pub fn with_subtype(mut self, subtype: &str) -> Self {
+        self.settings.subtype = Some(subtype_label(subtype));
+        self
+    }
+
+    /// Browse only for devices announced under a DNS-SD subtype
+    pub fn with_browse_subtype(mut self, subtype: &str) -> Self {
+        self.settings.browse_subtype = Some(subtype_label(subtype));
+        self
+    }
+
     /// Announce device via mDNS
     ///
-    /// Registers service `_honeylink._tcp.local` with TXT records:
+    /// Registers the configured service type (default `_honeylink._tcp.local`,
+    /// plus subtype if set) on the selected interfaces with TXT records:
     /// - device_id: Unique identifier
     /// - device_name: Human-readable name
     /// - device_type: Device category
     /// - version: HoneyLink protocol version
+    /// - services/transports/proto/presets: Advertised capabilities
     pub async fn announce(&mut self) -> Result<()> {
         info!(
             device_id = %self.device_id,
@@ -81,34 +302,15 @@ impl MdnsDiscovery {
         let daemon = ServiceDaemon::new()
             .map_err(|e| DiscoveryError::MdnsError(format!("Failed to create daemon: {}", e)))?;
 
-        // Get local IP addresses
-        let host_ipv4 = local_ip_address::local_ip()
-            .unwrap_or_else(|_| "127.0.0.1".parse().unwrap());
+        self.settings.apply_interface_selection(&daemon)?;
 
-        // Create TXT records
-        let mut properties = HashMap::new();
-        properties.insert("device_id".to_string(), self.device_id.clone());
-        properties.insert("device_name".to_string(), self.device_name.clone());
-        properties.insert(
-            "device_type".to_string(),
-            self.device_type.as_str().to_string(),
-        );
-        properties.insert(
-            "version".to_string(),
-            env!("CARGO_PKG_VERSION").to_string(),
-        );
-
-        // Create service info
-        let service_hostname = format!("{}.local.", self.device_id.replace('-', ""));
-        let service_info = ServiceInfo::new(
-            SERVICE_TYPE,
+        let service_info = Self::service_info(
             &self.device_id,
-            &service_hostname,
-            host_ipv4,
-            DEFAULT_PORT,
-            Some(properties),
-        )
-        .map_err(|e| DiscoveryError::MdnsError(format!("Failed to create service: {}", e)))?;
+            &self.device_name,
+            &self.device_type,
+            &self.capabilities,
+            &self.settings,
+        )?;
 
         // Register service
         daemon
@@ -131,11 +333,12 @@ impl MdnsDiscovery {
             .clone();
         drop(daemon_guard);
 
-        info!("Starting mDNS browsing for {}", SERVICE_TYPE);
+        let browse_type = self.settings.browse_type();
+        info!("Starting mDNS browsing for {}", browse_type);
 
         // Browse for HoneyLink devices
         let receiver = daemon
-            .browse(SERVICE_TYPE)
+            .browse(&browse_type)
             .map_err(|e| DiscoveryError::MdnsError(format!("Failed to browse: {}", e)))?;
 
         // Spawn background task to process events
@@ -203,7 +406,9 @@ impl MdnsDiscovery {
         // Spawn task to handle network events
         let device_id = self.device_id.clone();
         let device_name = self.device_name.clone();
-        let device_type = self.device_type.clone();
+        let device_type = self.device_type;
+        let capabilities = self.capabilities.clone();
+        let settings = self.settings.clone();
         let daemon = Arc::clone(&self.daemon);
         let event_tx = self.event_tx.clone();
 
@@ -216,6 +421,8 @@ impl MdnsDiscovery {
                     &device_id,
                     &device_name,
                     &device_type,
+                    &capabilities,
+                    &settings,
                     &daemon,
                 ).await {
                     error!("Failed to re-announce service: {}", e);
@@ -237,6 +444,8 @@ impl MdnsDiscovery {
         device_id: &str,
         device_name: &str,
         device_type: &DeviceType,
+        capabilities: &DeviceCapabilities,
+        settings: &MdnsSettings,
         daemon: &Arc<Mutex<Option<ServiceDaemon>>>,
     ) -> Result<()> {
         info!("Re-announcing service after network change");
@@ -247,40 +456,62 @@ impl MdnsDiscovery {
             .ok_or(DiscoveryError::NotStarted)?;
 
         // Unregister old service
-        let fullname = format!("{}.{}", device_id, SERVICE_TYPE);
+        let fullname = format!("{}.{}", device_id, settings.service_type);
         if let Err(e) = daemon_ref.unregister(&fullname) {
             warn!("Failed to unregister old service: {}", e);
         }
 
-        // Get new local IP
-        let host_ipv4 = local_ip_address::local_ip()
-            .unwrap_or_else(|_| "127.0.0.1".parse().unwrap());
+        // Register new service with the current interface addresses
+        let service_info =
+            Self::service_info(device_id, device_name, device_type, capabilities, settings)?;
 
-        // Create TXT records
-        let mut properties = HashMap::new();
-        properties.insert("device_id".to_string(), device_id.to_string());
-        properties.insert("device_name".to_string(), device_name.to_string());
-        properties.insert("device_type".to_string(), device_type.as_str().to_string());
-        properties.insert("version".to_string(), env!("CARGO_PKG_VERSION").to_string());
+        daemon_ref
+            .register(service_info)
+            .map_err(|e| DiscoveryError::MdnsError(format!("Failed to re-register: {}", e)))?;
 
-        // Register new service
+        info!("Service re-announced successfully");
+        Ok(())
+    }
+
+    /// Build the service registration for the current interface addresses
+    fn service_info(
+        device_id: &str,
+        device_name: &str,
+        device_type: &DeviceType,
+        capabilities: &DeviceCapabilities,
+        settings: &MdnsSettings,
+    ) -> Result<ServiceInfo> {
+        let addresses = settings.announce_addresses();
+        debug!(?addresses, port = settings.port, "mDNS announcement addresses");
+
+        let properties = Self::txt_properties(device_id, device_name, device_type, capabilities);
         let service_hostname = format!("{}.local.", device_id.replace('-', ""));
-        let service_info = ServiceInfo::new(
-            SERVICE_TYPE,
+
+        ServiceInfo::new(
+            &settings.announce_type(),
             device_id,
             &service_hostname,
-            host_ipv4,
-            DEFAULT_PORT,
+            &addresses[..],
+            settings.port,
             Some(properties),
         )
-        .map_err(|e| DiscoveryError::MdnsError(format!("Failed to create service: {}", e)))?;
-
-        daemon_ref
-            .register(service_info)
-            .map_err(|e| DiscoveryError::MdnsError(format!("Failed to re-register: {}", e)))?;
+        .map_err(|e| DiscoveryError::MdnsError(format!("Failed to create service: {}", e)))
+    }
 
-        info!("Service re-announced successfully");
-        Ok(())
+    /// Build TXT record properties for announcement
+    fn txt_properties(
+        device_id: &str,
+        device_name: &str,
+        device_type: &DeviceType,
+        capabilities: &DeviceCapabilities,
+    ) -> HashMap<String, String> {
+        let mut properties = HashMap::new();
+        properties.insert("device_id".to_string(), device_id.to_string());
+        properties.insert("device_name".to_string(), device_name.to_string());
+        properties.insert("device_type".to_string(), device_type.as_str().to_string());
+        properties.insert("version".to_string(), env!("CARGO_PKG_VERSION").to_string());
+        properties.extend(capabilities.to_txt_properties());
+        properties
     }
 
     /// Handle mDNS service event
@@ -341,6 +572,9 @@ impl MdnsDiscovery {
         let _version = properties.get("version")?.val_str().to_string();
 
         let device_type = DeviceType::from_str(device_type_str);
+        let capabilities = DeviceCapabilities::from_txt_properties(|key| {
+            properties.get(key).map(|p| p.val_str())
+        });
 
         let addresses: Vec<IpAddr> = info
             .get_addresses()
@@ -351,7 +585,8 @@ impl MdnsDiscovery {
         Some(
             DeviceInfo::new(device_id, device_name, device_type)
                 .with_addresses(addresses)
-                .with_port(info.get_port()),
+                .with_port(info.get_port())
+                .with_capabilities(capabilities),
         )
     }
 
@@ -428,6 +663,89 @@ mod tests {
         assert!(mdns.is_ok());
     }
 
+    #[test]
+    fn test_txt_properties_include_capabilities() {
+        use crate::capabilities::{Service, TransportCapability};
+
+        let caps = DeviceCapabilities::new()
+            .with_service(Service::FileTransfer)
+            .with_transport(TransportCapability::Quic);
+        let props =
+            MdnsDiscovery::txt_properties("DEV-001", "Test", &DeviceType::Desktop, &caps);
+
+        assert_eq!(props["device_id"], "DEV-001");
+        assert_eq!(props["services"], "file_transfer");
+        assert_eq!(props["transports"], "quic");
+        assert_eq!(props["proto"], "1-1");
+
+        let decoded = DeviceCapabilities::from_txt_properties(|k| props.get(k).map(|v| v.as_str()));
+        assert_eq!(decoded, caps);
+    }
+
+    #[test]
+    fn test_qualified_service_type() {
+        assert_eq!(qualified_service_type("_honeylink._tcp"), DEFAULT_SERVICE_TYPE);
+        assert_eq!(qualified_service_type("_honeylink._tcp.local"), DEFAULT_SERVICE_TYPE);
+        assert_eq!(qualified_service_type("_honeylink._udp.local."), "_honeylink._udp.local.");
+    }
+
+    #[test]
+    fn test_settings_from_config() {
+        let (tx, _rx) = mpsc::channel(10);
+        let config = DiscoveryConfig {
+            mdns_service_name: "_honeylink._udp".to_string(),
+            mdns_interfaces: vec!["eth0".to_string()],
+            mdns_enable_ipv6: false,
+            mdns_subtype: Some("_gaming".to_string()),
+            mdns_browse_subtype: Some("media".to_string()),
+            ..DiscoveryConfig::default()
+        };
+        let mdns = MdnsDiscovery::new("DEV-001", "Test", "desktop", tx)
+            .unwrap()
+            .with_config(&config)
+            .with_port(40123);
+
+        assert_eq!(mdns.settings.service_type, "_honeylink._udp.local.");
+        assert_eq!(mdns.settings.announce_type(), "_gaming._sub._honeylink._udp.local.");
+        assert_eq!(mdns.settings.browse_type(), "_media._sub._honeylink._udp.local.");
+        assert_eq!(mdns.settings.interfaces, vec!["eth0".to_string()]);
+        assert!(!mdns.settings.enable_ipv6);
+        assert_eq!(mdns.settings.port, 40123);
+    }
+
+    #[test]
+    fn test_select_addresses() {
+        let interfaces: Vec<(String, IpAddr)> = vec![
+            ("lo".to_string(), "127.0.0.1".parse().unwrap()),
+            ("eth0".to_string(), "192.168.1.10".parse().unwrap()),
+            ("eth0".to_string(), "2001:db8::10".parse().unwrap()),
+            ("wlan0".to_string(), "10.0.0.5".parse().unwrap()),
+            ("wlan0".to_string(), "10.0.0.5".parse().unwrap()),
+        ];
+
+        // All non-loopback interfaces, IPv4 and IPv6
+        let settings = MdnsSettings::default();
+        assert_eq!(
+            settings.select_addresses(&interfaces),
+            vec![
+                "192.168.1.10".parse::<IpAddr>().unwrap(),
+                "2001:db8::10".parse().unwrap(),
+                "10.0.0.5".parse().unwrap(),
+            ]
+        );
+
+        // Selected interface, IPv4 only
+        let settings = MdnsSettings {
+            interfaces: vec!["eth0".to_string()],
+            enable_ipv6: false,
+            ..MdnsSettings::default()
+        };
+        assert_eq!(
+            settings.select_addresses(&interfaces),
+            vec!["192.168.1.10".parse::<IpAddr>().unwrap()]
+        );
+    }
+
     #[tokio::test]
     async fn test_device_type_conversion() {
         assert_eq!(DeviceType::from_str("desktop"), DeviceType::Desktop);
@@ -0,0 +1,230 @@
+//! BLE radio abstraction
+//!
+//! `BleDiscovery` talks to the air through the `BleRadio` trait so the same
+//! advertising/scanning/GATT logic runs against real hardware (`BtleplugRadio`,
+//! `btleplug` feature) or the in-process `SimulatedRadio` used in tests and CI.
+//!
+//! # Design Rationale
+//!
+//! - **Small surface**: Advertise, scan, read/write a characteristic. Connection
+//!   management is left to the backend (connect on first GATT access)
+//! - **Peers by address**: Scan results and GATT calls identify peers by their
+//!   radio address string; mapping to HoneyLink device IDs happens in `BleDiscovery`
+//! - **Local GATT table**: The peripheral side serves a `GattServer`, so writes
+//!   from a remote central (e.g. Pairing State) are visible to the local device
+
+use crate::error::{DiscoveryError, Result};
+use crate::gatt::{DEVICE_INFO_CHAR_UUID, PAIRING_STATE_CHAR_UUID};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+
+/// Advertisement payload (advertising data + scan response)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BleAdvertisement {
+    /// Complete local name (scan response)
+    pub local_name: Option<String>,
+
+    /// Advertised 128-bit service UUIDs
+    pub service_uuids: Vec<String>,
+}
+
+impl BleAdvertisement {
+    /// Create advertisement for a single service UUID
+    pub fn new(service_uuid: &str) -> Self {
+        Self {
+            local_name: None,
+            service_uuids: vec![service_uuid.to_string()],
+        }
+    }
+
+    /// Set local name
+    pub fn with_local_name(mut self, local_name: impl Into<String>) -> Self {
+        self.local_name = Some(local_name.into());
+        self
+    }
+
+    /// Check if the advertisement lists `service_uuid` (case-insensitive)
+    pub fn has_service(&self, service_uuid: &str) -> bool {
+        self.service_uuids
+            .iter()
+            .any(|uuid| uuid.eq_ignore_ascii_case(service_uuid))
+    }
+}
+
+/// Single advertisement received while scanning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanResult {
+    /// Radio address of the advertiser
+    pub address: String,
+
+    /// Received advertisement
+    pub advertisement: BleAdvertisement,
+
+    /// Received signal strength (dBm), if reported by the backend
+    pub rssi: Option<i16>,
+}
+
+/// Radio backend for BLE discovery
+#[async_trait]
+pub trait BleRadio: Send + Sync {
+    /// Radio address of this device (backend specific format)
+    fn address(&self) -> String;
+
+    /// Start advertising and serve `gatt` to connecting centrals (Peripheral role)
+    async fn start_advertising(
+        &self,
+        advertisement: BleAdvertisement,
+        gatt: Arc<GattServer>,
+    ) -> Result<()>;
+
+    /// Stop advertising
+    async fn stop_advertising(&self) -> Result<()>;
+
+    /// Start scanning for advertisers of `service_uuid` (Central role)
+    ///
+    /// Every received advertisement is delivered on the returned channel until
+    /// `stop_scan()` is called or the receiver is dropped.
+    async fn start_scan(&self, service_uuid: &str) -> Result<mpsc::Receiver<ScanResult>>;
+
+    /// Stop scanning
+    async fn stop_scan(&self) -> Result<()>;
+
+    /// Read a characteristic value from a peer
+    async fn read_characteristic(&self, address: &str, char_uuid: &str) -> Result<Vec<u8>>;
+
+    /// Write a characteristic value on a peer (with response)
+    async fn write_characteristic(
+        &self,
+        address: &str,
+        char_uuid: &str,
+        value: &[u8],
+    ) -> Result<()>;
+}
+
+/// GATT characteristic stored in a `GattServer`
+#[derive(Debug, Clone)]
+struct Characteristic {
+    value: Vec<u8>,
+    writable: bool,
+}
+
+/// Local GATT service table served while advertising
+///
+/// Holds the HoneyLink service characteristics (Device Info, Pairing State).
+/// Remote reads and writes go through `read()`/`write()`; the owning device
+/// updates values via `set_value()`.
+#[derive(Debug, Default)]
+pub struct GattServer {
+    characteristics: RwLock<HashMap<String, Characteristic>>,
+}
+
+impl GattServer {
+    /// Create empty GATT server
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create GATT server with the HoneyLink characteristics
+    ///
+    /// Device Info is read-only, Pairing State is read/write.
+    pub fn honeylink(device_info: Vec<u8>, pairing_state: Vec<u8>) -> Self {
+        let mut characteristics = HashMap::new();
+        characteristics.insert(
+            DEVICE_INFO_CHAR_UUID.to_ascii_uppercase(),
+            Characteristic {
+                value: device_info,
+                writable: false,
+            },
+        );
+        characteristics.insert(
+            PAIRING_STATE_CHAR_UUID.to_ascii_uppercase(),
+            Characteristic {
+                value: pairing_state,
+                writable: true,
+            },
+        );
+
+        Self {
+            characteristics: RwLock::new(characteristics),
+        }
+    }
+
+    /// Set (or add) a characteristic value from the local side
+    ///
+    /// Local updates bypass the write permission; `writable` only controls
+    /// remote writes for newly added characteristics.
+    pub async fn set_value(&self, char_uuid: &str, value: Vec<u8>, writable: bool) {
+        let mut characteristics = self.characteristics.write().await;
+        characteristics
+            .entry(char_uuid.to_ascii_uppercase())
+            .and_modify(|c| c.value = value.clone())
+            .or_insert(Characteristic { value, writable });
+    }
+
+    /// Read characteristic value
+    pub async fn read(&self, char_uuid: &str) -> Result<Vec<u8>> {
+        self.characteristics
+            .read()
+            .await
+            .get(&char_uuid.to_ascii_uppercase())
+            .map(|c| c.value.clone())
+            .ok_or_else(|| {
+                DiscoveryError::BleError(format!("Characteristic {} not found", char_uuid))
+            })
+    }
+
+    /// Write characteristic value on behalf of a remote central
+    pub async fn write(&self, char_uuid: &str, value: &[u8]) -> Result<()> {
+        let mut characteristics = self.characteristics.write().await;
+        let characteristic = characteristics
+            .get_mut(&char_uuid.to_ascii_uppercase())
+            .ok_or_else(|| {
+                DiscoveryError::BleError(format!("Characteristic {} not found", char_uuid))
+            })?;
+
+        if !characteristic.writable {
+            return Err(DiscoveryError::BleError(format!(
+                "Characteristic {} is read-only",
+                char_uuid
+            )));
+        }
+
+        characteristic.value = value.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gatt_server_permissions() {
+        let gatt = GattServer::honeylink(vec![1; 20], vec![0; 20]);
+
+        assert_eq!(gatt.read(DEVICE_INFO_CHAR_UUID).await.unwrap(), vec![1; 20]);
+        assert!(gatt.write(DEVICE_INFO_CHAR_UUID, &[2; 20]).await.is_err());
+
+        gatt.write(&PAIRING_STATE_CHAR_UUID.to_lowercase(), &[3; 20])
+            .await
+            .unwrap();
+        assert_eq!(
+            gatt.read(PAIRING_STATE_CHAR_UUID).await.unwrap(),
+            vec![3; 20]
+        );
+
+        assert!(gatt
+            .read("0000FFFF-0000-1000-8000-00805F9B34FB")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_advertisement_service_match() {
+        let adv = BleAdvertisement::new(crate::gatt::HONEYLINK_SERVICE_UUID).with_local_name("Dev");
+        assert!(adv.has_service(&crate::gatt::HONEYLINK_SERVICE_UUID.to_lowercase()));
+        assert!(!adv.has_service(DEVICE_INFO_CHAR_UUID));
+    }
+}
@@ -0,0 +1,448 @@
+//! In-process simulated BLE radio
+//!
+//! A `SimulatedMedium` is a shared "air" that any number of `SimulatedRadio`s
+//! attach to. Radios advertise, scan and access each other's GATT servers
+//! without Bluetooth hardware, so BLE discovery and pairing flows run on CI.
+//!
+//! Link quality between two radios is configurable per pair (`LinkConditions`):
+//! the reported RSSI and the probability that an advertisement or GATT request
+//! is lost. Packet loss uses a seedable RNG so tests are reproducible.
+//!
+//! # Examples
+//!
+//! ```
+//! use honeylink_discovery::sim_radio::{LinkConditions, SimulatedMedium};
+//!
+//! let medium = SimulatedMedium::with_seed(7).with_default_link(LinkConditions::new(-55, 0.0));
+//! let phone = medium.radio("AA:00:00:00:00:01");
+//! let laptop = medium.radio("AA:00:00:00:00:02");
+//! medium.set_link("AA:00:00:00:00:01", "AA:00:00:00:00:02", LinkConditions::new(-80, 0.2));
+//! # let _ = (phone, laptop);
+//! ```
+
+use crate::error::{DiscoveryError, Result};
+use crate::radio::{BleAdvertisement, BleRadio, GattServer, ScanResult};
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Default interval between simulated advertisement rounds
+pub const DEFAULT_ADVERTISING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Link quality between two simulated radios
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConditions {
+    /// RSSI reported to the receiver (dBm)
+    pub rssi: i16,
+
+    /// Probability (0.0-1.0) that a single packet is lost
+    pub packet_loss: f64,
+}
+
+impl LinkConditions {
+    /// Create link conditions (`packet_loss` is clamped to 0.0-1.0)
+    pub fn new(rssi: i16, packet_loss: f64) -> Self {
+        Self {
+            rssi,
+            packet_loss: packet_loss.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Radios cannot hear each other
+    pub fn out_of_range() -> Self {
+        Self::new(i16::MIN, 1.0)
+    }
+}
+
+impl Default for LinkConditions {
+    /// Nearby device with a clean link
+    fn default() -> Self {
+        Self::new(-60, 0.0)
+    }
+}
+
+/// Radio attached to the medium
+#[derive(Default)]
+struct Node {
+    advertisement: Option<BleAdvertisement>,
+    gatt: Option<Arc<GattServer>>,
+}
+
+/// Shared medium state
+struct MediumState {
+    /// Ordered so packet loss draws are reproducible for a given seed
+    nodes: BTreeMap<String, Node>,
+    links: HashMap<(String, String), LinkConditions>,
+    default_link: LinkConditions,
+    rng: StdRng,
+}
+
+impl MediumState {
+    fn link(&self, a: &str, b: &str) -> LinkConditions {
+        self.links
+            .get(&link_key(a, b))
+            .copied()
+            .unwrap_or(self.default_link)
+    }
+
+    /// Roll the dice for one packet between `a` and `b`
+    fn delivered(&mut self, a: &str, b: &str) -> bool {
+        let loss = self.link(a, b).packet_loss;
+        loss <= 0.0 || self.rng.gen::<f64>() >= loss
+    }
+}
+
+/// Links are symmetric
+fn link_key(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+/// Shared in-process BLE medium
+#[derive(Clone)]
+pub struct SimulatedMedium {
+    state: Arc<Mutex<MediumState>>,
+    advertising_interval: Duration,
+}
+
+impl SimulatedMedium {
+    /// Create medium with an entropy-seeded packet loss RNG
+    pub fn new() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+
+    /// Create medium with a fixed seed (reproducible packet loss)
+    pub fn with_seed(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(rng: StdRng) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MediumState {
+                nodes: BTreeMap::new(),
+                links: HashMap::new(),
+                default_link: LinkConditions::default(),
+                rng,
+            })),
+            advertising_interval: DEFAULT_ADVERTISING_INTERVAL,
+        }
+    }
+
+    /// Set link conditions for radio pairs without an explicit `set_link()`
+    pub fn with_default_link(self, conditions: LinkConditions) -> Self {
+        self.lock().default_link = conditions;
+        self
+    }
+
+    /// Set interval between advertisement rounds seen by scanners
+    ///
+    /// Applies to radios created afterwards.
+    pub fn with_advertising_interval(mut self, interval: Duration) -> Self {
+        self.advertising_interval = interval;
+        self
+    }
+
+    /// Attach a radio with the given address
+    pub fn radio(&self, address: impl Into<String>) -> SimulatedRadio {
+        let address = address.into();
+        self.lock().nodes.entry(address.clone()).or_default();
+
+        SimulatedRadio {
+            address,
+            medium: self.clone(),
+            scan_task: Mutex::new(None),
+        }
+    }
+
+    /// Set link conditions between two radios (both directions)
+    pub fn set_link(&self, a: &str, b: &str, conditions: LinkConditions) {
+        self.lock().links.insert(link_key(a, b), conditions);
+    }
+
+    /// Add a virtual device that advertises the HoneyLink service
+    ///
+    /// The returned radio advertises and serves `gatt` until
+    /// `stop_advertising()` is called on it.
+    pub async fn add_virtual_device(
+        &self,
+        address: impl Into<String>,
+        advertisement: BleAdvertisement,
+        gatt: Arc<GattServer>,
+    ) -> Result<SimulatedRadio> {
+        let radio = self.radio(address);
+        radio.start_advertising(advertisement, gatt).await?;
+        Ok(radio)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MediumState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for SimulatedMedium {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Radio attached to a `SimulatedMedium`
+pub struct SimulatedRadio {
+    address: String,
+    medium: SimulatedMedium,
+    scan_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SimulatedRadio {
+    /// Reach a peer's GATT server, subject to link loss
+    fn peer_gatt(&self, address: &str) -> Result<Arc<GattServer>> {
+        let mut state = self.medium.lock();
+
+        let gatt = state
+            .nodes
+            .get(address)
+            .filter(|node| node.advertisement.is_some())
+            .and_then(|node| node.gatt.clone())
+            .ok_or_else(|| {
+                DiscoveryError::BleError(format!("Peer {} is not connectable", address))
+            })?;
+
+        if !state.delivered(&self.address, address) {
+            return Err(DiscoveryError::BleError(format!(
+                "GATT request to {} lost (simulated packet loss)",
+                address
+            )));
+        }
+
+        Ok(gatt)
+    }
+}
+
+#[async_trait]
+impl BleRadio for SimulatedRadio {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn start_advertising(
+        &self,
+        advertisement: BleAdvertisement,
+        gatt: Arc<GattServer>,
+    ) -> Result<()> {
+        let mut state = self.medium.lock();
+        let node = state.nodes.entry(self.address.clone()).or_default();
+        node.advertisement = Some(advertisement);
+        node.gatt = Some(gatt);
+        Ok(())
+    }
+
+    async fn stop_advertising(&self) -> Result<()> {
+        let mut state = self.medium.lock();
+        if let Some(node) = state.nodes.get_mut(&self.address) {
+            node.advertisement = None;
+            node.gatt = None;
+        }
+        Ok(())
+    }
+
+    async fn start_scan(&self, service_uuid: &str) -> Result<mpsc::Receiver<ScanResult>> {
+        let (tx, rx) = mpsc::channel(64);
+        let medium = self.medium.clone();
+        let own_address = self.address.clone();
+        let service_uuid = service_uuid.to_string();
+
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(medium.advertising_interval);
+            loop {
+                interval.tick().await;
+
+                let results: Vec<ScanResult> = {
+                    let mut state = medium.lock();
+                    let advertisers: Vec<(String, BleAdvertisement)> = state
+                        .nodes
+                        .iter()
+                        .filter(|(address, _)| **address != own_address)
+                        .filter_map(|(address, node)| {
+                            node.advertisement
+                                .as_ref()
+                                .filter(|adv| adv.has_service(&service_uuid))
+                                .map(|adv| (address.clone(), adv.clone()))
+                        })
+                        .collect();
+
+                    let mut results = Vec::with_capacity(advertisers.len());
+                    for (address, advertisement) in advertisers {
+                        if state.delivered(&own_address, &address) {
+                            results.push(ScanResult {
+                                rssi: Some(state.link(&own_address, &address).rssi),
+                                address,
+                                advertisement,
+                            });
+                        }
+                    }
+                    results
+                };
+
+                for result in results {
+                    if tx.send(result).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        if let Some(previous) = self
+            .scan_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(task)
+        {
+            previous.abort();
+        }
+
+        Ok(rx)
+    }
+
+    async fn stop_scan(&self) -> Result<()> {
+        if let Some(task) = self
+            .scan_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    async fn read_characteristic(&self, address: &str, char_uuid: &str) -> Result<Vec<u8>> {
+        self.peer_gatt(address)?.read(char_uuid).await
+    }
+
+    async fn write_characteristic(
+        &self,
+        address: &str,
+        char_uuid: &str,
+        value: &[u8],
+    ) -> Result<()> {
+        self.peer_gatt(address)?.write(char_uuid, value).await
+    }
+}
+
+impl Drop for SimulatedRadio {
+    fn drop(&mut self) {
+        if let Some(task) = self
+            .scan_task
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gatt::{DEVICE_INFO_CHAR_UUID, HONEYLINK_SERVICE_UUID};
+
+    fn advertisement(name: &str) -> BleAdvertisement {
+        BleAdvertisement::new(HONEYLINK_SERVICE_UUID).with_local_name(name)
+    }
+
+    #[tokio::test]
+    async fn test_scan_reports_link_rssi() {
+        let medium =
+            SimulatedMedium::with_seed(1).with_advertising_interval(Duration::from_millis(10));
+        medium.set_link("SIM-A", "SIM-B", LinkConditions::new(-72, 0.0));
+
+        let scanner = medium.radio("SIM-A");
+        let _peer = medium
+            .add_virtual_device("SIM-B", advertisement("Peer"), Arc::new(GattServer::new()))
+            .await
+            .unwrap();
+
+        let mut rx = scanner.start_scan(HONEYLINK_SERVICE_UUID).await.unwrap();
+        let result = rx.recv().await.unwrap();
+        assert_eq!(result.address, "SIM-B");
+        assert_eq!(result.rssi, Some(-72));
+        assert_eq!(result.advertisement.local_name.as_deref(), Some("Peer"));
+
+        scanner.stop_scan().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_peer_is_silent() {
+        let medium =
+            SimulatedMedium::with_seed(2).with_advertising_interval(Duration::from_millis(5));
+        medium.set_link("SIM-A", "SIM-FAR", LinkConditions::out_of_range());
+
+        let scanner = medium.radio("SIM-A");
+        let gatt = Arc::new(GattServer::honeylink(vec![1; 20], vec![0; 20]));
+        let _far = medium
+            .add_virtual_device("SIM-FAR", advertisement("Far"), gatt.clone())
+            .await
+            .unwrap();
+        let _near = medium
+            .add_virtual_device("SIM-NEAR", advertisement("Near"), gatt)
+            .await
+            .unwrap();
+
+        let mut rx = scanner.start_scan(HONEYLINK_SERVICE_UUID).await.unwrap();
+        for _ in 0..10 {
+            assert_eq!(rx.recv().await.unwrap().address, "SIM-NEAR");
+        }
+
+        assert!(scanner
+            .read_characteristic("SIM-FAR", DEVICE_INFO_CHAR_UUID)
+            .await
+            .is_err());
+        assert_eq!(
+            scanner
+                .read_characteristic("SIM-NEAR", DEVICE_INFO_CHAR_UUID)
+                .await
+                .unwrap(),
+            vec![1; 20]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_packet_loss_is_reproducible() {
+        async fn delivered(seed: u64) -> Vec<bool> {
+            let medium =
+                SimulatedMedium::with_seed(seed).with_default_link(LinkConditions::new(-60, 0.5));
+            let central = medium.radio("SIM-A");
+            let _peer = medium
+                .add_virtual_device(
+                    "SIM-B",
+                    advertisement("Peer"),
+                    Arc::new(GattServer::honeylink(vec![1; 20], vec![0; 20])),
+                )
+                .await
+                .unwrap();
+
+            let mut outcomes = Vec::new();
+            for _ in 0..32 {
+                outcomes.push(
+                    central
+                        .read_characteristic("SIM-B", DEVICE_INFO_CHAR_UUID)
+                        .await
+                        .is_ok(),
+                );
+            }
+            outcomes
+        }
+
+        let first = delivered(42).await;
+        assert_eq!(first, delivered(42).await);
+        assert!(first.contains(&true) && first.contains(&false));
+    }
+}
@@ -0,0 +1,227 @@
+//! Discovery event subscriptions
+//!
+//! `DiscoveryManager::subscribe()` hands out any number of independent
+//! subscriptions. Each one first replays the current device set as
+//! `DeviceFound` events, then delivers incremental events from a broadcast
+//! channel, optionally filtered by device type and capabilities.
+//!
+//! # Lag Handling
+//!
+//! The broadcast channel is bounded and never blocks the publishing protocol
+//! tasks. A subscriber that falls behind receives `SubscriptionEvent::Lagged`
+//! with a fresh snapshot of matching devices instead of the dropped events,
+//! and continues from there.
+
+use crate::capabilities::CapabilityFilter;
+use crate::protocol::ProtocolType;
+use crate::types::{DeviceInfo, DeviceType, DiscoveryEvent};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, RwLock};
+
+/// Device map shared with `DiscoveryManager` (device_id -> (DeviceInfo, source))
+pub(crate) type DeviceMap = Arc<RwLock<HashMap<String, (DeviceInfo, ProtocolType)>>>;
+
+/// Device filter applied to a subscription
+///
+/// An empty filter matches every device.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionFilter {
+    /// Accepted device types (empty = any)
+    pub device_types: Vec<DeviceType>,
+
+    /// Required capabilities
+    pub capabilities: CapabilityFilter,
+}
+
+impl SubscriptionFilter {
+    /// Create a filter that matches every device
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept devices of the given type (may be called repeatedly)
+    pub fn with_device_type(mut self, device_type: DeviceType) -> Self {
+        if !self.device_types.contains(&device_type) {
+            self.device_types.push(device_type);
+        }
+        self
+    }
+
+    /// Require capabilities
+    pub fn with_capabilities(mut self, capabilities: CapabilityFilter) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Check if a device passes this filter
+    pub fn matches(&self, device: &DeviceInfo) -> bool {
+        (self.device_types.is_empty() || self.device_types.contains(&device.device_type))
+            && self.capabilities.matches(&device.capabilities)
+    }
+}
+
+/// Event delivered to a subscriber
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionEvent {
+    /// Discovery event (initial snapshot entries are `DeviceFound`)
+    Event(DiscoveryEvent),
+
+    /// Subscriber fell behind and `missed` events were dropped
+    ///
+    /// `devices` is the current set of matching devices; it replaces
+    /// everything the subscriber knew before.
+    Lagged {
+        /// Number of dropped events
+        missed: u64,
+        /// Current matching devices
+        devices: Vec<DeviceInfo>,
+    },
+}
+
+/// Subscription to discovery events
+pub struct DiscoverySubscription {
+    devices: DeviceMap,
+    rx: broadcast::Receiver<DiscoveryEvent>,
+    filter: SubscriptionFilter,
+
+    /// Snapshot entries not yet delivered
+    pending: VecDeque<DeviceInfo>,
+
+    /// Devices delivered to this subscriber (for filtering `DeviceLost`)
+    visible: HashSet<String>,
+}
+
+impl DiscoverySubscription {
+    /// Create subscription from a device map and broadcast receiver
+    ///
+    /// Callers must hold the device map lock across `broadcast::subscribe()`
+    /// and this call, so no event falls between snapshot and stream.
+    pub(crate) fn new(
+        devices: DeviceMap,
+        snapshot: &HashMap<String, (DeviceInfo, ProtocolType)>,
+        rx: broadcast::Receiver<DiscoveryEvent>,
+        filter: SubscriptionFilter,
+    ) -> Self {
+        let pending: VecDeque<DeviceInfo> = matching(snapshot, &filter).into();
+        let visible = pending.iter().map(|d| d.device_id.clone()).collect();
+
+        Self {
+            devices,
+            rx,
+            filter,
+            pending,
+            visible,
+        }
+    }
+
+    /// Filter applied to this subscription
+    pub fn filter(&self) -> &SubscriptionFilter {
+        &self.filter
+    }
+
+    /// Receive the next event
+    ///
+    /// Returns `None` once the `DiscoveryManager` has been dropped.
+    pub async fn recv(&mut self) -> Option<SubscriptionEvent> {
+        if let Some(device) = self.pending.pop_front() {
+            return Some(SubscriptionEvent::Event(DiscoveryEvent::DeviceFound(
+                device,
+            )));
+        }
+
+        loop {
+            match self.rx.recv().await {
+                Ok(event) => {
+                    if let Some(event) = self.apply_filter(event) {
+                        return Some(SubscriptionEvent::Event(event));
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => return Some(self.resync(missed).await),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Map a broadcast event to what this subscriber should see
+    fn apply_filter(&mut self, event: DiscoveryEvent) -> Option<DiscoveryEvent> {
+        match event {
+            DiscoveryEvent::DeviceFound(device) => {
+                if self.filter.matches(&device) {
+                    self.visible.insert(device.device_id.clone());
+                    Some(DiscoveryEvent::DeviceFound(device))
+                } else if self.visible.remove(&device.device_id) {
+                    // Device no longer matches (e.g. capabilities changed)
+                    Some(DiscoveryEvent::DeviceLost(device.device_id))
+                } else {
+                    None
+                }
+            }
+            DiscoveryEvent::DeviceLost(device_id) => self
+                .visible
+                .remove(&device_id)
+                .then_some(DiscoveryEvent::DeviceLost(device_id)),
+            DiscoveryEvent::NetworkChanged => Some(DiscoveryEvent::NetworkChanged),
+        }
+    }
+
+    /// Replace dropped events with a fresh snapshot
+    async fn resync(&mut self, missed: u64) -> SubscriptionEvent {
+        // Publishers hold the write lock while sending, so resubscribing under
+        // the read lock neither loses nor repeats events.
+        let devices = self.devices.read().await;
+        self.rx = self.rx.resubscribe();
+
+        let snapshot = matching(&devices, &self.filter);
+        self.pending.clear();
+        self.visible = snapshot.iter().map(|d| d.device_id.clone()).collect();
+
+        SubscriptionEvent::Lagged {
+            missed,
+            devices: snapshot,
+        }
+    }
+}
+
+/// Matching devices from a device map, ordered by device ID
+fn matching(
+    devices: &HashMap<String, (DeviceInfo, ProtocolType)>,
+    filter: &SubscriptionFilter,
+) -> Vec<DeviceInfo> {
+    let mut matching: Vec<DeviceInfo> = devices
+        .values()
+        .map(|(device, _)| device)
+        .filter(|device| filter.matches(device))
+        .cloned()
+        .collect();
+    matching.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+    matching
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::{DeviceCapabilities, Service};
+
+    #[test]
+    fn test_subscription_filter_matches() {
+        let laptop = DeviceInfo::new("DEV-001", "Laptop", DeviceType::Desktop)
+            .with_capabilities(DeviceCapabilities::new().with_service(Service::FileTransfer));
+        let sensor = DeviceInfo::new("DEV-002", "Sensor", DeviceType::Iot);
+
+        assert!(SubscriptionFilter::new().matches(&laptop));
+        assert!(SubscriptionFilter::new().matches(&sensor));
+
+        let by_type = SubscriptionFilter::new()
+            .with_device_type(DeviceType::Iot)
+            .with_device_type(DeviceType::Mobile);
+        assert!(!by_type.matches(&laptop));
+        assert!(by_type.matches(&sensor));
+
+        let by_caps = SubscriptionFilter::new()
+            .with_capabilities(CapabilityFilter::new().require_service(Service::FileTransfer));
+        assert!(by_caps.matches(&laptop));
+        assert!(!by_caps.matches(&sensor));
+    }
+}
@@ -1,5 +1,6 @@
 //! Device information types for discovery
 
+use crate::capabilities::DeviceCapabilities;
 use serde::{Deserialize, Serialize};
 use std::net::IpAddr;
 
@@ -89,6 +90,10 @@ pub struct DeviceInfo {
     /// Signal strength (RSSI in dBm, for BLE)
     pub rssi: Option<i16>,
 
+    /// Advertised services, transports, protocol versions and policy presets
+    #[serde(default)]
+    pub capabilities: DeviceCapabilities,
+
     /// Discovery timestamp (Unix epoch milliseconds)
     pub discovered_at: u64,
 }
@@ -108,6 +113,7 @@ impl DeviceInfo {
             addresses: Vec::new(),
             port: 7843, // Default QUIC port
             rssi: None,
+            capabilities: DeviceCapabilities::default(),
             discovered_at: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
@@ -132,6 +138,12 @@ impl DeviceInfo {
         self.rssi = Some(rssi);
         self
     }
+
+    /// Set advertised capabilities
+    pub fn with_capabilities(mut self, capabilities: DeviceCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
 }
 
 /// Discovery events
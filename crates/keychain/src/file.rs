@@ -0,0 +1,646 @@
+//! Encrypted file-backed keychain
+//!
+//! `FileKeychain` stores credentials in a single JSON file for hosts without
+//! an OS keychain (headless Linux, containers, CI, edge gateways).
+//!
+//! # Security Model
+//!
+//! - **Wrapping key**: Derived with Argon2id from a passphrase, a secret file
+//!   or the machine ID (see `KeySource`)
+//! - **Per-entry AEAD**: Each credential is encrypted separately with
+//!   ChaCha20-Poly1305; the credential name is bound as associated data, so
+//!   entries cannot be swapped between names
+//! - **Key check**: A known value encrypted under the wrapping key detects a
+//!   wrong secret before any entry is touched
+//! - **Atomic writes**: Changes are written to a temporary file, synced and
+//!   renamed over the original
+//! - **File locking**: A sidecar `.lock` file serializes writers across
+//!   processes; readers take a shared lock
+//! - **Rotation**: `rotate_wrapping_key()` re-encrypts every entry under a new
+//!   salt and secret
+//!
+//! # File Format
+//!
+//! ```json
+//! {
+//!   "format": 1,
+//!   "kdf": { "salt": "...", "memory_kib": 19456, "iterations": 2, "parallelism": 1 },
+//!   "key_check": { "nonce": "...", "ciphertext": "..." },
+//!   "entries": { "api_key": { "nonce": "...", "ciphertext": "..." } }
+//! }
+//! ```
+
+use crate::{KeychainError, KeychainProvider, Result, SecureCredential};
+use base64::Engine as _;
+use honeylink_crypto::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use honeylink_crypto::file_key_store::{derive_passphrase_key, KdfParams};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroizing;
+
+/// Current file format version
+const FORMAT_VERSION: u32 = 1;
+
+/// Salt length for Argon2id (bytes)
+const SALT_SIZE: usize = 16;
+
+/// Plaintext of the key check entry
+const KEY_CHECK: &[u8] = b"honeylink-file-keychain";
+
+/// Associated data of the key check entry
+const KEY_CHECK_AAD: &[u8] = b"honeylink-file-keychain:key-check";
+
+/// Machine ID locations (systemd, then D-Bus)
+const MACHINE_ID_PATHS: &[&str] = &["/etc/machine-id", "/var/lib/dbus/machine-id"];
+
+/// Source of the secret the wrapping key is derived from
+#[derive(Clone)]
+pub enum KeySource {
+    /// Operator-supplied passphrase
+    Passphrase(Zeroizing<String>),
+
+    /// Contents of a file (e.g. a root-only secret provisioned at install time)
+    SecretFile(PathBuf),
+
+    /// Machine ID (`/etc/machine-id`)
+    ///
+    /// Binds the keychain to the host without operator input. The machine ID
+    /// is readable by all local users, so this protects against copying the
+    /// file to another machine, not against local attackers.
+    MachineId,
+}
+
+impl KeySource {
+    /// Create a passphrase key source
+    pub fn passphrase(passphrase: impl Into<String>) -> Self {
+        KeySource::Passphrase(Zeroizing::new(passphrase.into()))
+    }
+
+    /// Load the secret bytes, prefixed with a per-source domain label
+    fn secret(&self) -> Result<Zeroizing<Vec<u8>>> {
+        let (label, bytes): (&[u8], Zeroizing<Vec<u8>>) = match self {
+            KeySource::Passphrase(passphrase) => {
+                if passphrase.is_empty() {
+                    return Err(KeychainError::InvalidCredential(
+                        "Passphrase must be non-empty".to_string(),
+                    ));
+                }
+                (
+                    b"passphrase:",
+                    Zeroizing::new(passphrase.as_bytes().to_vec()),
+                )
+            }
+            KeySource::SecretFile(path) => {
+                let bytes = Zeroizing::new(fs::read(path).map_err(|e| {
+                    KeychainError::AccessDenied(format!(
+                        "Failed to read secret file {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?);
+                if bytes.is_empty() {
+                    return Err(KeychainError::InvalidCredential(format!(
+                        "Secret file {} is empty",
+                        path.display()
+                    )));
+                }
+                (b"secret-file:", bytes)
+            }
+            KeySource::MachineId => {
+                let id = MACHINE_ID_PATHS
+                    .iter()
+                    .find_map(|path| fs::read_to_string(path).ok())
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+                    .ok_or_else(|| {
+                        KeychainError::ServiceUnavailable("No machine ID available".to_string())
+                    })?;
+                (b"machine-id:", Zeroizing::new(id.into_bytes()))
+            }
+        };
+
+        let mut secret = Zeroizing::new(Vec::with_capacity(label.len() + bytes.len()));
+        secret.extend_from_slice(label);
+        secret.extend_from_slice(&bytes);
+        Ok(secret)
+    }
+}
+
+impl fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Passphrase(_) => f.write_str("Passphrase(<redacted>)"),
+            KeySource::SecretFile(path) => f.debug_tuple("SecretFile").field(path).finish(),
+            KeySource::MachineId => f.write_str("MachineId"),
+        }
+    }
+}
+
+/// Single AEAD-encrypted value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedValue {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// KDF section of the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfHeader {
+    salt: String,
+    #[serde(flatten)]
+    params: KdfParams,
+}
+
+/// On-disk representation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeychainFile {
+    format: u32,
+    kdf: KdfHeader,
+    key_check: SealedValue,
+    entries: BTreeMap<String, SealedValue>,
+}
+
+/// Wrapping key derived for a specific salt
+struct WrappingKey {
+    salt: String,
+    cipher: Arc<ChaCha20Poly1305Cipher>,
+}
+
+/// Encrypted file-backed credential store
+///
+/// # Example
+/// ```no_run
+/// use honeylink_keychain::{FileKeychain, KeySource, KeychainProvider};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let keychain = FileKeychain::open("/var/lib/honeylink/keychain.json", KeySource::MachineId)?;
+/// keychain.set_credential("api_key", b"secret-key-data")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileKeychain {
+    path: PathBuf,
+    lock_path: PathBuf,
+    source: Mutex<KeySource>,
+    params: KdfParams,
+    /// Cached wrapping key (re-derived when another process rotates the file)
+    wrapping_key: Mutex<Option<WrappingKey>>,
+}
+
+impl FileKeychain {
+    /// Open the keychain file at `path`, creating it if it does not exist
+    ///
+    /// # Errors
+    /// - `AccessDenied`: Wrong secret, or the file cannot be read/written
+    /// - `PlatformError`: Corrupted or unsupported file
+    pub fn open(path: impl AsRef<Path>, source: KeySource) -> Result<Self> {
+        Self::open_with_params(path, source, KdfParams::default())
+    }
+
+    /// Open the keychain, using `params` when a new file has to be created
+    pub fn open_with_params(
+        path: impl AsRef<Path>,
+        source: KeySource,
+        params: KdfParams,
+    ) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut lock_name = path.file_name().unwrap_or_default().to_os_string();
+        lock_name.push(".lock");
+
+        let keychain = Self {
+            lock_path: path.with_file_name(lock_name),
+            path,
+            source: Mutex::new(source),
+            params,
+            wrapping_key: Mutex::new(None),
+        };
+
+        // Create the file or verify the secret up front
+        let _lock = keychain.lock(true)?;
+        match keychain.read_file()? {
+            Some(file) => {
+                keychain.cipher_for(&file)?;
+            }
+            None => {
+                let source = keychain.source.lock().unwrap_or_else(|e| e.into_inner());
+                let (file, wrapping_key) = new_file(&source, params)?;
+                drop(source);
+                keychain.write_file(&file)?;
+                *keychain
+                    .wrapping_key
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) = Some(wrapping_key);
+            }
+        }
+
+        Ok(keychain)
+    }
+
+    /// Path of the keychain file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Names of all stored credentials
+    pub fn list_credentials(&self) -> Result<Vec<String>> {
+        let _lock = self.lock(false)?;
+        Ok(self.load()?.entries.keys().cloned().collect())
+    }
+
+    /// Re-encrypt all credentials under a new wrapping key
+    ///
+    /// Derives a new key from `new_source` with a fresh salt and replaces the
+    /// file atomically; on failure the file keeps the old wrapping key.
+    pub fn rotate_wrapping_key(&self, new_source: KeySource) -> Result<()> {
+        let _lock = self.lock(true)?;
+        let file = self.load()?;
+        let old_cipher = self.cipher_for(&file)?;
+
+        let (mut rotated, new_key) = new_file(&new_source, self.params)?;
+        for (name, sealed) in &file.entries {
+            let value = Zeroizing::new(open_value(&old_cipher, sealed, &entry_aad(name))?);
+            rotated.entries.insert(
+                name.clone(),
+                seal(&new_key.cipher, &value, &entry_aad(name))?,
+            );
+        }
+
+        self.write_file(&rotated)?;
+        *self.source.lock().unwrap_or_else(|e| e.into_inner()) = new_source;
+        *self.wrapping_key.lock().unwrap_or_else(|e| e.into_inner()) = Some(new_key);
+        Ok(())
+    }
+
+    /// Acquire the cross-process lock (exclusive for writers)
+    fn lock(&self, exclusive: bool) -> Result<File> {
+        if let Some(parent) = self
+            .lock_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&self.lock_path)
+            .map_err(io_error)?;
+
+        if exclusive {
+            lock_file.lock().map_err(io_error)?;
+        } else {
+            lock_file.lock_shared().map_err(io_error)?;
+        }
+
+        // Released when the returned handle is dropped
+        Ok(lock_file)
+    }
+
+    /// Read the keychain file (None if it does not exist)
+    fn read_file(&self) -> Result<Option<KeychainFile>> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_error(e)),
+        };
+
+        let file: KeychainFile = serde_json::from_slice(&data)
+            .map_err(|e| KeychainError::PlatformError(format!("Corrupted keychain file: {}", e)))?;
+
+        if file.format != FORMAT_VERSION {
+            return Err(KeychainError::PlatformError(format!(
+                "Unsupported keychain file format {}",
+                file.format
+            )));
+        }
+
+        Ok(Some(file))
+    }
+
+    /// Read the keychain file, which must exist
+    fn load(&self) -> Result<KeychainFile> {
+        self.read_file()?.ok_or_else(|| {
+            KeychainError::ServiceUnavailable(format!(
+                "Keychain file {} was removed",
+                self.path.display()
+            ))
+        })
+    }
+
+    /// Atomically replace the keychain file
+    fn write_file(&self, file: &KeychainFile) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+
+        let data = serde_json::to_vec_pretty(file)
+            .map_err(|e| KeychainError::PlatformError(e.to_string()))?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        let mut tmp = open_private(&tmp_path)?;
+        tmp.write_all(&data).map_err(io_error)?;
+        tmp.sync_all().map_err(io_error)?;
+        drop(tmp);
+
+        fs::rename(&tmp_path, &self.path).map_err(io_error)
+    }
+
+    /// Wrapping key for `file`, re-deriving it if the salt changed
+    fn cipher_for(&self, file: &KeychainFile) -> Result<Arc<ChaCha20Poly1305Cipher>> {
+        let mut cached = self.wrapping_key.lock().unwrap_or_else(|e| e.into_inner());
+
+        if cached.as_ref().map(|key| key.salt.as_str()) != Some(file.kdf.salt.as_str()) {
+            let salt = decode_b64(&file.kdf.salt)?;
+            let source = self.source.lock().unwrap_or_else(|e| e.into_inner());
+            let cipher = derive_cipher(&source, &salt, file.kdf.params)?;
+
+            open_value(&cipher, &file.key_check, KEY_CHECK_AAD).map_err(|_| {
+                KeychainError::AccessDenied("Wrong keychain secret (key check failed)".to_string())
+            })?;
+
+            *cached = Some(WrappingKey {
+                salt: file.kdf.salt.clone(),
+                cipher: Arc::new(cipher),
+            });
+        }
+
+        Ok(cached
+            .as_ref()
+            .map(|key| Arc::clone(&key.cipher))
+            .expect("wrapping key was just set"))
+    }
+}
+
+impl KeychainProvider for FileKeychain {
+    fn set_credential(&self, key: &str, value: &[u8]) -> Result<()> {
+        validate_name(key)?;
+
+        let _lock = self.lock(true)?;
+        let mut file = self.load()?;
+        let sealed = seal(&*self.cipher_for(&file)?, value, &entry_aad(key))?;
+        file.entries.insert(key.to_string(), sealed);
+        self.write_file(&file)
+    }
+
+    fn get_credential(&self, key: &str) -> Result<SecureCredential> {
+        let _lock = self.lock(false)?;
+        let file = self.load()?;
+        let sealed = file
+            .entries
+            .get(key)
+            .ok_or_else(|| KeychainError::CredentialNotFound(key.to_string()))?;
+
+        let value = open_value(&*self.cipher_for(&file)?, sealed, &entry_aad(key))?;
+        Ok(SecureCredential::new(value))
+    }
+
+    fn delete_credential(&self, key: &str) -> Result<()> {
+        let _lock = self.lock(true)?;
+        let mut file = self.load()?;
+        if file.entries.remove(key).is_some() {
+            self.write_file(&file)?;
+        }
+        Ok(())
+    }
+
+    fn has_credential(&self, key: &str) -> bool {
+        self.lock(false)
+            .and_then(|_lock| self.load())
+            .map(|file| file.entries.contains_key(key))
+            .unwrap_or(false)
+    }
+}
+
+impl fmt::Debug for FileKeychain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileKeychain")
+            .field("path", &self.path)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+/// Create an empty keychain file with a fresh salt
+fn new_file(source: &KeySource, params: KdfParams) -> Result<(KeychainFile, WrappingKey)> {
+    let mut salt = [0u8; SALT_SIZE];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    let cipher = derive_cipher(source, &salt, params)?;
+    let salt = encode_b64(&salt);
+
+    let file = KeychainFile {
+        format: FORMAT_VERSION,
+        kdf: KdfHeader {
+            salt: salt.clone(),
+            params,
+        },
+        key_check: seal(&cipher, KEY_CHECK, KEY_CHECK_AAD)?,
+        entries: BTreeMap::new(),
+    };
+
+    Ok((
+        file,
+        WrappingKey {
+            salt,
+            cipher: Arc::new(cipher),
+        },
+    ))
+}
+
+fn derive_cipher(
+    source: &KeySource,
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<ChaCha20Poly1305Cipher> {
+    let key = derive_passphrase_key(&source.secret()?, salt, params)
+        .map_err(|e| KeychainError::PlatformError(e.to_string()))?;
+    ChaCha20Poly1305Cipher::new(key.as_ref())
+        .map_err(|e| KeychainError::PlatformError(e.to_string()))
+}
+
+fn seal(cipher: &ChaCha20Poly1305Cipher, value: &[u8], aad: &[u8]) -> Result<SealedValue> {
+    let (nonce, ciphertext) = cipher
+        .encrypt(value, aad)
+        .map_err(|e| KeychainError::InvalidCredential(e.to_string()))?;
+
+    Ok(SealedValue {
+        nonce: encode_b64(&nonce),
+        ciphertext: encode_b64(&ciphertext),
+    })
+}
+
+fn open_value(
+    cipher: &ChaCha20Poly1305Cipher,
+    sealed: &SealedValue,
+    aad: &[u8],
+) -> Result<Vec<u8>> {
+    let nonce = decode_b64(&sealed.nonce)?;
+    if nonce.len() != NONCE_SIZE {
+        return Err(KeychainError::PlatformError(
+            "Invalid nonce length".to_string(),
+        ));
+    }
+
+    cipher
+        .decrypt(&nonce, &decode_b64(&sealed.ciphertext)?, aad)
+        .map_err(|_| KeychainError::AccessDenied("Credential decryption failed".to_string()))
+}
+
+/// Associated data binding an entry to its credential name
+fn entry_aad(name: &str) -> Vec<u8> {
+    format!("honeylink-file-keychain:entry:{}", name).into_bytes()
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > 256 || name.chars().any(char::is_control) {
+        return Err(KeychainError::InvalidCredential(format!(
+            "Invalid credential name '{}'",
+            name.escape_debug()
+        )));
+    }
+    Ok(())
+}
+
+/// Create (truncate) a file readable only by the owner
+fn open_private(path: &Path) -> Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).truncate(true).write(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(path).map_err(io_error)
+}
+
+fn encode_b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+fn decode_b64(data: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| KeychainError::PlatformError(format!("Corrupted keychain file: {}", e)))
+}
+
+fn io_error(e: std::io::Error) -> KeychainError {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => KeychainError::AccessDenied(e.to_string()),
+        _ => KeychainError::PlatformError(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(dir: &tempfile::TempDir, passphrase: &str) -> Result<FileKeychain> {
+        FileKeychain::open_with_params(
+            dir.path().join("keychain.json"),
+            KeySource::passphrase(passphrase),
+            KdfParams::TESTING,
+        )
+    }
+
+    #[test]
+    fn test_file_keychain_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let keychain = open(&dir, "passphrase").unwrap();
+
+        // Unlike SystemKeychain, binary values are supported
+        keychain
+            .set_credential("api_key", &[0xFF, 0x00, 0x7F])
+            .unwrap();
+        assert!(keychain.has_credential("api_key"));
+        assert_eq!(
+            keychain.get_credential("api_key").unwrap().as_bytes(),
+            &[0xFF, 0x00, 0x7F]
+        );
+
+        // Visible to a second instance (e.g. another process)
+        let other = open(&dir, "passphrase").unwrap();
+        assert_eq!(other.list_credentials().unwrap(), vec!["api_key"]);
+
+        let raw = fs::read_to_string(keychain.path()).unwrap();
+        assert!(!raw.contains(&encode_b64(&[0xFF, 0x00, 0x7F])));
+
+        other.delete_credential("api_key").unwrap();
+        assert!(!keychain.has_credential("api_key"));
+        assert!(matches!(
+            keychain.get_credential("api_key"),
+            Err(KeychainError::CredentialNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_keychain_wrong_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        open(&dir, "right").unwrap();
+
+        assert!(matches!(
+            open(&dir, "wrong"),
+            Err(KeychainError::AccessDenied(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_keychain_entries_bound_to_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let keychain = open(&dir, "passphrase").unwrap();
+        keychain.set_credential("a", b"value-a").unwrap();
+        keychain.set_credential("b", b"value-b").unwrap();
+
+        // Swap the ciphertexts on disk
+        let mut file = keychain.load().unwrap();
+        let a = file.entries["a"].clone();
+        let b = file.entries["b"].clone();
+        file.entries.insert("a".to_string(), b);
+        file.entries.insert("b".to_string(), a);
+        keychain.write_file(&file).unwrap();
+
+        assert!(keychain.get_credential("a").is_err());
+        assert!(keychain.get_credential("b").is_err());
+    }
+
+    #[test]
+    fn test_file_keychain_rotate_wrapping_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret_path = dir.path().join("secret");
+        fs::write(&secret_path, b"provisioned-secret").unwrap();
+
+        let keychain = open(&dir, "old-passphrase").unwrap();
+        keychain.set_credential("token", b"value").unwrap();
+        let old_salt = keychain.load().unwrap().kdf.salt;
+
+        keychain
+            .rotate_wrapping_key(KeySource::SecretFile(secret_path.clone()))
+            .unwrap();
+        assert_ne!(keychain.load().unwrap().kdf.salt, old_salt);
+        assert_eq!(
+            keychain.get_credential("token").unwrap().as_bytes(),
+            b"value"
+        );
+
+        assert!(open(&dir, "old-passphrase").is_err());
+        let reopened = FileKeychain::open_with_params(
+            dir.path().join("keychain.json"),
+            KeySource::SecretFile(secret_path),
+            KdfParams::TESTING,
+        )
+        .unwrap();
+        assert_eq!(
+            reopened.get_credential("token").unwrap().as_bytes(),
+            b"value"
+        );
+    }
+}
@@ -0,0 +1,751 @@
+//! Device identity and trusted peers kept in the keychain.
+//!
+//! A device is identified by an Ed25519 key. The same key signs policy
+//! profiles and, converted with `honeylink_crypto::certificate::noise_identity`,
+//! serves as the Noise static key of secure channels. Peers exchange
+//! identities through a pairing payload (typically shown as a QR code) and
+//! compare fingerprints out of band before trusting each other.
+//!
+//! # Credentials
+//! - `identity/device`: device ID, Ed25519 secret key and creation time
+//! - `identity/trusted-peers`: JSON list of trusted peers
+//!
+//! # Pairing Payload
+//! ```text
+//! honeylink://pair?v=1&id=<device id>&key=<base64url Ed25519 public key>
+//! ```
+//!
+//! # Example
+//! ```no_run
+//! use honeylink_keychain::identity::{DeviceIdentity, PairingPayload, TrustedPeers};
+//! use honeylink_keychain::SystemKeychain;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let keychain = SystemKeychain::new("honeylink", "default")?;
+//! let identity = DeviceIdentity::generate(None);
+//! identity.save(&keychain)?;
+//! println!("{}", identity.pairing().to_uri());
+//!
+//! let mut peers = TrustedPeers::load(&keychain)?;
+//! peers.add("laptop", &PairingPayload::parse("honeylink://pair?v=1&id=...&key=...")?)?;
+//! peers.save(&keychain)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{KeychainError, KeychainProvider};
+use base64::Engine as _;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use honeylink_core::types::DeviceId;
+use honeylink_crypto::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use honeylink_crypto::file_key_store::{derive_passphrase_key, KdfParams};
+use honeylink_crypto::key_agreement::PublicKey;
+use honeylink_crypto::key_store::KeyStoreError;
+use honeylink_crypto::signing;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Credential holding the device identity
+pub const IDENTITY_CREDENTIAL: &str = "identity/device";
+
+/// Credential holding the trusted peer list
+pub const TRUSTED_PEERS_CREDENTIAL: &str = "identity/trusted-peers";
+
+/// Prefix of pairing payloads
+pub const PAIRING_PREFIX: &str = "honeylink://pair?";
+
+/// Current encrypted backup format version
+pub const BACKUP_FORMAT: u32 = 1;
+
+/// Errors from identity and trust operations
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    /// No identity has been created on this device
+    #[error("No device identity in the keychain")]
+    NotFound,
+
+    /// Stored or supplied identity data is malformed
+    #[error("Invalid identity data: {0}")]
+    Invalid(String),
+
+    /// Pairing payload could not be parsed
+    #[error("Invalid pairing payload: {0}")]
+    InvalidPairing(String),
+
+    /// A peer with the same name or key is already trusted
+    #[error("Peer already trusted: {0}")]
+    DuplicatePeer(String),
+
+    /// Backup could not be decrypted
+    #[error("Backup decryption failed (wrong passphrase or corrupted backup)")]
+    Decryption,
+
+    /// Keychain access failed
+    #[error(transparent)]
+    Keychain(#[from] KeychainError),
+
+    /// Passphrase key derivation failed
+    #[error(transparent)]
+    KeyStore(#[from] KeyStoreError),
+
+    /// JSON encoding failed
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// Result type for identity operations
+pub type Result<T> = std::result::Result<T, IdentityError>;
+
+/// This device's Ed25519 identity
+pub struct DeviceIdentity {
+    device_id: DeviceId,
+    signing_key: SigningKey,
+    created_at: u64,
+}
+
+/// Keychain representation of `DeviceIdentity`
+#[derive(Serialize, Deserialize)]
+struct IdentityRecord {
+    device_id: String,
+    signing_key: String,
+    created_at: u64,
+}
+
+impl DeviceIdentity {
+    /// Generates a new identity
+    ///
+    /// Without a device ID, `DEV-` followed by the first 8 bytes of the
+    /// public key in hex is used.
+    pub fn generate(device_id: Option<DeviceId>) -> Self {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(secret.as_mut());
+        let signing_key = SigningKey::from_bytes(&secret);
+
+        let device_id = device_id.unwrap_or_else(|| {
+            let prefix: String = signing_key.verifying_key().as_bytes()[..8]
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect();
+            DeviceId::new(format!("DEV-{}", prefix)).expect("generated device ID is valid")
+        });
+
+        Self {
+            device_id,
+            signing_key,
+            created_at: unix_now(),
+        }
+    }
+
+    /// Returns true if an identity is stored in `keychain`
+    pub fn exists(keychain: &dyn KeychainProvider) -> bool {
+        keychain.has_credential(IDENTITY_CREDENTIAL)
+    }
+
+    /// Loads the identity from `keychain`
+    ///
+    /// # Errors
+    /// `NotFound` if no identity has been saved yet.
+    pub fn load(keychain: &dyn KeychainProvider) -> Result<Self> {
+        let credential = match keychain.get_credential(IDENTITY_CREDENTIAL) {
+            Ok(credential) => credential,
+            Err(KeychainError::CredentialNotFound(_)) => return Err(IdentityError::NotFound),
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_record(serde_json::from_slice(credential.as_bytes())?)
+    }
+
+    /// Stores the identity in `keychain`, replacing any existing one
+    pub fn save(&self, keychain: &dyn KeychainProvider) -> Result<()> {
+        let record = Zeroizing::new(serde_json::to_vec(&self.to_record())?);
+        keychain.set_credential(IDENTITY_CREDENTIAL, &record)?;
+        Ok(())
+    }
+
+    /// Device ID announced to peers
+    pub fn device_id(&self) -> &DeviceId {
+        &self.device_id
+    }
+
+    /// Ed25519 signing key
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.signing_key
+    }
+
+    /// Ed25519 public key
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Noise static public key derived from the identity key
+    pub fn noise_public_key(&self) -> PublicKey {
+        noise_public_key(&self.verifying_key())
+    }
+
+    /// Public key fingerprint for out-of-band comparison
+    pub fn fingerprint(&self) -> String {
+        signing::fingerprint(&self.verifying_key())
+    }
+
+    /// Creation time (Unix seconds)
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    /// Pairing payload to share with peers
+    pub fn pairing(&self) -> PairingPayload {
+        PairingPayload {
+            device_id: self.device_id.clone(),
+            signing_key: self.verifying_key(),
+        }
+    }
+
+    fn to_record(&self) -> IdentityRecord {
+        IdentityRecord {
+            device_id: self.device_id.to_string(),
+            signing_key: encode_b64(self.signing_key.as_bytes()),
+            created_at: self.created_at,
+        }
+    }
+
+    fn from_record(record: IdentityRecord) -> Result<Self> {
+        let secret = Zeroizing::new(decode_b64(&record.signing_key)?);
+        let secret: &[u8; 32] = secret
+            .as_slice()
+            .try_into()
+            .map_err(|_| IdentityError::Invalid("signing key must be 32 bytes".to_string()))?;
+
+        Ok(Self {
+            device_id: DeviceId::new(record.device_id).map_err(IdentityError::Invalid)?,
+            signing_key: SigningKey::from_bytes(secret),
+            created_at: record.created_at,
+        })
+    }
+}
+
+impl std::fmt::Debug for DeviceIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceIdentity")
+            .field("device_id", &self.device_id)
+            .field("fingerprint", &self.fingerprint())
+            .field("signing_key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Identity a peer shares for pairing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairingPayload {
+    /// Peer device ID
+    pub device_id: DeviceId,
+    /// Peer Ed25519 public key
+    pub signing_key: VerifyingKey,
+}
+
+impl PairingPayload {
+    /// Encodes the payload as a `honeylink://pair` URI
+    pub fn to_uri(&self) -> String {
+        format!(
+            "{}v=1&id={}&key={}",
+            PAIRING_PREFIX,
+            self.device_id,
+            encode_b64(self.signing_key.as_bytes())
+        )
+    }
+
+    /// Parses a URI produced by `to_uri`
+    pub fn parse(uri: &str) -> Result<Self> {
+        let query = uri
+            .trim()
+            .strip_prefix(PAIRING_PREFIX)
+            .ok_or_else(|| IdentityError::InvalidPairing("not a honeylink://pair URI".into()))?;
+
+        let (mut version, mut device_id, mut key) = (None, None, None);
+        for pair in query.split('&') {
+            match pair.split_once('=') {
+                Some(("v", value)) => version = Some(value),
+                Some(("id", value)) => device_id = Some(value),
+                Some(("key", value)) => key = Some(value),
+                _ => {} // Unknown fields are reserved for later versions
+            }
+        }
+
+        if version != Some("1") {
+            return Err(IdentityError::InvalidPairing(format!(
+                "unsupported version {:?}",
+                version
+            )));
+        }
+        let device_id = device_id
+            .ok_or_else(|| IdentityError::InvalidPairing("missing device ID".into()))
+            .and_then(|id| DeviceId::new(id.to_string()).map_err(IdentityError::InvalidPairing))?;
+        let key = key.ok_or_else(|| IdentityError::InvalidPairing("missing key".into()))?;
+
+        Ok(Self {
+            device_id,
+            signing_key: parse_verifying_key(key)?,
+        })
+    }
+
+    /// Public key fingerprint (compare with the peer's `fingerprint()`)
+    pub fn fingerprint(&self) -> String {
+        signing::fingerprint(&self.signing_key)
+    }
+}
+
+/// A peer this device trusts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedPeer {
+    name: String,
+    device_id: String,
+    #[serde(with = "verifying_key_b64")]
+    signing_key: VerifyingKey,
+    added_at: u64,
+}
+
+impl TrustedPeer {
+    /// Local name chosen when the peer was added
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Device ID from the pairing payload
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Ed25519 public key
+    pub fn signing_key(&self) -> &VerifyingKey {
+        &self.signing_key
+    }
+
+    /// Noise static public key (for `SecureChannelConfig::with_expected_remote`)
+    pub fn noise_public_key(&self) -> PublicKey {
+        noise_public_key(&self.signing_key)
+    }
+
+    /// Public key fingerprint
+    pub fn fingerprint(&self) -> String {
+        signing::fingerprint(&self.signing_key)
+    }
+
+    /// Time the peer was trusted (Unix seconds)
+    pub fn added_at(&self) -> u64 {
+        self.added_at
+    }
+}
+
+/// Trusted peer list stored in the keychain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedPeers {
+    peers: Vec<TrustedPeer>,
+}
+
+impl TrustedPeers {
+    /// Loads the list (empty if none was saved)
+    pub fn load(keychain: &dyn KeychainProvider) -> Result<Self> {
+        match keychain.get_credential(TRUSTED_PEERS_CREDENTIAL) {
+            Ok(credential) => Ok(Self {
+                peers: serde_json::from_slice(credential.as_bytes())?,
+            }),
+            Err(KeychainError::CredentialNotFound(_)) => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores the list in `keychain`
+    pub fn save(&self, keychain: &dyn KeychainProvider) -> Result<()> {
+        keychain.set_credential(TRUSTED_PEERS_CREDENTIAL, &serde_json::to_vec(&self.peers)?)?;
+        Ok(())
+    }
+
+    /// Trusted peers in the order they were added
+    pub fn peers(&self) -> &[TrustedPeer] {
+        &self.peers
+    }
+
+    /// Trusts the peer described by `pairing` under `name`
+    ///
+    /// # Errors
+    /// `DuplicatePeer` if the name or key is already trusted.
+    pub fn add(&mut self, name: &str, pairing: &PairingPayload) -> Result<&TrustedPeer> {
+        if let Some(existing) = self
+            .peers
+            .iter()
+            .find(|p| p.name == name || p.signing_key == pairing.signing_key)
+        {
+            return Err(IdentityError::DuplicatePeer(existing.name.clone()));
+        }
+
+        self.peers.push(TrustedPeer {
+            name: name.to_string(),
+            device_id: pairing.device_id.to_string(),
+            signing_key: pairing.signing_key,
+            added_at: unix_now(),
+        });
+        Ok(&self.peers[self.peers.len() - 1])
+    }
+
+    /// Removes the peer with this name or fingerprint
+    pub fn remove(&mut self, name_or_fingerprint: &str) -> Option<TrustedPeer> {
+        let index = self.peers.iter().position(|p| {
+            p.name == name_or_fingerprint || p.fingerprint() == name_or_fingerprint
+        })?;
+        Some(self.peers.remove(index))
+    }
+
+    /// Finds the peer owning `key`
+    pub fn find(&self, key: &VerifyingKey) -> Option<&TrustedPeer> {
+        self.peers.iter().find(|p| &p.signing_key == key)
+    }
+
+    /// Adds the peers of `other` that are not trusted yet
+    ///
+    /// Peers whose key is already trusted are skipped, as are peers whose
+    /// name is taken by a different key (the existing entry wins).
+    ///
+    /// # Returns
+    /// Number of peers added
+    pub fn merge(&mut self, other: &TrustedPeers) -> usize {
+        let mut added = 0;
+        for peer in &other.peers {
+            if self
+                .peers
+                .iter()
+                .any(|p| p.name == peer.name || p.signing_key == peer.signing_key)
+            {
+                continue;
+            }
+            self.peers.push(peer.clone());
+            added += 1;
+        }
+        added
+    }
+}
+
+/// Identity and trusted peers, for encrypted export and import
+#[derive(Debug)]
+pub struct IdentityBackup {
+    /// Device identity, if one existed
+    pub identity: Option<DeviceIdentity>,
+    /// Trusted peers
+    pub trusted_peers: TrustedPeers,
+}
+
+/// Backup plaintext
+#[derive(Serialize, Deserialize)]
+struct BackupContents {
+    identity: Option<IdentityRecord>,
+    trusted_peers: Vec<TrustedPeer>,
+}
+
+/// Encrypted backup file
+#[derive(Serialize, Deserialize)]
+struct SealedBackup {
+    format: u32,
+    salt: String,
+    #[serde(flatten)]
+    params: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl SealedBackup {
+    /// Header fields bound to the ciphertext
+    fn aad(&self) -> Vec<u8> {
+        format!(
+            "honeylink-identity-backup|{}|{}|{}|{}|{}",
+            self.format,
+            self.salt,
+            self.params.memory_kib,
+            self.params.iterations,
+            self.params.parallelism
+        )
+        .into_bytes()
+    }
+}
+
+impl IdentityBackup {
+    /// Collects the identity and trusted peers stored in `keychain`
+    pub fn from_keychain(keychain: &dyn KeychainProvider) -> Result<Self> {
+        let identity = match DeviceIdentity::load(keychain) {
+            Ok(identity) => Some(identity),
+            Err(IdentityError::NotFound) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            identity,
+            trusted_peers: TrustedPeers::load(keychain)?,
+        })
+    }
+
+    /// Writes the backup into `keychain`, replacing the identity (if the
+    /// backup has one) and merging the trusted peers into the stored list
+    ///
+    /// # Returns
+    /// Number of trusted peers added (see `TrustedPeers::merge`)
+    pub fn restore(&self, keychain: &dyn KeychainProvider) -> Result<usize> {
+        let mut peers = TrustedPeers::load(keychain)?;
+        let added = peers.merge(&self.trusted_peers);
+        self.save_identity(keychain)?;
+        peers.save(keychain)?;
+        Ok(added)
+    }
+
+    /// Writes the backup into `keychain`, replacing the identity (if the
+    /// backup has one) and the whole trusted peer list
+    pub fn restore_replacing_peers(&self, keychain: &dyn KeychainProvider) -> Result<()> {
+        self.save_identity(keychain)?;
+        self.trusted_peers.save(keychain)
+    }
+
+    fn save_identity(&self, keychain: &dyn KeychainProvider) -> Result<()> {
+        match &self.identity {
+            Some(identity) => identity.save(keychain),
+            None => Ok(()),
+        }
+    }
+
+    /// Encrypts the backup under a passphrase (Argon2id + ChaCha20-Poly1305)
+    pub fn seal(&self, passphrase: &str, params: KdfParams) -> Result<String> {
+        let contents = BackupContents {
+            identity: self.identity.as_ref().map(DeviceIdentity::to_record),
+            trusted_peers: self.trusted_peers.peers.clone(),
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(&contents)?);
+
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; NONCE_SIZE];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let mut sealed = SealedBackup {
+            format: BACKUP_FORMAT,
+            salt: encode_b64(&salt),
+            params,
+            nonce: encode_b64(&nonce),
+            ciphertext: String::new(),
+        };
+        let key = derive_passphrase_key(passphrase.as_bytes(), &salt, params)?;
+        let ciphertext = ChaCha20Poly1305Cipher::new(key.as_slice())
+            .and_then(|cipher| cipher.encrypt_with_nonce(&nonce, &plaintext, &sealed.aad()))
+            .map_err(|e| IdentityError::Invalid(e.to_string()))?;
+        sealed.ciphertext = encode_b64(&ciphertext);
+
+        Ok(serde_json::to_string_pretty(&sealed)?)
+    }
+
+    /// Decrypts a backup produced by `seal` with at least the default
+    /// Argon2id parameters
+    ///
+    /// # Errors
+    /// `Decryption` for a wrong passphrase or any modification of the file,
+    /// `Invalid` if the file asks for weaker KDF parameters.
+    pub fn open(sealed: &str, passphrase: &str) -> Result<Self> {
+        Self::open_with_floor(sealed, passphrase, KdfParams::default())
+    }
+
+    /// Decrypts a backup whose KDF parameters meet `floor`
+    ///
+    /// The parameters are read from the file, so a backup sealed with weaker
+    /// parameters than `floor` is refused instead of trusted.
+    pub fn open_with_floor(sealed: &str, passphrase: &str, floor: KdfParams) -> Result<Self> {
+        let sealed: SealedBackup = serde_json::from_str(sealed)?;
+        if sealed.format != BACKUP_FORMAT {
+            return Err(IdentityError::Invalid(format!(
+                "unsupported backup format {}",
+                sealed.format
+            )));
+        }
+        if !sealed.params.meets(&floor) {
+            return Err(IdentityError::Invalid(
+                "backup KDF parameters are below the required minimum".to_string(),
+            ));
+        }
+
+        let nonce = decode_b64(&sealed.nonce)?;
+        let ciphertext = decode_b64(&sealed.ciphertext)?;
+        let key = derive_passphrase_key(
+            passphrase.as_bytes(),
+            &decode_b64(&sealed.salt)?,
+            sealed.params,
+        )?;
+        let plaintext = ChaCha20Poly1305Cipher::new(key.as_slice())
+            .and_then(|cipher| cipher.decrypt(&nonce, &ciphertext, &sealed.aad()))
+            .map(Zeroizing::new)
+            .map_err(|_| IdentityError::Decryption)?;
+
+        let contents: BackupContents = serde_json::from_slice(&plaintext)?;
+        Ok(Self {
+            identity: contents
+                .identity
+                .map(DeviceIdentity::from_record)
+                .transpose()?,
+            trusted_peers: TrustedPeers {
+                peers: contents.trusted_peers,
+            },
+        })
+    }
+}
+
+/// Parses a base64url (or standard base64) Ed25519 public key
+pub fn parse_verifying_key(key_b64: &str) -> Result<VerifyingKey> {
+    let bytes: [u8; 32] = decode_b64(key_b64.trim())?
+        .try_into()
+        .map_err(|_| IdentityError::Invalid("public key must be 32 bytes".to_string()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| IdentityError::Invalid(e.to_string()))
+}
+
+fn noise_public_key(key: &VerifyingKey) -> PublicKey {
+    PublicKey::from(key.to_montgomery().to_bytes())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn encode_b64(data: &[u8]) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data)
+}
+
+fn decode_b64(data: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(data.trim_end_matches('='))
+        .or_else(|_| base64::engine::general_purpose::STANDARD.decode(data))
+        .map_err(|e| IdentityError::Invalid(format!("invalid base64: {}", e)))
+}
+
+mod verifying_key_b64 {
+    use ed25519_dalek::VerifyingKey;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(key: &VerifyingKey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::encode_b64(key.as_bytes()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<VerifyingKey, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        super::parse_verifying_key(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileKeychain, KeySource};
+
+    fn keychain(dir: &tempfile::TempDir, name: &str) -> FileKeychain {
+        FileKeychain::open_with_params(
+            dir.path().join(name),
+            KeySource::passphrase("test"),
+            KdfParams::TESTING,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_identity_roundtrip_and_pairing() {
+        let dir = tempfile::tempdir().unwrap();
+        let keychain = keychain(&dir, "keychain.json");
+        assert!(matches!(
+            DeviceIdentity::load(&keychain),
+            Err(IdentityError::NotFound)
+        ));
+
+        let identity = DeviceIdentity::generate(None);
+        assert!(identity.device_id().as_str().starts_with("DEV-"));
+        identity.save(&keychain).unwrap();
+
+        let loaded = DeviceIdentity::load(&keychain).unwrap();
+        assert_eq!(loaded.verifying_key(), identity.verifying_key());
+        assert_eq!(loaded.device_id(), identity.device_id());
+
+        let pairing = PairingPayload::parse(&identity.pairing().to_uri()).unwrap();
+        assert_eq!(pairing, identity.pairing());
+        assert_eq!(pairing.fingerprint(), identity.fingerprint());
+        assert!(PairingPayload::parse("honeylink://pair?v=2&id=DEV-1&key=x").is_err());
+
+        // Noise key matches the one the secure channel derives
+        let noise = honeylink_crypto::certificate::noise_identity(identity.signing_key()).unwrap();
+        assert_eq!(
+            noise.public_key().as_bytes(),
+            identity.noise_public_key().as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_trusted_peers() {
+        let dir = tempfile::tempdir().unwrap();
+        let keychain = keychain(&dir, "keychain.json");
+        let peer = DeviceIdentity::generate(None).pairing();
+
+        let mut peers = TrustedPeers::load(&keychain).unwrap();
+        peers.add("laptop", &peer).unwrap();
+        assert!(matches!(
+            peers.add("other-name", &peer),
+            Err(IdentityError::DuplicatePeer(_))
+        ));
+        peers.save(&keychain).unwrap();
+
+        let mut peers = TrustedPeers::load(&keychain).unwrap();
+        assert_eq!(peers.find(&peer.signing_key).unwrap().name(), "laptop");
+        assert!(peers.remove(&peer.fingerprint()).is_some());
+        assert!(peers.peers().is_empty());
+    }
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = keychain(&dir, "source.json");
+        DeviceIdentity::generate(None).save(&source).unwrap();
+        let mut peers = TrustedPeers::default();
+        peers
+            .add("phone", &DeviceIdentity::generate(None).pairing())
+            .unwrap();
+        peers.save(&source).unwrap();
+
+        let backup = IdentityBackup::from_keychain(&source)
+            .unwrap()
+            .seal("backup passphrase", KdfParams::TESTING)
+            .unwrap();
+        assert!(matches!(
+            IdentityBackup::open_with_floor(&backup, "wrong", KdfParams::TESTING),
+            Err(IdentityError::Decryption)
+        ));
+        // Weak parameters are refused before any key derivation
+        assert!(matches!(
+            IdentityBackup::open(&backup, "backup passphrase"),
+            Err(IdentityError::Invalid(_))
+        ));
+
+        let target = keychain(&dir, "target.json");
+        let restored =
+            IdentityBackup::open_with_floor(&backup, "backup passphrase", KdfParams::TESTING)
+                .unwrap();
+        assert_eq!(restored.restore(&target).unwrap(), 1);
+        assert_eq!(
+            DeviceIdentity::load(&target).unwrap().verifying_key(),
+            DeviceIdentity::load(&source).unwrap().verifying_key()
+        );
+        assert_eq!(TrustedPeers::load(&target).unwrap(), peers);
+
+        // Restoring again merges into the current list instead of replacing it
+        let mut current = TrustedPeers::load(&target).unwrap();
+        current
+            .add("tablet", &DeviceIdentity::generate(None).pairing())
+            .unwrap();
+        current.save(&target).unwrap();
+        assert_eq!(restored.restore(&target).unwrap(), 0);
+        assert_eq!(TrustedPeers::load(&target).unwrap(), current);
+
+        restored.restore_replacing_peers(&target).unwrap();
+        assert_eq!(TrustedPeers::load(&target).unwrap(), peers);
+    }
+}
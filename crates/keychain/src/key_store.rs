@@ -0,0 +1,264 @@
+//! `KeyStore` backend on top of a `KeychainProvider`.
+//!
+//! Lets `KeyLifecycle` and `RotationScheduler` from `honeylink-crypto` keep
+//! their keys in the OS keychain or a `FileKeychain`. Each key is stored as a
+//! credential holding the JSON key record. OS keychains cannot enumerate entries, so every scope
+//! keeps an index credential listing its key names.
+//!
+//! # Example
+//! ```no_run
+//! use honeylink_keychain::{KeychainKeyStore, SystemKeychain};
+//! use std::sync::Arc;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let keychain = SystemKeychain::new("honeylink", "device-keys")?;
+//! let store = KeychainKeyStore::new(Arc::new(keychain));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{KeychainError, KeychainProvider};
+use async_trait::async_trait;
+use base64::Engine as _;
+use honeylink_crypto::key_management::KeyScope;
+use honeylink_crypto::key_store::{
+    storage_key, validate_key_name, EncodedKey, KeyMaterial, KeyStore, KeyStoreError,
+};
+use honeylink_crypto::rotation_store::SealingKey;
+use rand::RngCore;
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroizing;
+
+/// Key store backed by a `KeychainProvider`.
+pub struct KeychainKeyStore {
+    keychain: Arc<dyn KeychainProvider>,
+    /// Serializes index read-modify-write cycles
+    index_lock: Mutex<()>,
+}
+
+impl KeychainKeyStore {
+    /// Creates a key store on top of a keychain provider.
+    pub fn new(keychain: Arc<dyn KeychainProvider>) -> Self {
+        Self {
+            keychain,
+            index_lock: Mutex::new(()),
+        }
+    }
+
+    /// Credential name of the per-scope index (not a valid key name).
+    fn index_key(scope: KeyScope) -> String {
+        format!("{}/.index", scope.storage_path())
+    }
+
+    fn read_index(&self, scope: KeyScope) -> Result<Vec<String>, KeyStoreError> {
+        match self.keychain.get_credential(&Self::index_key(scope)) {
+            Ok(credential) => Ok(serde_json::from_slice(credential.as_bytes())?),
+            Err(KeychainError::CredentialNotFound(_)) => Ok(Vec::new()),
+            Err(e) => Err(keychain_error(e)),
+        }
+    }
+
+    fn write_index(&self, scope: KeyScope, names: &[String]) -> Result<(), KeyStoreError> {
+        self.keychain
+            .set_credential(&Self::index_key(scope), &serde_json::to_vec(names)?)
+            .map_err(keychain_error)
+    }
+}
+
+#[async_trait]
+impl KeyStore for KeychainKeyStore {
+    fn backend(&self) -> &'static str {
+        "keychain"
+    }
+
+    async fn put(&self, material: KeyMaterial) -> Result<(), KeyStoreError> {
+        let scope = material.metadata.scope;
+        let name = material.metadata.name.clone();
+        validate_key_name(&name)?;
+
+        let record = Zeroizing::new(serde_json::to_vec(&EncodedKey::encode(&material))?);
+        self.keychain
+            .set_credential(&storage_key(scope, &name), &record)
+            .map_err(keychain_error)?;
+
+        let _guard = self.index_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut names = self.read_index(scope)?;
+        if !names.contains(&name) {
+            names.push(name);
+            names.sort();
+            self.write_index(scope, &names)?;
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, scope: KeyScope, name: &str) -> Result<KeyMaterial, KeyStoreError> {
+        let credential = self
+            .keychain
+            .get_credential(&storage_key(scope, name))
+            .map_err(|e| match e {
+                KeychainError::CredentialNotFound(_) => KeyStoreError::NotFound {
+                    scope,
+                    name: name.to_string(),
+                },
+                e => keychain_error(e),
+            })?;
+
+        serde_json::from_slice::<EncodedKey>(credential.as_bytes())?.decode()
+    }
+
+    async fn list(&self, scope: KeyScope) -> Result<Vec<String>, KeyStoreError> {
+        self.read_index(scope)
+    }
+
+    async fn delete(&self, scope: KeyScope, name: &str) -> Result<(), KeyStoreError> {
+        self.keychain
+            .delete_credential(&storage_key(scope, name))
+            .map_err(keychain_error)?;
+
+        let _guard = self.index_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut names = self.read_index(scope)?;
+        let before = names.len();
+        names.retain(|n| n != name);
+        if names.len() != before {
+            self.write_index(scope, &names)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the rotation state sealing key `name`, creating it on first use.
+///
+/// The key is 32 random bytes stored base64-encoded, since OS keychains only
+/// hold UTF-8 credentials.
+pub fn sealing_key_from_keychain(
+    keychain: &dyn KeychainProvider,
+    name: &str,
+) -> Result<SealingKey, KeyStoreError> {
+    match keychain.get_credential(name) {
+        Ok(credential) => {
+            let key = Zeroizing::new(
+                base64::engine::general_purpose::STANDARD
+                    .decode(credential.as_bytes())
+                    .map_err(|e| KeyStoreError::InvalidFormat(e.to_string()))?,
+            );
+            SealingKey::from_bytes(&key)
+        }
+        Err(KeychainError::CredentialNotFound(_)) => {
+            let mut key = Zeroizing::new([0u8; 32]);
+            rand::thread_rng().fill_bytes(key.as_mut());
+            let encoded =
+                Zeroizing::new(base64::engine::general_purpose::STANDARD.encode(&key[..]));
+            keychain
+                .set_credential(name, encoded.as_bytes())
+                .map_err(keychain_error)?;
+            SealingKey::from_bytes(key.as_ref())
+        }
+        Err(e) => Err(keychain_error(e)),
+    }
+}
+
+fn keychain_error(e: KeychainError) -> KeyStoreError {
+    KeyStoreError::Backend(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecureCredential;
+    use honeylink_crypto::rotation::KeyRotationManager;
+    use honeylink_crypto::rotation_store::RotationStateStore;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    /// In-memory keychain with the same UTF-8 restriction as `SystemKeychain`
+    #[derive(Default)]
+    struct FakeKeychain {
+        entries: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    impl KeychainProvider for FakeKeychain {
+        fn set_credential(&self, key: &str, value: &[u8]) -> crate::Result<()> {
+            std::str::from_utf8(value)
+                .map_err(|_| KeychainError::InvalidCredential("not UTF-8".to_string()))?;
+            self.entries
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), value.to_vec());
+            Ok(())
+        }
+
+        fn get_credential(&self, key: &str) -> crate::Result<SecureCredential> {
+            self.entries
+                .lock()
+                .unwrap()
+                .get(key)
+                .map(|v| SecureCredential::new(v.clone()))
+                .ok_or_else(|| KeychainError::CredentialNotFound(key.to_string()))
+        }
+
+        fn delete_credential(&self, key: &str) -> crate::Result<()> {
+            self.entries.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        fn has_credential(&self, key: &str) -> bool {
+            self.entries.lock().unwrap().contains_key(key)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keychain_store_roundtrip_and_index() {
+        let store = KeychainKeyStore::new(Arc::new(FakeKeychain::default()));
+
+        for name in ["svc-b", "svc-a"] {
+            store
+                .put(KeyMaterial::new(
+                    KeyScope::Service,
+                    name,
+                    vec![0xFF; 32], // Binary data must survive the UTF-8 keychain
+                    Duration::from_secs(3600),
+                    "test",
+                ))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            store.list(KeyScope::Service).await.unwrap(),
+            vec!["svc-a", "svc-b"]
+        );
+        assert_eq!(
+            store.get(KeyScope::Service, "svc-a").await.unwrap().data,
+            vec![0xFF; 32]
+        );
+
+        store.delete(KeyScope::Service, "svc-a").await.unwrap();
+        assert_eq!(store.list(KeyScope::Service).await.unwrap(), vec!["svc-b"]);
+        assert!(matches!(
+            store.get(KeyScope::Service, "svc-a").await,
+            Err(KeyStoreError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sealing_key_created_once() {
+        let keychain = FakeKeychain::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rotation.json");
+
+        let store = RotationStateStore::new(
+            &path,
+            sealing_key_from_keychain(&keychain, "rotation-state").unwrap(),
+        );
+        store.save(&KeyRotationManager::new()).unwrap();
+
+        // Second lookup returns the stored key, not a fresh one
+        let store = RotationStateStore::new(
+            &path,
+            sealing_key_from_keychain(&keychain, "rotation-state").unwrap(),
+        );
+        assert!(store.load().is_ok());
+    }
+}
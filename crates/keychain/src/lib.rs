@@ -10,6 +10,14 @@
 //! - **macOS**: Keychain Services
 //! - **Linux**: Secret Service API (GNOME Keyring, KWallet)
 //!
+//! Hosts without a keychain service (headless Linux, containers, CI) use
+//! `FileKeychain`, an encrypted file keyed by a passphrase, secret file or
+//! machine ID. `open_keychain()` selects the backend from `KeychainConfig`
+//! and falls back to the file automatically.
+//!
+//! The `identity` module keeps the device's Ed25519 identity and its trusted
+//! peers in whichever backend is selected.
+//!
 //! # Design Principles
 //!
 //! - **Platform-agnostic**: Single API works across all platforms
@@ -38,7 +46,7 @@
 //!
 //! // Retrieve credential
 //! let key = keychain.get_credential("api_key")?;
-//! assert_eq!(key, b"secret-key-data");
+//! assert_eq!(key.as_bytes(), b"secret-key-data");
 //!
 //! // Delete credential
 //! keychain.delete_credential("api_key")?;
@@ -46,7 +54,18 @@
 //! # }
 //! ```
 
+pub mod file;
+pub mod identity;
+pub mod key_store;
+
+pub use file::{FileKeychain, KeySource};
+pub use identity::{DeviceIdentity, IdentityBackup, IdentityError, PairingPayload, TrustedPeers};
+pub use key_store::{sealing_key_from_keychain, KeychainKeyStore};
+
+use honeylink_config::{KeychainBackend, KeychainConfig};
 use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
 use thiserror::Error;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -256,6 +275,18 @@ impl SystemKeychain {
         keyring::Entry::new(&self.service, &full_key)
             .expect("Failed to create keyring entry (should never fail)")
     }
+
+    /// Check whether the OS keychain service can be reached
+    ///
+    /// Probes a credential that is not expected to exist; a missing entry
+    /// means the service answered. On Linux this fails when no Secret Service
+    /// provider is running (headless hosts, containers).
+    pub fn is_available(&self) -> bool {
+        match self.create_entry(".availability-probe").get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => true,
+            Err(_) => false,
+        }
+    }
 }
 
 impl KeychainProvider for SystemKeychain {
@@ -268,8 +299,11 @@ impl KeychainProvider for SystemKeychain {
             KeychainError::InvalidCredential("Credential value must be valid UTF-8".to_string())
         })?;
 
-        entry.set_password(value_str).map_err(|e| {
-            KeychainError::PlatformError(format!("Failed to set credential: {}", e))
+        entry.set_password(value_str).map_err(|e| match e {
+            keyring::Error::NoStorageAccess(err) => {
+                KeychainError::ServiceUnavailable(err.to_string())
+            }
+            _ => KeychainError::PlatformError(format!("Failed to set credential: {}", e)),
         })
     }
 
@@ -278,6 +312,9 @@ impl KeychainProvider for SystemKeychain {
 
         let password = entry.get_password().map_err(|e| match e {
             keyring::Error::NoEntry => KeychainError::CredentialNotFound(key.to_string()),
+            keyring::Error::NoStorageAccess(err) => {
+                KeychainError::ServiceUnavailable(err.to_string())
+            }
             keyring::Error::PlatformFailure(msg) => {
                 KeychainError::PlatformError(format!("Platform error: {}", msg))
             }
@@ -296,6 +333,9 @@ impl KeychainProvider for SystemKeychain {
                 // Not an error if credential doesn't exist
                 Ok(())
             }
+            Err(keyring::Error::NoStorageAccess(err)) => {
+                Err(KeychainError::ServiceUnavailable(err.to_string()))
+            }
             Err(keyring::Error::PlatformFailure(msg)) => {
                 Err(KeychainError::PlatformError(format!("Platform error: {}", msg)))
             }
@@ -320,6 +360,61 @@ impl fmt::Debug for SystemKeychain {
     }
 }
 
+/// Open the credential store selected by `config`
+///
+/// # Backend Selection
+/// - `system`: OS keychain; fails with `ServiceUnavailable` if it cannot be reached
+/// - `file`: Encrypted `FileKeychain`
+/// - `auto`: OS keychain when available, otherwise `FileKeychain`
+///
+/// The file keychain secret is taken from `secret_file`, then the environment
+/// variable named by `passphrase_env`, then the machine ID.
+pub fn open_keychain(config: &KeychainConfig) -> Result<Arc<dyn KeychainProvider>> {
+    match config.backend {
+        KeychainBackend::System => {
+            let keychain = SystemKeychain::new(&config.service, &config.namespace)?;
+            if !keychain.is_available() {
+                return Err(KeychainError::ServiceUnavailable(
+                    "OS keychain is not reachable".to_string(),
+                ));
+            }
+            Ok(Arc::new(keychain))
+        }
+        KeychainBackend::File => Ok(Arc::new(open_file_keychain(config)?)),
+        KeychainBackend::Auto => {
+            let keychain = SystemKeychain::new(&config.service, &config.namespace)?;
+            if keychain.is_available() {
+                Ok(Arc::new(keychain))
+            } else {
+                Ok(Arc::new(open_file_keychain(config)?))
+            }
+        }
+    }
+}
+
+/// Open the file keychain described by `config`
+fn open_file_keychain(config: &KeychainConfig) -> Result<FileKeychain> {
+    let path = match &config.file_path {
+        Some(path) => PathBuf::from(path),
+        None => dirs::data_local_dir()
+            .ok_or_else(|| {
+                KeychainError::ServiceUnavailable("No local data directory available".to_string())
+            })?
+            .join(&config.service)
+            .join(format!("{}.keychain.json", config.namespace)),
+    };
+
+    let source = if let Some(secret_file) = &config.secret_file {
+        KeySource::SecretFile(PathBuf::from(secret_file))
+    } else if let Ok(passphrase) = std::env::var(&config.passphrase_env) {
+        KeySource::passphrase(passphrase)
+    } else {
+        KeySource::MachineId
+    };
+
+    FileKeychain::open(path, source)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -473,6 +568,27 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_open_keychain_file_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret_path = dir.path().join("secret");
+        std::fs::write(&secret_path, b"provisioned-secret").unwrap();
+
+        let config = KeychainConfig {
+            backend: KeychainBackend::File,
+            file_path: Some(dir.path().join("keychain.json").display().to_string()),
+            secret_file: Some(secret_path.display().to_string()),
+            ..KeychainConfig::default()
+        };
+
+        let keychain = open_keychain(&config).unwrap();
+        keychain.set_credential("api_key", b"value").unwrap();
+        assert_eq!(
+            open_keychain(&config).unwrap().get_credential("api_key").unwrap().as_bytes(),
+            b"value"
+        );
+    }
+
     #[test]
     fn test_keychain_error_display() {
         let err = KeychainError::CredentialNotFound("test".to_string());
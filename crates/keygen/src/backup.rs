@@ -0,0 +1,269 @@
+// Encrypted export and import of the device identity and trusted peers
+//
+// Backups are sealed with a passphrase (Argon2id + ChaCha20-Poly1305) taken
+// from HONEYLINK_BACKUP_PASSPHRASE or a passphrase file. Imports refuse
+// backups sealed with weaker Argon2id parameters than the defaults, and merge
+// the backed-up trusted peers into the current list unless --replace is given.
+
+use clap::{Args, Subcommand};
+use honeylink_crypto::KdfParams;
+use honeylink_keychain::identity::IdentityBackup;
+use honeylink_keychain::{DeviceIdentity, KeychainProvider};
+use zeroize::Zeroizing;
+
+/// Environment variable holding the backup passphrase
+const PASSPHRASE_ENV: &str = "HONEYLINK_BACKUP_PASSPHRASE";
+
+/// Source of the backup passphrase
+#[derive(Args)]
+pub struct PassphraseArgs {
+    /// File holding the backup passphrase (default: the
+    /// HONEYLINK_BACKUP_PASSPHRASE environment variable)
+    #[arg(long)]
+    passphrase_file: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommand {
+    /// Export the identity and trusted peers to an encrypted file
+    Export {
+        /// Output file path
+        #[arg(short, long)]
+        output: String,
+
+        #[command(flatten)]
+        passphrase: PassphraseArgs,
+    },
+
+    /// Restore the identity and trusted peers from an encrypted file
+    Import {
+        /// Backup file path
+        #[arg(short, long)]
+        input: String,
+
+        /// Replace an existing identity
+        #[arg(long)]
+        force: bool,
+
+        /// Replace the trusted peer list instead of merging into it
+        #[arg(long)]
+        replace: bool,
+
+        #[command(flatten)]
+        passphrase: PassphraseArgs,
+    },
+}
+
+pub fn run(
+    keychain: &dyn KeychainProvider,
+    command: BackupCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        BackupCommand::Export { output, passphrase } => {
+            export(keychain, &output, &passphrase.read()?, KdfParams::default())
+        }
+        BackupCommand::Import {
+            input,
+            force,
+            replace,
+            passphrase,
+        } => import(
+            keychain,
+            &input,
+            &passphrase.read()?,
+            ImportOptions {
+                force,
+                replace,
+                floor: KdfParams::default(),
+            },
+        ),
+    }
+}
+
+/// How `import` treats the current keychain contents
+struct ImportOptions {
+    /// Replace a different existing identity
+    force: bool,
+    /// Replace the trusted peer list instead of merging
+    replace: bool,
+    /// Weakest Argon2id parameters accepted from the backup
+    floor: KdfParams,
+}
+
+fn export(
+    keychain: &dyn KeychainProvider,
+    output: &str,
+    passphrase: &str,
+    params: KdfParams,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let backup = IdentityBackup::from_keychain(keychain)?;
+    if backup.identity.is_none() && backup.trusted_peers.peers().is_empty() {
+        return Err("Nothing to back up: no identity or trusted peers".into());
+    }
+    let sealed = backup.seal(passphrase, params)?;
+    std::fs::write(output, sealed)?;
+
+    eprintln!("✅ Backup exported to: {}", output);
+    print_identity(&backup);
+    eprintln!("   Trusted peers: {}", backup.trusted_peers.peers().len());
+    Ok(())
+}
+
+fn import(
+    keychain: &dyn KeychainProvider,
+    input: &str,
+    passphrase: &str,
+    options: ImportOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sealed = std::fs::read_to_string(input)?;
+    let backup = IdentityBackup::open_with_floor(&sealed, passphrase, options.floor)?;
+
+    if let (Some(restored), Ok(current)) = (&backup.identity, DeviceIdentity::load(keychain)) {
+        if restored.verifying_key() != current.verifying_key() && !options.force {
+            return Err(format!(
+                "Backup would replace identity {} (use --force)",
+                current.fingerprint()
+            )
+            .into());
+        }
+    }
+
+    let added = if options.replace {
+        backup.restore_replacing_peers(keychain)?;
+        None
+    } else {
+        Some(backup.restore(keychain)?)
+    };
+
+    eprintln!("✅ Backup imported from: {}", input);
+    print_identity(&backup);
+    let total = backup.trusted_peers.peers().len();
+    match added {
+        Some(added) => eprintln!(
+            "   Trusted peers: {} added, {} already known",
+            added,
+            total - added
+        ),
+        None => eprintln!("   Trusted peers: {} (list replaced)", total),
+    }
+    Ok(())
+}
+
+fn print_identity(backup: &IdentityBackup) {
+    if let Some(identity) = &backup.identity {
+        eprintln!(
+            "   Identity: {} ({})",
+            identity.device_id(),
+            identity.fingerprint()
+        );
+    }
+}
+
+impl PassphraseArgs {
+    fn read(&self) -> Result<Zeroizing<String>, Box<dyn std::error::Error>> {
+        let passphrase = match &self.passphrase_file {
+            Some(path) => Zeroizing::new(std::fs::read_to_string(path)?.trim_end().to_string()),
+            None => Zeroizing::new(std::env::var(PASSPHRASE_ENV).unwrap_or_default()),
+        };
+        if passphrase.is_empty() {
+            return Err(format!(
+                "Backup passphrase required: pass --passphrase-file or set {}",
+                PASSPHRASE_ENV
+            )
+            .into());
+        }
+        Ok(passphrase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeylink_keychain::identity::TrustedPeers;
+    use honeylink_keychain::{FileKeychain, KeySource};
+
+    fn keychain(dir: &tempfile::TempDir, name: &str) -> FileKeychain {
+        FileKeychain::open_with_params(
+            dir.path().join(name),
+            KeySource::passphrase("test"),
+            KdfParams::TESTING,
+        )
+        .unwrap()
+    }
+
+    fn options(force: bool, replace: bool) -> ImportOptions {
+        ImportOptions {
+            force,
+            replace,
+            floor: KdfParams::TESTING,
+        }
+    }
+
+    #[test]
+    fn test_import_merges_peers_unless_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = keychain(&dir, "source.json");
+        let identity = DeviceIdentity::generate(None);
+        identity.save(&source).unwrap();
+        let mut backed_up = TrustedPeers::default();
+        backed_up
+            .add("phone", &DeviceIdentity::generate(None).pairing())
+            .unwrap();
+        backed_up.save(&source).unwrap();
+
+        let path = dir.path().join("backup.json");
+        let path = path.to_str().unwrap();
+        export(&source, path, "passphrase", KdfParams::TESTING).unwrap();
+
+        let target = keychain(&dir, "target.json");
+        let mut local = TrustedPeers::default();
+        local
+            .add("tablet", &DeviceIdentity::generate(None).pairing())
+            .unwrap();
+        local.save(&target).unwrap();
+
+        import(&target, path, "passphrase", options(false, false)).unwrap();
+        let merged = TrustedPeers::load(&target).unwrap();
+        assert_eq!(merged.peers().len(), 2);
+        assert_eq!(
+            DeviceIdentity::load(&target).unwrap().verifying_key(),
+            identity.verifying_key()
+        );
+
+        import(&target, path, "passphrase", options(false, true)).unwrap();
+        assert_eq!(TrustedPeers::load(&target).unwrap(), backed_up);
+    }
+
+    #[test]
+    fn test_import_guards_identity_and_kdf_floor() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = keychain(&dir, "source.json");
+        DeviceIdentity::generate(None).save(&source).unwrap();
+        let path = dir.path().join("backup.json");
+        let path = path.to_str().unwrap();
+        export(&source, path, "passphrase", KdfParams::TESTING).unwrap();
+
+        // Weak parameters are refused by the default floor
+        let target = keychain(&dir, "target.json");
+        let strict = ImportOptions {
+            floor: KdfParams::default(),
+            ..options(false, false)
+        };
+        assert!(import(&target, path, "passphrase", strict).is_err());
+        assert!(!DeviceIdentity::exists(&target));
+
+        // A different identity is only replaced with --force
+        let own = DeviceIdentity::generate(None);
+        own.save(&target).unwrap();
+        assert!(import(&target, path, "passphrase", options(false, false)).is_err());
+        assert_eq!(
+            DeviceIdentity::load(&target).unwrap().verifying_key(),
+            own.verifying_key()
+        );
+        import(&target, path, "passphrase", options(true, false)).unwrap();
+        assert_ne!(
+            DeviceIdentity::load(&target).unwrap().verifying_key(),
+            own.verifying_key()
+        );
+    }
+}
@@ -0,0 +1,264 @@
+// Device identity and trusted peer commands
+//
+// The identity and peer list live in the keychain selected by the HoneyLink
+// configuration (`[keychain]` section), so the daemon and this tool share them.
+
+use base64::Engine;
+use clap::Subcommand;
+use ed25519_dalek::VerifyingKey;
+use honeylink_core::types::DeviceId;
+use honeylink_keychain::identity::{DeviceIdentity, IdentityError, PairingPayload, TrustedPeers};
+use honeylink_keychain::KeychainProvider;
+
+#[derive(Subcommand)]
+pub enum IdentityCommand {
+    /// Create this device's Ed25519 identity
+    Create {
+        /// Device ID (default: derived from the public key)
+        #[arg(short, long)]
+        device_id: Option<String>,
+
+        /// Replace an existing identity (peers trusting the old key must re-pair)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show the device identity
+    Show,
+
+    /// Print the identity fingerprint
+    Fingerprint,
+
+    /// Print the pairing payload (encode it as a QR code for peers to scan)
+    Pairing,
+}
+
+#[derive(Subcommand)]
+pub enum TrustCommand {
+    /// List trusted peers
+    List,
+
+    /// Trust a peer from its pairing payload
+    Add {
+        /// Local name for the peer
+        #[arg(short, long)]
+        name: String,
+
+        /// Pairing payload (honeylink://pair?...)
+        #[arg(short, long)]
+        pairing: String,
+    },
+
+    /// Stop trusting a peer
+    Remove {
+        /// Peer name or fingerprint
+        peer: String,
+    },
+}
+
+pub fn run_identity(
+    keychain: &dyn KeychainProvider,
+    command: IdentityCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        IdentityCommand::Create { device_id, force } => {
+            if DeviceIdentity::exists(keychain) && !force {
+                return Err("Device identity already exists (use --force to replace it)".into());
+            }
+            let device_id = device_id.map(DeviceId::new).transpose()?;
+            let identity = DeviceIdentity::generate(device_id);
+            identity.save(keychain)?;
+
+            eprintln!("✅ Device identity created: {}", identity.device_id());
+            eprintln!("   Fingerprint: {}", identity.fingerprint());
+            println!("{}", identity.pairing().to_uri());
+        }
+        IdentityCommand::Show => {
+            let identity = DeviceIdentity::load(keychain)?;
+
+            println!("🔑 Device Identity\n");
+            println!("Device ID: {}", identity.device_id());
+            println!("Created: {}", format_time(identity.created_at()));
+            println!(
+                "Signing Key (Ed25519): {}",
+                encode(identity.verifying_key().as_bytes())
+            );
+            println!(
+                "Noise Key (X25519): {}",
+                encode(identity.noise_public_key().as_bytes())
+            );
+            println!("Fingerprint: {}", identity.fingerprint());
+        }
+        IdentityCommand::Fingerprint => {
+            println!("{}", DeviceIdentity::load(keychain)?.fingerprint());
+        }
+        IdentityCommand::Pairing => {
+            println!("{}", DeviceIdentity::load(keychain)?.pairing().to_uri());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_trust(
+    keychain: &dyn KeychainProvider,
+    command: TrustCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut peers = TrustedPeers::load(keychain)?;
+
+    match command {
+        TrustCommand::List => {
+            if peers.peers().is_empty() {
+                eprintln!("No trusted peers");
+            }
+            for peer in peers.peers() {
+                println!("{}", peer.name());
+                println!("  Device ID: {}", peer.device_id());
+                println!("  Fingerprint: {}", peer.fingerprint());
+                println!("  Added: {}", format_time(peer.added_at()));
+            }
+        }
+        TrustCommand::Add { name, pairing } => {
+            let pairing = PairingPayload::parse(&pairing)?;
+            if let Ok(own) = DeviceIdentity::load(keychain) {
+                if own.verifying_key() == pairing.signing_key {
+                    return Err("Pairing payload is this device's own identity".into());
+                }
+            }
+            let fingerprint = peers.add(&name, &pairing)?.fingerprint();
+            peers.save(keychain)?;
+
+            eprintln!("✅ Trusted peer added: {} ({})", name, pairing.device_id);
+            eprintln!("   Fingerprint: {}", fingerprint);
+            eprintln!("   Compare it with the fingerprint shown on the peer device.");
+        }
+        TrustCommand::Remove { peer } => match peers.remove(&peer) {
+            Some(removed) => {
+                peers.save(keychain)?;
+                eprintln!("✅ Trusted peer removed: {}", removed.name());
+            }
+            None => return Err(format!("No trusted peer named {}", peer).into()),
+        },
+    }
+
+    Ok(())
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn format_time(unix: u64) -> String {
+    i64::try_from(unix)
+        .ok()
+        .and_then(|secs| chrono::DateTime::<chrono::Utc>::from_timestamp(secs, 0))
+        .unwrap_or_default()
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string()
+}
+
+/// Keys whose profile signatures are accepted: this device and trusted peers
+pub(crate) fn known_signers(
+    keychain: &dyn KeychainProvider,
+) -> Result<Vec<(String, VerifyingKey)>, IdentityError> {
+    let mut signers = Vec::new();
+    match DeviceIdentity::load(keychain) {
+        Ok(own) => signers.push((
+            format!("this device ({})", own.device_id()),
+            own.verifying_key(),
+        )),
+        Err(IdentityError::NotFound) => {}
+        Err(e) => return Err(e),
+    }
+    for peer in TrustedPeers::load(keychain)?.peers() {
+        signers.push((
+            format!("{} ({})", peer.name(), peer.device_id()),
+            *peer.signing_key(),
+        ));
+    }
+    Ok(signers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeylink_crypto::KdfParams;
+    use honeylink_keychain::{FileKeychain, KeySource};
+
+    fn keychain(dir: &tempfile::TempDir) -> FileKeychain {
+        FileKeychain::open_with_params(
+            dir.path().join("keychain.json"),
+            KeySource::passphrase("test"),
+            KdfParams::TESTING,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_identity_create_requires_force_to_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let keychain = keychain(&dir);
+        let create = |force| IdentityCommand::Create {
+            device_id: Some("DEV-TEST".to_string()),
+            force,
+        };
+
+        run_identity(&keychain, create(false)).unwrap();
+        let first = DeviceIdentity::load(&keychain).unwrap();
+        assert_eq!(first.device_id().as_str(), "DEV-TEST");
+
+        assert!(run_identity(&keychain, create(false)).is_err());
+        assert_eq!(
+            DeviceIdentity::load(&keychain).unwrap().verifying_key(),
+            first.verifying_key()
+        );
+        run_identity(&keychain, create(true)).unwrap();
+        assert_ne!(
+            DeviceIdentity::load(&keychain).unwrap().verifying_key(),
+            first.verifying_key()
+        );
+    }
+
+    #[test]
+    fn test_trust_add_and_remove() {
+        let dir = tempfile::tempdir().unwrap();
+        let keychain = keychain(&dir);
+        run_identity(
+            &keychain,
+            IdentityCommand::Create {
+                device_id: None,
+                force: false,
+            },
+        )
+        .unwrap();
+        let own = DeviceIdentity::load(&keychain).unwrap().pairing().to_uri();
+        let peer = DeviceIdentity::generate(None).pairing();
+        let add = |name: &str, pairing: String| TrustCommand::Add {
+            name: name.to_string(),
+            pairing,
+        };
+
+        // The device's own payload is not a peer
+        assert!(run_trust(&keychain, add("self", own)).is_err());
+
+        run_trust(&keychain, add("laptop", peer.to_uri())).unwrap();
+        assert!(run_trust(&keychain, add("again", peer.to_uri())).is_err());
+        assert_eq!(known_signers(&keychain).unwrap().len(), 2);
+
+        run_trust(
+            &keychain,
+            TrustCommand::Remove {
+                peer: peer.fingerprint(),
+            },
+        )
+        .unwrap();
+        assert!(TrustedPeers::load(&keychain).unwrap().peers().is_empty());
+        assert!(run_trust(
+            &keychain,
+            TrustCommand::Remove {
+                peer: "laptop".to_string()
+            }
+        )
+        .is_err());
+    }
+}
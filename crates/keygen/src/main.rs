@@ -0,0 +1,829 @@
+// HoneyLink Key Generation CLI Tool
+//
+// Provides command-line interface for:
+// - X25519 keypair generation
+// - HKDF key derivation (hierarchical)
+// - Key rotation management (encrypted state files)
+// - Key export/import in Base64 format
+// - Device certificate issuance and revocation
+// - Shamir k-of-n backup and recovery of the root key
+// - Device identity, pairing payloads and trusted peers (stored in the keychain)
+// - PolicyProfile bundle signing and verification
+// - Encrypted identity backups
+// - Self-test of the cryptographic primitives
+//
+// Zero C/C++ dependencies - pure Rust using RustCrypto crates.
+
+mod backup;
+mod identity;
+mod profile;
+mod self_test;
+
+use backup::BackupCommand;
+use base64::Engine;
+use clap::{Args, Parser, Subcommand};
+use honeylink_config::Config;
+use honeylink_crypto::certificate::{Capabilities, CertificateIssuer, RevocationList};
+use honeylink_crypto::rotation_store::{RotationStateStore, SealingKey};
+use honeylink_crypto::shamir::{self, Share};
+use honeylink_crypto::{KeyHierarchy, KeyRotationManager, KeyScope};
+use honeylink_keychain::KeychainProvider;
+use identity::{IdentityCommand, TrustCommand};
+use profile::ProfileCommand;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+
+#[derive(Parser)]
+#[command(name = "honeylink-keygen")]
+#[command(about = "HoneyLink key generation and management tool", long_about = None)]
+#[command(version)]
+struct Cli {
+    /// Configuration file selecting the keychain (default: standard locations)
+    #[arg(long, global = true)]
+    config: Option<String>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+/// Key that seals rotation state files
+#[derive(Args)]
+struct SealingArgs {
+    /// File holding a 32-byte Base64 sealing key (default: passphrase from
+    /// the HONEYLINK_ROTATION_PASSPHRASE environment variable)
+    #[arg(long)]
+    key_file: Option<String>,
+}
+
+/// Environment variable holding the rotation state passphrase
+const PASSPHRASE_ENV: &str = "HONEYLINK_ROTATION_PASSPHRASE";
+
+/// Organization root key
+///
+/// Read from a file or the environment so it never appears in argv (and
+/// with it in `ps` output and shell history).
+#[derive(Args)]
+struct RootKeyArgs {
+    /// File holding the Base64 root key (default: the HONEYLINK_ROOT_KEY
+    /// environment variable)
+    #[arg(long)]
+    root_file: Option<String>,
+}
+
+/// Environment variable holding the Base64 root key
+const ROOT_KEY_ENV: &str = "HONEYLINK_ROOT_KEY";
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Generate a new root key
+    GenerateRoot {
+        /// Output file path (optional, prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Derive child key from parent
+    Derive {
+        /// Parent key in Base64 format
+        #[arg(short, long)]
+        parent: String,
+
+        /// Target scope (root, device, session, stream)
+        #[arg(short, long)]
+        scope: String,
+
+        /// Output file path (optional, prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Create a new key rotation manager with default policies
+    InitRotation {
+        /// Output file path for rotation state
+        #[arg(short, long)]
+        output: String,
+
+        #[command(flatten)]
+        sealing: SealingArgs,
+    },
+
+    /// Add a new key version to rotation manager
+    AddVersion {
+        /// Rotation state file
+        #[arg(short, long)]
+        state: String,
+
+        /// Key scope (root, device, session, stream)
+        #[arg(short = 'c', long)]
+        scope: String,
+
+        /// Key material in Base64 format
+        #[arg(short, long)]
+        key: String,
+
+        #[command(flatten)]
+        sealing: SealingArgs,
+    },
+
+    /// Check rotation status
+    Status {
+        /// Rotation state file
+        #[arg(short, long)]
+        state: String,
+
+        #[command(flatten)]
+        sealing: SealingArgs,
+    },
+
+    /// Encrypt a plaintext rotation state file from an earlier version
+    MigrateRotation {
+        /// Rotation state file
+        #[arg(short, long)]
+        state: String,
+
+        #[command(flatten)]
+        sealing: SealingArgs,
+    },
+
+    /// Issue a device certificate signed by the root key
+    IssueCert {
+        #[command(flatten)]
+        root: RootKeyArgs,
+
+        /// Device ID of the subject
+        #[arg(short, long)]
+        device_id: String,
+
+        /// Device Ed25519 public key in Base64 format
+        #[arg(short = 'k', long)]
+        device_key: String,
+
+        /// Comma-separated capabilities (connect, relay, publish-policy, issue)
+        #[arg(short, long, default_value = "connect")]
+        capabilities: String,
+
+        /// Validity period in days
+        #[arg(long, default_value_t = 365)]
+        days: u64,
+
+        /// Output file path (optional, prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Revoke device certificates by serial number
+    RevokeCert {
+        #[command(flatten)]
+        root: RootKeyArgs,
+
+        /// Sign with an intermediate instead of the root: file holding the
+        /// intermediate's Base64 Ed25519 seed. Devices install the list
+        /// together with the intermediate's certificate chain.
+        #[arg(long)]
+        intermediate_key_file: Option<String>,
+
+        /// Serial numbers to revoke
+        #[arg(short, long, required = true, num_args = 1..)]
+        serial: Vec<u64>,
+
+        /// Revocation list file (JSON, created if missing)
+        #[arg(short, long)]
+        list: String,
+    },
+
+    /// Split the root key into Shamir shares (any THRESHOLD of SHARES recover it)
+    SplitRoot {
+        #[command(flatten)]
+        root: RootKeyArgs,
+
+        /// Number of shares needed for recovery (at least 2)
+        #[arg(short, long)]
+        threshold: u8,
+
+        /// Number of shares to create
+        #[arg(short, long)]
+        shares: u8,
+
+        /// Encode shares as uppercase base32 (QR alphanumeric mode)
+        #[arg(long)]
+        qr: bool,
+
+        /// Directory to write one share file per holder (prints to stdout if omitted)
+        #[arg(short, long)]
+        output_dir: Option<String>,
+    },
+
+    /// Recover the root key from Shamir shares
+    RecoverRoot {
+        /// File holding one share in base64 or base32 format (repeat for each share)
+        #[arg(short, long, required = true, num_args = 1..)]
+        share_file: Vec<String>,
+
+        /// Root key fingerprint printed by generate-root or split-root
+        #[arg(short, long)]
+        fingerprint: String,
+
+        /// Output file path (optional, prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Manage this device's identity
+    #[command(subcommand)]
+    Identity(IdentityCommand),
+
+    /// Manage trusted peers
+    #[command(subcommand)]
+    Trust(TrustCommand),
+
+    /// Sign and verify PolicyProfile bundles
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+
+    /// Export and import encrypted identity backups
+    #[command(subcommand)]
+    Backup(BackupCommand),
+
+    /// Run known-answer and round-trip tests of all cryptographic primitives
+    SelfTest,
+
+    /// Show key hierarchy demonstration
+    Demo,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli) {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    match cli.command {
+        Commands::GenerateRoot { output } => {
+            generate_root(output)?;
+        }
+        Commands::Derive { parent, scope, output } => {
+            derive_key(&parent, &scope, output)?;
+        }
+        Commands::InitRotation { output, sealing } => {
+            init_rotation(&rotation_store(&output, &sealing)?)?;
+        }
+        Commands::AddVersion {
+            state,
+            scope,
+            key,
+            sealing,
+        } => {
+            add_version(&rotation_store(&state, &sealing)?, &scope, &key)?;
+        }
+        Commands::Status { state, sealing } => {
+            show_status(&rotation_store(&state, &sealing)?)?;
+        }
+        Commands::MigrateRotation { state, sealing } => {
+            migrate_rotation(&rotation_store(&state, &sealing)?)?;
+        }
+        Commands::IssueCert {
+            root,
+            device_id,
+            device_key,
+            capabilities,
+            days,
+            output,
+        } => {
+            issue_cert(&root, &device_id, &device_key, &capabilities, days, output)?;
+        }
+        Commands::RevokeCert {
+            root,
+            intermediate_key_file,
+            serial,
+            list,
+        } => {
+            let issuer = match intermediate_key_file {
+                Some(path) => intermediate_issuer(&path)?,
+                None => root_issuer(&root)?,
+            };
+            revoke_cert(&issuer, serial, &list)?;
+        }
+        Commands::SplitRoot {
+            root,
+            threshold,
+            shares,
+            qr,
+            output_dir,
+        } => {
+            split_root(&root, threshold, shares, qr, output_dir)?;
+        }
+        Commands::RecoverRoot {
+            share_file,
+            fingerprint,
+            output,
+        } => {
+            recover_root(&share_file, &fingerprint, output)?;
+        }
+        Commands::Identity(command) => {
+            identity::run_identity(open_keychain(cli.config.as_deref())?.as_ref(), command)?;
+        }
+        Commands::Trust(command) => {
+            identity::run_trust(open_keychain(cli.config.as_deref())?.as_ref(), command)?;
+        }
+        Commands::Profile(command) => {
+            profile::run(open_keychain(cli.config.as_deref())?.as_ref(), command)?;
+        }
+        Commands::Backup(command) => {
+            backup::run(open_keychain(cli.config.as_deref())?.as_ref(), command)?;
+        }
+        Commands::SelfTest => {
+            self_test::run()?;
+        }
+        Commands::Demo => {
+            run_demo()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens the keychain selected by the `[keychain]` configuration section
+fn open_keychain(
+    config_path: Option<&str>,
+) -> Result<Arc<dyn KeychainProvider>, Box<dyn std::error::Error>> {
+    let config = match config_path {
+        Some(path) => Config::load_from_file(Path::new(path))?,
+        None => Config::load()?,
+    };
+    Ok(honeylink_keychain::open_keychain(&config.keychain)?)
+}
+
+fn generate_root(output: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    use rand::Rng;
+    use rand::rngs::OsRng;
+
+    // Generate X25519 keypair using OS random
+    let mut secret_bytes = [0u8; 32];
+    OsRng.fill(&mut secret_bytes);
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret_bytes);
+    if let Some(path) = output {
+        write_secret(Path::new(&path), encoded.as_bytes())?;
+        eprintln!("✅ Root key generated and saved to: {}", path);
+    } else {
+        println!("{}", encoded);
+    }
+    eprintln!("   Fingerprint: {}", shamir::fingerprint(&secret_bytes));
+    let issuer = CertificateIssuer::from_root_key(&secret_bytes)?;
+    eprintln!(
+        "   Root public key: {}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(issuer.verifying_key().as_bytes())
+    );
+
+    Ok(())
+}
+
+fn derive_key(
+    parent_b64: &str,
+    scope_str: &str,
+    output: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Parse parent key
+    let parent_bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(parent_b64)?;
+    if parent_bytes.len() != 32 {
+        return Err("Parent key must be 32 bytes".into());
+    }
+    let mut parent_key = [0u8; 32];
+    parent_key.copy_from_slice(&parent_bytes);
+
+    // Parse scope
+    let scope = match scope_str.to_lowercase().as_str() {
+        "root" => KeyScope::Root,
+        "device" | "device-master" => KeyScope::DeviceMaster,
+        "session" => KeyScope::Session,
+        "stream" => KeyScope::Stream,
+        _ => return Err(format!("Invalid scope: {}", scope_str).into()),
+    };
+
+    // Derive child key
+    let hierarchy = KeyHierarchy::from_bytes(parent_key);
+    let child_key = hierarchy.derive_simple(scope)?;
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(child_key);
+
+    if let Some(path) = output {
+        write_secret(Path::new(&path), encoded.as_bytes())?;
+        eprintln!("✅ Derived {:?} key and saved to: {}", scope, path);
+    } else {
+        println!("{}", encoded);
+    }
+
+    Ok(())
+}
+
+fn rotation_store(
+    path: &str,
+    sealing: &SealingArgs,
+) -> Result<RotationStateStore, Box<dyn std::error::Error>> {
+    let key = match &sealing.key_file {
+        Some(key_file) => {
+            let key_b64 = std::fs::read_to_string(key_file)?;
+            SealingKey::from_bytes(&decode_key(key_b64.trim(), "Sealing key")?)?
+        }
+        None => match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => SealingKey::passphrase(passphrase),
+            _ => {
+                return Err(format!(
+                    "Rotation state is encrypted: pass --key-file or set {}",
+                    PASSPHRASE_ENV
+                )
+                .into())
+            }
+        },
+    };
+    Ok(RotationStateStore::new(path, key))
+}
+
+fn init_rotation(store: &RotationStateStore) -> Result<(), Box<dyn std::error::Error>> {
+    if store.exists() {
+        return Err(format!("{} already exists", store.path().display()).into());
+    }
+    store.save(&KeyRotationManager::new())?;
+    eprintln!("✅ Rotation manager initialized: {}", store.path().display());
+    Ok(())
+}
+
+fn add_version(
+    store: &RotationStateStore,
+    scope_str: &str,
+    key_b64: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Parse scope
+    let scope = match scope_str.to_lowercase().as_str() {
+        "root" => KeyScope::Root,
+        "device" | "device-master" => KeyScope::DeviceMaster,
+        "session" => KeyScope::Session,
+        "stream" => KeyScope::Stream,
+        _ => return Err(format!("Invalid scope: {}", scope_str).into()),
+    };
+
+    // Parse key
+    let key_bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(key_b64)?;
+    if key_bytes.len() != 32 {
+        return Err("Key must be 32 bytes".into());
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&key_bytes);
+
+    // Add version (load, update and save under the file lock)
+    let now = chrono::Utc::now().timestamp();
+    let version = store.add_key_version(scope, key, now)?;
+
+    eprintln!("✅ Added {:?} key version {}", scope, version);
+    Ok(())
+}
+
+fn show_status(store: &RotationStateStore) -> Result<(), Box<dyn std::error::Error>> {
+    let manager = store.load()?;
+
+    let now = chrono::Utc::now().timestamp();
+    let status = manager.get_status(now);
+
+    println!("🔑 Key Rotation Status\n");
+
+    for (scope, info) in status {
+        println!("Scope: {:?}", scope);
+        println!("  Active Version: {:?}", info.active_version);
+        println!("  Usable Versions: {}", info.usable_versions);
+        println!("  Needs Rotation: {}", if info.needs_rotation { "⚠️  YES" } else { "✅ No" });
+        if let Some(next) = info.next_rotation {
+            let dt = chrono::DateTime::<chrono::Utc>::from_timestamp(next, 0)
+                .unwrap_or_default();
+            println!("  Next Rotation: {}", dt.format("%Y-%m-%d %H:%M:%S UTC"));
+        } else {
+            println!("  Next Rotation: Manual only");
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+fn migrate_rotation(store: &RotationStateStore) -> Result<(), Box<dyn std::error::Error>> {
+    match store.migrate()? {
+        0 => eprintln!("✅ Encrypted plaintext rotation state: {}", store.path().display()),
+        format => eprintln!("✅ Rotation state already uses format {}", format),
+    }
+    Ok(())
+}
+
+/// Writes key material to a file readable only by the owner
+///
+/// Existing files are truncated and their permissions tightened as well.
+fn write_secret(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).truncate(true).write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+fn decode_key(key_b64: &str, what: &str) -> Result<[u8; 32], Box<dyn std::error::Error>> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(key_b64)?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("{} must be 32 bytes", what).into())
+}
+
+/// Reads the root key from `--root-file` or the environment
+fn read_root_key(
+    args: &RootKeyArgs,
+) -> Result<zeroize::Zeroizing<[u8; 32]>, Box<dyn std::error::Error>> {
+    let encoded = zeroize::Zeroizing::new(match &args.root_file {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => match std::env::var(ROOT_KEY_ENV) {
+            Ok(key) if !key.is_empty() => key,
+            _ => return Err(format!("Pass --root-file or set {}", ROOT_KEY_ENV).into()),
+        },
+    });
+    let key = decode_key(encoded.trim(), "Root key")?;
+    Ok(zeroize::Zeroizing::new(key))
+}
+
+/// Certificate authority of the root key (seed derived under its own HKDF label)
+fn root_issuer(args: &RootKeyArgs) -> Result<CertificateIssuer, Box<dyn std::error::Error>> {
+    Ok(CertificateIssuer::from_root_key(&read_root_key(args)?[..])?)
+}
+
+/// Intermediate issuer from a file holding its Base64 Ed25519 seed
+fn intermediate_issuer(path: &str) -> Result<CertificateIssuer, Box<dyn std::error::Error>> {
+    let encoded = zeroize::Zeroizing::new(std::fs::read_to_string(path)?);
+    let seed = zeroize::Zeroizing::new(decode_key(encoded.trim(), "Intermediate key")?);
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&seed);
+    Ok(CertificateIssuer::new(signing_key))
+}
+
+fn issue_cert(
+    root: &RootKeyArgs,
+    device_id: &str,
+    device_key_b64: &str,
+    capabilities: &str,
+    days: u64,
+    output: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let issuer = root_issuer(root)?;
+    let device_id = honeylink_core::types::DeviceId::new(device_id.to_string())?;
+    let device_key =
+        ed25519_dalek::VerifyingKey::from_bytes(&decode_key(device_key_b64, "Device key")?)?;
+    let capabilities = Capabilities::parse(capabilities)?;
+
+    let certificate = issuer.issue(
+        &device_id,
+        &device_key,
+        capabilities,
+        std::time::Duration::from_secs(days.saturating_mul(24 * 3600)),
+    );
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(certificate.to_bytes());
+
+    if let Some(path) = output {
+        std::fs::write(&path, encoded)?;
+        eprintln!("✅ Certificate issued and saved to: {}", path);
+    } else {
+        println!("{}", encoded);
+    }
+    eprintln!("   Serial: {}", certificate.serial());
+    eprintln!("   Capabilities: {}", capabilities.names().join(", "));
+    eprintln!(
+        "   Root public key: {}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(issuer.verifying_key().as_bytes())
+    );
+
+    Ok(())
+}
+
+fn revoke_cert(
+    issuer: &CertificateIssuer,
+    serials: Vec<u64>,
+    list_path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Extend the existing list so devices holding it accept the new one
+    let previous: Option<RevocationList> = match std::fs::read_to_string(list_path) {
+        Ok(json) => Some(serde_json::from_str(&json)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let list = issuer.revoke(previous.as_ref(), serials)?;
+
+    std::fs::write(list_path, serde_json::to_string_pretty(&list)?)?;
+    eprintln!(
+        "✅ Revocation list #{} saved to: {} ({} serials revoked)",
+        list.sequence(),
+        list_path,
+        list.serials().len()
+    );
+    eprintln!(
+        "   Issuer public key: {}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(issuer.verifying_key().as_bytes())
+    );
+
+    Ok(())
+}
+
+fn split_root(
+    root: &RootKeyArgs,
+    threshold: u8,
+    shares: u8,
+    qr: bool,
+    output_dir: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = read_root_key(root)?;
+    let shares = shamir::split_secret(&root[..], threshold, shares)?;
+    let total = shares.len();
+
+    for share in &shares {
+        let encoded = zeroize::Zeroizing::new(if qr {
+            share.to_base32()
+        } else {
+            share.to_base64()
+        });
+        match &output_dir {
+            Some(dir) => {
+                let path =
+                    std::path::Path::new(dir).join(format!("root-share-{}.txt", share.index()));
+                write_secret(&path, encoded.as_bytes())?;
+                eprintln!("   Share {}/{} saved to: {}", share.index(), total, path.display());
+            }
+            None => println!("{}", *encoded),
+        }
+    }
+
+    eprintln!("✅ Root key split into {} shares ({} needed for recovery)", total, threshold);
+    eprintln!("   Fingerprint: {}", shamir::fingerprint(&root[..]));
+    Ok(())
+}
+
+fn recover_root(
+    share_files: &[String],
+    fingerprint: &str,
+    output: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut shares = Vec::with_capacity(share_files.len());
+    for path in share_files {
+        let text = zeroize::Zeroizing::new(std::fs::read_to_string(path)?);
+        shares.push(Share::parse(&text)?);
+    }
+    let root = shamir::combine_verified(&shares, fingerprint)?;
+    let encoded = zeroize::Zeroizing::new(
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(&root[..]),
+    );
+
+    if let Some(path) = output {
+        write_secret(Path::new(&path), encoded.as_bytes())?;
+        eprintln!("✅ Root key recovered and saved to: {}", path);
+    } else {
+        println!("{}", *encoded);
+    }
+
+    Ok(())
+}
+
+fn run_demo() -> Result<(), Box<dyn std::error::Error>> {
+    use rand::Rng;
+    use rand::rngs::OsRng;
+
+    println!("🍯 HoneyLink Key Hierarchy Demo\n");
+    println!("Demonstrating 4-level key derivation:");
+    println!("  k_root → k_device → k_session → k_stream\n");
+
+    // Generate root key
+    println!("1️⃣  Generating root key (X25519)...");
+    let mut root_key = [0u8; 32];
+    OsRng.fill(&mut root_key);
+    println!("   Root: {}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(root_key));
+
+    // Derive device key
+    println!("\n2️⃣  Deriving device master key (HKDF-SHA512)...");
+    let hierarchy = KeyHierarchy::from_bytes(root_key);
+    let device_key = hierarchy.derive_simple(KeyScope::DeviceMaster)?;
+    println!("   Device: {}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(device_key));
+
+    // Derive session key
+    println!("\n3️⃣  Deriving session key...");
+    let hierarchy = KeyHierarchy::from_bytes(device_key);
+    let session_key = hierarchy.derive_simple(KeyScope::Session)?;
+    println!("   Session: {}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(session_key));
+
+    // Derive stream key
+    println!("\n4️⃣  Deriving stream key...");
+    let hierarchy = KeyHierarchy::from_bytes(session_key);
+    let stream_key = hierarchy.derive_simple(KeyScope::Stream)?;
+    println!("   Stream: {}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(stream_key));
+
+    println!("\n✅ All keys derived successfully using pure Rust cryptography!");
+    println!("   - X25519-dalek for root keypair");
+    println!("   - HKDF-SHA512 for key derivation");
+    println!("   - Zero C/C++ dependencies\n");
+
+    // Demonstrate rotation
+    println!("5️⃣  Key Rotation Demo...");
+    let mut manager = KeyRotationManager::new();
+    let now = chrono::Utc::now().timestamp();
+
+    // Add root key version
+    manager.add_key_version(KeyScope::Root, root_key, now)?;
+    println!("   Added Root key v1");
+
+    // Add device key version
+    manager.add_key_version(KeyScope::DeviceMaster, device_key, now)?;
+    println!("   Added Device key v1");
+
+    // Show status
+    let status = manager.get_status(now);
+    println!("\n📊 Rotation Status:");
+    for (scope, info) in status {
+        if info.active_version.is_some() {
+            println!("   {:?}: v{} active, {} usable",
+                scope,
+                info.active_version.unwrap(),
+                info.usable_versions
+            );
+        }
+    }
+
+    println!("\n🎉 Demo complete! Use --help to see all commands.");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_secret_files_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("root.key");
+        std::fs::write(&path, b"old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_secret(&path, b"secret").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"secret");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_split_and_recover_root_through_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root_file = dir.path().join("root.key");
+        write_secret(
+            &root_file,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .encode([9u8; 32])
+                .as_bytes(),
+        )
+        .unwrap();
+        let root = RootKeyArgs {
+            root_file: Some(root_file.to_string_lossy().into_owned()),
+        };
+        let output_dir = dir.path().to_string_lossy().into_owned();
+
+        assert!(split_root(&root, 1, 3, false, Some(output_dir.clone())).is_err());
+        split_root(&root, 2, 3, true, Some(output_dir)).unwrap();
+
+        let shares: Vec<String> = [1, 3]
+            .iter()
+            .map(|i| {
+                dir.path()
+                    .join(format!("root-share-{}.txt", i))
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        let recovered = dir.path().join("recovered.key");
+        recover_root(
+            &shares,
+            &shamir::fingerprint(&[9u8; 32]),
+            Some(recovered.to_string_lossy().into_owned()),
+        )
+        .unwrap();
+        let recovered = RootKeyArgs {
+            root_file: Some(recovered.to_string_lossy().into_owned()),
+        };
+        assert_eq!(*read_root_key(&recovered).unwrap(), [9u8; 32]);
+    }
+}
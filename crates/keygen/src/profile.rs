@@ -0,0 +1,228 @@
+// PolicyProfile bundle signing and verification
+//
+// A bundle is a JSON file holding one profile or an array of profiles, as
+// exported by the policy engine. Profiles are signed with this device's
+// identity key; verification accepts this device and trusted peers unless an
+// explicit key is given.
+
+use crate::identity::known_signers;
+use clap::Subcommand;
+use ed25519_dalek::VerifyingKey;
+use honeylink_keychain::identity::{parse_verifying_key, DeviceIdentity};
+use honeylink_keychain::KeychainProvider;
+use honeylink_policy_engine::PolicyProfile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Subcommand)]
+pub enum ProfileCommand {
+    /// Sign every profile in a bundle with this device's identity
+    Sign {
+        /// Bundle to sign (JSON profile or array of profiles)
+        #[arg(short, long)]
+        input: String,
+
+        /// Output file path (optional, prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Verify the signature of every profile in a bundle
+    Verify {
+        /// Bundle to verify
+        #[arg(short, long)]
+        input: String,
+
+        /// Ed25519 public key in Base64 (default: this device and trusted peers)
+        #[arg(short, long)]
+        key: Option<String>,
+    },
+}
+
+/// One profile or an array of profiles
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Bundle {
+    Single(Box<PolicyProfile>),
+    Many(Vec<PolicyProfile>),
+}
+
+impl Bundle {
+    fn profiles_mut(&mut self) -> &mut [PolicyProfile] {
+        match self {
+            Bundle::Single(profile) => std::slice::from_mut(profile.as_mut()),
+            Bundle::Many(profiles) => profiles,
+        }
+    }
+
+    fn into_profiles(self) -> Vec<PolicyProfile> {
+        match self {
+            Bundle::Single(profile) => vec![*profile],
+            Bundle::Many(profiles) => profiles,
+        }
+    }
+}
+
+pub fn run(
+    keychain: &dyn KeychainProvider,
+    command: ProfileCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        ProfileCommand::Sign { input, output } => sign_bundle(keychain, &input, output),
+        ProfileCommand::Verify { input, key } => verify_bundle(keychain, &input, key.as_deref()),
+    }
+}
+
+fn read_bundle(path: &str) -> Result<Bundle, Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| format!("{}: not a profile bundle: {}", path, e).into())
+}
+
+fn sign_bundle(
+    keychain: &dyn KeychainProvider,
+    input: &str,
+    output: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let identity = DeviceIdentity::load(keychain)?;
+    let mut bundle = read_bundle(input)?;
+
+    let profiles = bundle.profiles_mut();
+    for profile in profiles.iter_mut() {
+        profile.validate()?;
+        profile.sign(identity.signing_key());
+    }
+    let count = profiles.len();
+    let json = serde_json::to_string_pretty(&bundle)?;
+
+    if let Some(path) = output {
+        std::fs::write(&path, json)?;
+        eprintln!("✅ Signed {} profile(s) and saved to: {}", count, path);
+    } else {
+        println!("{}", json);
+    }
+    eprintln!(
+        "   Signer: {} ({})",
+        identity.device_id(),
+        identity.fingerprint()
+    );
+
+    Ok(())
+}
+
+fn verify_bundle(
+    keychain: &dyn KeychainProvider,
+    input: &str,
+    key: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let signers: Vec<(String, VerifyingKey)> = match key {
+        Some(key) => vec![("given key".to_string(), parse_verifying_key(key)?)],
+        None => known_signers(keychain)?,
+    };
+    if signers.is_empty() {
+        return Err("No identity or trusted peers to verify against (pass --key)".into());
+    }
+
+    let mut failures = 0;
+    for profile in read_bundle(input)?.into_profiles() {
+        match signers
+            .iter()
+            .find(|(_, key)| profile.verify_with(key).is_ok())
+        {
+            Some((signer, _)) => println!("✅ {}: signed by {}", profile.profile_id, signer),
+            None => {
+                failures += 1;
+                println!(
+                    "❌ {}: no valid signature from a trusted key",
+                    profile.profile_id
+                );
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} profile(s) failed verification", failures).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use honeylink_crypto::KdfParams;
+    use honeylink_keychain::identity::TrustedPeers;
+    use honeylink_keychain::{FileKeychain, KeySource};
+
+    fn keychain(dir: &tempfile::TempDir, name: &str) -> FileKeychain {
+        FileKeychain::open_with_params(
+            dir.path().join(name),
+            KeySource::passphrase("test"),
+            KdfParams::TESTING,
+        )
+        .unwrap()
+    }
+
+    fn profile(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "profile_id": id,
+            "profile_name": "Test profile",
+            "profile_version": "1.0.0",
+            "use_case": "IoT",
+            "latency_budget_ms": 50,
+            "bandwidth_floor_mbps": 1.0,
+            "bandwidth_ceiling_mbps": 10.0,
+            "fec_mode": "LIGHT",
+            "priority": 5,
+            "power_profile": "normal",
+            "signature": "",
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z"
+        })
+    }
+
+    #[test]
+    fn test_sign_and_verify_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = keychain(&dir, "signer.json");
+        let identity = DeviceIdentity::generate(None);
+        identity.save(&signer).unwrap();
+
+        let input = dir.path().join("bundle.json");
+        let signed = dir.path().join("signed.json");
+        let bundle = serde_json::json!([profile("prof_a_v1"), profile("prof_b_v1")]);
+        std::fs::write(&input, bundle.to_string()).unwrap();
+        let (input, signed) = (input.to_str().unwrap(), signed.to_str().unwrap());
+
+        sign_bundle(&signer, input, Some(signed.to_string())).unwrap();
+        verify_bundle(&signer, signed, None).unwrap();
+        assert!(verify_bundle(&signer, input, None).is_err());
+
+        // A peer verifies through its trust list, or with an explicit key
+        let verifier = keychain(&dir, "verifier.json");
+        assert!(verify_bundle(&verifier, signed, None).is_err());
+        let key = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .encode(identity.verifying_key().as_bytes());
+        verify_bundle(&verifier, signed, Some(&key)).unwrap();
+        let mut peers = TrustedPeers::default();
+        peers.add("signer", &identity.pairing()).unwrap();
+        peers.save(&verifier).unwrap();
+        verify_bundle(&verifier, signed, None).unwrap();
+
+        // Any change after signing is detected
+        let mut tampered: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(signed).unwrap()).unwrap();
+        tampered[1]["latency_budget_ms"] = 10.into();
+        std::fs::write(signed, tampered.to_string()).unwrap();
+        assert!(verify_bundle(&verifier, signed, None).is_err());
+    }
+
+    #[test]
+    fn test_sign_rejects_invalid_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = keychain(&dir, "signer.json");
+        DeviceIdentity::generate(None).save(&signer).unwrap();
+
+        let input = dir.path().join("bundle.json");
+        std::fs::write(&input, profile("not_a_profile_id").to_string()).unwrap();
+        assert!(sign_bundle(&signer, input.to_str().unwrap(), None).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+// Self-test of the cryptographic primitives
+//
+// Runs known-answer tests (RFC 7748, RFC 8032, RFC 8439 and HKDF-SHA512 over
+// the RFC 5869 test case 1 inputs) and round trips of every
+// primitive the device relies on, so operators can check a build on the
+// target platform before deploying it.
+
+use honeylink_core::types::DeviceId;
+use honeylink_crypto::certificate::noise_identity;
+use honeylink_crypto::{
+    shamir, signing, Capabilities, CertificateChain, CertificateIssuer, ChaCha20Poly1305Cipher,
+    DeriveContext, HandshakeRole, KeyAgreement, KeyDerivation, NoiseHandshake, PacketOpener,
+    PacketProtection, PacketSealer, SecretKey, TrustStore,
+};
+use std::time::Duration;
+
+type CheckResult = Result<(), Box<dyn std::error::Error>>;
+type Check = fn() -> CheckResult;
+
+const CHECKS: &[(&str, Check)] = &[
+    ("X25519 (RFC 7748)", check_x25519),
+    ("Ed25519 (RFC 8032)", check_ed25519),
+    ("HKDF-SHA512", check_hkdf),
+    ("ChaCha20-Poly1305 (RFC 8439)", check_aead),
+    ("Noise XX handshake", check_noise),
+    ("Packet protection", check_packet_protection),
+    ("Shamir secret sharing", check_shamir),
+    ("Device certificates", check_certificates),
+    #[cfg(feature = "pq-hybrid")]
+    ("X25519 + ML-KEM-768", check_hybrid_kem),
+];
+
+/// Runs every check; fails if any of them failed
+pub fn run() -> CheckResult {
+    println!("🔬 HoneyLink Cryptographic Self-Test\n");
+
+    let mut failures = 0;
+    for (name, check) in CHECKS {
+        match check() {
+            Ok(()) => println!("  ✅ {}", name),
+            Err(e) => {
+                failures += 1;
+                println!("  ❌ {}: {}", name, e);
+            }
+        }
+    }
+    println!();
+
+    if failures > 0 {
+        return Err(format!("{} of {} checks failed", failures, CHECKS.len()).into());
+    }
+    println!("✅ All {} checks passed", CHECKS.len());
+    Ok(())
+}
+
+fn check_x25519() -> CheckResult {
+    let alice = SecretKey::from_bytes(&hex(
+        "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+    ))?;
+    let bob = SecretKey::from_bytes(&hex(
+        "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+    ))?;
+    ensure(
+        alice.public_key().as_bytes()[..]
+            == hex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")[..],
+        "public key mismatch",
+    )?;
+
+    let shared = KeyAgreement::derive_shared_secret(&alice, &bob.public_key())?;
+    ensure(
+        shared.as_bytes()[..]
+            == hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742")[..],
+        "shared secret mismatch",
+    )?;
+    let reverse = KeyAgreement::derive_shared_secret(&bob, &alice.public_key())?;
+    ensure(
+        shared.as_bytes() == reverse.as_bytes(),
+        "shared secrets differ",
+    )
+}
+
+fn check_ed25519() -> CheckResult {
+    let seed: [u8; 32] = hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+        .try_into()
+        .map_err(|_| "bad seed")?;
+    let key = ed25519_dalek::SigningKey::from_bytes(&seed);
+    ensure(
+        key.verifying_key().as_bytes()[..]
+            == hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")[..],
+        "public key mismatch",
+    )?;
+
+    let signature = signing::sign(&key, b"");
+    ensure(
+        signature.to_bytes()[..]
+            == hex(concat!(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155",
+                "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            ))[..],
+        "signature mismatch",
+    )?;
+    signing::verify(&key.verifying_key(), b"", &signature)?;
+    ensure(
+        signing::verify(&key.verifying_key(), b"tampered", &signature).is_err(),
+        "tampered message accepted",
+    )
+}
+
+fn check_hkdf() -> CheckResult {
+    // RFC 5869 test case 1 inputs with SHA-512
+    let okm = KeyDerivation::derive(
+        &[0x0bu8; 22],
+        Some(&hex("000102030405060708090a0b0c")),
+        &hex("f0f1f2f3f4f5f6f7f8f9"),
+        42,
+    )?;
+    ensure(
+        okm[..]
+            == hex(concat!(
+                "832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c14815793",
+                "38da362cb8d9f925d7cb"
+            ))[..],
+        "output key material mismatch",
+    )?;
+
+    let ikm = [0x0bu8; 32];
+    let a = KeyDerivation::derive_with_context(&ikm, &DeriveContext::device_master("DEV-A"), 32)?;
+    let b = KeyDerivation::derive_with_context(&ikm, &DeriveContext::device_master("DEV-A"), 32)?;
+    let c = KeyDerivation::derive_with_context(&ikm, &DeriveContext::device_master("DEV-B"), 32)?;
+    ensure(a == b, "derivation is not deterministic")?;
+    ensure(a != c, "contexts are not separated")
+}
+
+fn check_aead() -> CheckResult {
+    // RFC 8439 section 2.8.2
+    let key: Vec<u8> = (0x80..=0x9f).collect();
+    let nonce: [u8; 12] = hex("070000004041424344454647")
+        .try_into()
+        .map_err(|_| "bad nonce")?;
+    let aad = hex("50515253c0c1c2c3c4c5c6c7");
+    let plaintext: &[u8] = b"Ladies and Gentlemen of the class of '99: \
+        If I could offer you only one tip for the future, sunscreen would be it.";
+    let sealed = ChaCha20Poly1305Cipher::new(&key)?.encrypt_with_nonce(&nonce, plaintext, &aad)?;
+    ensure(
+        sealed[..]
+            == hex(concat!(
+                "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6",
+                "3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36",
+                "92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc",
+                "3ff4def08e4b7a9de576d26586cec64b6116",
+                "1ae10b594f09e26a7e902ecbd0600691"
+            ))[..],
+        "ciphertext or tag mismatch",
+    )?;
+
+    let cipher = ChaCha20Poly1305Cipher::new_random();
+    let (nonce, mut ciphertext) = cipher.encrypt(b"self-test", b"aad")?;
+    ensure(
+        cipher.decrypt(&nonce, &ciphertext, b"aad")? == b"self-test",
+        "round trip mismatch",
+    )?;
+    ensure(
+        cipher.decrypt(&nonce, &ciphertext, b"other").is_err(),
+        "modified AAD accepted",
+    )?;
+    ciphertext[0] ^= 1;
+    ensure(
+        cipher.decrypt(&nonce, &ciphertext, b"aad").is_err(),
+        "tampered ciphertext accepted",
+    )
+}
+
+fn check_noise() -> CheckResult {
+    let (a_static, a_public) = KeyAgreement::generate_keypair();
+    let (b_static, b_public) = KeyAgreement::generate_keypair();
+    let mut a = NoiseHandshake::new(HandshakeRole::Initiator, a_static, b"self-test");
+    let mut b = NoiseHandshake::new(HandshakeRole::Responder, b_static, b"self-test");
+
+    b.read_message(&a.write_message(b"")?)?;
+    a.read_message(&b.write_message(b"")?)?;
+    b.read_message(&a.write_message(b"")?)?;
+    let (a, b) = (a.into_transport()?, b.into_transport()?);
+
+    ensure(
+        a.send_key == b.recv_key && a.recv_key == b.send_key,
+        "key mismatch",
+    )?;
+    ensure(
+        a.handshake_hash == b.handshake_hash,
+        "handshake hash mismatch",
+    )?;
+    ensure(
+        a.remote_static.as_bytes() == b_public.as_bytes()
+            && b.remote_static.as_bytes() == a_public.as_bytes(),
+        "static keys not authenticated",
+    )
+}
+
+fn check_packet_protection() -> CheckResult {
+    let secret = [0x42u8; 32];
+    let mut sealer = PacketSealer::new(PacketProtection::derive(&secret, "self-test")?);
+    let mut opener = PacketOpener::new(PacketProtection::derive(&secret, "self-test")?);
+
+    let mut packet = b"payload".to_vec();
+    let seq = sealer.seal(b"header", &mut packet)?;
+    opener.open(seq, b"header", &mut packet.clone())?;
+    ensure(
+        opener.open(seq, b"header", &mut packet).is_err(),
+        "replayed packet accepted",
+    )
+}
+
+fn check_shamir() -> CheckResult {
+    let secret = [0x5au8; 32];
+    let shares = shamir::split_secret(&secret, 3, 5)?;
+    let recovered = shamir::combine_verified(&shares[1..4], &shamir::fingerprint(&secret))?;
+    ensure(recovered[..] == secret[..], "recovered secret mismatch")?;
+    ensure(
+        shamir::combine_verified(&shares[..2], &shamir::fingerprint(&secret)).is_err(),
+        "recovered below threshold",
+    )
+}
+
+fn check_certificates() -> CheckResult {
+    let root = ed25519_dalek::SigningKey::from_bytes(&rand::random());
+    let device = ed25519_dalek::SigningKey::from_bytes(&rand::random());
+    let device_id = DeviceId::new("DEV-SELFTEST".to_string())?;
+
+    let issuer = CertificateIssuer::new(root);
+    let certificate = issuer.issue(
+        &device_id,
+        &device.verifying_key(),
+        Capabilities::CONNECT,
+        Duration::from_secs(3600),
+    );
+    let serial = certificate.serial();
+    let chain = CertificateChain::new(certificate);
+
+    let trust = TrustStore::new().with_root(issuer.verifying_key());
+    let verified = trust.verify_now(&chain)?;
+    ensure(verified.device_id == device_id, "subject mismatch")?;
+    ensure(
+        chain.leaf().noise_public_key().as_bytes()
+            == noise_identity(&device)?.public_key().as_bytes(),
+        "Noise identity mismatch",
+    )?;
+
+    trust.apply_revocations(&issuer.revoke(None, vec![serial])?)?;
+    ensure(
+        trust.verify_now(&chain).is_err(),
+        "revoked certificate accepted",
+    )
+}
+
+#[cfg(feature = "pq-hybrid")]
+fn check_hybrid_kem() -> CheckResult {
+    use honeylink_crypto::HybridKem;
+
+    let (secret, public) = HybridKem::generate_keypair();
+    let (ciphertext, sender) = HybridKem::encapsulate(&public)?;
+    let receiver = HybridKem::decapsulate(&secret, &ciphertext)?;
+    ensure(
+        sender.as_bytes() == receiver.as_bytes(),
+        "shared secret mismatch",
+    )
+}
+
+fn ensure(condition: bool, message: &str) -> CheckResult {
+    if condition {
+        Ok(())
+    } else {
+        Err(message.into())
+    }
+}
+
+fn hex(text: &str) -> Vec<u8> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).expect("valid test vector"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_check_passes() {
+        for (name, check) in CHECKS {
+            if let Err(e) = check() {
+                panic!("{} failed: {}", name, e);
+            }
+        }
+    }
+}
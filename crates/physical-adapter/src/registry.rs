@@ -10,8 +10,15 @@
 //!    - Gracefully transition (no packet loss during switch)
 //!    - Target: P95 < 2s switchover latency
 //! 3. Fallback priority: WiFi6E > WiFi7 > 5G > Ethernet
+//!
+//! Every link quality reading is classified as Up, Degraded (`is_degraded()`)
+//! or Down (read failed); transitions are published as
+//! `Event::LinkStateChanged` to the shared `EventBus` attached with
+//! `with_event_bus()`.
 
 use crate::adapter::{AdapterType, FiveGAdapter, ThzAdapter, WiFi6eAdapter, WiFi7Adapter};
+use honeylink_core::events::{Event, LinkState};
+use honeylink_core::EventBus;
 use honeylink_transport::{LinkQualityMetrics, Packet, PhysicalLayer, PowerMode, TransportError};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -41,6 +48,10 @@ pub struct AdapterRegistry {
     strategy: HotSwapStrategy,
     /// Link quality monitoring interval
     monitor_interval: Duration,
+    /// Last observed state per adapter, to publish only transitions
+    link_states: Arc<RwLock<HashMap<AdapterType, LinkState>>>,
+    /// Shared event bus receiving `LinkStateChanged` events (optional)
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl AdapterRegistry {
@@ -51,9 +62,17 @@ impl AdapterRegistry {
             active_adapter: Arc::new(RwLock::new(None)),
             strategy,
             monitor_interval: Duration::from_secs(5), // MOD-007 spec
+            link_states: Arc::new(RwLock::new(HashMap::new())),
+            event_bus: None,
         }
     }
 
+    /// Publishes link state transitions to `event_bus`
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     /// Registers a WiFi 6E adapter
     pub async fn register_wifi6e(
         &self,
@@ -179,7 +198,11 @@ impl AdapterRegistry {
         }
 
         // Get current link quality
-        let current_quality = match self.get_link_quality().await {
+        let current_quality = self.get_link_quality().await;
+        if let Some(active) = self.active_adapter().await {
+            self.observe_link(active, &current_quality).await;
+        }
+        let current_quality = match current_quality {
             Ok(q) => q,
             Err(_) => {
                 // Current link is down, force switch
@@ -202,7 +225,9 @@ impl AdapterRegistry {
         let mut best_adapter: Option<(AdapterType, LinkQualityMetrics)> = None;
 
         for (adapter_type, adapter) in adapters.iter() {
-            if let Ok(metrics) = adapter.get_link_quality().await {
+            let quality = adapter.get_link_quality().await;
+            self.observe_link(*adapter_type, &quality).await;
+            if let Ok(metrics) = quality {
                 let is_better = match self.strategy {
                     HotSwapStrategy::HighestRssi => {
                         best_adapter
@@ -241,6 +266,34 @@ impl AdapterRegistry {
         }
     }
 
+    /// Records an adapter's link state and publishes it if it changed
+    async fn observe_link(
+        &self,
+        adapter_type: AdapterType,
+        quality: &Result<LinkQualityMetrics, TransportError>,
+    ) {
+        let state = match quality {
+            Ok(metrics) if metrics.is_degraded() => LinkState::Degraded,
+            Ok(_) => LinkState::Up,
+            Err(_) => LinkState::Down,
+        };
+
+        let previous = self.link_states.write().await.insert(adapter_type, state);
+        if previous == Some(state) {
+            return;
+        }
+
+        if let Some(event_bus) = &self.event_bus {
+            let event = Event::LinkStateChanged {
+                adapter: adapter_type.as_str().to_string(),
+                state,
+            };
+            if let Err(e) = event_bus.publish(event) {
+                eprintln!("Failed to publish link state change: {}", e);
+            }
+        }
+    }
+
     /// Starts background link quality monitoring and automatic Hot Swap
     ///
     /// # Returns
@@ -306,4 +359,28 @@ mod tests {
 
         assert_eq!(registry.active_adapter().await, Some(AdapterType::THz));
     }
+
+    #[tokio::test]
+    async fn test_link_state_changes_published() {
+        let event_bus = Arc::new(EventBus::new());
+        let mut links = event_bus.subscribe([honeylink_core::Topic::Link]);
+        let registry = AdapterRegistry::new(HotSwapStrategy::HighestRssi)
+            .with_event_bus(Arc::clone(&event_bus));
+        registry.register_thz().await.unwrap();
+        registry.set_active(AdapterType::THz).await.unwrap();
+
+        assert!(!registry.evaluate_hot_swap().await.unwrap());
+        let received = links.recv().await.unwrap();
+        assert_eq!(
+            received.event,
+            Event::LinkStateChanged {
+                adapter: "THz".to_string(),
+                state: LinkState::Up,
+            }
+        );
+
+        // Unchanged state is not republished
+        registry.evaluate_hot_swap().await.unwrap();
+        assert!(links.try_recv().unwrap().is_none());
+    }
 }
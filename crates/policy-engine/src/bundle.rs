@@ -0,0 +1,233 @@
+//! Signed profile bundles for sharing custom profiles between devices
+//!
+//! A bundle is a list of individually signed profiles, exported as JSON or
+//! TOML:
+//!
+//! ```toml
+//! [[profiles]]
+//! profile_id = "prof_studio_v1"
+//! profile_version = "1.2.0"
+//! # ...
+//! ```
+//!
+//! JSON bundles are `{"profiles": [...]}`; a bare profile or array of
+//! profiles, as handled by `honeylink-keygen profile sign`, is also accepted.
+//! Importing verifies every signature before touching storage, then installs
+//! each profile with `ProfileStorage::upgrade`.
+
+use crate::error::{PolicyError, Result};
+use crate::profile::{PolicyProfile, ProfileStorage, ProfileUpgrade};
+use crate::types::UseCase;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// A set of signed profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    /// Profiles in the bundle
+    pub profiles: Vec<PolicyProfile>,
+}
+
+/// Accepted JSON bundle layouts
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonBundle {
+    Many(Vec<PolicyProfile>),
+    Single(Box<PolicyProfile>),
+    Bundle(ProfileBundle),
+}
+
+impl ProfileBundle {
+    /// Create a bundle from profiles
+    pub fn new(profiles: Vec<PolicyProfile>) -> Self {
+        Self { profiles }
+    }
+
+    /// Export the non-deprecated profiles of `storage`
+    pub fn export<S: ProfileStorage + ?Sized>(
+        storage: &S,
+        use_case: Option<UseCase>,
+    ) -> Result<Self> {
+        let mut profiles = storage.list(use_case)?;
+        profiles.sort_by(|a, b| a.profile_id.cmp(&b.profile_id));
+        Ok(Self { profiles })
+    }
+
+    /// Sign every profile with `signing_key`
+    pub fn sign(&mut self, signing_key: &SigningKey) {
+        for profile in &mut self.profiles {
+            profile.sign(signing_key);
+        }
+    }
+
+    /// Check that every profile is signed by one of `trusted_keys`
+    ///
+    /// # Errors
+    /// Returns `PolicyError::SignatureInvalid` naming the first profile
+    /// without a valid signature from a trusted key.
+    pub fn verify(&self, trusted_keys: &[VerifyingKey]) -> Result<()> {
+        for profile in &self.profiles {
+            if !trusted_keys
+                .iter()
+                .any(|key| profile.verify_with(key).is_ok())
+            {
+                return Err(PolicyError::SignatureInvalid(format!(
+                    "Profile {} is not signed by a trusted key",
+                    profile.profile_id
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify, validate and install every profile into `storage`
+    ///
+    /// Nothing is written unless all profiles are signed by a trusted key and
+    /// valid. Profiles are then installed in order; a version conflict stops
+    /// the import after the profiles before it.
+    ///
+    /// # Returns
+    /// The outcome for each profile ID, in bundle order
+    pub fn import_into<S: ProfileStorage + ?Sized>(
+        &self,
+        storage: &mut S,
+        trusted_keys: &[VerifyingKey],
+    ) -> Result<Vec<(String, ProfileUpgrade)>> {
+        self.verify(trusted_keys)?;
+        for profile in &self.profiles {
+            profile.validate()?;
+        }
+
+        self.profiles
+            .iter()
+            .map(|profile| {
+                let outcome = storage.upgrade(profile.clone())?;
+                Ok((profile.profile_id.clone(), outcome))
+            })
+            .collect()
+    }
+
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a JSON bundle, bare profile array or single profile
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(match serde_json::from_str(json)? {
+            JsonBundle::Bundle(bundle) => bundle,
+            JsonBundle::Many(profiles) => Self { profiles },
+            JsonBundle::Single(profile) => Self {
+                profiles: vec![*profile],
+            },
+        })
+    }
+
+    /// Serialize as TOML
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| PolicyError::Bundle(e.to_string()))
+    }
+
+    /// Parse a TOML bundle
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| PolicyError::Bundle(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::{create_arvr_spatial_preset, create_gaming_input_preset};
+    use crate::profile::InMemoryProfileStorage;
+    use semver::Version;
+
+    fn signed_bundle(key: &SigningKey) -> ProfileBundle {
+        let mut bundle = ProfileBundle::new(vec![
+            create_gaming_input_preset(),
+            create_arvr_spatial_preset(),
+        ]);
+        bundle.sign(key);
+        bundle
+    }
+
+    #[test]
+    fn test_json_and_toml_round_trip() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let bundle = signed_bundle(&key);
+
+        for decoded in [
+            ProfileBundle::from_json(&bundle.to_json().unwrap()).unwrap(),
+            ProfileBundle::from_toml(&bundle.to_toml().unwrap()).unwrap(),
+        ] {
+            assert_eq!(decoded.profiles.len(), 2);
+            assert!(decoded.verify(&[key.verifying_key()]).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_accepts_keygen_layouts() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let bundle = signed_bundle(&key);
+
+        let array = serde_json::to_string(&bundle.profiles).unwrap();
+        assert_eq!(ProfileBundle::from_json(&array).unwrap().profiles.len(), 2);
+
+        let single = serde_json::to_string(&bundle.profiles[0]).unwrap();
+        assert_eq!(ProfileBundle::from_json(&single).unwrap().profiles.len(), 1);
+    }
+
+    #[test]
+    fn test_import_requires_trusted_signatures() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let stranger = SigningKey::from_bytes(&[4u8; 32]);
+        let mut bundle = signed_bundle(&key);
+        bundle.profiles[1].sign(&stranger);
+
+        let mut storage = InMemoryProfileStorage::new();
+        assert!(matches!(
+            bundle.import_into(&mut storage, &[key.verifying_key()]),
+            Err(PolicyError::SignatureInvalid(_))
+        ));
+        assert!(storage.list(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_upgrades_newer_versions() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let mut storage = InMemoryProfileStorage::new();
+
+        let outcomes = signed_bundle(&key)
+            .import_into(&mut storage, &[key.verifying_key()])
+            .unwrap();
+        assert!(outcomes
+            .iter()
+            .all(|(_, outcome)| *outcome == ProfileUpgrade::Created));
+
+        let mut newer = create_gaming_input_preset();
+        newer.profile_version = Version::new(1, 1, 0);
+        newer.latency_budget_ms = 4;
+        let mut bundle = ProfileBundle::new(vec![newer]);
+        bundle.sign(&key);
+
+        let outcomes = bundle
+            .import_into(&mut storage, &[key.verifying_key()])
+            .unwrap();
+        assert_eq!(
+            outcomes[0].1,
+            ProfileUpgrade::Upgraded {
+                previous: Version::new(1, 0, 0)
+            }
+        );
+        assert_eq!(
+            storage
+                .get("prof_gaming_input_v1")
+                .unwrap()
+                .latency_budget_ms,
+            4
+        );
+
+        let exported = ProfileBundle::export(&storage, None).unwrap();
+        assert_eq!(exported.profiles.len(), 2);
+        assert!(exported.verify(&[key.verifying_key()]).is_ok());
+    }
+}
@@ -0,0 +1,158 @@
+//! Canonical binary encoding for policy signatures
+//!
+//! Signatures cover a deterministic byte string instead of JSON or `Debug`
+//! output, so they survive re-serialization and changes to formatting code.
+//!
+//! # Layout (version 1)
+//! ```text
+//! message  = str(domain) || u8(version) || fields...
+//! str      = u32_be(len) || utf8 bytes
+//! u8/u16   = fixed-width big-endian integer
+//! f64      = u64_be(IEEE 754 bits), -0.0 encoded as 0.0
+//! time     = i64_be(Unix seconds) || u32_be(subsecond nanoseconds)
+//! option   = 0x00 | 0x01 || value
+//! map      = u32_be(count) || (str(key) || str(value))* sorted by key
+//! enum     = u8 code (see `FecMode`, `PowerProfile`, `UseCase` below)
+//! ```
+//!
+//! Enum codes are fixed here and never reordered:
+//! - `UseCase`: IoT=0, ArVr=1, Media8K=2, Gaming=3, Custom=4
+//! - `FecMode`: None=0, Light=1, Heavy=2
+//! - `PowerProfile`: UltraLow=0, Low=1, Normal=2, High=3
+//!
+//! Test vectors for other SDKs live in `test-vectors/` at the crate root.
+
+use crate::types::{FecMode, PowerProfile, UseCase};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Builds a canonical message field by field
+pub(crate) struct CanonicalEncoder {
+    buf: Vec<u8>,
+}
+
+impl CanonicalEncoder {
+    /// Start a message with its domain-separation tag and encoding version
+    pub(crate) fn new(domain: &str, version: u8) -> Self {
+        let mut encoder = Self {
+            buf: Vec::with_capacity(256),
+        };
+        encoder.str(domain).u8(version);
+        encoder
+    }
+
+    pub(crate) fn str(&mut self, value: &str) -> &mut Self {
+        self.buf.extend_from_slice(&(value.len() as u32).to_be_bytes());
+        self.buf.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) -> &mut Self {
+        self.buf.push(value);
+        self
+    }
+
+    pub(crate) fn u16(&mut self, value: u16) -> &mut Self {
+        self.buf.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub(crate) fn f64(&mut self, value: f64) -> &mut Self {
+        // Normalize -0.0 so equal values always encode equally
+        let value = if value == 0.0 { 0.0 } else { value };
+        self.buf.extend_from_slice(&value.to_bits().to_be_bytes());
+        self
+    }
+
+    pub(crate) fn time(&mut self, value: &DateTime<Utc>) -> &mut Self {
+        self.buf.extend_from_slice(&value.timestamp().to_be_bytes());
+        self.buf
+            .extend_from_slice(&value.timestamp_subsec_nanos().to_be_bytes());
+        self
+    }
+
+    pub(crate) fn option<T>(
+        &mut self,
+        value: Option<T>,
+        encode: impl FnOnce(&mut Self, T) -> &mut Self,
+    ) -> &mut Self {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                encode(self, value)
+            }
+            None => self.u8(0),
+        }
+    }
+
+    pub(crate) fn map(&mut self, map: &HashMap<String, String>) -> &mut Self {
+        let mut entries: Vec<_> = map.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        self.buf
+            .extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for (key, value) in entries {
+            self.str(key).str(value);
+        }
+        self
+    }
+
+    pub(crate) fn use_case(&mut self, value: UseCase) -> &mut Self {
+        self.u8(match value {
+            UseCase::IoT => 0,
+            UseCase::ArVr => 1,
+            UseCase::Media8K => 2,
+            UseCase::Gaming => 3,
+            UseCase::Custom => 4,
+        })
+    }
+
+    pub(crate) fn fec_mode(&mut self, value: FecMode) -> &mut Self {
+        self.u8(match value {
+            FecMode::None => 0,
+            FecMode::Light => 1,
+            FecMode::Heavy => 2,
+        })
+    }
+
+    pub(crate) fn power_profile(&mut self, value: PowerProfile) -> &mut Self {
+        self.u8(match value {
+            PowerProfile::UltraLow => 0,
+            PowerProfile::Low => 1,
+            PowerProfile::Normal => 2,
+            PowerProfile::High => 3,
+        })
+    }
+
+    pub(crate) fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let mut metadata = HashMap::new();
+        metadata.insert("b".to_string(), "2".to_string());
+        metadata.insert("a".to_string(), "1".to_string());
+
+        let bytes = CanonicalEncoder::new("t", 1)
+            .u16(0x0102)
+            .f64(-0.0)
+            .option(None::<u8>, |e, v| e.u8(v))
+            .option(Some(7u8), |e, v| e.u8(v))
+            .map(&metadata)
+            .finish();
+
+        let mut expected = vec![0, 0, 0, 1, b't', 1, 0x01, 0x02];
+        expected.extend_from_slice(&0.0f64.to_bits().to_be_bytes());
+        expected.extend_from_slice(&[0, 1, 7]);
+        expected.extend_from_slice(&[0, 0, 0, 2]);
+        expected.extend_from_slice(&[0, 0, 0, 1, b'a', 0, 0, 0, 1, b'1']);
+        expected.extend_from_slice(&[0, 0, 0, 1, b'b', 0, 0, 0, 1, b'2']);
+        assert_eq!(bytes, expected);
+    }
+}
@@ -33,6 +33,10 @@ pub enum PolicyError {
     #[error("Storage error: {0}")]
     Storage(String),
 
+    /// Profile bundle could not be encoded or parsed
+    #[error("Bundle error: {0}")]
+    Bundle(String),
+
     /// JSON serialization/deserialization error
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
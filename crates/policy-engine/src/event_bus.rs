@@ -4,15 +4,89 @@
 //! - At-least-once delivery guarantee
 //! - Configuration snapshots for rollback
 //! - Automatic fallback on failure
+//!
+//! Applies, rollbacks and invalidations are also forwarded to the shared
+//! `honeylink_core::EventBus` when one is attached with `with_core_bus`.
+//!
+//! Subscribers that apply policies should receive through
+//! `PolicyVerifier::subscribe`, which drops updates and invalidations no
+//! trusted policy authority signed.
 
+use crate::canonical::CanonicalEncoder;
 use crate::error::{PolicyError, Result};
 use crate::types::QoSPolicyUpdate;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use honeylink_core::events::{Event, PolicyChangeKind};
+use honeylink_crypto::certificate::RevocationList;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 
 /// Maximum number of pending policy updates in channel
 const EVENT_BUS_CAPACITY: usize = 1024;
 
+/// Domain-separation tag for invalidation signatures
+const INVALIDATION_SIGNATURE_DOMAIN: &str = "honeylink-policy-invalidation";
+
+/// Version of the invalidation signing encoding
+const INVALIDATION_SIGNATURE_VERSION: u8 = 1;
+
+/// Where a policy invalidation came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidationOrigin {
+    /// Raised on this node (lifecycle expiry or the local engine)
+    Local,
+
+    /// Received from elsewhere; base64 Ed25519 signature of a policy
+    /// authority over the policy ID
+    Signed(String),
+}
+
+impl InvalidationOrigin {
+    /// Sign the invalidation of `policy_id` with a policy-authority key
+    pub fn signed(policy_id: &str, authority_key: &SigningKey) -> Self {
+        let signature = authority_key.sign(&invalidation_bytes(policy_id));
+        Self::Signed(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()))
+    }
+
+    /// Check a signed invalidation of `policy_id` against an authority key
+    ///
+    /// # Errors
+    /// `PolicyError::SignatureInvalid` for local origins, malformed
+    /// signatures and signatures by another key.
+    pub fn verify(&self, policy_id: &str, authority_key: &VerifyingKey) -> Result<()> {
+        let Self::Signed(signature) = self else {
+            return Err(PolicyError::SignatureInvalid(
+                "Invalidation is not signed".to_string(),
+            ));
+        };
+        let signature_bytes: [u8; 64] = base64::engine::general_purpose::STANDARD
+            .decode(signature)
+            .map_err(|e| PolicyError::SignatureInvalid(format!("Invalid base64: {}", e)))?
+            .try_into()
+            .map_err(|_| PolicyError::SignatureInvalid("Signature must be 64 bytes".to_string()))?;
+
+        authority_key
+            .verify(
+                &invalidation_bytes(policy_id),
+                &Signature::from_bytes(&signature_bytes),
+            )
+            .map_err(|e| {
+                PolicyError::SignatureInvalid(format!("Signature verification failed: {}", e))
+            })
+    }
+}
+
+/// Canonical encoding covered by invalidation signatures
+fn invalidation_bytes(policy_id: &str) -> Vec<u8> {
+    CanonicalEncoder::new(
+        INVALIDATION_SIGNATURE_DOMAIN,
+        INVALIDATION_SIGNATURE_VERSION,
+    )
+    .str(policy_id)
+    .finish()
+}
+
 /// Policy update event types
 #[derive(Debug, Clone)]
 pub enum PolicyEvent {
@@ -26,7 +100,15 @@ pub enum PolicyEvent {
     },
 
     /// Policy expired or deprecated
-    Invalidate { policy_id: String },
+    Invalidate {
+        policy_id: String,
+        origin: InvalidationOrigin,
+    },
+
+    /// New device certificate revocation list from an organization root
+    ///
+    /// Subscribers install it with `TrustStore::apply_revocations`.
+    Revocation(RevocationList),
 }
 
 /// Event bus for distributing policy updates to subscribers
@@ -38,6 +120,9 @@ pub struct PolicyEventBus {
     /// Snapshot storage for rollback on failure
     /// Maps policy_id -> last known good configuration
     snapshots: Arc<RwLock<std::collections::HashMap<String, QoSPolicyUpdate>>>,
+
+    /// Shared bus receiving policy changes
+    core_bus: Option<Arc<honeylink_core::EventBus>>,
 }
 
 impl PolicyEventBus {
@@ -48,12 +133,23 @@ impl PolicyEventBus {
         Self {
             sender,
             snapshots: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            core_bus: None,
         }
     }
 
+    /// Forward policy changes to the shared core event bus
+    ///
+    /// Publishing then succeeds without local subscribers.
+    pub fn with_core_bus(mut self, core_bus: Arc<honeylink_core::EventBus>) -> Self {
+        self.core_bus = Some(core_bus);
+        self
+    }
+
     /// Subscribe to policy events
     ///
-    /// Returns a receiver that can be used to listen for policy updates
+    /// Returns a receiver that can be used to listen for policy updates.
+    /// Events are not verified; consumers applying them should subscribe
+    /// through `PolicyVerifier::subscribe` instead.
     pub fn subscribe(&self) -> broadcast::Receiver<PolicyEvent> {
         self.sender.subscribe()
     }
@@ -79,12 +175,8 @@ impl PolicyEventBus {
         }
 
         // Publish event
-        let subscriber_count = self
-            .sender
-            .send(PolicyEvent::Update(update))
-            .map_err(|e| PolicyError::EventBus(format!("Failed to send update: {}", e)))?;
-
-        Ok(subscriber_count)
+        self.forward(&update, PolicyChangeKind::Applied)?;
+        self.send(PolicyEvent::Update(update), "update")
     }
 
     /// Rollback a policy to its last known good configuration
@@ -108,19 +200,46 @@ impl PolicyEventBus {
         };
 
         // Publish rollback event
-        let subscriber_count = self
-            .sender
-            .send(PolicyEvent::Rollback {
+        self.forward(&snapshot, PolicyChangeKind::RolledBack)?;
+        self.send(
+            PolicyEvent::Rollback {
                 policy_id: policy_id.to_string(),
                 snapshot,
-            })
-            .map_err(|e| PolicyError::EventBus(format!("Failed to send rollback: {}", e)))?;
+            },
+            "rollback",
+        )
+    }
 
-        Ok(subscriber_count)
+    /// Roll a policy back to an earlier configuration
+    ///
+    /// `snapshot` replaces the stored snapshot and is sent as the rollback
+    /// target.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of subscribers notified
+    /// * `Err(PolicyError)` - If validation fails or channel is closed
+    pub async fn publish_rollback_to(&self, snapshot: QoSPolicyUpdate) -> Result<usize> {
+        snapshot.validate()?;
+
+        {
+            let mut snapshots = self.snapshots.write().await;
+            snapshots.insert(snapshot.policy_id.clone(), snapshot.clone());
+        }
+
+        self.forward(&snapshot, PolicyChangeKind::RolledBack)?;
+        self.send(
+            PolicyEvent::Rollback {
+                policy_id: snapshot.policy_id.clone(),
+                snapshot,
+            },
+            "rollback",
+        )
     }
 
     /// Invalidate a policy (due to expiration or deprecation)
     ///
+    /// The event is marked as raised locally.
+    ///
     /// # Arguments
     /// * `policy_id` - ID of the policy to invalidate
     ///
@@ -129,20 +248,69 @@ impl PolicyEventBus {
     /// * `Err(PolicyError)` - If channel is closed
     pub async fn publish_invalidate(&self, policy_id: &str) -> Result<usize> {
         // Remove snapshot as policy is no longer valid
-        {
+        let snapshot = {
             let mut snapshots = self.snapshots.write().await;
-            snapshots.remove(policy_id);
+            snapshots.remove(policy_id)
+        };
+
+        // Publish invalidate event (policies never applied here are not forwarded)
+        if let Some(snapshot) = &snapshot {
+            self.forward(snapshot, PolicyChangeKind::Invalidated)?;
         }
+        self.send(
+            PolicyEvent::Invalidate {
+                policy_id: policy_id.to_string(),
+                origin: InvalidationOrigin::Local,
+            },
+            "invalidate",
+        )
+    }
 
-        // Publish invalidate event
-        let subscriber_count = self
-            .sender
-            .send(PolicyEvent::Invalidate {
+    /// Relay an invalidation received from another node
+    ///
+    /// `origin` must be `InvalidationOrigin::Signed`; verifying subscribers
+    /// only act on it if a trusted policy authority produced the signature.
+    /// The bus cannot check the signer, so it keeps its snapshot and does
+    /// not forward the invalidation to the core bus.
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of subscribers notified
+    /// * `Err(PolicyError)` - If the invalidation is unsigned or channel is closed
+    pub async fn publish_remote_invalidate(
+        &self,
+        policy_id: &str,
+        origin: InvalidationOrigin,
+    ) -> Result<usize> {
+        if origin == InvalidationOrigin::Local {
+            return Err(PolicyError::SignatureInvalid(
+                "Remote invalidations must be signed".to_string(),
+            ));
+        }
+        self.send(
+            PolicyEvent::Invalidate {
                 policy_id: policy_id.to_string(),
-            })
-            .map_err(|e| PolicyError::EventBus(format!("Failed to send invalidate: {}", e)))?;
+                origin,
+            },
+            "invalidate",
+        )
+    }
 
-        Ok(subscriber_count)
+    /// Distribute a device certificate revocation list
+    ///
+    /// # Arguments
+    /// * `list` - Revocation list signed by an organization root
+    ///
+    /// # Returns
+    /// * `Ok(usize)` - Number of subscribers notified
+    /// * `Err(PolicyError)` - If the list signature is invalid or channel is closed
+    pub fn publish_revocation(&self, list: RevocationList) -> Result<usize> {
+        // Refuse to forward lists that no subscriber could accept
+        list.verify_signature()
+            .map_err(|e| PolicyError::SignatureInvalid(e.to_string()))?;
+
+        self.sender
+            .send(PolicyEvent::Revocation(list))
+            .map_err(|e| PolicyError::EventBus(format!("Failed to send revocation: {}", e)))
     }
 
     /// Get current snapshot for a policy (for debugging/monitoring)
@@ -151,6 +319,12 @@ impl PolicyEventBus {
         snapshots.get(policy_id).cloned()
     }
 
+    /// Get the snapshots of every applied policy that has not been invalidated
+    pub async fn snapshots(&self) -> Vec<QoSPolicyUpdate> {
+        let snapshots = self.snapshots.read().await;
+        snapshots.values().cloned().collect()
+    }
+
     /// Get count of active subscribers
     pub fn subscriber_count(&self) -> usize {
         self.sender.receiver_count()
@@ -161,6 +335,36 @@ impl PolicyEventBus {
         let mut snapshots = self.snapshots.write().await;
         snapshots.clear();
     }
+
+    /// Send to local subscribers
+    fn send(&self, event: PolicyEvent, kind: &str) -> Result<usize> {
+        match self.sender.send(event) {
+            Ok(subscriber_count) => Ok(subscriber_count),
+            // Consumers may listen on the core bus only
+            Err(_) if self.core_bus.is_some() => Ok(0),
+            Err(e) => Err(PolicyError::EventBus(format!(
+                "Failed to send {}: {}",
+                kind, e
+            ))),
+        }
+    }
+
+    /// Publish a policy change on the core bus, if attached
+    fn forward(&self, policy: &QoSPolicyUpdate, change: PolicyChangeKind) -> Result<()> {
+        let Some(core_bus) = &self.core_bus else {
+            return Ok(());
+        };
+
+        core_bus
+            .publish(Event::PolicyUpdated {
+                policy_id: policy.policy_id.clone(),
+                device_id: policy.device_id.clone(),
+                stream_id: policy.stream_id,
+                change,
+            })
+            .map(|_| ())
+            .map_err(|e| PolicyError::EventBus(format!("Failed to forward to core bus: {}", e)))
+    }
 }
 
 impl Default for PolicyEventBus {
@@ -170,18 +374,21 @@ impl Default for PolicyEventBus {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::types::{FecMode, PowerProfile};
     use chrono::{Duration, Utc};
     use semver::Version;
 
-    fn create_test_policy(policy_id: &str) -> QoSPolicyUpdate {
+    /// Unsigned policy `pol_<policy_id>` valid for an hour, shared by the
+    /// crate's tests
+    pub(crate) fn create_test_policy(policy_id: &str) -> QoSPolicyUpdate {
         QoSPolicyUpdate {
             schema_version: Version::new(1, 2, 0),
             policy_id: format!("pol_{}", policy_id),
             profile_id: "prof_test".to_string(),
             stream_id: 1,
+            device_id: None,
             latency_budget_ms: 50,
             bandwidth_floor_mbps: 10.0,
             bandwidth_ceiling_mbps: Some(100.0),
@@ -287,8 +494,9 @@ mod tests {
 
         // Receive invalidate event
         match receiver.recv().await.unwrap() {
-            PolicyEvent::Invalidate { policy_id } => {
+            PolicyEvent::Invalidate { policy_id, origin } => {
                 assert_eq!(policy_id, policy.policy_id);
+                assert_eq!(origin, InvalidationOrigin::Local);
             }
             _ => panic!("Expected Invalidate event"),
         }
@@ -313,6 +521,61 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_publish_revocation() {
+        use ed25519_dalek::SigningKey;
+        use honeylink_crypto::certificate::{CertificateIssuer, TrustStore};
+
+        let bus = PolicyEventBus::new();
+        let mut receiver = bus.subscribe();
+
+        let root = CertificateIssuer::new(SigningKey::from_bytes(&[7u8; 32]));
+        let trust = TrustStore::new().with_root(root.verifying_key());
+        bus.publish_revocation(root.revoke(None, [42]).unwrap()).unwrap();
+
+        match receiver.recv().await.unwrap() {
+            PolicyEvent::Revocation(list) => {
+                assert!(trust.apply_revocations(&list).unwrap());
+                assert!(list.contains(42));
+            }
+            _ => panic!("Expected Revocation event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forwards_to_core_bus() {
+        use honeylink_core::events::Topic;
+
+        let core_bus = Arc::new(honeylink_core::EventBus::new());
+        let mut policies = core_bus.subscribe([Topic::Policy]);
+        let bus = PolicyEventBus::new().with_core_bus(Arc::clone(&core_bus));
+
+        // No local subscribers needed once the core bus is attached
+        let delivered = bus
+            .publish_update(create_test_policy("core"))
+            .await
+            .unwrap();
+        assert_eq!(delivered, 0);
+        bus.publish_invalidate("pol_core").await.unwrap();
+        bus.publish_invalidate("pol_unknown").await.unwrap();
+
+        let changes: Vec<_> = std::iter::from_fn(|| policies.try_recv().unwrap())
+            .map(|envelope| match envelope.event {
+                Event::PolicyUpdated {
+                    policy_id, change, ..
+                } => (policy_id, change),
+                other => panic!("Unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("pol_core".to_string(), PolicyChangeKind::Applied),
+                ("pol_core".to_string(), PolicyChangeKind::Invalidated),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_clear_snapshots() {
         let bus = PolicyEventBus::new();
@@ -0,0 +1,516 @@
+//! File-backed profile storage
+//!
+//! Keeps every profile, plus its previous versions, and the revision history
+//! of applied policies in a single JSON file on the device. Each mutation is
+//! applied to a copy of the state, written to a temporary file, synced and
+//! renamed over the original, so a crash leaves either the old or the new
+//! state on disk and in memory.
+//!
+//! Since every write rewrites the whole file, the revision history is
+//! bounded: each policy keeps its latest `DEFAULT_REVISION_LIMIT` revisions,
+//! and at most `DEFAULT_POLICY_LIMIT` policies keep a history at all.
+//!
+//! # File Format
+//! ```json
+//! {
+//!   "format": 1,
+//!   "profiles": {
+//!     "prof_gaming_input_v1": { "current": { ... }, "history": [ { ... } ] }
+//!   },
+//!   "policies": {
+//!     "pol_...": [ { "version": 1, "actor": "...", "reason": "...", ... } ]
+//!   }
+//! }
+//! ```
+
+use crate::error::{PolicyError, Result};
+use crate::history::{append_revision, AuditQuery, PolicyChange, PolicyRevision};
+use crate::profile::{PolicyProfile, ProfileStorage};
+use crate::types::UseCase;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Current storage file format version
+pub const PROFILE_STORE_FORMAT: u32 = 1;
+
+/// Default number of previous versions kept per profile
+pub const DEFAULT_HISTORY_LIMIT: usize = 16;
+
+/// Default number of revisions kept per policy
+pub const DEFAULT_REVISION_LIMIT: usize = 32;
+
+/// Default number of policies whose revisions are kept
+pub const DEFAULT_POLICY_LIMIT: usize = 256;
+
+/// A profile and the versions it replaced (oldest first)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfileRecord {
+    current: PolicyProfile,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<PolicyProfile>,
+}
+
+/// On-disk representation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredProfiles {
+    format: u32,
+    profiles: BTreeMap<String, ProfileRecord>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    policies: BTreeMap<String, Vec<PolicyRevision>>,
+}
+
+/// Profile storage persisted to a local JSON file
+///
+/// Replacing a profile with a different `profile_version` moves the old one
+/// into its history, bounded by `with_history_limit`. Policy revisions are
+/// bounded by `with_revision_limit` and `with_policy_limit`; revisions that
+/// were dropped can no longer be rolled back to.
+#[derive(Debug)]
+pub struct FileProfileStorage {
+    path: PathBuf,
+    history_limit: usize,
+    revision_limit: usize,
+    policy_limit: usize,
+    store: StoredProfiles,
+}
+
+impl FileProfileStorage {
+    /// Open the store at `path`, starting empty if the file does not exist
+    ///
+    /// # Errors
+    /// Returns `PolicyError::Storage` if the file cannot be read or uses an
+    /// unsupported format version.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let store = match std::fs::read(&path) {
+            Ok(bytes) => {
+                let stored: StoredProfiles = serde_json::from_slice(&bytes)?;
+                if stored.format != PROFILE_STORE_FORMAT {
+                    return Err(PolicyError::Storage(format!(
+                        "Unsupported profile store format {} in {}",
+                        stored.format,
+                        path.display()
+                    )));
+                }
+                stored
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StoredProfiles {
+                format: PROFILE_STORE_FORMAT,
+                profiles: BTreeMap::new(),
+                policies: BTreeMap::new(),
+            },
+            Err(e) => return Err(storage_error(&path, e)),
+        };
+
+        Ok(Self {
+            path,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            revision_limit: DEFAULT_REVISION_LIMIT,
+            policy_limit: DEFAULT_POLICY_LIMIT,
+            store,
+        })
+    }
+
+    /// Set how many previous versions are kept per profile
+    pub fn with_history_limit(mut self, history_limit: usize) -> Self {
+        self.history_limit = history_limit;
+        self
+    }
+
+    /// Set how many revisions are kept per policy (at least 1)
+    pub fn with_revision_limit(mut self, revision_limit: usize) -> Self {
+        self.revision_limit = revision_limit.max(1);
+        self
+    }
+
+    /// Set how many policies keep a revision history (at least 1)
+    ///
+    /// Past the limit, the histories of the least recently changed policies
+    /// are dropped, invalidated policies first.
+    pub fn with_policy_limit(mut self, policy_limit: usize) -> Self {
+        self.policy_limit = policy_limit.max(1);
+        self
+    }
+
+    /// Path of the backing file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Apply `mutate` to a copy of the store and commit it once persisted
+    fn transact<T>(&mut self, mutate: impl FnOnce(&mut StoredProfiles) -> Result<T>) -> Result<T> {
+        let mut store = self.store.clone();
+        let value = mutate(&mut store)?;
+        self.save(&store)?;
+        self.store = store;
+        Ok(value)
+    }
+
+    /// Atomically replace the backing file
+    fn save(&self, stored: &StoredProfiles) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| storage_error(parent, e))?;
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let file = File::create(&tmp_path).map_err(|e| storage_error(&tmp_path, e))?;
+            serde_json::to_writer_pretty(&file, stored)?;
+            file.sync_all().map_err(|e| storage_error(&tmp_path, e))?;
+        }
+        std::fs::rename(&tmp_path, &self.path).map_err(|e| storage_error(&self.path, e))
+    }
+}
+
+impl ProfileStorage for FileProfileStorage {
+    fn create(&mut self, profile: PolicyProfile) -> Result<()> {
+        profile.validate()?;
+
+        self.transact(|store| {
+            if store.profiles.contains_key(&profile.profile_id) {
+                return Err(PolicyError::Conflict(format!(
+                    "Profile {} already exists",
+                    profile.profile_id
+                )));
+            }
+
+            store.profiles.insert(
+                profile.profile_id.clone(),
+                ProfileRecord {
+                    current: profile,
+                    history: Vec::new(),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn get(&self, profile_id: &str) -> Result<PolicyProfile> {
+        self.store
+            .profiles
+            .get(profile_id)
+            .map(|record| record.current.clone())
+            .ok_or_else(|| not_found(profile_id))
+    }
+
+    fn update(&mut self, profile: PolicyProfile) -> Result<()> {
+        profile.validate()?;
+        let history_limit = self.history_limit;
+
+        self.transact(|store| {
+            let record = store
+                .profiles
+                .get_mut(&profile.profile_id)
+                .ok_or_else(|| not_found(&profile.profile_id))?;
+
+            let previous = std::mem::replace(&mut record.current, profile);
+            if previous.profile_version != record.current.profile_version {
+                record.history.push(previous);
+                let excess = record.history.len().saturating_sub(history_limit);
+                record.history.drain(..excess);
+            }
+            Ok(())
+        })
+    }
+
+    fn delete(&mut self, profile_id: &str) -> Result<()> {
+        self.transact(|store| {
+            let record = store
+                .profiles
+                .get_mut(profile_id)
+                .ok_or_else(|| not_found(profile_id))?;
+            record.current.deprecate_now();
+            Ok(())
+        })
+    }
+
+    fn list(&self, use_case: Option<UseCase>) -> Result<Vec<PolicyProfile>> {
+        Ok(self
+            .store
+            .profiles
+            .values()
+            .map(|record| &record.current)
+            .filter(|p| !p.is_deprecated())
+            .filter(|p| use_case.is_none_or(|uc| p.use_case == uc))
+            .cloned()
+            .collect())
+    }
+
+    fn history(&self, profile_id: &str) -> Result<Vec<PolicyProfile>> {
+        self.store
+            .profiles
+            .get(profile_id)
+            .map(|record| record.history.clone())
+            .ok_or_else(|| not_found(profile_id))
+    }
+
+    fn record_revision(&mut self, revision: PolicyRevision) -> Result<()> {
+        let (revision_limit, policy_limit) = (self.revision_limit, self.policy_limit);
+
+        self.transact(|store| {
+            let policy_id = revision.policy_id().to_string();
+            let history = store.policies.entry(policy_id.clone()).or_default();
+            append_revision(history, revision)?;
+
+            let excess = history.len().saturating_sub(revision_limit);
+            history.drain(..excess);
+            prune_policies(&mut store.policies, &policy_id, policy_limit);
+            Ok(())
+        })
+    }
+
+    fn revisions(&self, policy_id: &str) -> Result<Vec<PolicyRevision>> {
+        Ok(self
+            .store
+            .policies
+            .get(policy_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn audit(&self, query: &AuditQuery) -> Result<Vec<PolicyRevision>> {
+        Ok(query.apply(self.store.policies.values().flatten()))
+    }
+}
+
+/// Drop the histories of the least recently changed policies beyond
+/// `policy_limit`, invalidated ones first, never `keep`
+fn prune_policies(
+    policies: &mut BTreeMap<String, Vec<PolicyRevision>>,
+    keep: &str,
+    policy_limit: usize,
+) {
+    let excess = policies.len().saturating_sub(policy_limit);
+    if excess == 0 {
+        return;
+    }
+
+    let mut candidates: Vec<(bool, DateTime<Utc>, String)> = policies
+        .iter()
+        .filter(|(policy_id, _)| policy_id.as_str() != keep)
+        .filter_map(|(policy_id, history)| {
+            let last = history.last()?;
+            let active = last.change != PolicyChange::Invalidated;
+            Some((active, last.recorded_at, policy_id.clone()))
+        })
+        .collect();
+    candidates.sort();
+
+    for (_, _, policy_id) in candidates.into_iter().take(excess) {
+        policies.remove(&policy_id);
+    }
+}
+
+fn not_found(profile_id: &str) -> PolicyError {
+    PolicyError::NotFound(format!("Profile {} not found", profile_id))
+}
+
+fn storage_error(path: &Path, error: std::io::Error) -> PolicyError {
+    PolicyError::Storage(format!("{}: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::{create_gaming_input_preset, create_iot_lowpower_preset};
+    use crate::profile::ProfileUpgrade;
+    use semver::Version;
+
+    #[test]
+    fn test_persists_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+
+        let mut storage = FileProfileStorage::open(&path).unwrap();
+        storage.create(create_gaming_input_preset()).unwrap();
+        storage.create(create_iot_lowpower_preset()).unwrap();
+        storage.delete("prof_iot_lowpower_v2").unwrap();
+
+        let reopened = FileProfileStorage::open(&path).unwrap();
+        let listed = reopened.list(None).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].profile_id, "prof_gaming_input_v1");
+        assert!(reopened
+            .get("prof_iot_lowpower_v2")
+            .unwrap()
+            .is_deprecated());
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_version_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut storage = FileProfileStorage::open(&path)
+            .unwrap()
+            .with_history_limit(2);
+
+        let mut profile = create_gaming_input_preset();
+        storage.create(profile.clone()).unwrap();
+
+        // Same-version edits replace in place
+        profile.latency_budget_ms = 8;
+        storage.update(profile.clone()).unwrap();
+        assert!(storage.history(&profile.profile_id).unwrap().is_empty());
+
+        for minor in 1..=3 {
+            profile.profile_version = Version::new(1, minor, 0);
+            assert!(matches!(
+                storage.upgrade(profile.clone()).unwrap(),
+                ProfileUpgrade::Upgraded { .. }
+            ));
+        }
+
+        let history = FileProfileStorage::open(&path)
+            .unwrap()
+            .history(&profile.profile_id)
+            .unwrap();
+        let versions: Vec<_> = history.iter().map(|p| p.profile_version.minor).collect();
+        assert_eq!(versions, vec![1, 2]);
+        assert_eq!(
+            storage.get(&profile.profile_id).unwrap().profile_version,
+            Version::new(1, 3, 0)
+        );
+    }
+
+    #[test]
+    fn test_failed_mutation_leaves_state_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut storage = FileProfileStorage::open(&path).unwrap();
+
+        storage.create(create_gaming_input_preset()).unwrap();
+        assert!(storage.create(create_gaming_input_preset()).is_err());
+        assert!(storage.update(create_iot_lowpower_preset()).is_err());
+
+        assert_eq!(storage.list(None).unwrap().len(), 1);
+        assert_eq!(
+            FileProfileStorage::open(&path)
+                .unwrap()
+                .list(None)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_policy_revisions_persist() {
+        use crate::history::{PolicyChange, PolicyRevision};
+        use crate::types::QoSPolicyUpdate;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut storage = FileProfileStorage::open(&path).unwrap();
+
+        let policy: QoSPolicyUpdate = serde_json::from_value(serde_json::json!({
+            "schema_version": "1.0.0",
+            "policy_id": "pol_persisted",
+            "profile_id": "prof_gaming_input_v1",
+            "stream_id": 1,
+            "latency_budget_ms": 8,
+            "bandwidth_floor_mbps": 1.0,
+            "fec_mode": "NONE",
+            "priority": 7,
+            "expiration_ts": "2099-01-01T00:00:00Z",
+            "signature": ""
+        }))
+        .unwrap();
+        for (version, actor) in [(1, "local"), (2, "DEV-PEER")] {
+            storage
+                .record_revision(PolicyRevision::new(
+                    version,
+                    actor,
+                    "test",
+                    PolicyChange::Applied,
+                    policy.clone(),
+                ))
+                .unwrap();
+        }
+        assert!(storage
+            .record_revision(PolicyRevision::new(
+                2,
+                "local",
+                "replay",
+                PolicyChange::Applied,
+                policy,
+            ))
+            .is_err());
+
+        let reopened = FileProfileStorage::open(&path).unwrap();
+        assert_eq!(reopened.revisions("pol_persisted").unwrap().len(), 2);
+        let audit = reopened
+            .audit(&AuditQuery::new().with_actor("DEV-PEER"))
+            .unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].version, 2);
+        assert!(reopened.diff("pol_persisted", 1, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_revision_retention() {
+        use crate::event_bus::tests::create_test_policy;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        let mut storage = FileProfileStorage::open(&path)
+            .unwrap()
+            .with_revision_limit(2)
+            .with_policy_limit(2);
+
+        let revision = |version, policy_id: &str, change| {
+            PolicyRevision::new(
+                version,
+                "local",
+                "test",
+                change,
+                create_test_policy(policy_id),
+            )
+        };
+        for version in 1..=3 {
+            storage
+                .record_revision(revision(version, "kept", PolicyChange::Applied))
+                .unwrap();
+        }
+        let versions: Vec<_> = storage
+            .revisions("pol_kept")
+            .unwrap()
+            .iter()
+            .map(|r| r.version)
+            .collect();
+        assert_eq!(versions, vec![2, 3]);
+        // Versions stay consecutive after older ones were dropped
+        storage
+            .record_revision(revision(4, "kept", PolicyChange::Applied))
+            .unwrap();
+
+        // Invalidated policies are evicted before active ones
+        storage
+            .record_revision(revision(1, "done", PolicyChange::Invalidated))
+            .unwrap();
+        storage
+            .record_revision(revision(1, "new", PolicyChange::Applied))
+            .unwrap();
+
+        let reopened = FileProfileStorage::open(&path).unwrap();
+        assert!(reopened.revisions("pol_done").unwrap().is_empty());
+        assert_eq!(reopened.revisions("pol_kept").unwrap().len(), 2);
+        assert_eq!(reopened.revisions("pol_new").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_unknown_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        std::fs::write(&path, r#"{"format": 99, "profiles": {}}"#).unwrap();
+
+        assert!(matches!(
+            FileProfileStorage::open(&path),
+            Err(PolicyError::Storage(_))
+        ));
+    }
+}
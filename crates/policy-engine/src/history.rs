@@ -0,0 +1,347 @@
+//! Versioned policy history and audit trail
+//!
+//! Every change to an applied policy is recorded as a `PolicyRevision`:
+//! version 1 is the first apply, and each apply, rollback, renewal or
+//! invalidation appends the next version with its timestamp, actor and
+//! reason. Revisions are recorded before the change is published and are
+//! persisted through `ProfileStorage`, so `FileProfileStorage` keeps them
+//! (within its retention limits) across restarts.
+
+use crate::error::{PolicyError, Result};
+use crate::types::QoSPolicyUpdate;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What a revision did to the policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PolicyChange {
+    /// A new configuration was applied
+    Applied,
+
+    /// The configuration of an earlier version was restored
+    RolledBack { to_version: u32 },
+
+    /// The lifecycle manager extended the expiration of an active policy
+    Renewed,
+
+    /// The policy was expired or deprecated
+    Invalidated,
+}
+
+/// One recorded version of a policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRevision {
+    /// Version within the policy, starting at 1
+    pub version: u32,
+
+    /// When the change was made
+    pub recorded_at: DateTime<Utc>,
+
+    /// Operator, service or device that made the change
+    pub actor: String,
+
+    /// Why the change was made
+    pub reason: String,
+
+    /// Kind of change
+    pub change: PolicyChange,
+
+    /// Configuration in effect after the change
+    pub policy: QoSPolicyUpdate,
+}
+
+impl PolicyRevision {
+    /// Create a revision recorded now
+    pub fn new(
+        version: u32,
+        actor: impl Into<String>,
+        reason: impl Into<String>,
+        change: PolicyChange,
+        policy: QoSPolicyUpdate,
+    ) -> Self {
+        Self {
+            version,
+            recorded_at: Utc::now(),
+            actor: actor.into(),
+            reason: reason.into(),
+            change,
+            policy,
+        }
+    }
+
+    /// Policy this revision belongs to
+    pub fn policy_id(&self) -> &str {
+        &self.policy.policy_id
+    }
+}
+
+/// A field that differs between two revisions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Field name as serialized in `QoSPolicyUpdate`
+    pub field: String,
+
+    /// Value in the older revision (`null` if absent)
+    pub from: Value,
+
+    /// Value in the newer revision (`null` if absent)
+    pub to: Value,
+}
+
+/// Differences between two versions of a policy
+///
+/// The signature is excluded; it changes with every re-signing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyDiff {
+    /// Policy both revisions belong to
+    pub policy_id: String,
+
+    /// Version of the older revision
+    pub from_version: u32,
+
+    /// Version of the newer revision
+    pub to_version: u32,
+
+    /// Changed fields, sorted by name
+    pub changes: Vec<FieldChange>,
+}
+
+impl PolicyDiff {
+    /// Compare the configurations of two revisions of the same policy
+    pub fn between(from: &PolicyRevision, to: &PolicyRevision) -> Result<Self> {
+        let from_fields = fields(&from.policy)?;
+        let mut to_fields = fields(&to.policy)?;
+
+        let mut changes = Vec::new();
+        for (field, old) in from_fields {
+            let new = to_fields.remove(&field).unwrap_or(Value::Null);
+            if old != new {
+                changes.push(FieldChange {
+                    field,
+                    from: old,
+                    to: new,
+                });
+            }
+        }
+        changes.extend(to_fields.into_iter().map(|(field, new)| FieldChange {
+            field,
+            from: Value::Null,
+            to: new,
+        }));
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+
+        Ok(Self {
+            policy_id: to.policy_id().to_string(),
+            from_version: from.version,
+            to_version: to.version,
+            changes,
+        })
+    }
+
+    /// Whether the two versions have the same configuration
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+fn fields(policy: &QoSPolicyUpdate) -> Result<serde_json::Map<String, Value>> {
+    let mut fields = match serde_json::to_value(policy)? {
+        Value::Object(fields) => fields,
+        _ => serde_json::Map::new(),
+    };
+    fields.remove("signature");
+    Ok(fields)
+}
+
+/// Filter for listing revisions across all policies
+///
+/// Every criterion is optional; results are newest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditQuery {
+    /// Only revisions of this policy
+    pub policy_id: Option<String>,
+
+    /// Only changes made by this actor
+    pub actor: Option<String>,
+
+    /// Only policies applied to this peer device
+    pub device_id: Option<String>,
+
+    /// Only changes recorded at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only changes recorded before this time
+    pub until: Option<DateTime<Utc>>,
+
+    /// Maximum number of revisions returned
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    /// Match every revision
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only revisions of `policy_id`
+    pub fn with_policy_id(mut self, policy_id: impl Into<String>) -> Self {
+        self.policy_id = Some(policy_id.into());
+        self
+    }
+
+    /// Only changes made by `actor`
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Only policies applied to `device_id`
+    pub fn with_device_id(mut self, device_id: impl Into<String>) -> Self {
+        self.device_id = Some(device_id.into());
+        self
+    }
+
+    /// Only changes recorded in `[since, until)`
+    pub fn between(mut self, since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self.until = Some(until);
+        self
+    }
+
+    /// Return at most `limit` revisions
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether `revision` satisfies every criterion
+    pub fn matches(&self, revision: &PolicyRevision) -> bool {
+        self.policy_id
+            .as_ref()
+            .is_none_or(|id| revision.policy_id() == id)
+            && self.actor.as_ref().is_none_or(|a| &revision.actor == a)
+            && self
+                .device_id
+                .as_ref()
+                .is_none_or(|d| revision.policy.device_id.as_ref() == Some(d))
+            && self.since.is_none_or(|t| revision.recorded_at >= t)
+            && self.until.is_none_or(|t| revision.recorded_at < t)
+    }
+
+    /// Select, order and truncate `revisions`
+    pub fn apply<'a>(
+        &self,
+        revisions: impl IntoIterator<Item = &'a PolicyRevision>,
+    ) -> Vec<PolicyRevision> {
+        let mut selected: Vec<PolicyRevision> = revisions
+            .into_iter()
+            .filter(|r| self.matches(r))
+            .cloned()
+            .collect();
+        selected.sort_by(|a, b| {
+            b.recorded_at
+                .cmp(&a.recorded_at)
+                .then_with(|| b.version.cmp(&a.version))
+        });
+        if let Some(limit) = self.limit {
+            selected.truncate(limit);
+        }
+        selected
+    }
+}
+
+/// Append `revision` to a policy's history, enforcing consecutive versions
+pub(crate) fn append_revision(
+    history: &mut Vec<PolicyRevision>,
+    revision: PolicyRevision,
+) -> Result<()> {
+    let expected = history.last().map_or(1, |last| last.version + 1);
+    if revision.version != expected {
+        return Err(PolicyError::Conflict(format!(
+            "Policy {} revision {} recorded out of order (expected {})",
+            revision.policy_id(),
+            revision.version,
+            expected
+        )));
+    }
+    history.push(revision);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::tests::create_test_policy;
+    use chrono::Duration;
+
+    fn policy() -> QoSPolicyUpdate {
+        let mut policy = create_test_policy("history");
+        policy.device_id = Some("DEV-B".to_string());
+        policy.signature = "sig_a".to_string();
+        policy
+    }
+
+    #[test]
+    fn test_diff_ignores_signature() {
+        let v1 = PolicyRevision::new(1, "ops", "initial", PolicyChange::Applied, policy());
+        let mut changed = v1.policy.clone();
+        changed.latency_budget_ms = 20;
+        changed.power_profile = None;
+        changed.signature = "sig_b".to_string();
+        let v2 = PolicyRevision::new(2, "ops", "tighten", PolicyChange::Applied, changed);
+
+        let diff = PolicyDiff::between(&v1, &v2).unwrap();
+        let fields: Vec<_> = diff.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["latency_budget_ms", "power_profile"]);
+        assert_eq!(diff.changes[0].from, Value::from(50));
+        assert_eq!(diff.changes[0].to, Value::from(20));
+        assert!(PolicyDiff::between(&v1, &v1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_append_requires_consecutive_versions() {
+        let mut history = Vec::new();
+        append_revision(
+            &mut history,
+            PolicyRevision::new(1, "ops", "initial", PolicyChange::Applied, policy()),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            append_revision(
+                &mut history,
+                PolicyRevision::new(3, "ops", "skip", PolicyChange::Applied, policy()),
+            ),
+            Err(PolicyError::Conflict(_))
+        ));
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn test_audit_query() {
+        let mut revisions = Vec::new();
+        for (version, actor) in [(1, "ops"), (2, "DEV-A"), (3, "ops")] {
+            let mut revision =
+                PolicyRevision::new(version, actor, "change", PolicyChange::Applied, policy());
+            revision.recorded_at = Utc::now() + Duration::minutes(version as i64);
+            revisions.push(revision);
+        }
+
+        let by_ops = AuditQuery::new().with_actor("ops").apply(&revisions);
+        let versions: Vec<_> = by_ops.iter().map(|r| r.version).collect();
+        assert_eq!(versions, vec![3, 1]);
+
+        let latest = AuditQuery::new()
+            .with_device_id("DEV-B")
+            .with_limit(1)
+            .apply(&revisions);
+        assert_eq!(latest[0].version, 3);
+
+        assert!(AuditQuery::new()
+            .with_policy_id("pol_other")
+            .apply(&revisions)
+            .is_empty());
+    }
+}
@@ -3,8 +3,15 @@
 //! QoS policy and profile management with:
 //! - QoSPolicyUpdate schema with SemVer versioning
 //! - Profile CRUD with Ed25519 signature verification
+//! - File-backed profile storage with version history
+//! - Signed profile bundles for import/export (JSON/TOML)
 //! - Preset profiles for IoT/AR-VR/8K/Gaming use cases
 //! - Event bus for policy distribution with fallback
+//! - Policy expiry, renewal and deprecation warnings for applied policies
+//! - Versioned policy history with multi-step rollback, diffs and audit queries
+//! - Ed25519-signed policy updates verified against trusted authorities
+//! - Peer-to-peer policy negotiation during session setup
+//! - Context-aware rules (battery, link quality, peer type, time) with hysteresis
 //!
 //! **Module Specification**: MOD-002-POLICY-ENGINE
 //! **Requirements**: FR-04 (QoS adjustment), FR-06 (Profile templates)
@@ -16,19 +23,40 @@
 //! - Uses `tokio` (pure Rust)
 //! - Uses `serde` (pure Rust)
 
+pub mod bundle;
+pub mod canonical;
 pub mod error;
 pub mod event_bus;
+pub mod file_storage;
+pub mod history;
+pub mod lifecycle;
+pub mod negotiation;
 pub mod policy;
 pub mod presets;
 pub mod profile;
+pub mod rules;
 pub mod telemetry;
 pub mod types;
+pub mod verifier;
 
 // Re-export commonly used types
+pub use bundle::ProfileBundle;
 pub use error::{PolicyError, Result};
-pub use event_bus::{PolicyEvent, PolicyEventBus};
+pub use event_bus::{InvalidationOrigin, PolicyEvent, PolicyEventBus};
+pub use file_storage::FileProfileStorage;
+pub use history::{AuditQuery, PolicyChange, PolicyDiff, PolicyRevision};
+pub use lifecycle::{
+    HistoryFuture, LifecycleConfig, LifecycleHistory, PolicyLifecycleManager, SessionActivity,
+    SweepReport,
+};
+pub use negotiation::{
+    NegotiationLimits, NegotiationMessage, NegotiationOutcome, NegotiationStep, PolicyNegotiator,
+    PolicyProposal,
+};
 pub use policy::PolicyEngine;
 pub use presets::create_presets;
-pub use profile::{InMemoryProfileStorage, PolicyProfile, ProfileStorage};
+pub use profile::{InMemoryProfileStorage, PolicyProfile, ProfileStorage, ProfileUpgrade};
+pub use rules::{PolicyContext, PolicyRule, RulesEngine};
 pub use telemetry::PolicyTelemetry;
 pub use types::{FecMode, PowerProfile, Priority, QoSPolicyUpdate, UseCase};
+pub use verifier::{PolicyVerifier, VerifiedSubscription};
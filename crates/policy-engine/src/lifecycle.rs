@@ -0,0 +1,409 @@
+//! Policy lifecycle enforcement
+//!
+//! `expiration_ts` and `deprecated_after` are only checked when a policy is
+//! published. The `PolicyLifecycleManager` periodically sweeps every applied
+//! policy (the event bus snapshots) and:
+//! - re-signs and republishes policies close to expiry while their session is active
+//! - publishes `PolicyEvent::Invalidate` for expired or deprecated policies,
+//!   which also drops their snapshot
+//! - records a telemetry warning once a policy enters the deprecation window
+//!
+//! With a `LifecycleHistory` attached (as `PolicyEngine::lifecycle_manager`
+//! does), renewals and invalidations are recorded in the policy history
+//! before they are published.
+
+use crate::error::{PolicyError, Result};
+use crate::event_bus::PolicyEventBus;
+use crate::history::PolicyChange;
+use crate::telemetry::PolicyTelemetry;
+use crate::types::QoSPolicyUpdate;
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::SigningKey;
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Tells the lifecycle manager whether a policy's session is still running
+///
+/// Implemented for any `Fn(&QoSPolicyUpdate) -> bool`.
+pub trait SessionActivity: Send + Sync {
+    /// Whether the session the policy was applied to is still active
+    fn is_session_active(&self, policy: &QoSPolicyUpdate) -> bool;
+}
+
+impl<F> SessionActivity for F
+where
+    F: Fn(&QoSPolicyUpdate) -> bool + Send + Sync,
+{
+    fn is_session_active(&self, policy: &QoSPolicyUpdate) -> bool {
+        self(policy)
+    }
+}
+
+/// Future returned by `LifecycleHistory::record`
+pub type HistoryFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// Records the lifecycle manager's changes in the policy history
+pub trait LifecycleHistory: Send + Sync {
+    /// Record `change` of `policy`, the configuration in effect afterwards
+    fn record<'a>(
+        &'a self,
+        change: PolicyChange,
+        reason: &'a str,
+        policy: &'a QoSPolicyUpdate,
+    ) -> HistoryFuture<'a>;
+}
+
+/// Lifecycle manager timing
+#[derive(Debug, Clone)]
+pub struct LifecycleConfig {
+    /// Interval between background sweeps
+    pub check_interval: std::time::Duration,
+
+    /// Renew active policies expiring within this window
+    pub renew_before: Duration,
+
+    /// TTL given to renewed policies (capped at `deprecated_after`)
+    pub renewal_ttl: Duration,
+
+    /// Warn this long before a policy's `deprecated_after`
+    pub deprecation_warning: Duration,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            check_interval: std::time::Duration::from_secs(30),
+            renew_before: Duration::minutes(10),
+            renewal_ttl: Duration::hours(12),
+            deprecation_warning: Duration::hours(24),
+        }
+    }
+}
+
+/// Policies affected by one sweep
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SweepReport {
+    /// Policies republished with a new expiration
+    pub renewed: Vec<String>,
+
+    /// Policies invalidated because they expired or were deprecated
+    pub invalidated: Vec<String>,
+
+    /// Policies that entered the deprecation warning window
+    pub deprecation_warnings: Vec<String>,
+}
+
+/// Enforces expiry, renewal and deprecation of applied policies
+pub struct PolicyLifecycleManager {
+    event_bus: Arc<PolicyEventBus>,
+    authority_key: SigningKey,
+    activity: Box<dyn SessionActivity>,
+    config: LifecycleConfig,
+    telemetry: Option<PolicyTelemetry>,
+    history: Option<Box<dyn LifecycleHistory>>,
+
+    /// Policies already warned about, so each warning is recorded once
+    warned: Mutex<HashSet<String>>,
+}
+
+impl PolicyLifecycleManager {
+    /// Create a manager for the policies applied on `event_bus`
+    ///
+    /// Renewed policies are signed with `authority_key`.
+    pub fn new(
+        event_bus: Arc<PolicyEventBus>,
+        authority_key: SigningKey,
+        activity: impl SessionActivity + 'static,
+    ) -> Self {
+        Self {
+            event_bus,
+            authority_key,
+            activity: Box::new(activity),
+            config: LifecycleConfig::default(),
+            telemetry: None,
+            history: None,
+            warned: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Override the default timing
+    pub fn with_config(mut self, config: LifecycleConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Record lifecycle events and deprecation warnings
+    pub fn with_telemetry(mut self, telemetry: PolicyTelemetry) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Record renewals and invalidations in the policy history
+    pub fn with_history(mut self, history: impl LifecycleHistory + 'static) -> Self {
+        self.history = Some(Box::new(history));
+        self
+    }
+
+    /// Lifecycle timing in use
+    pub fn config(&self) -> &LifecycleConfig {
+        &self.config
+    }
+
+    /// Sweep every applied policy as of `now`
+    pub async fn sweep(&self, now: DateTime<Utc>) -> SweepReport {
+        let mut report = SweepReport::default();
+
+        let mut policies = self.event_bus.snapshots().await;
+        policies.sort_by(|a, b| a.policy_id.cmp(&b.policy_id));
+        let mut active = 0u64;
+
+        for policy in policies {
+            if policy.deprecated_after.is_some_and(|d| d <= now) {
+                self.invalidate(&policy, "deprecated").await;
+                report.invalidated.push(policy.policy_id);
+                continue;
+            }
+
+            if policy.expiration_ts <= now + self.config.renew_before
+                && self.activity.is_session_active(&policy)
+                && self.renew(&policy, now).await
+            {
+                report.renewed.push(policy.policy_id.clone());
+            } else if policy.expiration_ts <= now {
+                self.invalidate(&policy, "expired").await;
+                report.invalidated.push(policy.policy_id);
+                continue;
+            }
+
+            active += 1;
+            if let Some(deprecated_after) = policy.deprecated_after {
+                if deprecated_after - now <= self.config.deprecation_warning
+                    && self.warned.lock().await.insert(policy.policy_id.clone())
+                {
+                    if let Some(telemetry) = &self.telemetry {
+                        // Telemetry is best effort
+                        let _ = telemetry
+                            .record_policy_deprecation_warning(
+                                &policy.policy_id,
+                                &policy.profile_id,
+                                (deprecated_after - now).num_seconds(),
+                            )
+                            .await;
+                    }
+                    report.deprecation_warnings.push(policy.policy_id);
+                }
+            }
+        }
+
+        if let Some(telemetry) = &self.telemetry {
+            let _ = telemetry.record_active_policies(active).await;
+        }
+
+        report
+    }
+
+    /// Run `sweep` every `check_interval` until the task is aborted
+    pub fn start(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.config.check_interval);
+
+            loop {
+                ticker.tick().await;
+                self.sweep(Utc::now()).await;
+            }
+        })
+    }
+
+    /// Republish `policy` with a fresh expiration
+    ///
+    /// Returns false if the expiration cannot be extended (it is already
+    /// capped at `deprecated_after`), the renewal could not be recorded or
+    /// the renewed policy is invalid.
+    async fn renew(&self, policy: &QoSPolicyUpdate, now: DateTime<Utc>) -> bool {
+        let mut expiration_ts = now + self.config.renewal_ttl;
+        if let Some(deprecated_after) = policy.deprecated_after {
+            expiration_ts = expiration_ts.min(deprecated_after);
+        }
+        if expiration_ts <= policy.expiration_ts {
+            return false;
+        }
+
+        let mut renewed = policy.clone();
+        renewed.expiration_ts = expiration_ts;
+        renewed.sign(&self.authority_key);
+        if renewed.validate().is_err() {
+            return false;
+        }
+
+        if let Some(history) = &self.history {
+            if history
+                .record(PolicyChange::Renewed, "renewed", &renewed)
+                .await
+                .is_err()
+            {
+                return false;
+            }
+        }
+
+        match self.event_bus.publish_update(renewed).await {
+            // Without subscribers the snapshot is still refreshed
+            Ok(_) | Err(PolicyError::EventBus(_)) => {}
+            Err(_) => return false,
+        }
+
+        if let Some(telemetry) = &self.telemetry {
+            let _ = telemetry
+                .record_policy_lifecycle(&policy.policy_id, "renewed")
+                .await;
+        }
+        true
+    }
+
+    /// Publish `Invalidate` and forget the policy
+    async fn invalidate(&self, policy: &QoSPolicyUpdate, reason: &str) {
+        let policy_id = policy.policy_id.as_str();
+        if let Some(history) = &self.history {
+            // A policy past its lifetime is invalidated even if recording fails
+            let _ = history
+                .record(PolicyChange::Invalidated, reason, policy)
+                .await;
+        }
+
+        // Fails only without subscribers; the snapshot is removed regardless
+        let _ = self.event_bus.publish_invalidate(policy_id).await;
+        self.warned.lock().await.remove(policy_id);
+
+        if let Some(telemetry) = &self.telemetry {
+            let _ = telemetry.record_policy_lifecycle(policy_id, reason).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::tests::create_test_policy;
+    use crate::event_bus::PolicyEvent;
+
+    fn authority() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn policy(policy_id: &str, ttl: Duration) -> QoSPolicyUpdate {
+        let mut policy = create_test_policy(policy_id);
+        policy.expiration_ts = Utc::now() + ttl;
+        policy.sign(&authority());
+        policy
+    }
+
+    fn manager(bus: &Arc<PolicyEventBus>, active: bool) -> PolicyLifecycleManager {
+        PolicyLifecycleManager::new(bus.clone(), authority(), move |_: &QoSPolicyUpdate| active)
+    }
+
+    #[tokio::test]
+    async fn test_expired_policy_invalidated_and_pruned() {
+        let bus = Arc::new(PolicyEventBus::new());
+        let mut events = bus.subscribe();
+        bus.publish_update(policy("short", Duration::minutes(30)))
+            .await
+            .unwrap();
+        bus.publish_update(policy("long", Duration::hours(12)))
+            .await
+            .unwrap();
+        let _ = events.recv().await;
+        let _ = events.recv().await;
+
+        let report = manager(&bus, false)
+            .sweep(Utc::now() + Duration::hours(1))
+            .await;
+
+        assert_eq!(report.invalidated, vec!["pol_short".to_string()]);
+        assert!(report.renewed.is_empty());
+        assert!(bus.get_snapshot("pol_short").await.is_none());
+        assert!(bus.get_snapshot("pol_long").await.is_some());
+        match events.recv().await.unwrap() {
+            PolicyEvent::Invalidate { policy_id, .. } => assert_eq!(policy_id, "pol_short"),
+            _ => panic!("Expected Invalidate event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_active_policy_renewed_before_expiry() {
+        let bus = Arc::new(PolicyEventBus::new());
+        let _events = bus.subscribe();
+        let original = policy("active", Duration::minutes(5));
+        bus.publish_update(original.clone()).await.unwrap();
+
+        let now = Utc::now();
+        let report = manager(&bus, true).sweep(now).await;
+        assert_eq!(report.renewed, vec!["pol_active".to_string()]);
+
+        let renewed = bus.get_snapshot("pol_active").await.unwrap();
+        assert!(renewed.expiration_ts >= now + Duration::hours(12));
+        assert!(renewed
+            .verify_signature(&authority().verifying_key())
+            .is_ok());
+        assert_eq!(renewed.latency_budget_ms, original.latency_budget_ms);
+    }
+
+    #[tokio::test]
+    async fn test_renewal_capped_at_deprecation() {
+        let bus = Arc::new(PolicyEventBus::new());
+        let _events = bus.subscribe();
+        let mut deprecating = policy("deprecating", Duration::minutes(5));
+        deprecating.deprecated_after = Some(Utc::now() + Duration::hours(1));
+        deprecating.sign(&authority());
+        bus.publish_update(deprecating.clone()).await.unwrap();
+
+        let manager = manager(&bus, true);
+        let report = manager.sweep(Utc::now()).await;
+        assert_eq!(report.renewed, vec!["pol_deprecating".to_string()]);
+        assert_eq!(
+            report.deprecation_warnings,
+            vec!["pol_deprecating".to_string()]
+        );
+        assert_eq!(
+            bus.get_snapshot("pol_deprecating")
+                .await
+                .unwrap()
+                .expiration_ts,
+            deprecating.deprecated_after.unwrap()
+        );
+
+        // Already renewed up to deprecation and warned
+        let report = manager.sweep(Utc::now()).await;
+        assert_eq!(report, SweepReport::default());
+
+        let report = manager.sweep(Utc::now() + Duration::hours(2)).await;
+        assert_eq!(report.invalidated, vec!["pol_deprecating".to_string()]);
+        assert!(bus.snapshots().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_background_task_renews() {
+        let bus = Arc::new(PolicyEventBus::new());
+        let mut events = bus.subscribe();
+        bus.publish_update(policy("background", Duration::minutes(5)))
+            .await
+            .unwrap();
+        let _ = events.recv().await;
+
+        let manager = Arc::new(manager(&bus, true).with_config(LifecycleConfig {
+            check_interval: std::time::Duration::from_millis(10),
+            ..LifecycleConfig::default()
+        }));
+        let handle = manager.start();
+
+        match events.recv().await.unwrap() {
+            PolicyEvent::Update(update) => {
+                assert_eq!(update.policy_id, "pol_background");
+                assert!(update.expiration_ts > Utc::now() + Duration::hours(1));
+            }
+            _ => panic!("Expected Update event"),
+        }
+        handle.abort();
+    }
+}
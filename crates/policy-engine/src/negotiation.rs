@@ -0,0 +1,594 @@
+//! Peer-to-peer policy negotiation
+//!
+//! Runs after pairing so both devices agree on one QoS policy:
+//! 1. The initiator proposes a profile, already clamped to its own limits
+//! 2. The responder accepts it if it fits its limits, otherwise counter-offers
+//!    the proposal clamped to its limits (or rejects if it cannot be met)
+//! 3. Counter-offers are answered the same way until one side accepts, a side
+//!    rejects, or the round limit is reached
+//!
+//! Each side counts rounds itself rather than trusting `PolicyProposal::round`,
+//! only takes an `Accept` of the exact proposal it sent last, and requires the
+//! initiator's policy ID to be `pol_` followed by a UUID.
+//!
+//! The negotiator is sans-IO: it consumes and produces `NegotiationMessage`s
+//! and leaves transport and policy application to the caller. It holds the
+//! state of one negotiation, so use a fresh negotiator (or a clone of an
+//! unused one) per session.
+
+use crate::error::{PolicyError, Result};
+use crate::profile::PolicyProfile;
+use crate::types::{FecMode, PowerProfile, Priority};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Default maximum number of proposal rounds before giving up
+pub const DEFAULT_MAX_ROUNDS: u8 = 4;
+
+/// QoS limits a device can honor
+///
+/// Derived from the device's radio/link capabilities and its current power
+/// constraints (e.g. battery saver caps `max_power_profile`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NegotiationLimits {
+    /// Highest bandwidth the device can sustain in Mbps
+    pub max_bandwidth_mbps: f64,
+
+    /// Tightest latency budget the device can meet in milliseconds
+    pub min_latency_budget_ms: u16,
+
+    /// FEC modes the device implements
+    pub supported_fec_modes: Vec<FecMode>,
+
+    /// Highest power profile the device is willing to run
+    pub max_power_profile: PowerProfile,
+
+    /// Highest stream priority the device grants to a peer
+    pub max_priority: Priority,
+}
+
+impl Default for NegotiationLimits {
+    fn default() -> Self {
+        Self {
+            max_bandwidth_mbps: f64::MAX,
+            min_latency_budget_ms: 1,
+            supported_fec_modes: vec![FecMode::None, FecMode::Light, FecMode::Heavy],
+            max_power_profile: PowerProfile::High,
+            max_priority: 7,
+        }
+    }
+}
+
+/// Policy parameters under negotiation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyProposal {
+    /// Policy ID both ends use for the agreed policy (prefix: pol_)
+    pub policy_id: String,
+
+    /// Proposal round (1 = initial proposal)
+    pub round: u8,
+
+    /// Profile the proposal originates from
+    pub profile_id: String,
+
+    /// Schema version of the originating profile
+    pub schema_version: Version,
+
+    /// Latency budget in milliseconds
+    pub latency_budget_ms: u16,
+
+    /// Minimum bandwidth in Mbps (never lowered by a counter-offer)
+    pub bandwidth_floor_mbps: f64,
+
+    /// Maximum bandwidth in Mbps
+    pub bandwidth_ceiling_mbps: f64,
+
+    /// FEC mode
+    pub fec_mode: FecMode,
+
+    /// Stream priority (0-7)
+    pub priority: Priority,
+
+    /// Power profile
+    pub power_profile: PowerProfile,
+}
+
+impl PolicyProposal {
+    /// Create an initial proposal from a profile
+    pub fn from_profile(profile: &PolicyProfile) -> Self {
+        Self {
+            policy_id: format!("pol_{}", Uuid::now_v7()),
+            round: 1,
+            profile_id: profile.profile_id.clone(),
+            schema_version: profile.profile_version.clone(),
+            latency_budget_ms: profile.latency_budget_ms,
+            bandwidth_floor_mbps: profile.bandwidth_floor_mbps,
+            bandwidth_ceiling_mbps: profile.bandwidth_ceiling_mbps,
+            fec_mode: profile.fec_mode,
+            priority: profile.priority,
+            power_profile: profile.power_profile,
+        }
+    }
+
+    /// Check whether the proposal can be honored within `limits`
+    pub fn fits(&self, limits: &NegotiationLimits) -> bool {
+        self.bandwidth_ceiling_mbps <= limits.max_bandwidth_mbps
+            && self.latency_budget_ms >= limits.min_latency_budget_ms
+            && limits.supported_fec_modes.contains(&self.fec_mode)
+            && power_rank(self.power_profile) <= power_rank(limits.max_power_profile)
+            && self.priority <= limits.max_priority
+    }
+
+    /// Clamp the proposal to `limits`
+    ///
+    /// Returns `None` if the bandwidth floor exceeds what the device can
+    /// sustain or no FEC mode is supported at all.
+    pub fn constrain(&self, limits: &NegotiationLimits) -> Option<Self> {
+        if self.bandwidth_floor_mbps > limits.max_bandwidth_mbps {
+            return None;
+        }
+
+        let power_profile = if power_rank(self.power_profile) > power_rank(limits.max_power_profile)
+        {
+            limits.max_power_profile
+        } else {
+            self.power_profile
+        };
+
+        Some(Self {
+            latency_budget_ms: self.latency_budget_ms.max(limits.min_latency_budget_ms),
+            bandwidth_ceiling_mbps: self.bandwidth_ceiling_mbps.min(limits.max_bandwidth_mbps),
+            fec_mode: closest_fec_mode(self.fec_mode, &limits.supported_fec_modes)?,
+            priority: self.priority.min(limits.max_priority),
+            power_profile,
+            ..self.clone()
+        })
+    }
+}
+
+/// Message exchanged between negotiating peers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NegotiationMessage {
+    /// Initial proposal from the initiator
+    Propose(PolicyProposal),
+
+    /// Proposal adjusted to the sender's limits
+    CounterOffer(PolicyProposal),
+
+    /// Proposal accepted as-is; both sides apply it
+    Accept(PolicyProposal),
+
+    /// Negotiation aborted
+    Reject { policy_id: String, reason: String },
+}
+
+/// Final result of a negotiation
+#[derive(Debug, Clone, PartialEq)]
+pub enum NegotiationOutcome {
+    /// Both sides agreed on the proposal
+    Agreed(PolicyProposal),
+
+    /// Negotiation failed
+    Rejected(String),
+}
+
+/// Result of handling one incoming message
+#[derive(Debug, Clone, PartialEq)]
+pub struct NegotiationStep {
+    /// Message to send back to the peer, if any
+    pub reply: Option<NegotiationMessage>,
+
+    /// Set once the negotiation has finished on this side
+    pub outcome: Option<NegotiationOutcome>,
+}
+
+/// One side of a policy negotiation
+#[derive(Debug, Clone)]
+pub struct PolicyNegotiator {
+    limits: NegotiationLimits,
+    max_rounds: u8,
+
+    /// Proposals sent or received so far
+    round: u8,
+
+    /// Last proposal sent to the peer; an `Accept` must match it
+    last_sent: Option<PolicyProposal>,
+}
+
+impl PolicyNegotiator {
+    /// Create a negotiator enforcing the local device limits
+    pub fn new(limits: NegotiationLimits) -> Self {
+        Self {
+            limits,
+            max_rounds: DEFAULT_MAX_ROUNDS,
+            round: 0,
+            last_sent: None,
+        }
+    }
+
+    /// Set the maximum number of proposal rounds
+    pub fn with_max_rounds(mut self, max_rounds: u8) -> Self {
+        self.max_rounds = max_rounds.max(1);
+        self
+    }
+
+    /// Local device limits
+    pub fn limits(&self) -> &NegotiationLimits {
+        &self.limits
+    }
+
+    /// Proposal rounds counted locally so far
+    pub fn round(&self) -> u8 {
+        self.round
+    }
+
+    /// Start a negotiation by proposing `profile`, clamped to local limits
+    ///
+    /// # Errors
+    /// Returns `PolicyError::Validation` if the local device cannot run the
+    /// profile at all, or if this negotiator has already been used.
+    pub fn propose(&mut self, profile: &PolicyProfile) -> Result<NegotiationMessage> {
+        if self.round > 0 {
+            return Err(PolicyError::Validation(
+                "Negotiation already in progress".to_string(),
+            ));
+        }
+
+        let proposal = PolicyProposal::from_profile(profile)
+            .constrain(&self.limits)
+            .ok_or_else(|| {
+                PolicyError::Validation(format!(
+                    "Profile {} exceeds local device limits",
+                    profile.profile_id
+                ))
+            })?;
+
+        self.round = 1;
+        self.last_sent = Some(proposal.clone());
+        Ok(NegotiationMessage::Propose(proposal))
+    }
+
+    /// Handle a message from the peer
+    pub fn handle(&mut self, message: &NegotiationMessage) -> NegotiationStep {
+        match message {
+            NegotiationMessage::Propose(proposal) => {
+                if self.round > 0 {
+                    self.reject(proposal, "Unexpected proposal".to_string())
+                } else if !is_valid_policy_id(&proposal.policy_id) {
+                    self.reject(
+                        proposal,
+                        format!("Invalid policy ID {}", proposal.policy_id),
+                    )
+                } else {
+                    self.evaluate(proposal)
+                }
+            }
+            NegotiationMessage::CounterOffer(proposal) => {
+                if self.answers_last_sent(proposal) {
+                    self.evaluate(proposal)
+                } else {
+                    self.reject(
+                        proposal,
+                        "Counter-offer does not answer our proposal".to_string(),
+                    )
+                }
+            }
+            NegotiationMessage::Accept(proposal) => {
+                if self.last_sent.as_ref() == Some(proposal) {
+                    NegotiationStep {
+                        reply: None,
+                        outcome: Some(NegotiationOutcome::Agreed(proposal.clone())),
+                    }
+                } else {
+                    self.reject(
+                        proposal,
+                        "Accepted policy differs from our last proposal".to_string(),
+                    )
+                }
+            }
+            NegotiationMessage::Reject { reason, .. } => NegotiationStep {
+                reply: None,
+                outcome: Some(NegotiationOutcome::Rejected(reason.clone())),
+            },
+        }
+    }
+
+    /// Counter-offers keep the policy ID, profile and bandwidth floor
+    fn answers_last_sent(&self, proposal: &PolicyProposal) -> bool {
+        self.last_sent.as_ref().is_some_and(|sent| {
+            sent.policy_id == proposal.policy_id
+                && sent.profile_id == proposal.profile_id
+                && sent.schema_version == proposal.schema_version
+                && sent.bandwidth_floor_mbps == proposal.bandwidth_floor_mbps
+        })
+    }
+
+    fn evaluate(&mut self, proposal: &PolicyProposal) -> NegotiationStep {
+        self.round = self.round.saturating_add(1);
+
+        if proposal.fits(&self.limits) {
+            return NegotiationStep {
+                reply: Some(NegotiationMessage::Accept(proposal.clone())),
+                outcome: Some(NegotiationOutcome::Agreed(proposal.clone())),
+            };
+        }
+
+        if self.round >= self.max_rounds {
+            return self.reject(
+                proposal,
+                format!("No agreement after {} rounds", self.round),
+            );
+        }
+
+        match proposal.constrain(&self.limits) {
+            Some(mut counter) => {
+                self.round += 1;
+                counter.round = self.round;
+                self.last_sent = Some(counter.clone());
+                NegotiationStep {
+                    reply: Some(NegotiationMessage::CounterOffer(counter)),
+                    outcome: None,
+                }
+            }
+            None => self.reject(
+                proposal,
+                format!(
+                    "Bandwidth floor {} Mbps or FEC mode {:?} cannot be met",
+                    proposal.bandwidth_floor_mbps, proposal.fec_mode
+                ),
+            ),
+        }
+    }
+
+    fn reject(&self, proposal: &PolicyProposal, reason: String) -> NegotiationStep {
+        NegotiationStep {
+            reply: Some(NegotiationMessage::Reject {
+                policy_id: proposal.policy_id.clone(),
+                reason: reason.clone(),
+            }),
+            outcome: Some(NegotiationOutcome::Rejected(reason)),
+        }
+    }
+}
+
+/// Policy IDs are `pol_` followed by a UUID
+fn is_valid_policy_id(policy_id: &str) -> bool {
+    policy_id
+        .strip_prefix("pol_")
+        .is_some_and(|id| Uuid::parse_str(id).is_ok())
+}
+
+/// Ordering of power profiles from least to most power hungry
+fn power_rank(profile: PowerProfile) -> u8 {
+    match profile {
+        PowerProfile::UltraLow => 0,
+        PowerProfile::Low => 1,
+        PowerProfile::Normal => 2,
+        PowerProfile::High => 3,
+    }
+}
+
+/// Requested FEC mode if supported, else the next stronger, else the next weaker
+fn closest_fec_mode(requested: FecMode, supported: &[FecMode]) -> Option<FecMode> {
+    let preference: &[FecMode] = match requested {
+        FecMode::None => &[FecMode::None, FecMode::Light, FecMode::Heavy],
+        FecMode::Light => &[FecMode::Light, FecMode::Heavy, FecMode::None],
+        FecMode::Heavy => &[FecMode::Heavy, FecMode::Light, FecMode::None],
+    };
+    preference
+        .iter()
+        .copied()
+        .find(|mode| supported.contains(mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets::{create_gaming_input_preset, create_iot_lowpower_preset};
+
+    fn battery_limits() -> NegotiationLimits {
+        NegotiationLimits {
+            max_bandwidth_mbps: 20.0,
+            min_latency_budget_ms: 10,
+            supported_fec_modes: vec![FecMode::None, FecMode::Light],
+            max_power_profile: PowerProfile::Low,
+            max_priority: 5,
+        }
+    }
+
+    /// Exchange messages until neither side has anything left to send
+    fn run(
+        initiator: &PolicyNegotiator,
+        responder: &PolicyNegotiator,
+        profile: &PolicyProfile,
+    ) -> (NegotiationOutcome, NegotiationOutcome) {
+        let mut sides = [initiator.clone(), responder.clone()];
+        let mut outcomes = [None, None];
+        let mut message = Some(sides[0].propose(profile).unwrap());
+        let mut turn = 1;
+
+        while let Some(current) = message.take() {
+            let step = sides[turn].handle(&current);
+            outcomes[turn] = outcomes[turn].take().or(step.outcome);
+            message = step.reply;
+            turn = 1 - turn;
+        }
+
+        let [a, b] = outcomes;
+        (a.unwrap(), b.unwrap())
+    }
+
+    #[test]
+    fn test_accept_when_within_limits() {
+        let negotiator = PolicyNegotiator::new(NegotiationLimits::default());
+        let profile = create_gaming_input_preset();
+
+        let (initiator, responder) = run(&negotiator, &negotiator, &profile);
+        assert_eq!(initiator, responder);
+        match initiator {
+            NegotiationOutcome::Agreed(proposal) => {
+                assert_eq!(proposal.round, 1);
+                assert_eq!(proposal.profile_id, profile.profile_id);
+                assert_eq!(proposal.latency_budget_ms, profile.latency_budget_ms);
+            }
+            other => panic!("Expected agreement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_counter_offer_respects_both_limits() {
+        let initiator = PolicyNegotiator::new(NegotiationLimits {
+            max_priority: 4,
+            ..NegotiationLimits::default()
+        });
+        let responder = PolicyNegotiator::new(battery_limits());
+        let profile = create_gaming_input_preset();
+
+        let (a, b) = run(&initiator, &responder, &profile);
+        assert_eq!(a, b);
+        let NegotiationOutcome::Agreed(agreed) = a else {
+            panic!("Expected agreement");
+        };
+
+        assert!(agreed.fits(initiator.limits()));
+        assert!(agreed.fits(responder.limits()));
+        assert_eq!(agreed.round, 2);
+        assert_eq!(agreed.priority, 4);
+        assert_eq!(agreed.power_profile, PowerProfile::Low);
+        assert!(agreed.bandwidth_ceiling_mbps <= 20.0);
+        assert_eq!(agreed.bandwidth_floor_mbps, profile.bandwidth_floor_mbps);
+    }
+
+    #[test]
+    fn test_reject_when_floor_unreachable() {
+        let initiator = PolicyNegotiator::new(NegotiationLimits::default());
+        let responder = PolicyNegotiator::new(NegotiationLimits {
+            max_bandwidth_mbps: 0.05,
+            ..NegotiationLimits::default()
+        });
+
+        let (a, b) = run(&initiator, &responder, &create_iot_lowpower_preset());
+        assert!(matches!(a, NegotiationOutcome::Rejected(_)));
+        assert!(matches!(b, NegotiationOutcome::Rejected(_)));
+    }
+
+    #[test]
+    fn test_propose_fails_beyond_local_limits() {
+        let mut negotiator = PolicyNegotiator::new(NegotiationLimits {
+            supported_fec_modes: Vec::new(),
+            ..NegotiationLimits::default()
+        });
+        assert!(negotiator.propose(&create_gaming_input_preset()).is_err());
+    }
+
+    fn proposal_of(message: NegotiationMessage) -> PolicyProposal {
+        match message {
+            NegotiationMessage::Propose(proposal) => proposal,
+            other => panic!("Expected proposal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_round_limit() {
+        let mut negotiator = PolicyNegotiator::new(battery_limits()).with_max_rounds(2);
+        let mut counter = proposal_of(negotiator.propose(&create_gaming_input_preset()).unwrap());
+        // The peer's round number is ignored; rounds are counted locally
+        counter.round = 1;
+        counter.priority = 7;
+
+        let step = negotiator.handle(&NegotiationMessage::CounterOffer(counter));
+        assert_eq!(negotiator.round(), 2);
+        assert!(matches!(
+            step.reply,
+            Some(NegotiationMessage::Reject { .. })
+        ));
+        assert!(matches!(
+            step.outcome,
+            Some(NegotiationOutcome::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_accept_must_match_last_proposal() {
+        let profile = create_gaming_input_preset();
+        let mut negotiator = PolicyNegotiator::new(NegotiationLimits::default());
+        let sent = proposal_of(negotiator.propose(&profile).unwrap());
+
+        let mut altered = sent.clone();
+        altered.latency_budget_ms += 1;
+        let step = negotiator
+            .clone()
+            .handle(&NegotiationMessage::Accept(altered));
+        assert!(matches!(
+            step.outcome,
+            Some(NegotiationOutcome::Rejected(_))
+        ));
+
+        let step = negotiator.handle(&NegotiationMessage::Accept(sent.clone()));
+        assert_eq!(step.outcome, Some(NegotiationOutcome::Agreed(sent.clone())));
+
+        // Nothing was proposed, so there is nothing to accept
+        let step = PolicyNegotiator::new(NegotiationLimits::default())
+            .handle(&NegotiationMessage::Accept(sent));
+        assert!(matches!(
+            step.outcome,
+            Some(NegotiationOutcome::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_peer_chosen_fields_are_checked() {
+        let profile = create_gaming_input_preset();
+
+        let mut proposal = PolicyProposal::from_profile(&profile);
+        proposal.policy_id = "pol_../../etc".to_string();
+        let step = PolicyNegotiator::new(NegotiationLimits::default())
+            .handle(&NegotiationMessage::Propose(proposal));
+        assert!(matches!(
+            step.outcome,
+            Some(NegotiationOutcome::Rejected(_))
+        ));
+
+        // A counter-offer may not switch policy ID or lower the floor
+        let mut negotiator = PolicyNegotiator::new(NegotiationLimits::default());
+        let sent = proposal_of(negotiator.propose(&profile).unwrap());
+        let mut renamed = sent.clone();
+        renamed.policy_id = PolicyProposal::from_profile(&profile).policy_id;
+        let mut lowered = sent;
+        lowered.bandwidth_floor_mbps = 0.0;
+        for counter in [renamed, lowered] {
+            let step = negotiator
+                .clone()
+                .handle(&NegotiationMessage::CounterOffer(counter));
+            assert!(matches!(
+                step.outcome,
+                Some(NegotiationOutcome::Rejected(_))
+            ));
+        }
+
+        // A second proposal mid-negotiation is refused
+        let step = negotiator.handle(&NegotiationMessage::Propose(PolicyProposal::from_profile(
+            &profile,
+        )));
+        assert!(matches!(
+            step.outcome,
+            Some(NegotiationOutcome::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_message_serialization() {
+        let message = NegotiationMessage::Reject {
+            policy_id: "pol_test".to_string(),
+            reason: "busy".to_string(),
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        assert!(json.contains("\"type\":\"reject\""));
+        assert_eq!(
+            serde_json::from_str::<NegotiationMessage>(&json).unwrap(),
+            message
+        );
+    }
+}
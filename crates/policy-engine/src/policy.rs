@@ -2,14 +2,25 @@
 //!
 //! Coordinates policy updates, profile management, and event distribution
 
-use crate::error::Result;
+use crate::bundle::ProfileBundle;
+use crate::error::{PolicyError, Result};
 use crate::event_bus::PolicyEventBus;
-use crate::profile::{PolicyProfile, ProfileStorage};
+use crate::history::{AuditQuery, PolicyChange, PolicyDiff, PolicyRevision};
+use crate::lifecycle::{HistoryFuture, LifecycleHistory, PolicyLifecycleManager, SessionActivity};
+use crate::negotiation::PolicyProposal;
+use crate::profile::{PolicyProfile, ProfileStorage, ProfileUpgrade};
+use crate::rules::{PolicyContext, RulesEngine};
 use crate::types::{QoSPolicyUpdate, UseCase};
 use chrono::Utc;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use rand::RngCore;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedRwLockWriteGuard, RwLock};
 use uuid::Uuid;
+use zeroize::Zeroizing;
+
+/// Actor recorded in the policy history when none is configured
+const DEFAULT_ACTOR: &str = "local";
 
 /// Main Policy Engine coordinator
 ///
@@ -17,33 +28,111 @@ use uuid::Uuid;
 /// 1. Profile management (CRUD operations)
 /// 2. Policy instance creation from profiles
 /// 3. Event distribution to QoS Scheduler
-/// 4. Rollback on failure
+/// 4. Rollback on failure, to the last snapshot or any recorded revision
+///
+/// Every policy it creates is signed with its policy-authority key;
+/// subscribers check updates with a `PolicyVerifier` trusting `authority_key()`.
 pub struct PolicyEngine<S: ProfileStorage> {
     /// Profile storage backend
     storage: Arc<RwLock<S>>,
 
     /// Event bus for policy distribution
     event_bus: Arc<PolicyEventBus>,
+
+    /// Ed25519 key signing issued policy updates
+    authority_key: SigningKey,
+
+    /// Actor recorded in the policy history for changes made by this engine
+    actor: String,
 }
 
 impl<S: ProfileStorage> PolicyEngine<S> {
     /// Create a new PolicyEngine instance
+    ///
+    /// Uses a freshly generated authority key, which changes on every start.
+    /// That is only suitable for tests and single-process setups: production
+    /// deployments must call `with_authority_key` with a persistent key (e.g.
+    /// one loaded from the keychain) so that subscribers' `PolicyVerifier`s
+    /// keep trusting its updates across restarts.
     pub fn new(storage: S) -> Self {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(secret.as_mut());
+        let authority_key = SigningKey::from_bytes(&secret);
+
         Self {
             storage: Arc::new(RwLock::new(storage)),
             event_bus: Arc::new(PolicyEventBus::new()),
+            authority_key,
+            actor: DEFAULT_ACTOR.to_string(),
         }
     }
 
+    /// Sign policy updates with the given policy-authority key
+    pub fn with_authority_key(mut self, authority_key: SigningKey) -> Self {
+        self.authority_key = authority_key;
+        self
+    }
+
+    /// Record changes made through `apply_policy` and friends as `actor`
+    /// (default: "local")
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = actor.into();
+        self
+    }
+
+    /// Forward applied, rolled back and invalidated policies to the shared
+    /// core event bus
+    ///
+    /// Call before handing out `event_bus()` or a lifecycle manager.
+    pub fn with_core_bus(mut self, core_bus: Arc<honeylink_core::EventBus>) -> Self {
+        self.event_bus = Arc::new(PolicyEventBus::new().with_core_bus(core_bus));
+        self
+    }
+
+    /// Public key subscribers must trust to accept this engine's policies
+    pub fn authority_key(&self) -> VerifyingKey {
+        self.authority_key.verifying_key()
+    }
+
     /// Get reference to event bus for subscribers
     pub fn event_bus(&self) -> Arc<PolicyEventBus> {
         Arc::clone(&self.event_bus)
     }
 
+    /// Create a lifecycle manager for the policies this engine applies
+    ///
+    /// Renewals are signed with this engine's authority key and, like
+    /// invalidations, recorded in the policy history as this engine's actor;
+    /// `activity` decides which policies still belong to a running session.
+    pub fn lifecycle_manager(
+        &self,
+        activity: impl SessionActivity + 'static,
+    ) -> PolicyLifecycleManager {
+        PolicyLifecycleManager::new(
+            Arc::clone(&self.event_bus),
+            self.authority_key.clone(),
+            activity,
+        )
+        .with_history(StorageHistory {
+            storage: Arc::clone(&self.storage),
+            actor: self.actor.clone(),
+        })
+    }
+
+    /// Lock the storage and run `mutate` on the blocking thread pool
+    async fn mutate_storage<T: Send + 'static>(
+        &self,
+        mutate: impl FnOnce(&mut S) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let storage = Arc::clone(&self.storage).write_owned().await;
+        let (_, value) = run_blocking(storage, mutate).await?;
+        Ok(value)
+    }
+
     /// Create a new profile
     pub async fn create_profile(&self, profile: PolicyProfile) -> Result<()> {
-        let mut storage = self.storage.write().await;
-        storage.create(profile)
+        self.mutate_storage(move |storage| storage.create(profile))
+            .await
     }
 
     /// Get a profile by ID
@@ -54,14 +143,15 @@ impl<S: ProfileStorage> PolicyEngine<S> {
 
     /// Update an existing profile
     pub async fn update_profile(&self, profile: PolicyProfile) -> Result<()> {
-        let mut storage = self.storage.write().await;
-        storage.update(profile)
+        self.mutate_storage(move |storage| storage.update(profile))
+            .await
     }
 
     /// Delete a profile (soft delete)
     pub async fn delete_profile(&self, profile_id: &str) -> Result<()> {
-        let mut storage = self.storage.write().await;
-        storage.delete(profile_id)
+        let profile_id = profile_id.to_string();
+        self.mutate_storage(move |storage| storage.delete(&profile_id))
+            .await
     }
 
     /// List all profiles, optionally filtered by use case
@@ -70,6 +160,27 @@ impl<S: ProfileStorage> PolicyEngine<S> {
         storage.list(use_case)
     }
 
+    /// Export non-deprecated profiles as a bundle with their stored signatures
+    pub async fn export_profiles(&self, use_case: Option<UseCase>) -> Result<ProfileBundle> {
+        let storage = self.storage.read().await;
+        ProfileBundle::export(&*storage, use_case)
+    }
+
+    /// Import a bundle whose profiles are signed by one of `trusted_keys`
+    ///
+    /// Newer `profile_version`s replace stored profiles, deleted profiles
+    /// stay deleted; see `ProfileBundle::import_into`.
+    pub async fn import_profiles(
+        &self,
+        bundle: &ProfileBundle,
+        trusted_keys: &[VerifyingKey],
+    ) -> Result<Vec<(String, ProfileUpgrade)>> {
+        let bundle = bundle.clone();
+        let trusted_keys = trusted_keys.to_vec();
+        self.mutate_storage(move |storage| bundle.import_into(storage, &trusted_keys))
+            .await
+    }
+
     /// Create a QoS policy instance from a profile
     ///
     /// # Arguments
@@ -79,7 +190,8 @@ impl<S: ProfileStorage> PolicyEngine<S> {
     /// * `ttl_hours` - Time-to-live in hours (default: 12h per spec)
     ///
     /// # Returns
-    /// Generated policy update that can be published to event bus
+    /// Generated policy update, signed by the authority key, that can be
+    /// published to event bus
     pub async fn create_policy_from_profile(
         &self,
         profile_id: &str,
@@ -98,11 +210,12 @@ impl<S: ProfileStorage> PolicyEngine<S> {
         let expiration_ts = Utc::now() + chrono::Duration::hours(ttl as i64);
 
         // Create policy update from profile
-        let policy = QoSPolicyUpdate {
+        let mut policy = QoSPolicyUpdate {
             schema_version: profile.profile_version.clone(),
             policy_id,
             profile_id: profile.profile_id.clone(),
             stream_id,
+            device_id: Some(device_id.to_string()),
             latency_budget_ms: profile.latency_budget_ms,
             bandwidth_floor_mbps: profile.bandwidth_floor_mbps,
             bandwidth_ceiling_mbps: Some(profile.bandwidth_ceiling_mbps),
@@ -111,31 +224,302 @@ impl<S: ProfileStorage> PolicyEngine<S> {
             power_profile: Some(profile.power_profile),
             deprecated_after: profile.deprecated_after,
             expiration_ts,
-            signature: format!("policy:{}:device:{}", profile.signature, device_id),
+            signature: String::new(),
+        };
+
+        // Validate before signing
+        policy.validate()?;
+        policy.sign(&self.authority_key);
+
+        Ok(policy)
+    }
+
+    /// Create a QoS policy instance from a negotiated agreement
+    ///
+    /// Both peers call this with the agreed proposal, so their updates share
+    /// `policy_id` and parameters; each is signed by the local authority key.
+    ///
+    /// # Arguments
+    /// * `agreement` - Proposal both sides accepted
+    /// * `stream_id` - Stream ID (0-7) to apply policy to
+    /// * `device_id` - Peer device this policy applies to
+    /// * `ttl_hours` - Time-to-live in hours (default: 12h per spec)
+    pub fn create_policy_from_agreement(
+        &self,
+        agreement: &PolicyProposal,
+        stream_id: u8,
+        device_id: &str,
+        ttl_hours: Option<u32>,
+    ) -> Result<QoSPolicyUpdate> {
+        let ttl = ttl_hours.unwrap_or(12);
+        let expiration_ts = Utc::now() + chrono::Duration::hours(ttl as i64);
+
+        let mut policy = QoSPolicyUpdate {
+            schema_version: agreement.schema_version.clone(),
+            policy_id: agreement.policy_id.clone(),
+            profile_id: agreement.profile_id.clone(),
+            stream_id,
+            device_id: Some(device_id.to_string()),
+            latency_budget_ms: agreement.latency_budget_ms,
+            bandwidth_floor_mbps: agreement.bandwidth_floor_mbps,
+            bandwidth_ceiling_mbps: Some(agreement.bandwidth_ceiling_mbps),
+            fec_mode: agreement.fec_mode,
+            priority: agreement.priority,
+            power_profile: Some(agreement.power_profile),
+            deprecated_after: None,
+            expiration_ts,
+            signature: String::new(),
         };
 
-        // Validate before returning
         policy.validate()?;
+        policy.sign(&self.authority_key);
 
         Ok(policy)
     }
 
     /// Apply a policy update by publishing to event bus
     ///
-    /// Automatically saves snapshot for rollback
+    /// Automatically saves snapshot for rollback and records a revision as
+    /// this engine's actor.
     pub async fn apply_policy(&self, policy: QoSPolicyUpdate) -> Result<usize> {
+        self.apply_policy_as(policy, &self.actor, "applied").await
+    }
+
+    /// Apply a policy update on behalf of `actor`, recording `reason`
+    ///
+    /// The revision is recorded before the update is published, so
+    /// subscribers never see a change missing from the history.
+    pub async fn apply_policy_as(
+        &self,
+        policy: QoSPolicyUpdate,
+        actor: &str,
+        reason: &str,
+    ) -> Result<usize> {
+        policy.validate()?;
+
+        // Hold the storage lock until published so versions follow publish order
+        let storage = Arc::clone(&self.storage).write_owned().await;
+        let version = next_version(&*storage, &policy.policy_id)?;
+        let revision = PolicyRevision::new(
+            version,
+            actor,
+            reason,
+            PolicyChange::Applied,
+            policy.clone(),
+        );
+        let (_storage, ()) =
+            run_blocking(storage, move |storage| storage.record_revision(revision)).await?;
+
         self.event_bus.publish_update(policy).await
     }
 
+    /// Re-evaluate context rules for an applied policy
+    ///
+    /// When the active rules change, the adjusted update is validated,
+    /// signed and published under the same `policy_id`.
+    ///
+    /// # Returns
+    /// The published update, or `None` if the rules did not change
+    pub async fn apply_rules(
+        &self,
+        rules: &mut RulesEngine,
+        base: &QoSPolicyUpdate,
+        context: &PolicyContext,
+    ) -> Result<Option<QoSPolicyUpdate>> {
+        let evaluation = rules.evaluate(base, context);
+        if !evaluation.changed {
+            return Ok(None);
+        }
+
+        let mut update = evaluation.update;
+        update.validate()?;
+        update.sign(&self.authority_key);
+        self.apply_policy_as(update.clone(), &self.actor, "context rules changed")
+            .await?;
+
+        Ok(Some(update))
+    }
+
     /// Rollback a policy to its last known good configuration
     pub async fn rollback_policy(&self, policy_id: &str) -> Result<usize> {
         self.event_bus.publish_rollback(policy_id).await
     }
 
+    /// Roll a policy back to the configuration of revision `version`
+    ///
+    /// The restored configuration keeps the current expiration, is re-signed
+    /// and published as a `Rollback` event, and recorded as a new revision.
+    ///
+    /// # Errors
+    /// Returns `PolicyError::NotFound` if the revision does not exist and
+    /// `PolicyError::Validation` if the policy has been invalidated.
+    pub async fn rollback_policy_to(
+        &self,
+        policy_id: &str,
+        version: u32,
+        actor: &str,
+        reason: &str,
+    ) -> Result<QoSPolicyUpdate> {
+        let storage = Arc::clone(&self.storage).write_owned().await;
+        let target = storage.revision(policy_id, version)?;
+        let latest = storage
+            .revisions(policy_id)?
+            .pop()
+            .unwrap_or_else(|| target.clone());
+        if latest.change == PolicyChange::Invalidated {
+            return Err(PolicyError::Validation(format!(
+                "Policy {} has been invalidated",
+                policy_id
+            )));
+        }
+
+        // Only the configuration is rolled back, not the lifetime
+        let current = self
+            .event_bus
+            .get_snapshot(policy_id)
+            .await
+            .unwrap_or_else(|| latest.policy.clone());
+        let mut restored = target.policy;
+        restored.expiration_ts = current.expiration_ts;
+        restored.sign(&self.authority_key);
+        restored.validate()?;
+
+        let revision = PolicyRevision::new(
+            latest.version + 1,
+            actor,
+            reason,
+            PolicyChange::RolledBack {
+                to_version: version,
+            },
+            restored.clone(),
+        );
+        let (_storage, ()) =
+            run_blocking(storage, move |storage| storage.record_revision(revision)).await?;
+
+        self.event_bus.publish_rollback_to(restored.clone()).await?;
+        Ok(restored)
+    }
+
     /// Invalidate a policy (due to expiration or deprecation)
     pub async fn invalidate_policy(&self, policy_id: &str) -> Result<usize> {
+        let storage = Arc::clone(&self.storage).write_owned().await;
+        let _storage = record_change(
+            storage,
+            policy_id,
+            &self.actor,
+            "invalidated",
+            PolicyChange::Invalidated,
+            None,
+        )
+        .await?;
+
         self.event_bus.publish_invalidate(policy_id).await
     }
+
+    /// Recorded revisions of a policy, oldest first
+    pub async fn policy_history(&self, policy_id: &str) -> Result<Vec<PolicyRevision>> {
+        let storage = self.storage.read().await;
+        storage.revisions(policy_id)
+    }
+
+    /// Differences between two revisions of a policy
+    pub async fn policy_diff(
+        &self,
+        policy_id: &str,
+        from_version: u32,
+        to_version: u32,
+    ) -> Result<PolicyDiff> {
+        let storage = self.storage.read().await;
+        storage.diff(policy_id, from_version, to_version)
+    }
+
+    /// Revisions of every policy matching `query`, newest first
+    pub async fn audit_log(&self, query: &AuditQuery) -> Result<Vec<PolicyRevision>> {
+        let storage = self.storage.read().await;
+        storage.audit(query)
+    }
+}
+
+/// Records lifecycle renewals and invalidations in an engine's storage
+struct StorageHistory<S> {
+    storage: Arc<RwLock<S>>,
+    actor: String,
+}
+
+impl<S: ProfileStorage> LifecycleHistory for StorageHistory<S> {
+    fn record<'a>(
+        &'a self,
+        change: PolicyChange,
+        reason: &'a str,
+        policy: &'a QoSPolicyUpdate,
+    ) -> HistoryFuture<'a> {
+        Box::pin(async move {
+            let storage = Arc::clone(&self.storage).write_owned().await;
+            record_change(
+                storage,
+                &policy.policy_id,
+                &self.actor,
+                reason,
+                change,
+                Some(policy.clone()),
+            )
+            .await
+            .map(|_| ())
+        })
+    }
+}
+
+/// Append `change` to the history of `policy_id`
+///
+/// `policy` is the configuration after the change, the latest recorded one
+/// if `None`. Policies without history (not applied through the engine) are
+/// skipped, as are repeated invalidations. The storage lock is handed back so
+/// the caller can publish the change before releasing it.
+async fn record_change<S: ProfileStorage>(
+    storage: OwnedRwLockWriteGuard<S>,
+    policy_id: &str,
+    actor: &str,
+    reason: &str,
+    change: PolicyChange,
+    policy: Option<QoSPolicyUpdate>,
+) -> Result<OwnedRwLockWriteGuard<S>> {
+    let Some(latest) = storage.revisions(policy_id)?.pop() else {
+        return Ok(storage);
+    };
+    if change == PolicyChange::Invalidated && latest.change == PolicyChange::Invalidated {
+        return Ok(storage);
+    }
+
+    let policy = policy.unwrap_or(latest.policy);
+    let revision = PolicyRevision::new(latest.version + 1, actor, reason, change, policy);
+    let (storage, ()) =
+        run_blocking(storage, move |storage| storage.record_revision(revision)).await?;
+    Ok(storage)
+}
+
+/// Run `mutate` on locked storage on the blocking thread pool
+///
+/// Backends may write and fsync on every mutation (`FileProfileStorage`
+/// does), which must not stall the async runtime. The lock is handed back
+/// once `mutate` succeeds.
+async fn run_blocking<S: ProfileStorage, T: Send + 'static>(
+    mut storage: OwnedRwLockWriteGuard<S>,
+    mutate: impl FnOnce(&mut S) -> Result<T> + Send + 'static,
+) -> Result<(OwnedRwLockWriteGuard<S>, T)> {
+    tokio::task::spawn_blocking(move || {
+        let value = mutate(&mut storage)?;
+        Ok((storage, value))
+    })
+    .await
+    .map_err(|e| PolicyError::Storage(format!("Storage task failed: {}", e)))?
+}
+
+/// Version the next revision of `policy_id` must carry
+fn next_version<S: ProfileStorage + ?Sized>(storage: &S, policy_id: &str) -> Result<u32> {
+    Ok(storage
+        .revisions(policy_id)?
+        .last()
+        .map_or(1, |latest| latest.version + 1))
 }
 
 #[cfg(test)]
@@ -170,6 +554,8 @@ mod tests {
         assert_eq!(policy.stream_id, 3);
         assert_eq!(policy.profile_id, profile.profile_id);
         assert!(policy.policy_id.starts_with("pol_"));
+        assert_eq!(policy.device_id.as_deref(), Some("dev_test123"));
+        assert!(policy.verify_signature(&engine.authority_key()).is_ok());
 
         // Subscribe to events
         let mut receiver = engine.event_bus().subscribe();
@@ -200,7 +586,7 @@ mod tests {
         engine.invalidate_policy(&policy.policy_id).await.unwrap();
 
         match receiver.recv().await.unwrap() {
-            crate::event_bus::PolicyEvent::Invalidate { policy_id } => {
+            crate::event_bus::PolicyEvent::Invalidate { policy_id, .. } => {
                 assert_eq!(policy_id, policy.policy_id);
             }
             _ => panic!("Expected Invalidate event"),
@@ -212,6 +598,232 @@ mod tests {
         assert!(deleted.deprecated_after.is_some());
     }
 
+    #[tokio::test]
+    async fn test_policies_signed_with_configured_authority() {
+        use crate::verifier::PolicyVerifier;
+
+        let authority = SigningKey::from_bytes(&[5u8; 32]);
+        let engine = PolicyEngine::new(InMemoryProfileStorage::new())
+            .with_authority_key(authority.clone());
+        assert_eq!(engine.authority_key(), authority.verifying_key());
+
+        let profile = create_iot_lowpower_preset();
+        engine.create_profile(profile.clone()).await.unwrap();
+        let policy = engine
+            .create_policy_from_profile(&profile.profile_id, 1, "dev_test", None)
+            .await
+            .unwrap();
+
+        let trusting = PolicyVerifier::new().with_authority(authority.verifying_key());
+        assert!(trusting.verify(&policy).is_ok());
+
+        let other_engine = PolicyEngine::new(InMemoryProfileStorage::new());
+        let stranger = PolicyVerifier::new().with_authority(other_engine.authority_key());
+        assert!(stranger.verify(&policy).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_policy_from_agreement() {
+        use crate::presets::create_gaming_input_preset;
+
+        let engine = PolicyEngine::new(InMemoryProfileStorage::new());
+        let mut agreement = PolicyProposal::from_profile(&create_gaming_input_preset());
+        agreement.bandwidth_ceiling_mbps = 20.0;
+
+        let policy = engine
+            .create_policy_from_agreement(&agreement, 2, "dev_peer", None)
+            .unwrap();
+
+        assert_eq!(policy.policy_id, agreement.policy_id);
+        assert_eq!(policy.bandwidth_ceiling_mbps, Some(20.0));
+        assert_eq!(policy.device_id.as_deref(), Some("dev_peer"));
+        assert!(policy.verify_signature(&engine.authority_key()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_apply_rules_publishes_on_change() {
+        use crate::rules::{Condition, PolicyRule, RuleAction};
+        use crate::types::PowerProfile;
+
+        let engine = PolicyEngine::new(InMemoryProfileStorage::new());
+        let profile = create_iot_lowpower_preset();
+        engine.create_profile(profile.clone()).await.unwrap();
+        let base = engine
+            .create_policy_from_profile(&profile.profile_id, 1, "dev_test", None)
+            .await
+            .unwrap();
+        let mut receiver = engine.event_bus().subscribe();
+
+        let mut rules = RulesEngine::new(vec![PolicyRule::new(
+            "low_battery",
+            Condition::BatteryBelow { percent: 20 },
+            RuleAction {
+                power_profile: Some(PowerProfile::Low),
+                ..RuleAction::default()
+            },
+        )]);
+        let low = PolicyContext::new().with_battery_percent(5);
+
+        let update = engine
+            .apply_rules(&mut rules, &base, &low)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.policy_id, base.policy_id);
+        assert_eq!(update.power_profile, Some(PowerProfile::Low));
+        assert!(update.verify_signature(&engine.authority_key()).is_ok());
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            crate::event_bus::PolicyEvent::Update(_)
+        ));
+
+        // Unchanged rule set publishes nothing
+        assert!(engine
+            .apply_rules(&mut rules, &base, &low)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_version_and_audit() {
+        use crate::types::PowerProfile;
+
+        let engine = PolicyEngine::new(InMemoryProfileStorage::new()).with_actor("controller");
+        let profile = create_iot_lowpower_preset();
+        engine.create_profile(profile.clone()).await.unwrap();
+        let mut receiver = engine.event_bus().subscribe();
+
+        let v1 = engine
+            .create_policy_from_profile(&profile.profile_id, 1, "dev_test", None)
+            .await
+            .unwrap();
+        engine.apply_policy(v1.clone()).await.unwrap();
+
+        let mut v2 = v1.clone();
+        v2.latency_budget_ms = 40;
+        v2.sign(&SigningKey::from_bytes(&[9u8; 32]));
+        engine
+            .apply_policy_as(v2.clone(), "DEV-PEER", "peer request")
+            .await
+            .unwrap();
+
+        let mut v3 = v2.clone();
+        v3.power_profile = Some(PowerProfile::High);
+        engine.apply_policy(v3).await.unwrap();
+
+        let diff = engine.policy_diff(&v1.policy_id, 1, 3).await.unwrap();
+        let fields: Vec<_> = diff.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["latency_budget_ms", "power_profile"]);
+
+        // Two steps back in one rollback
+        let restored = engine
+            .rollback_policy_to(&v1.policy_id, 1, "operator", "latency regression")
+            .await
+            .unwrap();
+        assert_eq!(restored.latency_budget_ms, v1.latency_budget_ms);
+        assert_eq!(restored.power_profile, v1.power_profile);
+        assert!(restored.verify_signature(&engine.authority_key()).is_ok());
+        assert_eq!(
+            engine.event_bus().get_snapshot(&v1.policy_id).await,
+            Some(restored.clone())
+        );
+        let events: Vec<_> = (0..4).map(|_| receiver.try_recv().unwrap()).collect();
+        assert!(matches!(
+            &events[3],
+            crate::event_bus::PolicyEvent::Rollback { snapshot, .. } if *snapshot == restored
+        ));
+
+        engine.invalidate_policy(&v1.policy_id).await.unwrap();
+        let history = engine.policy_history(&v1.policy_id).await.unwrap();
+        let changes: Vec<_> = history.iter().map(|r| r.change).collect();
+        assert_eq!(
+            changes,
+            vec![
+                PolicyChange::Applied,
+                PolicyChange::Applied,
+                PolicyChange::Applied,
+                PolicyChange::RolledBack { to_version: 1 },
+                PolicyChange::Invalidated,
+            ]
+        );
+        assert!(matches!(
+            engine
+                .rollback_policy_to(&v1.policy_id, 2, "operator", "undo")
+                .await,
+            Err(PolicyError::Validation(_))
+        ));
+
+        let audit = engine
+            .audit_log(&AuditQuery::new().with_actor("DEV-PEER"))
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].version, 2);
+        assert_eq!(audit[0].reason, "peer request");
+        assert_eq!(
+            engine.audit_log(&AuditQuery::new()).await.unwrap()[0].version,
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_manager_renews_with_engine_key() {
+        let engine = PolicyEngine::new(InMemoryProfileStorage::new());
+        let profile = create_iot_lowpower_preset();
+        engine.create_profile(profile.clone()).await.unwrap();
+        let _receiver = engine.event_bus().subscribe();
+
+        let active = engine
+            .create_policy_from_profile(&profile.profile_id, 1, "dev_active", Some(1))
+            .await
+            .unwrap();
+        let idle = engine
+            .create_policy_from_profile(&profile.profile_id, 2, "dev_idle", Some(1))
+            .await
+            .unwrap();
+        engine.apply_policy(active.clone()).await.unwrap();
+        engine.apply_policy(idle.clone()).await.unwrap();
+
+        let manager = engine.lifecycle_manager(|policy: &QoSPolicyUpdate| {
+            policy.device_id.as_deref() == Some("dev_active")
+        });
+        let report = manager
+            .sweep(Utc::now() + chrono::Duration::minutes(55))
+            .await;
+        assert_eq!(report.renewed, vec![active.policy_id.clone()]);
+
+        let renewed = engine
+            .event_bus()
+            .get_snapshot(&active.policy_id)
+            .await
+            .unwrap();
+        assert!(renewed.verify_signature(&engine.authority_key()).is_ok());
+
+        let report = manager.sweep(Utc::now() + chrono::Duration::hours(2)).await;
+        assert_eq!(report.invalidated, vec![idle.policy_id.clone()]);
+        assert!(engine
+            .event_bus()
+            .get_snapshot(&idle.policy_id)
+            .await
+            .is_none());
+
+        // Both lifecycle changes are in the history
+        let changes = |history: Vec<PolicyRevision>| -> Vec<PolicyChange> {
+            history.into_iter().map(|r| r.change).collect()
+        };
+        let history = engine.policy_history(&active.policy_id).await.unwrap();
+        assert_eq!(history[1].policy, renewed);
+        assert_eq!(
+            changes(history),
+            vec![PolicyChange::Applied, PolicyChange::Renewed]
+        );
+        assert_eq!(
+            changes(engine.policy_history(&idle.policy_id).await.unwrap()),
+            vec![PolicyChange::Applied, PolicyChange::Invalidated]
+        );
+    }
+
     #[tokio::test]
     async fn test_create_policy_validation() {
         let storage = InMemoryProfileStorage::new();
@@ -3,14 +3,23 @@
 //! Implements profile CRUD operations, validation, and Ed25519 signature
 //! verification according to spec/modules/policy-profile-engine.md
 
+use crate::canonical::CanonicalEncoder;
 use crate::error::{PolicyError, Result};
+use crate::history::{append_revision, AuditQuery, PolicyDiff, PolicyRevision};
 use crate::types::{FecMode, PowerProfile, Priority, UseCase};
 use chrono::{DateTime, Utc};
-use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use semver::Version;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
+/// Domain separation tag of the `PolicyProfile` signing encoding
+const PROFILE_SIGNATURE_DOMAIN: &str = "honeylink-policy-profile";
+
+/// Version of the `PolicyProfile` signing encoding
+const PROFILE_SIGNATURE_VERSION: u8 = 1;
+
 /// Policy Profile template definition
 ///
 /// Profiles define reusable QoS configurations for specific use cases
@@ -115,6 +124,18 @@ impl PolicyProfile {
         Ok(())
     }
 
+    /// Check whether `deprecated_after` has passed
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated_after
+            .is_some_and(|deprecated_after| deprecated_after <= Utc::now())
+    }
+
+    /// Soft-delete: deprecate now unless already deprecated earlier
+    pub(crate) fn deprecate_now(&mut self) {
+        let now = Utc::now();
+        self.deprecated_after = Some(self.deprecated_after.map_or(now, |d| d.min(now)));
+    }
+
     /// Verify Ed25519 signature using provided public key
     ///
     /// # Arguments
@@ -123,6 +144,13 @@ impl PolicyProfile {
     /// # Returns
     /// Ok(()) if signature is valid, Err otherwise
     pub fn verify_signature(&self, public_key_bytes: &[u8; 32]) -> Result<()> {
+        let public_key = VerifyingKey::from_bytes(public_key_bytes)
+            .map_err(|e| PolicyError::SignatureInvalid(format!("Invalid public key: {}", e)))?;
+        self.verify_with(&public_key)
+    }
+
+    /// Verify Ed25519 signature against a parsed public key
+    pub fn verify_with(&self, public_key: &VerifyingKey) -> Result<()> {
         // Decode base64 signature
         use base64::Engine;
         let signature_bytes = base64::engine::general_purpose::STANDARD
@@ -144,42 +172,89 @@ impl PolicyProfile {
                 .map_err(|_| PolicyError::SignatureInvalid("Invalid signature bytes".to_string()))?,
         );
 
-        let public_key = VerifyingKey::from_bytes(public_key_bytes)
-            .map_err(|e| PolicyError::SignatureInvalid(format!("Invalid public key: {}", e)))?;
-
-        // Create canonical message to verify
-        let message = self.create_canonical_message();
-
         public_key
-            .verify(message.as_bytes(), &signature)
+            .verify(&self.canonical_bytes(), &signature)
             .map_err(|e| PolicyError::SignatureInvalid(format!("Signature verification failed: {}", e)))
     }
 
-    /// Create canonical message for signing/verification
+    /// Sign the profile with an Ed25519 key (replaces `signature`)
+    pub fn sign(&mut self, signing_key: &SigningKey) {
+        use base64::Engine;
+        let signature = signing_key.sign(&self.canonical_bytes());
+        self.signature = base64::engine::general_purpose::STANDARD.encode(signature.to_bytes());
+    }
+
+    /// Sign the profile with a raw 32-byte Ed25519 secret key
     ///
-    /// This creates a deterministic string representation of the profile
-    /// for signature generation and verification
-    fn create_canonical_message(&self) -> String {
-        format!(
-            "{}|{}|{}|{:?}|{}|{}|{}|{:?}|{}|{:?}",
-            self.profile_id,
-            self.profile_name,
-            self.profile_version,
-            self.use_case,
-            self.latency_budget_ms,
-            self.bandwidth_floor_mbps,
-            self.bandwidth_ceiling_mbps,
-            self.fec_mode,
-            self.priority,
-            self.power_profile
-        )
+    /// Counterpart of `verify_signature` for callers holding key bytes.
+    pub fn sign_with_bytes(&mut self, secret_key_bytes: &[u8; 32]) {
+        self.sign(&SigningKey::from_bytes(secret_key_bytes));
+    }
+
+    /// Consume the profile and return it signed with `signing_key`
+    pub fn signed(mut self, signing_key: &SigningKey) -> Self {
+        self.sign(signing_key);
+        self
+    }
+
+    /// Canonical encoding covered by the signature
+    ///
+    /// Every semantically relevant field in declaration order, using the
+    /// layout described in `crate::canonical`. `signature`, `created_at` and
+    /// `updated_at` are bookkeeping and excluded so storage can touch them
+    /// without invalidating the signature.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        CanonicalEncoder::new(PROFILE_SIGNATURE_DOMAIN, PROFILE_SIGNATURE_VERSION)
+            .str(&self.profile_id)
+            .str(&self.profile_name)
+            .str(&self.profile_version.to_string())
+            .use_case(self.use_case)
+            .u16(self.latency_budget_ms)
+            .f64(self.bandwidth_floor_mbps)
+            .f64(self.bandwidth_ceiling_mbps)
+            .fec_mode(self.fec_mode)
+            .u8(self.priority)
+            .power_profile(self.power_profile)
+            .option(self.deprecated_after.as_ref(), CanonicalEncoder::time)
+            .map(&self.metadata)
+            .finish()
     }
 }
 
+/// Outcome of installing a profile with `ProfileStorage::upgrade`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileUpgrade {
+    /// Profile did not exist and was created
+    Created,
+
+    /// Stored profile was replaced by a newer `profile_version`
+    Upgraded { previous: Version },
+
+    /// Identical profile already stored
+    Unchanged,
+
+    /// Stored profile is newer; the incoming one was ignored
+    Outdated { current: Version },
+
+    /// Stored profile was deleted; the incoming one was ignored
+    Deleted { current: Version },
+}
+
 /// Profile storage trait for abstracting persistence layer
 ///
-/// Implementations can use CockroachDB, in-memory storage, or other backends
-pub trait ProfileStorage: Send + Sync {
+/// Implementations: `InMemoryProfileStorage` for tests and
+/// `FileProfileStorage` for on-device persistence with version history.
+///
+/// Deletion is soft: `delete` sets `deprecated_after` and the profile stays
+/// readable through `get`, but `list` only returns profiles that are not yet
+/// deprecated.
+///
+/// Backends also persist the revision history of applied policies, which
+/// backs multi-step rollback and the audit trail.
+///
+/// Reads are expected to be served from memory. Mutations may block on disk;
+/// `PolicyEngine` runs them on the blocking thread pool.
+pub trait ProfileStorage: Send + Sync + 'static {
     /// Create a new profile
     fn create(&mut self, profile: PolicyProfile) -> Result<()>;
 
@@ -190,16 +265,105 @@ pub trait ProfileStorage: Send + Sync {
     fn update(&mut self, profile: PolicyProfile) -> Result<()>;
 
     /// Delete a profile (soft delete by setting deprecated_after)
+    ///
+    /// An earlier `deprecated_after` is kept.
     fn delete(&mut self, profile_id: &str) -> Result<()>;
 
-    /// List all profiles, optionally filtered by use case
+    /// List profiles that are not deprecated, optionally filtered by use case
     fn list(&self, use_case: Option<UseCase>) -> Result<Vec<PolicyProfile>>;
+
+    /// Previous versions of a profile, oldest first
+    ///
+    /// Backends without history return an empty list for existing profiles.
+    fn history(&self, profile_id: &str) -> Result<Vec<PolicyProfile>> {
+        self.get(profile_id).map(|_| Vec::new())
+    }
+
+    /// Install `profile`, replacing the stored one only if it is newer
+    ///
+    /// Deletion is final for imports: a deleted profile is never replaced,
+    /// whatever the incoming version, and `ProfileUpgrade::Deleted` is
+    /// returned. Restore it explicitly with `update`.
+    ///
+    /// # Errors
+    /// Returns `PolicyError::Conflict` if a different profile is stored under
+    /// the same `profile_version`.
+    fn upgrade(&mut self, profile: PolicyProfile) -> Result<ProfileUpgrade> {
+        let current = match self.get(&profile.profile_id) {
+            Ok(current) => current,
+            Err(PolicyError::NotFound(_)) => {
+                self.create(profile)?;
+                return Ok(ProfileUpgrade::Created);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if current.is_deprecated() {
+            return Ok(ProfileUpgrade::Deleted {
+                current: current.profile_version,
+            });
+        }
+
+        match profile.profile_version.cmp(&current.profile_version) {
+            Ordering::Greater => {
+                self.update(profile)?;
+                Ok(ProfileUpgrade::Upgraded {
+                    previous: current.profile_version,
+                })
+            }
+            Ordering::Less => Ok(ProfileUpgrade::Outdated {
+                current: current.profile_version,
+            }),
+            Ordering::Equal if profile.canonical_bytes() == current.canonical_bytes() => {
+                Ok(ProfileUpgrade::Unchanged)
+            }
+            Ordering::Equal => Err(PolicyError::Conflict(format!(
+                "Profile {} version {} already exists with different content",
+                profile.profile_id, profile.profile_version
+            ))),
+        }
+    }
+
+    /// Append a revision to its policy's history
+    ///
+    /// # Errors
+    /// Returns `PolicyError::Conflict` unless `revision.version` directly
+    /// follows the last recorded version (1 for a new policy).
+    fn record_revision(&mut self, revision: PolicyRevision) -> Result<()>;
+
+    /// Recorded revisions of a policy, oldest first (empty if none)
+    fn revisions(&self, policy_id: &str) -> Result<Vec<PolicyRevision>>;
+
+    /// Revisions of every policy matching `query`, newest first
+    fn audit(&self, query: &AuditQuery) -> Result<Vec<PolicyRevision>>;
+
+    /// A specific revision of a policy
+    fn revision(&self, policy_id: &str, version: u32) -> Result<PolicyRevision> {
+        self.revisions(policy_id)?
+            .into_iter()
+            .find(|r| r.version == version)
+            .ok_or_else(|| {
+                PolicyError::NotFound(format!(
+                    "Policy {} has no revision {}",
+                    policy_id, version
+                ))
+            })
+    }
+
+    /// Differences between two revisions of a policy
+    fn diff(&self, policy_id: &str, from_version: u32, to_version: u32) -> Result<PolicyDiff> {
+        PolicyDiff::between(
+            &self.revision(policy_id, from_version)?,
+            &self.revision(policy_id, to_version)?,
+        )
+    }
 }
 
 /// In-memory profile storage for testing and development
 #[derive(Default)]
 pub struct InMemoryProfileStorage {
     profiles: HashMap<String, PolicyProfile>,
+    revisions: HashMap<String, Vec<PolicyRevision>>,
 }
 
 impl InMemoryProfileStorage {
@@ -246,7 +410,7 @@ impl ProfileStorage for InMemoryProfileStorage {
 
     fn delete(&mut self, profile_id: &str) -> Result<()> {
         let mut profile = self.get(profile_id)?;
-        profile.deprecated_after = Some(Utc::now());
+        profile.deprecate_now();
         self.profiles.insert(profile_id.to_string(), profile);
         Ok(())
     }
@@ -255,11 +419,28 @@ impl ProfileStorage for InMemoryProfileStorage {
         let profiles: Vec<PolicyProfile> = self
             .profiles
             .values()
+            .filter(|p| !p.is_deprecated())
             .filter(|p| use_case.map_or(true, |uc| p.use_case == uc))
             .cloned()
             .collect();
         Ok(profiles)
     }
+
+    fn record_revision(&mut self, revision: PolicyRevision) -> Result<()> {
+        let history = self
+            .revisions
+            .entry(revision.policy_id().to_string())
+            .or_default();
+        append_revision(history, revision)
+    }
+
+    fn revisions(&self, policy_id: &str) -> Result<Vec<PolicyRevision>> {
+        Ok(self.revisions.get(policy_id).cloned().unwrap_or_default())
+    }
+
+    fn audit(&self, query: &AuditQuery) -> Result<Vec<PolicyRevision>> {
+        Ok(query.apply(self.revisions.values().flatten()))
+    }
 }
 
 #[cfg(test)]
@@ -289,6 +470,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sign_and_verify() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut profile = create_test_profile("signed", UseCase::Gaming);
+        assert!(profile.verify_with(&signing_key.verifying_key()).is_err());
+
+        profile.sign(&signing_key);
+        assert!(profile
+            .verify_signature(signing_key.verifying_key().as_bytes())
+            .is_ok());
+
+        profile.latency_budget_ms += 1;
+        assert!(profile.verify_with(&signing_key.verifying_key()).is_err());
+    }
+
     #[test]
     fn test_profile_validation() {
         let profile = create_test_profile("test", UseCase::IoT);
@@ -341,18 +537,124 @@ mod tests {
         storage.create(create_test_profile("test2", UseCase::Gaming)).unwrap();
         storage.create(create_test_profile("test3", UseCase::IoT)).unwrap();
 
+        // Deleted profile no longer listed
         let all_profiles = storage.list(None).unwrap();
-        assert_eq!(all_profiles.len(), 3);
+        assert_eq!(all_profiles.len(), 2);
 
         let iot_profiles = storage.list(Some(UseCase::IoT)).unwrap();
-        assert_eq!(iot_profiles.len(), 2);
+        assert_eq!(iot_profiles.len(), 1);
     }
 
     #[test]
-    fn test_canonical_message() {
-        let profile = create_test_profile("test", UseCase::IoT);
-        let msg = profile.create_canonical_message();
-        assert!(msg.contains("prof_test"));
-        assert!(msg.contains("IoT"));
+    fn test_delete_keeps_earlier_deprecation() {
+        let mut storage = InMemoryProfileStorage::new();
+        let profile = create_test_profile("old", UseCase::IoT);
+        storage.create(profile.clone()).unwrap();
+        storage.delete(&profile.profile_id).unwrap();
+        let first = storage.get(&profile.profile_id).unwrap().deprecated_after;
+
+        storage.delete(&profile.profile_id).unwrap();
+        assert_eq!(storage.get(&profile.profile_id).unwrap().deprecated_after, first);
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let mut storage = InMemoryProfileStorage::new();
+        let v1 = create_test_profile("up", UseCase::Gaming);
+        assert_eq!(storage.upgrade(v1.clone()).unwrap(), ProfileUpgrade::Created);
+        assert_eq!(storage.upgrade(v1.clone()).unwrap(), ProfileUpgrade::Unchanged);
+
+        let mut v2 = v1.clone();
+        v2.profile_version = Version::new(1, 1, 0);
+        v2.latency_budget_ms = 20;
+        assert_eq!(
+            storage.upgrade(v2.clone()).unwrap(),
+            ProfileUpgrade::Upgraded {
+                previous: Version::new(1, 0, 0)
+            }
+        );
+        assert_eq!(
+            storage.upgrade(v1).unwrap(),
+            ProfileUpgrade::Outdated {
+                current: Version::new(1, 1, 0)
+            }
+        );
+        assert_eq!(storage.get(&v2.profile_id).unwrap().latency_budget_ms, 20);
+
+        v2.latency_budget_ms = 30;
+        assert!(matches!(
+            storage.upgrade(v2.clone()),
+            Err(PolicyError::Conflict(_))
+        ));
+
+        // Deleted profiles are not revived, by the same or a newer version
+        let profile_id = v2.profile_id.clone();
+        storage.delete(&profile_id).unwrap();
+        let mut v3 = v2.clone();
+        v3.profile_version = Version::new(2, 0, 0);
+        for incoming in [v2, v3] {
+            assert_eq!(
+                storage.upgrade(incoming).unwrap(),
+                ProfileUpgrade::Deleted {
+                    current: Version::new(1, 1, 0)
+                }
+            );
+        }
+        assert!(storage.get(&profile_id).unwrap().is_deprecated());
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let mut profile = create_test_profile("test", UseCase::IoT);
+        let bytes = profile.canonical_bytes();
+        assert!(bytes.starts_with(&[0, 0, 0, 24]));
+        assert_eq!(&bytes[4..28], PROFILE_SIGNATURE_DOMAIN.as_bytes());
+        assert_eq!(bytes[28], PROFILE_SIGNATURE_VERSION);
+
+        // Bookkeeping fields are not covered
+        profile.updated_at += chrono::Duration::seconds(1);
+        profile.signature.clear();
+        assert_eq!(profile.canonical_bytes(), bytes);
+
+        // Metadata and deprecation are
+        profile.metadata.insert("owner".to_string(), "ops".to_string());
+        let with_metadata = profile.canonical_bytes();
+        assert_ne!(with_metadata, bytes);
+        profile.deprecated_after = Some(Utc::now());
+        assert_ne!(profile.canonical_bytes(), with_metadata);
+    }
+
+    #[test]
+    fn test_signature_covers_metadata() {
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut profile = create_test_profile("meta", UseCase::ArVr).signed(&signing_key);
+        assert!(profile.verify_with(&signing_key.verifying_key()).is_ok());
+
+        profile.metadata.insert("tier".to_string(), "gold".to_string());
+        assert!(profile.verify_with(&signing_key.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn test_vector_profile_v1() {
+        let vector: serde_json::Value =
+            serde_json::from_str(include_str!("../test-vectors/profile-signature-v1.json")).unwrap();
+        let secret_key: [u8; 32] = hex::decode(vector["secret_key"].as_str().unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut profile: PolicyProfile =
+            serde_json::from_value(vector["profile"].clone()).unwrap();
+
+        assert_eq!(
+            hex::encode(profile.canonical_bytes()),
+            vector["canonical_bytes"].as_str().unwrap()
+        );
+
+        profile.sign_with_bytes(&secret_key);
+        assert_eq!(profile.signature, vector["signature"].as_str().unwrap());
+
+        let public_key = SigningKey::from_bytes(&secret_key).verifying_key();
+        assert_eq!(hex::encode(public_key.as_bytes()), vector["public_key"].as_str().unwrap());
+        assert!(profile.verify_signature(public_key.as_bytes()).is_ok());
     }
 }
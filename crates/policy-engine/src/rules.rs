@@ -0,0 +1,578 @@
+//! Context-aware policy rules
+//!
+//! Declarative rules adjust an applied `QoSPolicyUpdate` to live conditions:
+//! battery level, link quality from the physical adapters, the peer's device
+//! type and time of day. For example:
+//!
+//! ```
+//! use honeylink_policy_engine::rules::{Condition, PolicyRule, RuleAction};
+//! use honeylink_policy_engine::{FecMode, PowerProfile};
+//!
+//! let low_battery = PolicyRule::new(
+//!     "low_battery",
+//!     Condition::BatteryBelow { percent: 20 },
+//!     RuleAction {
+//!         power_profile: Some(PowerProfile::Low),
+//!         fec_mode: Some(FecMode::None),
+//!         ..RuleAction::default()
+//!     },
+//! );
+//! ```
+//!
+//! Rules are applied in order and, for each field, the first active rule that
+//! sets it wins. Hysteresis keeps a rule active (or inactive) until its
+//! condition has changed for the configured duration, so signals hovering
+//! around a threshold do not flap the policy. Hysteresis is tracked per
+//! policy, so sessions sharing a `RulesEngine` do not affect each other.
+//!
+//! The engine does not collect signals itself: the caller builds a
+//! `PolicyContext` from its sources (battery level from the platform,
+//! `PhysicalLayer::get_link_quality` from the active adapter, the peer's
+//! `GattDeviceInfo::device_type` from discovery) and calls
+//! `PolicyEngine::apply_rules` whenever one of them changes, or on the
+//! adapter's link-quality polling interval.
+
+use crate::types::{FecMode, PowerProfile, Priority, QoSPolicyUpdate};
+use chrono::{DateTime, Duration, Timelike, Utc};
+use honeylink_discovery::DeviceType;
+use honeylink_transport::LinkQualityMetrics;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default time a condition must hold before a rule activates (seconds)
+pub const DEFAULT_ACTIVATE_AFTER_SECS: u64 = 0;
+
+/// Default time a condition must be gone before a rule releases (seconds)
+pub const DEFAULT_RELEASE_AFTER_SECS: u64 = 30;
+
+/// Live signals rules are evaluated against, supplied by the caller
+#[derive(Debug, Clone)]
+pub struct PolicyContext {
+    /// Local battery level (0-100), `None` on mains power
+    pub battery_percent: Option<u8>,
+
+    /// Latest link quality reported by the physical adapter
+    pub link_quality: Option<LinkQualityMetrics>,
+
+    /// Device type of the session peer
+    pub peer_device_type: Option<DeviceType>,
+
+    /// Evaluation time
+    pub time: DateTime<Utc>,
+}
+
+impl Default for PolicyContext {
+    fn default() -> Self {
+        Self {
+            battery_percent: None,
+            link_quality: None,
+            peer_device_type: None,
+            time: Utc::now(),
+        }
+    }
+}
+
+impl PolicyContext {
+    /// Create an empty context at the current time
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the battery level
+    pub fn with_battery_percent(mut self, percent: u8) -> Self {
+        self.battery_percent = Some(percent.min(100));
+        self
+    }
+
+    /// Set the link quality
+    pub fn with_link_quality(mut self, metrics: LinkQualityMetrics) -> Self {
+        self.link_quality = Some(metrics);
+        self
+    }
+
+    /// Set the peer device type
+    pub fn with_peer_device_type(mut self, device_type: DeviceType) -> Self {
+        self.peer_device_type = Some(device_type);
+        self
+    }
+
+    /// Set the evaluation time
+    pub fn at(mut self, time: DateTime<Utc>) -> Self {
+        self.time = time;
+        self
+    }
+}
+
+/// Condition a rule is triggered by
+///
+/// Conditions on a missing signal evaluate to false.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "signal", rename_all = "snake_case")]
+pub enum Condition {
+    /// Battery level strictly below `percent`
+    BatteryBelow { percent: u8 },
+
+    /// Link quality reports `LinkQualityMetrics::is_degraded`
+    LinkDegraded,
+
+    /// Packet loss rate strictly above `rate` (0.0-1.0)
+    LossRateAbove { rate: f32 },
+
+    /// Peer has the given device type
+    PeerDeviceType { device_type: DeviceType },
+
+    /// UTC hour within `[start_hour, end_hour)`, wrapping past midnight
+    TimeBetween { start_hour: u8, end_hour: u8 },
+
+    /// All conditions hold
+    All { conditions: Vec<Condition> },
+
+    /// Any condition holds
+    Any { conditions: Vec<Condition> },
+
+    /// Condition does not hold
+    Not { condition: Box<Condition> },
+}
+
+impl Condition {
+    /// Evaluate against `context`
+    pub fn matches(&self, context: &PolicyContext) -> bool {
+        match self {
+            Self::BatteryBelow { percent } => context
+                .battery_percent
+                .is_some_and(|battery| battery < *percent),
+            Self::LinkDegraded => context
+                .link_quality
+                .is_some_and(|metrics| metrics.is_degraded()),
+            Self::LossRateAbove { rate } => context
+                .link_quality
+                .is_some_and(|metrics| metrics.loss_rate > *rate),
+            Self::PeerDeviceType { device_type } => context.peer_device_type == Some(*device_type),
+            Self::TimeBetween {
+                start_hour,
+                end_hour,
+            } => {
+                let hour = context.time.hour() as u8;
+                if start_hour <= end_hour {
+                    (*start_hour..*end_hour).contains(&hour)
+                } else {
+                    hour >= *start_hour || hour < *end_hour
+                }
+            }
+            Self::All { conditions } => conditions.iter().all(|c| c.matches(context)),
+            Self::Any { conditions } => conditions.iter().any(|c| c.matches(context)),
+            Self::Not { condition } => !condition.matches(context),
+        }
+    }
+}
+
+/// Policy adjustments applied while a rule is active
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleAction {
+    /// Override the power profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_profile: Option<PowerProfile>,
+
+    /// Override the FEC mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fec_mode: Option<FecMode>,
+
+    /// Cap bandwidth (floor and ceiling) in Mbps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bandwidth_mbps: Option<f64>,
+
+    /// Override the latency budget in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_budget_ms: Option<u16>,
+
+    /// Override the stream priority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+}
+
+/// Declarative policy rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    /// Unique rule name
+    pub name: String,
+
+    /// Trigger condition
+    pub condition: Condition,
+
+    /// Adjustments while active
+    pub action: RuleAction,
+
+    /// Seconds the condition must hold before the rule activates
+    #[serde(default = "default_activate_after_secs")]
+    pub activate_after_secs: u64,
+
+    /// Seconds the condition must be gone before the rule releases
+    #[serde(default = "default_release_after_secs")]
+    pub release_after_secs: u64,
+}
+
+fn default_activate_after_secs() -> u64 {
+    DEFAULT_ACTIVATE_AFTER_SECS
+}
+
+fn default_release_after_secs() -> u64 {
+    DEFAULT_RELEASE_AFTER_SECS
+}
+
+impl PolicyRule {
+    /// Create a rule with default hysteresis
+    pub fn new(name: impl Into<String>, condition: Condition, action: RuleAction) -> Self {
+        Self {
+            name: name.into(),
+            condition,
+            action,
+            activate_after_secs: DEFAULT_ACTIVATE_AFTER_SECS,
+            release_after_secs: DEFAULT_RELEASE_AFTER_SECS,
+        }
+    }
+
+    /// Set the hysteresis durations in seconds
+    pub fn with_hysteresis(mut self, activate_after_secs: u64, release_after_secs: u64) -> Self {
+        self.activate_after_secs = activate_after_secs;
+        self.release_after_secs = release_after_secs;
+        self
+    }
+}
+
+/// Result of evaluating the rules for one policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleEvaluation {
+    /// Names of the active rules, in rule order
+    pub active_rules: Vec<String>,
+
+    /// Base policy with the active rules applied (unsigned)
+    pub update: QoSPolicyUpdate,
+
+    /// Whether the active rules differ from the previous evaluation
+    pub changed: bool,
+}
+
+/// Hysteresis state of one rule
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    active: bool,
+    /// When the condition started disagreeing with `active`
+    pending_since: Option<DateTime<Utc>>,
+}
+
+/// Evaluates policy rules with hysteresis
+#[derive(Debug, Default)]
+pub struct RulesEngine {
+    rules: Vec<PolicyRule>,
+    /// Hysteresis state keyed by (policy ID, rule name)
+    states: HashMap<(String, String), RuleState>,
+    /// Active rules of each policy at its last evaluation
+    applied: HashMap<String, Vec<String>>,
+}
+
+impl RulesEngine {
+    /// Create an engine with the given rules
+    pub fn new(rules: Vec<PolicyRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    /// Add a rule after the existing ones
+    pub fn with_rule(mut self, rule: PolicyRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Configured rules
+    pub fn rules(&self) -> &[PolicyRule] {
+        &self.rules
+    }
+
+    /// Evaluate rules for `base`, updating hysteresis state
+    ///
+    /// `changed` is true when the set of active rules for `base.policy_id`
+    /// differs from the previous evaluation (initially: no active rules).
+    pub fn evaluate(&mut self, base: &QoSPolicyUpdate, context: &PolicyContext) -> RuleEvaluation {
+        let mut active_rules = Vec::new();
+        for rule in &self.rules {
+            let state = self
+                .states
+                .entry((base.policy_id.clone(), rule.name.clone()))
+                .or_default();
+            if step(state, rule, rule.condition.matches(context), context.time) {
+                active_rules.push(rule.name.clone());
+            }
+        }
+
+        let previous = self
+            .applied
+            .insert(base.policy_id.clone(), active_rules.clone())
+            .unwrap_or_default();
+
+        RuleEvaluation {
+            update: self.apply(base, &active_rules),
+            changed: previous != active_rules,
+            active_rules,
+        }
+    }
+
+    /// Evaluate rules for `base` without hysteresis or state changes
+    ///
+    /// Intended for testing rules: every rule whose condition matches is
+    /// treated as active, and `changed` reports whether the result differs
+    /// from `base`.
+    pub fn dry_run(&self, base: &QoSPolicyUpdate, context: &PolicyContext) -> RuleEvaluation {
+        let active_rules: Vec<String> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.condition.matches(context))
+            .map(|rule| rule.name.clone())
+            .collect();
+
+        let update = self.apply(base, &active_rules);
+        RuleEvaluation {
+            changed: update != *base,
+            update,
+            active_rules,
+        }
+    }
+
+    /// Forget the applied rules and hysteresis state of a policy (e.g. once
+    /// it is invalidated)
+    pub fn forget(&mut self, policy_id: &str) {
+        self.applied.remove(policy_id);
+        self.states.retain(|(id, _), _| id != policy_id);
+    }
+
+    /// Apply the actions of `active_rules` to `base`
+    fn apply(&self, base: &QoSPolicyUpdate, active_rules: &[String]) -> QoSPolicyUpdate {
+        let mut action = RuleAction::default();
+        for rule in self
+            .rules
+            .iter()
+            .filter(|rule| active_rules.contains(&rule.name))
+        {
+            let a = &rule.action;
+            action.power_profile = action.power_profile.or(a.power_profile);
+            action.fec_mode = action.fec_mode.or(a.fec_mode);
+            action.max_bandwidth_mbps = action.max_bandwidth_mbps.or(a.max_bandwidth_mbps);
+            action.latency_budget_ms = action.latency_budget_ms.or(a.latency_budget_ms);
+            action.priority = action.priority.or(a.priority);
+        }
+
+        let mut update = base.clone();
+        if let Some(power_profile) = action.power_profile {
+            update.power_profile = Some(power_profile);
+        }
+        if let Some(fec_mode) = action.fec_mode {
+            update.fec_mode = fec_mode;
+        }
+        if let Some(cap) = action.max_bandwidth_mbps {
+            update.bandwidth_floor_mbps = update.bandwidth_floor_mbps.min(cap);
+            update.bandwidth_ceiling_mbps = Some(
+                update
+                    .bandwidth_ceiling_mbps
+                    .map_or(cap, |ceiling| ceiling.min(cap)),
+            );
+        }
+        if let Some(latency_budget_ms) = action.latency_budget_ms {
+            update.latency_budget_ms = latency_budget_ms;
+        }
+        if let Some(priority) = action.priority {
+            update.priority = priority;
+        }
+        update
+    }
+}
+
+/// Advance a rule's hysteresis state; returns whether it is active
+fn step(state: &mut RuleState, rule: &PolicyRule, matches: bool, now: DateTime<Utc>) -> bool {
+    if matches == state.active {
+        state.pending_since = None;
+        return state.active;
+    }
+
+    let hold_secs = if matches {
+        rule.activate_after_secs
+    } else {
+        rule.release_after_secs
+    };
+    let since = *state.pending_since.get_or_insert(now);
+
+    if now - since >= Duration::seconds(hold_secs as i64) {
+        state.active = matches;
+        state.pending_since = None;
+    }
+    state.active
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::tests::create_test_policy;
+
+    fn base_policy() -> QoSPolicyUpdate {
+        create_test_policy("rules")
+    }
+
+    fn low_battery() -> PolicyRule {
+        PolicyRule::new(
+            "low_battery",
+            Condition::BatteryBelow { percent: 20 },
+            RuleAction {
+                power_profile: Some(PowerProfile::Low),
+                fec_mode: Some(FecMode::None),
+                ..RuleAction::default()
+            },
+        )
+    }
+
+    fn degraded_link() -> PolicyRule {
+        PolicyRule::new(
+            "degraded_link",
+            Condition::LinkDegraded,
+            RuleAction {
+                fec_mode: Some(FecMode::Heavy),
+                ..RuleAction::default()
+            },
+        )
+    }
+
+    fn iot_peer() -> PolicyRule {
+        PolicyRule::new(
+            "iot_peer",
+            Condition::PeerDeviceType {
+                device_type: DeviceType::Iot,
+            },
+            RuleAction {
+                max_bandwidth_mbps: Some(1.0),
+                ..RuleAction::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_dry_run_applies_matching_rules_in_order() {
+        let engine = RulesEngine::new(vec![low_battery(), degraded_link(), iot_peer()]);
+        let degraded = LinkQualityMetrics {
+            loss_rate: 0.2,
+            ..LinkQualityMetrics::new()
+        };
+        let context = PolicyContext::new()
+            .with_battery_percent(10)
+            .with_link_quality(degraded)
+            .with_peer_device_type(DeviceType::Iot);
+
+        let result = engine.dry_run(&base_policy(), &context);
+        assert_eq!(
+            result.active_rules,
+            vec!["low_battery", "degraded_link", "iot_peer"]
+        );
+        assert!(result.changed);
+        // low_battery comes first and wins the FEC mode
+        assert_eq!(result.update.fec_mode, FecMode::None);
+        assert_eq!(result.update.power_profile, Some(PowerProfile::Low));
+        assert_eq!(result.update.bandwidth_floor_mbps, 1.0);
+        assert_eq!(result.update.bandwidth_ceiling_mbps, Some(1.0));
+        assert!(result.update.validate().is_ok());
+
+        let idle = engine.dry_run(&base_policy(), &PolicyContext::new());
+        assert!(idle.active_rules.is_empty());
+        assert!(!idle.changed);
+    }
+
+    #[test]
+    fn test_hysteresis_prevents_flapping() {
+        let mut engine = RulesEngine::new(vec![low_battery().with_hysteresis(0, 60)]);
+        let base = base_policy();
+        let start = Utc::now();
+        let at = |secs: i64, battery: u8| {
+            PolicyContext::new()
+                .with_battery_percent(battery)
+                .at(start + Duration::seconds(secs))
+        };
+
+        let result = engine.evaluate(&base, &at(0, 19));
+        assert!(result.changed);
+        assert_eq!(result.update.power_profile, Some(PowerProfile::Low));
+
+        // Battery hovers around the threshold: stays active
+        assert!(!engine.evaluate(&base, &at(10, 21)).changed);
+        assert!(!engine.evaluate(&base, &at(20, 19)).changed);
+        assert!(!engine.evaluate(&base, &at(30, 25)).changed);
+
+        // Released only after 60s above the threshold
+        let result = engine.evaluate(&base, &at(89, 25));
+        assert!(!result.changed);
+        let result = engine.evaluate(&base, &at(90, 25));
+        assert!(result.changed);
+        assert_eq!(result.update, base);
+    }
+
+    #[test]
+    fn test_hysteresis_is_per_policy() {
+        let mut engine = RulesEngine::new(vec![low_battery().with_hysteresis(0, 60)]);
+        let first = base_policy();
+        let mut second = base_policy();
+        second.policy_id = "pol_rules_2".to_string();
+        let start = Utc::now();
+        let at = |secs: i64, battery: u8| {
+            PolicyContext::new()
+                .with_battery_percent(battery)
+                .at(start + Duration::seconds(secs))
+        };
+
+        assert!(engine.evaluate(&first, &at(0, 19)).changed);
+
+        // The second policy has not seen low battery, so nothing holds it active
+        let result = engine.evaluate(&second, &at(10, 25));
+        assert!(result.active_rules.is_empty());
+        assert!(!result.changed);
+        assert_eq!(
+            engine.evaluate(&first, &at(10, 25)).active_rules,
+            ["low_battery"]
+        );
+
+        // Forgetting a policy drops its hysteresis state too
+        engine.forget(&first.policy_id);
+        assert!(engine.evaluate(&first, &at(20, 25)).active_rules.is_empty());
+    }
+
+    #[test]
+    fn test_time_window_wraps_midnight() {
+        let night = Condition::TimeBetween {
+            start_hour: 22,
+            end_hour: 6,
+        };
+        let at_hour = |hour: u32| PolicyContext::new().at(Utc::now().with_hour(hour).unwrap());
+
+        assert!(night.matches(&at_hour(23)));
+        assert!(night.matches(&at_hour(3)));
+        assert!(!night.matches(&at_hour(12)));
+    }
+
+    #[test]
+    fn test_rules_deserialize() {
+        let json = r#"[
+            {
+                "name": "night_saver",
+                "condition": {
+                    "signal": "all",
+                    "conditions": [
+                        { "signal": "time_between", "start_hour": 22, "end_hour": 6 },
+                        { "signal": "not", "condition": { "signal": "peer_device_type", "device_type": "desktop" } }
+                    ]
+                },
+                "action": { "power_profile": "ultra_low" },
+                "release_after_secs": 120
+            }
+        ]"#;
+
+        let rules: Vec<PolicyRule> = serde_json::from_str(json).unwrap();
+        assert_eq!(rules[0].activate_after_secs, DEFAULT_ACTIVATE_AFTER_SECS);
+        assert_eq!(rules[0].release_after_secs, 120);
+        assert_eq!(rules[0].action.power_profile, Some(PowerProfile::UltraLow));
+    }
+}
@@ -217,6 +217,64 @@ impl PolicyTelemetry {
 
         Ok(())
     }
+
+    /// Record an applied policy approaching its `deprecated_after` time
+    ///
+    /// # Arguments
+    /// * `policy_id` - Policy identifier
+    /// * `profile_id` - Profile the policy was created from
+    /// * `remaining_secs` - Seconds left until deprecation
+    pub async fn record_policy_deprecation_warning(
+        &self,
+        policy_id: &str,
+        profile_id: &str,
+        remaining_secs: i64,
+    ) -> Result<()> {
+        let labels = vec![
+            ("policy_id".to_string(), policy_id.to_string()),
+            ("profile_id".to_string(), profile_id.to_string()),
+        ];
+
+        let metric = Metric::new(
+            "policy_deprecation_remaining_seconds".to_string(),
+            MetricType::Gauge,
+            remaining_secs as f64,
+            labels,
+        );
+
+        self.collector
+            .record_metric(metric)
+            .await
+            .map_err(|e| crate::error::PolicyError::EventBus(format!("Telemetry error: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Record a policy lifecycle transition
+    ///
+    /// # Arguments
+    /// * `policy_id` - Policy identifier
+    /// * `event` - Lifecycle event: "renewed", "expired", "deprecated"
+    pub async fn record_policy_lifecycle(&self, policy_id: &str, event: &str) -> Result<()> {
+        let labels = vec![
+            ("policy_id".to_string(), policy_id.to_string()),
+            ("event".to_string(), event.to_string()),
+        ];
+
+        let metric = Metric::new(
+            "policy_lifecycle_events_total".to_string(),
+            MetricType::Counter,
+            1.0,
+            labels,
+        );
+
+        self.collector
+            .record_metric(metric)
+            .await
+            .map_err(|e| crate::error::PolicyError::EventBus(format!("Telemetry error: {}", e)))?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -314,4 +372,23 @@ mod tests {
             .await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_record_policy_lifecycle() {
+        let mut collector = TelemetryCollector::new();
+        collector
+            .initialize(TelemetryConfig::default())
+            .await
+            .unwrap();
+        let telemetry = PolicyTelemetry::new(Arc::new(collector));
+
+        assert!(telemetry
+            .record_policy_deprecation_warning("policy-789", "prof_gaming_input_v1", 3600)
+            .await
+            .is_ok());
+        assert!(telemetry
+            .record_policy_lifecycle("policy-789", "renewed")
+            .await
+            .is_ok());
+    }
 }
@@ -3,10 +3,18 @@
 //! Implements the QoSPolicyUpdate schema and related types according to
 //! spec/architecture/interfaces.md and spec/modules/policy-profile-engine.md
 
+use crate::canonical::CanonicalEncoder;
 use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use semver::Version;
 
+/// Domain separation tag of the `QoSPolicyUpdate` signing encoding
+const UPDATE_SIGNATURE_DOMAIN: &str = "honeylink-qos-policy-update";
+
+/// Version of the `QoSPolicyUpdate` signing encoding
+const UPDATE_SIGNATURE_VERSION: u8 = 1;
+
 /// FEC (Forward Error Correction) mode enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -70,7 +78,7 @@ pub enum UseCase {
 ///
 /// This is the primary event type for policy distribution according to
 /// spec/architecture/interfaces.md
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QoSPolicyUpdate {
     /// Schema version (SemVer) for compatibility checking
     pub schema_version: Version,
@@ -84,6 +92,10 @@ pub struct QoSPolicyUpdate {
     /// Stream ID (0-7) to apply this policy to
     pub stream_id: u8,
 
+    /// Device this policy is issued for (None = any device)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+
     /// Target latency budget in milliseconds
     pub latency_budget_ms: u16,
 
@@ -111,7 +123,7 @@ pub struct QoSPolicyUpdate {
     /// Policy expiration timestamp (TTL)
     pub expiration_ts: DateTime<Utc>,
 
-    /// Ed25519 signature (base64-encoded) for integrity verification
+    /// Ed25519 signature (base64-encoded) by the issuing policy authority
     pub signature: String,
 }
 
@@ -202,6 +214,54 @@ impl QoSPolicyUpdate {
         Ok(())
     }
 
+    /// Sign the update with a policy-authority key (replaces `signature`)
+    pub fn sign(&mut self, authority_key: &SigningKey) {
+        use base64::Engine;
+        let signature = authority_key.sign(&self.canonical_bytes());
+        self.signature = base64::engine::general_purpose::STANDARD.encode(signature.to_bytes());
+    }
+
+    /// Verify the Ed25519 signature against a policy-authority key
+    ///
+    /// Only checks the signature; use `PolicyVerifier` to also reject
+    /// invalid or expired updates.
+    pub fn verify_signature(&self, authority_key: &VerifyingKey) -> crate::error::Result<()> {
+        use crate::error::PolicyError;
+        use base64::Engine;
+
+        let signature_bytes: [u8; 64] = base64::engine::general_purpose::STANDARD
+            .decode(&self.signature)
+            .map_err(|e| PolicyError::SignatureInvalid(format!("Invalid base64: {}", e)))?
+            .try_into()
+            .map_err(|_| PolicyError::SignatureInvalid("Signature must be 64 bytes".to_string()))?;
+
+        authority_key
+            .verify(&self.canonical_bytes(), &Signature::from_bytes(&signature_bytes))
+            .map_err(|e| PolicyError::SignatureInvalid(format!("Signature verification failed: {}", e)))
+    }
+
+    /// Canonical encoding covered by the signature
+    ///
+    /// Every field except `signature`, in declaration order, using the
+    /// layout described in `crate::canonical`.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        CanonicalEncoder::new(UPDATE_SIGNATURE_DOMAIN, UPDATE_SIGNATURE_VERSION)
+            .str(&self.schema_version.to_string())
+            .str(&self.policy_id)
+            .str(&self.profile_id)
+            .u8(self.stream_id)
+            .option(self.device_id.as_deref(), CanonicalEncoder::str)
+            .u16(self.latency_budget_ms)
+            .f64(self.bandwidth_floor_mbps)
+            .option(self.bandwidth_ceiling_mbps, CanonicalEncoder::f64)
+            .fec_mode(self.fec_mode)
+            .u8(self.priority)
+            .option(self.power_profile, CanonicalEncoder::power_profile)
+            .option(self.deprecated_after.as_ref(), CanonicalEncoder::time)
+            .time(&self.expiration_ts)
+            .finish()
+    }
+
     /// Check if this policy is compatible with a given schema version
     ///
     /// Uses SemVer rules: major version must match, minor/patch can be newer
@@ -222,6 +282,7 @@ mod tests {
             policy_id: "pol_test123".to_string(),
             profile_id: "prof_iot_lowpower_v2".to_string(),
             stream_id: 3,
+            device_id: Some("DEV-TEST".to_string()),
             latency_budget_ms: 50,
             bandwidth_floor_mbps: 0.5,
             bandwidth_ceiling_mbps: Some(2.0),
@@ -292,6 +353,33 @@ mod tests {
         assert!(!policy.is_compatible_with(&Version::new(1, 3, 0)));
     }
 
+    #[test]
+    fn test_sign_and_verify() {
+        let authority = SigningKey::from_bytes(&[9u8; 32]);
+        let mut policy = create_valid_policy();
+        assert!(policy.verify_signature(&authority.verifying_key()).is_err());
+
+        policy.sign(&authority);
+        assert!(policy.verify_signature(&authority.verifying_key()).is_ok());
+
+        // Survives a JSON roundtrip
+        let decoded: QoSPolicyUpdate =
+            serde_json::from_str(&serde_json::to_string(&policy).unwrap()).unwrap();
+        assert!(decoded.verify_signature(&authority.verifying_key()).is_ok());
+
+        // Wrong key or any modified field fails
+        let other = SigningKey::from_bytes(&[10u8; 32]);
+        assert!(policy.verify_signature(&other.verifying_key()).is_err());
+
+        let mut tampered = policy.clone();
+        tampered.device_id = Some("DEV-OTHER".to_string());
+        assert!(tampered.verify_signature(&authority.verifying_key()).is_err());
+
+        let mut tampered = policy.clone();
+        tampered.expiration_ts += Duration::hours(1);
+        assert!(tampered.verify_signature(&authority.verifying_key()).is_err());
+    }
+
     #[test]
     fn test_fec_mode_serialization() {
         let json = serde_json::to_string(&FecMode::Light).unwrap();
@@ -0,0 +1,360 @@
+//! Verification of received policy updates
+//!
+//! Subscribers only apply a `QoSPolicyUpdate` that passes validation
+//! (including TTL and deprecation) and carries a valid signature from one of
+//! their trusted policy authorities. Invalidations must have been raised
+//! locally or be signed by a trusted authority. Every decision is reported
+//! through `PolicyTelemetry::record_policy_validation` when telemetry is
+//! attached.
+//!
+//! `PolicyVerifier::subscribe` wraps a `PolicyEventBus` subscription and only
+//! yields events that pass these checks.
+
+use crate::error::{PolicyError, Result};
+use crate::event_bus::{InvalidationOrigin, PolicyEvent, PolicyEventBus};
+use crate::telemetry::PolicyTelemetry;
+use crate::types::QoSPolicyUpdate;
+use ed25519_dalek::VerifyingKey;
+use tokio::sync::broadcast;
+
+/// Checks policy updates against trusted policy-authority keys
+#[derive(Clone, Default)]
+pub struct PolicyVerifier {
+    /// Authority public keys whose signatures are accepted
+    authorities: Vec<VerifyingKey>,
+
+    /// Optional telemetry for accepted/rejected updates
+    telemetry: Option<PolicyTelemetry>,
+}
+
+impl PolicyVerifier {
+    /// Create a verifier that trusts no authority yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust an additional policy authority
+    pub fn with_authority(mut self, authority_key: VerifyingKey) -> Self {
+        self.add_authority(authority_key);
+        self
+    }
+
+    /// Record validation results via telemetry
+    pub fn with_telemetry(mut self, telemetry: PolicyTelemetry) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Trust an additional policy authority
+    pub fn add_authority(&mut self, authority_key: VerifyingKey) {
+        if !self.authorities.contains(&authority_key) {
+            self.authorities.push(authority_key);
+        }
+    }
+
+    /// Stop trusting a policy authority
+    ///
+    /// # Returns
+    /// true if the key was trusted
+    pub fn remove_authority(&mut self, authority_key: &VerifyingKey) -> bool {
+        let before = self.authorities.len();
+        self.authorities.retain(|key| key != authority_key);
+        self.authorities.len() != before
+    }
+
+    /// Trusted authority keys
+    pub fn authorities(&self) -> &[VerifyingKey] {
+        &self.authorities
+    }
+
+    /// Check that an update is valid, unexpired and signed by a trusted authority
+    ///
+    /// # Errors
+    /// * `PolicyError::Validation` / `PolicyError::Deprecated` - invalid, expired or deprecated update
+    /// * `PolicyError::SignatureInvalid` - no trusted authority signed the update
+    pub fn verify(&self, update: &QoSPolicyUpdate) -> Result<()> {
+        update.validate()?;
+
+        if self
+            .authorities
+            .iter()
+            .any(|key| update.verify_signature(key).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(PolicyError::SignatureInvalid(format!(
+                "{} is not signed by a trusted policy authority",
+                update.policy_id
+            )))
+        }
+    }
+
+    /// Verify an update and record the result
+    ///
+    /// Telemetry failures are ignored so they never change the decision.
+    pub async fn accept(&self, update: &QoSPolicyUpdate) -> Result<()> {
+        let result = self.verify(update);
+
+        if let Some(telemetry) = &self.telemetry {
+            let error_count = u32::from(result.is_err());
+            let _ = telemetry
+                .record_policy_validation(&update.policy_id, result.is_ok(), error_count)
+                .await;
+        }
+
+        result
+    }
+
+    /// Check that an invalidation was raised locally or signed by a trusted authority
+    ///
+    /// # Errors
+    /// `PolicyError::SignatureInvalid` if no trusted authority signed a
+    /// remote invalidation
+    pub fn verify_invalidation(&self, policy_id: &str, origin: &InvalidationOrigin) -> Result<()> {
+        if *origin == InvalidationOrigin::Local
+            || self
+                .authorities
+                .iter()
+                .any(|key| origin.verify(policy_id, key).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(PolicyError::SignatureInvalid(format!(
+                "Invalidation of {} is not signed by a trusted policy authority",
+                policy_id
+            )))
+        }
+    }
+
+    /// Verify an event before acting on it
+    ///
+    /// `Update` and `Rollback` snapshots are verified like `accept`,
+    /// invalidations with `verify_invalidation`. `Revocation` events always
+    /// pass here; subscribers install them with `TrustStore::apply_revocations`,
+    /// which checks the issuer.
+    pub async fn accept_event(&self, event: &PolicyEvent) -> Result<()> {
+        match event {
+            PolicyEvent::Update(update) => self.accept(update).await,
+            PolicyEvent::Rollback { snapshot, .. } => self.accept(snapshot).await,
+            PolicyEvent::Invalidate { policy_id, origin } => {
+                let result = self.verify_invalidation(policy_id, origin);
+                if let Some(telemetry) = &self.telemetry {
+                    let _ = telemetry
+                        .record_policy_validation(
+                            policy_id,
+                            result.is_ok(),
+                            u32::from(result.is_err()),
+                        )
+                        .await;
+                }
+                result
+            }
+            PolicyEvent::Revocation(_) => Ok(()),
+        }
+    }
+
+    /// Subscribe to `bus`, receiving only events that pass `accept_event`
+    pub fn subscribe(&self, bus: &PolicyEventBus) -> VerifiedSubscription {
+        VerifiedSubscription {
+            receiver: bus.subscribe(),
+            verifier: self.clone(),
+            rejected: 0,
+        }
+    }
+}
+
+/// Policy event subscription that drops events failing verification
+pub struct VerifiedSubscription {
+    receiver: broadcast::Receiver<PolicyEvent>,
+    verifier: PolicyVerifier,
+    rejected: u64,
+}
+
+impl VerifiedSubscription {
+    /// Wait for the next event that passes verification
+    ///
+    /// Rejected events are skipped and counted in `rejected()`.
+    ///
+    /// # Errors
+    /// `PolicyError::EventBus` if the bus was closed or this subscriber
+    /// lagged behind and missed events
+    pub async fn recv(&mut self) -> Result<PolicyEvent> {
+        loop {
+            let event = self
+                .receiver
+                .recv()
+                .await
+                .map_err(|e| PolicyError::EventBus(format!("Failed to receive: {}", e)))?;
+            match self.verifier.accept_event(&event).await {
+                Ok(()) => return Ok(event),
+                Err(_) => self.rejected += 1,
+            }
+        }
+    }
+
+    /// Number of events dropped because they failed verification
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_bus::tests::create_test_policy;
+    use chrono::{Duration, Utc};
+    use ed25519_dalek::SigningKey;
+    use honeylink_telemetry::TelemetryCollector;
+    use std::sync::Arc;
+
+    fn signed_policy(authority: &SigningKey) -> QoSPolicyUpdate {
+        let mut policy = create_test_policy("verify");
+        policy.sign(authority);
+        policy
+    }
+
+    #[tokio::test]
+    async fn test_accepts_only_trusted_authorities() {
+        let authority = SigningKey::from_bytes(&[1u8; 32]);
+        let stranger = SigningKey::from_bytes(&[2u8; 32]);
+
+        let collector = TelemetryCollector::new();
+        let verifier = PolicyVerifier::new()
+            .with_authority(authority.verifying_key())
+            .with_telemetry(PolicyTelemetry::new(Arc::new(collector)));
+
+        assert!(verifier.accept(&signed_policy(&authority)).await.is_ok());
+        assert!(matches!(
+            verifier.accept(&signed_policy(&stranger)).await,
+            Err(PolicyError::SignatureInvalid(_))
+        ));
+
+        // Forged policy string from older engines is rejected
+        let mut forged = signed_policy(&authority);
+        forged.signature = "policy:abc:device:DEV-TEST".to_string();
+        assert!(verifier.accept(&forged).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_expired_and_tampered_updates() {
+        let authority = SigningKey::from_bytes(&[1u8; 32]);
+        let verifier = PolicyVerifier::new().with_authority(authority.verifying_key());
+
+        let mut expired = signed_policy(&authority);
+        expired.expiration_ts = Utc::now() - Duration::minutes(1);
+        expired.sign(&authority);
+        assert!(matches!(
+            verifier.verify(&expired),
+            Err(PolicyError::Validation(_))
+        ));
+
+        let mut tampered = signed_policy(&authority);
+        tampered.priority = 7;
+        assert!(verifier.verify(&tampered).is_err());
+
+        let event = PolicyEvent::Rollback {
+            policy_id: tampered.policy_id.clone(),
+            snapshot: tampered,
+        };
+        assert!(verifier.accept_event(&event).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalidations_need_local_origin_or_trusted_signature() {
+        let authority = SigningKey::from_bytes(&[1u8; 32]);
+        let stranger = SigningKey::from_bytes(&[2u8; 32]);
+        let verifier = PolicyVerifier::new().with_authority(authority.verifying_key());
+        let invalidate = |origin| PolicyEvent::Invalidate {
+            policy_id: "pol_verify".to_string(),
+            origin,
+        };
+
+        assert!(verifier
+            .accept_event(&invalidate(InvalidationOrigin::Local))
+            .await
+            .is_ok());
+        assert!(verifier
+            .accept_event(&invalidate(InvalidationOrigin::signed(
+                "pol_verify",
+                &authority
+            )))
+            .await
+            .is_ok());
+        assert!(verifier
+            .accept_event(&invalidate(InvalidationOrigin::signed(
+                "pol_verify",
+                &stranger
+            )))
+            .await
+            .is_err());
+        // Signatures are bound to the policy ID
+        assert!(verifier
+            .accept_event(&invalidate(InvalidationOrigin::signed(
+                "pol_other",
+                &authority
+            )))
+            .await
+            .is_err());
+        assert!(verifier
+            .accept_event(&invalidate(InvalidationOrigin::Signed(
+                "forged".to_string()
+            )))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verified_subscription_drops_rejected_events() {
+        let authority = SigningKey::from_bytes(&[1u8; 32]);
+        let stranger = SigningKey::from_bytes(&[2u8; 32]);
+        let bus = PolicyEventBus::new();
+        let mut events = PolicyVerifier::new()
+            .with_authority(authority.verifying_key())
+            .subscribe(&bus);
+
+        bus.publish_update(signed_policy(&stranger)).await.unwrap();
+        bus.publish_remote_invalidate(
+            "pol_verify",
+            InvalidationOrigin::signed("pol_verify", &stranger),
+        )
+        .await
+        .unwrap();
+        bus.publish_update(signed_policy(&authority)).await.unwrap();
+        bus.publish_remote_invalidate(
+            "pol_verify",
+            InvalidationOrigin::signed("pol_verify", &authority),
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            PolicyEvent::Update(_)
+        ));
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            PolicyEvent::Invalidate { .. }
+        ));
+        assert_eq!(events.rejected(), 2);
+
+        // Unsigned remote invalidations are refused by the bus itself
+        assert!(bus
+            .publish_remote_invalidate("pol_verify", InvalidationOrigin::Local)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_authority_management() {
+        let key = SigningKey::from_bytes(&[3u8; 32]).verifying_key();
+        let mut verifier = PolicyVerifier::new().with_authority(key);
+        verifier.add_authority(key);
+        assert_eq!(verifier.authorities().len(), 1);
+
+        assert!(verifier.remove_authority(&key));
+        assert!(!verifier.remove_authority(&key));
+        assert!(verifier
+            .verify(&signed_policy(&SigningKey::from_bytes(&[3u8; 32])))
+            .is_err());
+    }
+}
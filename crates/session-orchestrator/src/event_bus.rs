@@ -2,9 +2,15 @@
 //!
 //! Publishes events to subscribers (Crypto, Policy Engine, Telemetry)
 //! using tokio mpsc channels per spec/modules/session-orchestrator.md
+//!
+//! State changes are also forwarded to the shared `honeylink_core::EventBus`
+//! when one is attached with `with_core_bus`.
 
 use chrono::{DateTime, Utc};
+use honeylink_core::events::{Event, SessionPhase};
+use honeylink_core::types::SessionId;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
@@ -97,31 +103,83 @@ impl SessionEvent {
             Self::SessionError { .. } => "session.error",
         }
     }
+
+    /// Equivalent event on the shared core bus, if any
+    pub fn to_core_event(&self) -> Option<Event> {
+        match self {
+            Self::SessionStateChanged {
+                session_id,
+                from_state,
+                to_state,
+                ..
+            } => Some(Event::SessionStateChanged {
+                session_id: SessionId::from_uuid(*session_id),
+                from: (*from_state).into(),
+                to: (*to_state).into(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<SessionState> for SessionPhase {
+    fn from(state: SessionState) -> Self {
+        match state {
+            SessionState::Pending => Self::Pending,
+            SessionState::Paired => Self::Paired,
+            SessionState::Active => Self::Active,
+            SessionState::Suspended => Self::Suspended,
+            SessionState::Closed => Self::Closed,
+        }
+    }
 }
 
 /// Event bus for publishing session events
 pub struct EventBus {
     sender: broadcast::Sender<SessionEvent>,
+
+    /// Shared bus receiving state changes
+    core_bus: Option<Arc<honeylink_core::EventBus>>,
 }
 
 impl EventBus {
     /// Create new event bus with default capacity
     pub fn new() -> Self {
-        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
-        Self { sender }
+        Self::with_capacity(EVENT_CHANNEL_CAPACITY)
     }
 
     /// Create event bus with custom capacity
     pub fn with_capacity(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender }
+        Self {
+            sender,
+            core_bus: None,
+        }
+    }
+
+    /// Forward state changes to the shared core event bus
+    pub fn with_core_bus(mut self, core_bus: Arc<honeylink_core::EventBus>) -> Self {
+        self.core_bus = Some(core_bus);
+        self
     }
 
     /// Publish event to all subscribers
     ///
     /// # Errors
-    /// Returns `Error::EventBusError` if no subscribers are listening
+    /// Returns `Error::EventBusError` if no subscribers are listening (local
+    /// subscribers are optional once a core bus is attached) or the core bus
+    /// fails to journal the event
     pub fn publish(&self, event: SessionEvent) -> Result<()> {
+        if let Some(core_bus) = &self.core_bus {
+            if let Some(core_event) = event.to_core_event() {
+                core_bus
+                    .publish(core_event)
+                    .map_err(|e| Error::EventBusError(e.to_string()))?;
+            }
+            let _ = self.sender.send(event);
+            return Ok(());
+        }
+
         self.sender.send(event).map_err(|e| {
             Error::EventBusError(format!("No subscribers listening: {}", e))
         })?;
@@ -215,6 +273,42 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_state_changes_forwarded_to_core_bus() {
+        use honeylink_core::events::Topic;
+
+        let core_bus = Arc::new(honeylink_core::EventBus::new());
+        let mut sessions = core_bus.subscribe([Topic::Session]);
+        let bus = EventBus::new().with_core_bus(Arc::clone(&core_bus));
+
+        let session_id = Uuid::now_v7();
+        bus.publish(SessionEvent::SessionStateChanged {
+            session_id,
+            from_state: SessionState::Paired,
+            to_state: SessionState::Active,
+            timestamp: Utc::now(),
+            trace_id: "trace123".to_string(),
+        })
+        .unwrap();
+        bus.publish(SessionEvent::SessionActivity {
+            session_id,
+            activity_type: "heartbeat".to_string(),
+            timestamp: Utc::now(),
+        })
+        .unwrap();
+
+        let received = sessions.recv().await.unwrap();
+        assert_eq!(
+            received.event,
+            Event::SessionStateChanged {
+                session_id: SessionId::from_uuid(session_id),
+                from: SessionPhase::Paired,
+                to: SessionPhase::Active,
+            }
+        );
+        assert!(sessions.try_recv().unwrap().is_none());
+    }
+
     #[test]
     fn test_session_event_accessors() {
         let session_id = Uuid::now_v7();
@@ -9,6 +9,9 @@
 //! - Idempotency-key support (24h retention)
 //! - TTL management (12h default + 30min sliding window)
 //! - SemVer protocol version negotiation
+//! - Peer-to-peer QoS policy negotiation (Paired → Active/Closed)
+//! - Durable session storage (redb) with restart recovery
+//! - Session resumption tickets (reconnect without full handshake)
 //! - Event bus integration (tokio broadcast channels)
 //! - OpenTelemetry metrics
 
@@ -16,7 +19,11 @@ pub mod error;
 pub mod event_bus;
 pub mod idempotency;
 pub mod metrics;
+pub mod negotiation;
 pub mod persistence;
+pub mod recovery;
+pub mod redb_store;
+pub mod resumption;
 pub mod session;
 pub mod state_machine;
 pub mod telemetry;
@@ -26,7 +33,14 @@ pub use error::{Error, Result};
 pub use event_bus::{EventBus, SessionEvent};
 pub use idempotency::{IdempotencyRecord, IdempotencyStore};
 pub use metrics::Metrics;
+pub use negotiation::{NegotiatedSession, NegotiationChannel, SessionPolicyNegotiation};
 pub use persistence::{InMemorySessionStore, SessionStore};
+pub use recovery::{recover_sessions, RecoveryReport};
+pub use redb_store::RedbSessionStore;
+pub use resumption::{
+    IssuedTicket, ResumedSession, ResumptionRequest, ResumptionTicketIssuer,
+    DEFAULT_SUSPEND_TIMEOUT,
+};
 pub use session::Session;
 pub use state_machine::{SessionState, SessionStateMachine, TransitionEvent};
 pub use telemetry::SessionTelemetry;
@@ -0,0 +1,480 @@
+//! Policy negotiation during session setup
+//!
+//! Runs the `PolicyNegotiator` exchange between two paired devices, applies
+//! the agreed `QoSPolicyUpdate` through the local `PolicyEngine`, and moves the
+//! session from Paired to Active (`PolicyApplied`) or Closed (`PolicyRejected`).
+//! With `with_resumption` a resumption ticket is issued for the now Active
+//! session, to be handed to the peer over the established session.
+
+use async_trait::async_trait;
+use honeylink_policy_engine::{
+    NegotiationMessage, NegotiationOutcome, PolicyEngine, PolicyNegotiator, PolicyProfile,
+    PolicyProposal, ProfileStorage, QoSPolicyUpdate,
+};
+
+use crate::error::{Error, Result};
+use crate::resumption::{IssuedTicket, ResumptionTicketIssuer};
+use crate::session::Session;
+use crate::state_machine::{SessionState, SessionStateMachine, TransitionEvent};
+
+/// Message channel to the peer device
+///
+/// Implemented over whatever link carries session control traffic.
+#[async_trait]
+pub trait NegotiationChannel: Send {
+    /// Send a negotiation message to the peer
+    async fn send(&mut self, message: NegotiationMessage) -> Result<()>;
+
+    /// Receive the next negotiation message from the peer
+    async fn recv(&mut self) -> Result<NegotiationMessage>;
+}
+
+/// Result of a successful negotiation
+#[derive(Debug)]
+pub struct NegotiatedSession {
+    /// Policy agreed with the peer and applied locally
+    pub policy: QoSPolicyUpdate,
+
+    /// Resumption ticket for the session (only with `with_resumption`)
+    pub ticket: Option<IssuedTicket>,
+}
+
+/// Negotiates and applies the session policy on one side of a session
+pub struct SessionPolicyNegotiation<'a, S: ProfileStorage> {
+    engine: &'a PolicyEngine<S>,
+    negotiator: PolicyNegotiator,
+    peer_device_id: String,
+    stream_id: u8,
+    ttl_hours: Option<u32>,
+    resumption: Option<(&'a ResumptionTicketIssuer, &'a Session)>,
+}
+
+impl<'a, S: ProfileStorage> SessionPolicyNegotiation<'a, S> {
+    /// Create a negotiation for the stream shared with `peer_device_id`
+    pub fn new(
+        engine: &'a PolicyEngine<S>,
+        negotiator: PolicyNegotiator,
+        peer_device_id: impl Into<String>,
+        stream_id: u8,
+    ) -> Self {
+        Self {
+            engine,
+            negotiator,
+            peer_device_id: peer_device_id.into(),
+            stream_id,
+            ttl_hours: None,
+            resumption: None,
+        }
+    }
+
+    /// Issue a resumption ticket for `session` when it becomes Active
+    ///
+    /// Issuing failures close the session like a failed policy apply.
+    pub fn with_resumption(
+        mut self,
+        issuer: &'a ResumptionTicketIssuer,
+        session: &'a Session,
+    ) -> Self {
+        self.resumption = Some((issuer, session));
+        self
+    }
+
+    /// Set the TTL of the agreed policy (default: 12h per spec)
+    pub fn with_ttl_hours(mut self, ttl_hours: u32) -> Self {
+        self.ttl_hours = Some(ttl_hours);
+        self
+    }
+
+    /// Propose `profile` to the peer and negotiate until agreement or rejection
+    ///
+    /// # Errors
+    /// Returns `Error::PolicyRejected` (after taking the `PolicyRejected`
+    /// transition) if no policy could be agreed or applied.
+    pub async fn initiate<C: NegotiationChannel>(
+        &self,
+        profile: &PolicyProfile,
+        channel: &mut C,
+        state_machine: &mut SessionStateMachine,
+    ) -> Result<NegotiatedSession> {
+        ensure_paired(state_machine)?;
+
+        let mut negotiator = self.negotiator.clone();
+        let proposal = match negotiator.propose(profile) {
+            Ok(proposal) => proposal,
+            Err(e) => return reject(state_machine, e.to_string()),
+        };
+        channel.send(proposal).await?;
+
+        self.run(negotiator, channel, state_machine).await
+    }
+
+    /// Wait for the peer's proposal and negotiate until agreement or rejection
+    ///
+    /// # Errors
+    /// Returns `Error::PolicyRejected` (after taking the `PolicyRejected`
+    /// transition) if no policy could be agreed or applied.
+    pub async fn respond<C: NegotiationChannel>(
+        &self,
+        channel: &mut C,
+        state_machine: &mut SessionStateMachine,
+    ) -> Result<NegotiatedSession> {
+        ensure_paired(state_machine)?;
+        self.run(self.negotiator.clone(), channel, state_machine)
+            .await
+    }
+
+    /// Exchange messages until one side accepts or rejects
+    ///
+    /// `negotiator` carries this session's negotiation state: it counts the
+    /// rounds locally, so a peer cannot extend the exchange by resetting
+    /// `PolicyProposal::round`.
+    async fn run<C: NegotiationChannel>(
+        &self,
+        mut negotiator: PolicyNegotiator,
+        channel: &mut C,
+        state_machine: &mut SessionStateMachine,
+    ) -> Result<NegotiatedSession> {
+        loop {
+            let message = channel.recv().await?;
+
+            if let NegotiationMessage::Propose(proposal) = &message {
+                if self.policy_id_in_use(&proposal.policy_id).await {
+                    let reason = format!("Policy ID {} is already in use", proposal.policy_id);
+                    channel
+                        .send(NegotiationMessage::Reject {
+                            policy_id: proposal.policy_id.clone(),
+                            reason: reason.clone(),
+                        })
+                        .await?;
+                    return reject(state_machine, reason);
+                }
+            }
+
+            let step = negotiator.handle(&message);
+
+            if let Some(reply) = step.reply {
+                channel.send(reply).await?;
+            }
+
+            match step.outcome {
+                Some(NegotiationOutcome::Agreed(agreement)) => {
+                    let policy = match self.apply(&agreement).await {
+                        Ok(policy) => policy,
+                        Err(e) => return reject(state_machine, e.to_string()),
+                    };
+                    let ticket = match self.issue_ticket() {
+                        Ok(ticket) => ticket,
+                        Err(e) => return reject(state_machine, e.to_string()),
+                    };
+                    state_machine.transition(TransitionEvent::PolicyApplied)?;
+                    return Ok(NegotiatedSession { policy, ticket });
+                }
+                Some(NegotiationOutcome::Rejected(reason)) => {
+                    return reject(state_machine, reason);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Resumption ticket for the session as it enters Active
+    fn issue_ticket(&self) -> Result<Option<IssuedTicket>> {
+        let Some((issuer, session)) = self.resumption else {
+            return Ok(None);
+        };
+        let mut active = session.clone();
+        active.set_state(SessionState::Active);
+        issuer.issue(&active).map(Some)
+    }
+
+    /// Whether the local engine already has a policy under `policy_id`
+    async fn policy_id_in_use(&self, policy_id: &str) -> bool {
+        self.engine
+            .event_bus()
+            .get_snapshot(policy_id)
+            .await
+            .is_some()
+    }
+
+    /// Build the agreed policy and publish it on the local event bus
+    async fn apply(
+        &self,
+        agreement: &PolicyProposal,
+    ) -> honeylink_policy_engine::Result<QoSPolicyUpdate> {
+        let policy = self.engine.create_policy_from_agreement(
+            agreement,
+            self.stream_id,
+            &self.peer_device_id,
+            self.ttl_hours,
+        )?;
+        self.engine.apply_policy(policy.clone()).await?;
+        Ok(policy)
+    }
+}
+
+/// Negotiation only runs between authenticated devices
+fn ensure_paired(state_machine: &SessionStateMachine) -> Result<()> {
+    if state_machine.can_transition(TransitionEvent::PolicyApplied) {
+        Ok(())
+    } else {
+        Err(Error::InvalidStateTransition {
+            from: state_machine.state(),
+            to: SessionState::Active,
+        })
+    }
+}
+
+/// Close the session after a failed negotiation
+fn reject<T>(state_machine: &mut SessionStateMachine, reason: String) -> Result<T> {
+    state_machine.transition(TransitionEvent::PolicyRejected)?;
+    Err(Error::PolicyRejected(reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{InMemorySessionStore, SessionStore};
+    use crate::resumption::ResumptionRequest;
+    use honeylink_policy_engine::presets::{
+        create_gaming_input_preset, create_iot_lowpower_preset,
+    };
+    use honeylink_policy_engine::{
+        InMemoryProfileStorage, NegotiationLimits, PolicyEvent, PolicyVerifier, PowerProfile,
+    };
+    use tokio::sync::mpsc;
+
+    struct MemoryChannel {
+        tx: mpsc::UnboundedSender<NegotiationMessage>,
+        rx: mpsc::UnboundedReceiver<NegotiationMessage>,
+    }
+
+    #[async_trait]
+    impl NegotiationChannel for MemoryChannel {
+        async fn send(&mut self, message: NegotiationMessage) -> Result<()> {
+            self.tx
+                .send(message)
+                .map_err(|e| Error::NetworkTimeout(e.to_string()))
+        }
+
+        async fn recv(&mut self) -> Result<NegotiationMessage> {
+            self.rx
+                .recv()
+                .await
+                .ok_or_else(|| Error::NetworkTimeout("Peer closed".to_string()))
+        }
+    }
+
+    fn channel_pair() -> (MemoryChannel, MemoryChannel) {
+        let (a_tx, b_rx) = mpsc::unbounded_channel();
+        let (b_tx, a_rx) = mpsc::unbounded_channel();
+        (
+            MemoryChannel { tx: a_tx, rx: a_rx },
+            MemoryChannel { tx: b_tx, rx: b_rx },
+        )
+    }
+
+    fn paired() -> SessionStateMachine {
+        SessionStateMachine::from_state(SessionState::Paired)
+    }
+
+    #[tokio::test]
+    async fn test_negotiated_policy_applied_on_both_ends() {
+        let engine_a = PolicyEngine::new(InMemoryProfileStorage::new());
+        let engine_b = PolicyEngine::new(InMemoryProfileStorage::new());
+        let mut events_a = PolicyVerifier::new()
+            .with_authority(engine_a.authority_key())
+            .subscribe(&engine_a.event_bus());
+        let mut events_b = PolicyVerifier::new()
+            .with_authority(engine_b.authority_key())
+            .subscribe(&engine_b.event_bus());
+
+        let battery = NegotiationLimits {
+            max_bandwidth_mbps: 20.0,
+            max_power_profile: PowerProfile::Low,
+            ..NegotiationLimits::default()
+        };
+        let initiator = SessionPolicyNegotiation::new(
+            &engine_a,
+            PolicyNegotiator::new(NegotiationLimits::default()),
+            "DEV-B",
+            1,
+        );
+        let responder =
+            SessionPolicyNegotiation::new(&engine_b, PolicyNegotiator::new(battery), "DEV-A", 1);
+
+        let (mut channel_a, mut channel_b) = channel_pair();
+        let (mut sm_a, mut sm_b) = (paired(), paired());
+        let profile = create_gaming_input_preset();
+
+        let (result_a, result_b) = tokio::join!(
+            initiator.initiate(&profile, &mut channel_a, &mut sm_a),
+            responder.respond(&mut channel_b, &mut sm_b),
+        );
+        let (policy_a, policy_b) = (result_a.unwrap().policy, result_b.unwrap().policy);
+
+        assert_eq!(sm_a.state(), SessionState::Active);
+        assert_eq!(sm_b.state(), SessionState::Active);
+        assert_eq!(policy_a.policy_id, policy_b.policy_id);
+        assert_eq!(policy_a.bandwidth_ceiling_mbps, Some(20.0));
+        assert_eq!(policy_b.power_profile, Some(PowerProfile::Low));
+        assert_eq!(policy_a.device_id.as_deref(), Some("DEV-B"));
+        assert!(policy_a.verify_signature(&engine_a.authority_key()).is_ok());
+
+        for events in [&mut events_a, &mut events_b] {
+            match events.recv().await.unwrap() {
+                PolicyEvent::Update(update) => assert_eq!(update.policy_id, policy_a.policy_id),
+                _ => panic!("Expected Update event"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resumption_ticket_issued_on_activation() {
+        let engine_a = PolicyEngine::new(InMemoryProfileStorage::new());
+        let engine_b = PolicyEngine::new(InMemoryProfileStorage::new());
+        let _events_a = engine_a.event_bus().subscribe();
+        let _events_b = engine_b.event_bus().subscribe();
+
+        let mut session = Session::new(
+            "DEV-A".to_string(),
+            "DEV-B".to_string(),
+            "1.0.0".to_string(),
+            12,
+        );
+        session.set_shared_key_id("key_1".to_string());
+        session.set_state(SessionState::Paired);
+        let issuer = ResumptionTicketIssuer::new_random();
+
+        let initiator = SessionPolicyNegotiation::new(
+            &engine_a,
+            PolicyNegotiator::new(NegotiationLimits::default()),
+            "DEV-B",
+            1,
+        )
+        .with_resumption(&issuer, &session);
+        let responder = SessionPolicyNegotiation::new(
+            &engine_b,
+            PolicyNegotiator::new(NegotiationLimits::default()),
+            "DEV-A",
+            1,
+        );
+
+        let (mut channel_a, mut channel_b) = channel_pair();
+        let (mut sm_a, mut sm_b) = (paired(), paired());
+        let profile = create_gaming_input_preset();
+        let (result_a, result_b) = tokio::join!(
+            initiator.initiate(&profile, &mut channel_a, &mut sm_a),
+            responder.respond(&mut channel_b, &mut sm_b),
+        );
+
+        let ticket = result_a.unwrap().ticket.unwrap();
+        assert_eq!(ticket.expires_at, session.expires_at);
+        assert!(result_b.unwrap().ticket.is_none());
+        assert_eq!(sm_a.state(), SessionState::Active);
+
+        // The ticket resumes the session once it is Suspended
+        let mut store = InMemorySessionStore::new();
+        session.set_state(SessionState::Suspended);
+        store.create(session.clone()).await.unwrap();
+        let request = ResumptionRequest::new(&ticket).unwrap();
+        let resumed = issuer.resume(&mut store, &request).await.unwrap();
+        assert_eq!(resumed.session.session_id, session.session_id);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_negotiation_closes_both_sessions() {
+        let engine_a = PolicyEngine::new(InMemoryProfileStorage::new());
+        let engine_b = PolicyEngine::new(InMemoryProfileStorage::new());
+
+        let initiator = SessionPolicyNegotiation::new(
+            &engine_a,
+            PolicyNegotiator::new(NegotiationLimits::default()),
+            "DEV-B",
+            1,
+        );
+        let responder = SessionPolicyNegotiation::new(
+            &engine_b,
+            PolicyNegotiator::new(NegotiationLimits {
+                max_bandwidth_mbps: 0.05,
+                ..NegotiationLimits::default()
+            }),
+            "DEV-A",
+            1,
+        );
+
+        let (mut channel_a, mut channel_b) = channel_pair();
+        let (mut sm_a, mut sm_b) = (paired(), paired());
+        let profile = create_iot_lowpower_preset();
+
+        let (result_a, result_b) = tokio::join!(
+            initiator.initiate(&profile, &mut channel_a, &mut sm_a),
+            responder.respond(&mut channel_b, &mut sm_b),
+        );
+
+        assert!(matches!(result_a, Err(Error::PolicyRejected(_))));
+        assert!(matches!(result_b, Err(Error::PolicyRejected(_))));
+        assert_eq!(sm_a.state(), SessionState::Closed);
+        assert_eq!(sm_b.state(), SessionState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_reused_policy_id_is_rejected() {
+        let engine_b = PolicyEngine::new(InMemoryProfileStorage::new());
+        let profile = create_gaming_input_preset();
+
+        // The peer proposes the ID of a policy the responder already runs
+        let _events = engine_b.event_bus().subscribe();
+        engine_b.create_profile(profile.clone()).await.unwrap();
+        let existing = engine_b
+            .create_policy_from_profile(&profile.profile_id, 2, "DEV-C", None)
+            .await
+            .unwrap();
+        engine_b.apply_policy(existing.clone()).await.unwrap();
+
+        let responder = SessionPolicyNegotiation::new(
+            &engine_b,
+            PolicyNegotiator::new(NegotiationLimits::default()),
+            "DEV-A",
+            1,
+        );
+        let mut proposer = PolicyNegotiator::new(NegotiationLimits::default());
+        let NegotiationMessage::Propose(mut proposal) = proposer.propose(&profile).unwrap() else {
+            panic!("Expected proposal");
+        };
+        proposal.policy_id = existing.policy_id.clone();
+
+        let (mut channel_a, mut channel_b) = channel_pair();
+        channel_a
+            .send(NegotiationMessage::Propose(proposal))
+            .await
+            .unwrap();
+        let mut sm_b = paired();
+
+        let result = responder.respond(&mut channel_b, &mut sm_b).await;
+        assert!(matches!(result, Err(Error::PolicyRejected(_))));
+        assert_eq!(sm_b.state(), SessionState::Closed);
+        assert!(matches!(
+            channel_a.recv().await.unwrap(),
+            NegotiationMessage::Reject { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_requires_paired_session() {
+        let engine = PolicyEngine::new(InMemoryProfileStorage::new());
+        let negotiation = SessionPolicyNegotiation::new(
+            &engine,
+            PolicyNegotiator::new(NegotiationLimits::default()),
+            "DEV-B",
+            1,
+        );
+        let (mut channel, _peer) = channel_pair();
+        let mut sm = SessionStateMachine::new();
+
+        let result = negotiation
+            .initiate(&create_gaming_input_preset(), &mut channel, &mut sm)
+            .await;
+        assert!(matches!(result, Err(Error::InvalidStateTransition { .. })));
+        assert_eq!(sm.state(), SessionState::Pending);
+    }
+}
@@ -1,9 +1,11 @@
 //! Session persistence trait and in-memory implementation
 //!
-//! Defines DB abstraction for session storage. Production should implement
-//! with CockroachDB or other distributed SQL per spec/modules/session-orchestrator.md
+//! Defines DB abstraction for session storage. `RedbSessionStore` provides a
+//! durable embedded implementation; multi-region deployments should implement
+//! it with CockroachDB or other distributed SQL per spec/modules/session-orchestrator.md
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -15,6 +17,7 @@ use crate::session::Session;
 /// Abstraction for session storage. Implementations should provide:
 /// - CockroachDB for production (distributed SQL, multi-region)
 /// - PostgreSQL for single-region deployments
+/// - redb for single-node deployments and devices (`RedbSessionStore`)
 /// - In-memory for testing
 #[async_trait]
 pub trait SessionStore: Send + Sync {
@@ -43,6 +46,14 @@ pub trait SessionStore: Send + Sync {
     ///
     /// Returns number of sessions deleted
     async fn cleanup_expired(&mut self) -> Result<usize>;
+
+    /// Mark a resumption ticket as used
+    ///
+    /// Must be stored as durably as the sessions, so a ticket cannot be
+    /// replayed after a restart. Entries past `expires_at` may be dropped.
+    ///
+    /// Returns `false` if `ticket_id` was already consumed.
+    async fn consume_ticket(&mut self, ticket_id: Uuid, expires_at: DateTime<Utc>) -> Result<bool>;
 }
 
 /// In-memory session store for testing and single-instance deployments
@@ -50,6 +61,7 @@ pub trait SessionStore: Send + Sync {
 /// NOTE: Not suitable for production multi-instance setups (no shared state)
 pub struct InMemorySessionStore {
     sessions: HashMap<Uuid, Session>,
+    consumed_tickets: HashMap<Uuid, DateTime<Utc>>,
 }
 
 impl InMemorySessionStore {
@@ -57,6 +69,7 @@ impl InMemorySessionStore {
     pub fn new() -> Self {
         Self {
             sessions: HashMap::new(),
+            consumed_tickets: HashMap::new(),
         }
     }
 }
@@ -135,6 +148,16 @@ impl SessionStore for InMemorySessionStore {
         self.sessions.retain(|_, session| !session.is_expired());
         Ok(initial_count - self.sessions.len())
     }
+
+    async fn consume_ticket(&mut self, ticket_id: Uuid, expires_at: DateTime<Utc>) -> Result<bool> {
+        let now = Utc::now();
+        self.consumed_tickets
+            .retain(|_, expires_at| *expires_at > now);
+        Ok(self
+            .consumed_tickets
+            .insert(ticket_id, expires_at)
+            .is_none())
+    }
 }
 
 #[cfg(test)]
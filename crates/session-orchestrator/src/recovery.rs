@@ -0,0 +1,162 @@
+//! Session recovery after restart
+//!
+//! On startup the orchestrator reloads persisted sessions:
+//! - Suspended sessions still within the suspend timeout
+//!   (`DEFAULT_SUSPEND_TIMEOUT`) are restored
+//! - Active sessions lost their transport and keys with the process, so they
+//!   are closed and removed; the peers pair again
+//! - Expired sessions, Suspended sessions past the suspend timeout,
+//!   unfinished handshakes (Pending/Paired) and Closed sessions are closed
+//!   and removed
+//!
+//! A restored session is resumed by redeeming the peer's resumption ticket
+//! with `ResumptionTicketIssuer::resume`, which checks the ticket, binder and
+//! suspend window before moving it back to Active.
+
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::persistence::SessionStore;
+use crate::resumption::DEFAULT_SUSPEND_TIMEOUT;
+use crate::session::Session;
+use crate::state_machine::SessionState;
+
+/// Outcome of `recover_sessions`
+#[derive(Debug, Default)]
+pub struct RecoveryReport {
+    /// Suspended sessions that can still be resumed
+    pub restored: Vec<Session>,
+
+    /// IDs of sessions closed and removed from the store
+    pub expired: Vec<Uuid>,
+}
+
+impl RecoveryReport {
+    /// Restored session shared with `peer_device_id`, most recently active first
+    pub fn resumable_with(&self, peer_device_id: &str) -> Option<&Session> {
+        self.restored
+            .iter()
+            .filter(|s| s.device_a_id == peer_device_id || s.device_b_id == peer_device_id)
+            .max_by_key(|s| s.last_activity_at)
+    }
+}
+
+/// Reload persisted sessions after a restart
+///
+/// Restores Suspended sessions within the suspend timeout and removes
+/// everything else.
+pub async fn recover_sessions<S>(store: &mut S) -> Result<RecoveryReport>
+where
+    S: SessionStore + ?Sized,
+{
+    let mut sessions = Vec::new();
+    for state in [
+        SessionState::Pending,
+        SessionState::Paired,
+        SessionState::Active,
+        SessionState::Suspended,
+        SessionState::Closed,
+    ] {
+        sessions.extend(store.list_by_state(state).await?);
+    }
+
+    let mut report = RecoveryReport::default();
+    for session in sessions {
+        let resumable = session.state == SessionState::Suspended
+            && !session.is_expired()
+            && !session.suspend_timed_out(DEFAULT_SUSPEND_TIMEOUT);
+
+        if resumable {
+            report.restored.push(session);
+        } else {
+            store.delete(session.session_id).await?;
+            report.expired.push(session.session_id);
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::InMemorySessionStore;
+    use crate::redb_store::RedbSessionStore;
+    use crate::resumption::{ResumptionRequest, ResumptionTicketIssuer};
+    use chrono::{Duration, Utc};
+    use tempfile::TempDir;
+
+    fn session(peer: &str, state: SessionState) -> Session {
+        let mut session = Session::new(
+            "PHONE".to_string(),
+            peer.to_string(),
+            "1.0.0".to_string(),
+            12,
+        );
+        session.set_state(state);
+        session
+    }
+
+    #[tokio::test]
+    async fn test_recover_restores_suspended_and_expires_rest() {
+        let mut store = InMemorySessionStore::new();
+        let suspended = session("LAPTOP", SessionState::Suspended);
+        let active = session("TV", SessionState::Active);
+        let pending = session("TABLET", SessionState::Pending);
+        let mut stale = session("WATCH", SessionState::Suspended);
+        stale.expires_at = Utc::now() - Duration::minutes(1);
+        let mut timed_out = session("CAR", SessionState::Suspended);
+        timed_out.suspended_at = Some(Utc::now() - DEFAULT_SUSPEND_TIMEOUT - Duration::minutes(1));
+        for s in [&suspended, &active, &pending, &stale, &timed_out] {
+            store.create(s.clone()).await.unwrap();
+        }
+
+        let report = recover_sessions(&mut store).await.unwrap();
+
+        assert_eq!(report.restored.len(), 1);
+        assert_eq!(report.expired.len(), 4);
+        assert_eq!(
+            report.resumable_with("LAPTOP").unwrap().session_id,
+            suspended.session_id
+        );
+        for removed in [&active, &pending, &stale, &timed_out] {
+            assert!(report.expired.contains(&removed.session_id));
+            assert!(store.get(removed.session_id).await.unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_with_ticket_after_reboot() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sessions.redb");
+        let ticket_key = [7u8; 32];
+
+        let mut paired = session("LAPTOP", SessionState::Active);
+        let ticket = {
+            let mut store = RedbSessionStore::open(&path).unwrap();
+            store.create(paired.clone()).await.unwrap();
+            let ticket = ResumptionTicketIssuer::new(&ticket_key)
+                .unwrap()
+                .issue(&paired)
+                .unwrap();
+            paired.set_state(SessionState::Suspended);
+            store.update(paired.clone()).await.unwrap();
+            ticket
+        };
+
+        // Device reboots
+        let mut store = RedbSessionStore::open(&path).unwrap();
+        let report = recover_sessions(&mut store).await.unwrap();
+        assert_eq!(
+            report.resumable_with("LAPTOP").unwrap().session_id,
+            paired.session_id
+        );
+
+        let issuer = ResumptionTicketIssuer::new(&ticket_key).unwrap();
+        let request = ResumptionRequest::new(&ticket).unwrap();
+        let resumed = issuer.resume(&mut store, &request).await.unwrap();
+        assert_eq!(resumed.session.session_id, paired.session_id);
+        assert_eq!(resumed.machine.state(), SessionState::Active);
+        assert_eq!(store.count_active().await.unwrap(), 1);
+    }
+}
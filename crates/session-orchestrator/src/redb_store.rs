@@ -0,0 +1,408 @@
+//! Durable session store backed by redb
+//!
+//! Sessions survive process restarts and device reboots. redb is an embedded,
+//! pure-Rust key-value store with ACID transactions: every `SessionStore`
+//! write (session row plus its index entries) commits in one transaction, so
+//! a crash leaves either the old or the new state on disk, never a mix.
+//!
+//! Layout:
+//! - `sessions`: session ID (u128) → JSON-encoded `Session`
+//! - `sessions_by_device`: device ID → session IDs (both peers are indexed)
+//! - `sessions_by_state`: state name → session IDs
+//! - `consumed_tickets`: resumption ticket ID (u128) → ticket expiry (Unix ms)
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use redb::{
+    Database, MultimapTableDefinition, ReadTransaction, ReadableTable, ReadableTableMetadata,
+    TableDefinition, WriteTransaction,
+};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::persistence::SessionStore;
+use crate::session::Session;
+use crate::state_machine::SessionState;
+
+const SESSIONS: TableDefinition<u128, &[u8]> = TableDefinition::new("sessions");
+const BY_DEVICE: MultimapTableDefinition<&str, u128> =
+    MultimapTableDefinition::new("sessions_by_device");
+const BY_STATE: MultimapTableDefinition<&str, u128> =
+    MultimapTableDefinition::new("sessions_by_state");
+const CONSUMED_TICKETS: TableDefinition<u128, i64> = TableDefinition::new("consumed_tickets");
+
+/// Persistent `SessionStore` for single-node deployments and devices
+pub struct RedbSessionStore {
+    db: Database,
+}
+
+impl RedbSessionStore {
+    /// Open the store at `path`, creating the database file if missing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                Error::PersistenceError(format!(
+                    "Failed to create session store directory {}: {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let db = Database::create(path).map_err(db_error)?;
+
+        // Create tables up front so read transactions never see them missing
+        let txn = db.begin_write().map_err(db_error)?;
+        txn.open_table(SESSIONS).map_err(db_error)?;
+        txn.open_multimap_table(BY_DEVICE).map_err(db_error)?;
+        txn.open_multimap_table(BY_STATE).map_err(db_error)?;
+        txn.open_table(CONSUMED_TICKETS).map_err(db_error)?;
+        txn.commit().map_err(db_error)?;
+
+        Ok(Self { db })
+    }
+
+    /// Number of stored sessions in every state
+    pub fn len(&self) -> Result<usize> {
+        let txn = self.read()?;
+        let table = txn.open_table(SESSIONS).map_err(db_error)?;
+        Ok(table.len().map_err(db_error)? as usize)
+    }
+
+    /// Whether the store holds no sessions
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Every stored session
+    pub fn list_all(&self) -> Result<Vec<Session>> {
+        let txn = self.read()?;
+        let table = txn.open_table(SESSIONS).map_err(db_error)?;
+        let mut sessions = Vec::new();
+        for entry in table.iter().map_err(db_error)? {
+            let (_, bytes) = entry.map_err(db_error)?;
+            sessions.push(decode(bytes.value())?);
+        }
+        Ok(sessions)
+    }
+
+    fn read(&self) -> Result<ReadTransaction> {
+        self.db.begin_read().map_err(db_error)
+    }
+
+    fn write(&self) -> Result<WriteTransaction> {
+        self.db.begin_write().map_err(db_error)
+    }
+
+    /// Load the sessions listed under `key` in an index table
+    fn list_indexed(
+        &self,
+        index: MultimapTableDefinition<&str, u128>,
+        key: &str,
+    ) -> Result<Vec<Session>> {
+        let txn = self.read()?;
+        let ids = txn.open_multimap_table(index).map_err(db_error)?;
+        let table = txn.open_table(SESSIONS).map_err(db_error)?;
+
+        let mut sessions = Vec::new();
+        for id in ids.get(key).map_err(db_error)? {
+            let id = id.map_err(db_error)?.value();
+            if let Some(bytes) = table.get(id).map_err(db_error)? {
+                sessions.push(decode(bytes.value())?);
+            }
+        }
+        Ok(sessions)
+    }
+}
+
+/// Write `session` and its index entries, replacing `previous` if given
+fn put(txn: &WriteTransaction, session: &Session, previous: Option<&Session>) -> Result<()> {
+    let id = session.session_id.as_u128();
+    if let Some(previous) = previous {
+        unindex(txn, previous)?;
+    }
+
+    txn.open_table(SESSIONS)
+        .map_err(db_error)?
+        .insert(id, encode(session)?.as_slice())
+        .map_err(db_error)?;
+
+    let mut by_device = txn.open_multimap_table(BY_DEVICE).map_err(db_error)?;
+    by_device
+        .insert(session.device_a_id.as_str(), id)
+        .map_err(db_error)?;
+    by_device
+        .insert(session.device_b_id.as_str(), id)
+        .map_err(db_error)?;
+
+    txn.open_multimap_table(BY_STATE)
+        .map_err(db_error)?
+        .insert(state_key(session.state), id)
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Remove the index entries of `session`
+fn unindex(txn: &WriteTransaction, session: &Session) -> Result<()> {
+    let id = session.session_id.as_u128();
+
+    let mut by_device = txn.open_multimap_table(BY_DEVICE).map_err(db_error)?;
+    by_device
+        .remove(session.device_a_id.as_str(), id)
+        .map_err(db_error)?;
+    by_device
+        .remove(session.device_b_id.as_str(), id)
+        .map_err(db_error)?;
+
+    txn.open_multimap_table(BY_STATE)
+        .map_err(db_error)?
+        .remove(state_key(session.state), id)
+        .map_err(db_error)?;
+    Ok(())
+}
+
+fn load(txn: &WriteTransaction, session_id: Uuid) -> Result<Option<Session>> {
+    let table = txn.open_table(SESSIONS).map_err(db_error)?;
+    let bytes = table.get(session_id.as_u128()).map_err(db_error)?;
+    bytes.map(|bytes| decode(bytes.value())).transpose()
+}
+
+fn state_key(state: SessionState) -> &'static str {
+    match state {
+        SessionState::Pending => "pending",
+        SessionState::Paired => "paired",
+        SessionState::Active => "active",
+        SessionState::Suspended => "suspended",
+        SessionState::Closed => "closed",
+    }
+}
+
+fn encode(session: &Session) -> Result<Vec<u8>> {
+    serde_json::to_vec(session)
+        .map_err(|e| Error::PersistenceError(format!("Failed to encode session: {}", e)))
+}
+
+fn decode(bytes: &[u8]) -> Result<Session> {
+    serde_json::from_slice(bytes)
+        .map_err(|e| Error::PersistenceError(format!("Failed to decode session: {}", e)))
+}
+
+fn db_error(e: impl Into<redb::Error>) -> Error {
+    Error::PersistenceError(format!("Session store error: {}", e.into()))
+}
+
+#[async_trait]
+impl SessionStore for RedbSessionStore {
+    async fn create(&mut self, session: Session) -> Result<()> {
+        let txn = self.write()?;
+        if load(&txn, session.session_id)?.is_some() {
+            return Err(Error::PersistenceError(format!(
+                "Session {} already exists",
+                session.session_id
+            )));
+        }
+
+        put(&txn, &session, None)?;
+        txn.commit().map_err(db_error)
+    }
+
+    async fn get(&self, session_id: Uuid) -> Result<Option<Session>> {
+        let txn = self.read()?;
+        let table = txn.open_table(SESSIONS).map_err(db_error)?;
+        let bytes = table.get(session_id.as_u128()).map_err(db_error)?;
+        bytes.map(|bytes| decode(bytes.value())).transpose()
+    }
+
+    async fn update(&mut self, session: Session) -> Result<()> {
+        let txn = self.write()?;
+        let previous = load(&txn, session.session_id)?
+            .ok_or_else(|| Error::SessionNotFound(session.session_id.to_string()))?;
+
+        put(&txn, &session, Some(&previous))?;
+        txn.commit().map_err(db_error)
+    }
+
+    async fn delete(&mut self, session_id: Uuid) -> Result<()> {
+        let txn = self.write()?;
+        let previous = load(&txn, session_id)?
+            .ok_or_else(|| Error::SessionNotFound(session_id.to_string()))?;
+
+        unindex(&txn, &previous)?;
+        txn.open_table(SESSIONS)
+            .map_err(db_error)?
+            .remove(session_id.as_u128())
+            .map_err(db_error)?;
+        txn.commit().map_err(db_error)
+    }
+
+    async fn list_by_device(&self, device_id: &str) -> Result<Vec<Session>> {
+        self.list_indexed(BY_DEVICE, device_id)
+    }
+
+    async fn list_by_state(&self, state: SessionState) -> Result<Vec<Session>> {
+        self.list_indexed(BY_STATE, state_key(state))
+    }
+
+    async fn count_active(&self) -> Result<usize> {
+        let txn = self.read()?;
+        let by_state = txn.open_multimap_table(BY_STATE).map_err(db_error)?;
+        let ids = by_state
+            .get(state_key(SessionState::Active))
+            .map_err(db_error)?;
+        Ok(ids.len() as usize)
+    }
+
+    async fn cleanup_expired(&mut self) -> Result<usize> {
+        let expired: Vec<Session> = self
+            .list_all()?
+            .into_iter()
+            .filter(Session::is_expired)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = self.write()?;
+        for session in &expired {
+            unindex(&txn, session)?;
+            txn.open_table(SESSIONS)
+                .map_err(db_error)?
+                .remove(session.session_id.as_u128())
+                .map_err(db_error)?;
+        }
+        txn.commit().map_err(db_error)?;
+        Ok(expired.len())
+    }
+
+    async fn consume_ticket(&mut self, ticket_id: Uuid, expires_at: DateTime<Utc>) -> Result<bool> {
+        let now = Utc::now().timestamp_millis();
+        let txn = self.write()?;
+        let fresh = {
+            let mut table = txn.open_table(CONSUMED_TICKETS).map_err(db_error)?;
+            table
+                .retain(|_, expires_at| expires_at > now)
+                .map_err(db_error)?;
+            let previous = table
+                .insert(ticket_id.as_u128(), expires_at.timestamp_millis())
+                .map_err(db_error)?;
+            previous.is_none()
+        };
+        txn.commit().map_err(db_error)?;
+        Ok(fresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn session(a: &str, b: &str) -> Session {
+        Session::new(a.to_string(), b.to_string(), "1.0.0".to_string(), 12)
+    }
+
+    #[tokio::test]
+    async fn test_sessions_survive_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sessions.redb");
+
+        let mut stored = session("DEV-A", "DEV-B");
+        stored.set_shared_key_id("key_1".to_string());
+        let session_id = stored.session_id;
+        {
+            let mut store = RedbSessionStore::open(&path).unwrap();
+            store.create(stored.clone()).await.unwrap();
+            assert!(store.create(stored.clone()).await.is_err());
+        }
+
+        let store = RedbSessionStore::open(&path).unwrap();
+        let loaded = store.get(session_id).await.unwrap().unwrap();
+        assert_eq!(loaded.shared_key_id, "key_1");
+        assert_eq!(loaded.device_b_id, "DEV-B");
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_indexes_follow_updates() {
+        let dir = TempDir::new().unwrap();
+        let mut store = RedbSessionStore::open(dir.path().join("sessions.redb")).unwrap();
+
+        let mut ab = session("DEV-A", "DEV-B");
+        store.create(ab.clone()).await.unwrap();
+        store.create(session("DEV-A", "DEV-C")).await.unwrap();
+        store.create(session("DEV-D", "DEV-E")).await.unwrap();
+
+        assert_eq!(store.list_by_device("DEV-A").await.unwrap().len(), 2);
+        assert_eq!(store.list_by_device("DEV-B").await.unwrap().len(), 1);
+        assert_eq!(
+            store
+                .list_by_state(SessionState::Pending)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        ab.set_state(SessionState::Active);
+        store.update(ab.clone()).await.unwrap();
+        assert_eq!(store.count_active().await.unwrap(), 1);
+        assert_eq!(
+            store
+                .list_by_state(SessionState::Pending)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        store.delete(ab.session_id).await.unwrap();
+        assert!(store.list_by_device("DEV-B").await.unwrap().is_empty());
+        assert_eq!(store.count_active().await.unwrap(), 0);
+        assert!(matches!(
+            store.delete(ab.session_id).await,
+            Err(Error::SessionNotFound(_))
+        ));
+        assert!(matches!(
+            store.update(ab).await,
+            Err(Error::SessionNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_expired() {
+        let dir = TempDir::new().unwrap();
+        let mut store = RedbSessionStore::open(dir.path().join("sessions.redb")).unwrap();
+
+        let mut expired = session("DEV-A", "DEV-B");
+        expired.expires_at = chrono::Utc::now() - chrono::Duration::hours(1);
+        store.create(expired).await.unwrap();
+        store.create(session("DEV-A", "DEV-C")).await.unwrap();
+
+        assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+        assert_eq!(store.list_by_device("DEV-A").await.unwrap().len(), 1);
+        assert!(store.list_by_device("DEV-B").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_consumed_tickets_survive_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("sessions.redb");
+        let ticket_id = Uuid::now_v7();
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+
+        {
+            let mut store = RedbSessionStore::open(&path).unwrap();
+            assert!(store.consume_ticket(ticket_id, expires_at).await.unwrap());
+            assert!(!store.consume_ticket(ticket_id, expires_at).await.unwrap());
+        }
+
+        let mut store = RedbSessionStore::open(&path).unwrap();
+        assert!(!store.consume_ticket(ticket_id, expires_at).await.unwrap());
+        assert!(store
+            .consume_ticket(Uuid::now_v7(), expires_at)
+            .await
+            .unwrap());
+    }
+}
@@ -0,0 +1,485 @@
+//! Session resumption tickets
+//!
+//! When a session becomes Active (`SessionPolicyNegotiation::with_resumption`)
+//! the orchestrator issues a resumption ticket:
+//! the session ID, shared key ID, negotiated protocol version, expiry and a
+//! fresh resumption secret, sealed with ChaCha20-Poly1305 under a key only
+//! the issuer holds. The peer keeps the ticket and the secret.
+//!
+//! After a network blip the peer sends a `ResumptionRequest` as QUIC 0-RTT
+//! early data (`QuicTransport::connect_with_early_data`; the listener reads
+//! it with `Connection::accept_early_data` and decodes it with
+//! `ResumptionRequest::from_bytes`) carrying the ticket, a fresh nonce and a
+//! binder derived from the secret. The issuer checks the ticket and binder, moves
+//! the Suspended session back to Active (`NetworkRestored`) and both sides
+//! derive a new session key from the secret and nonce, skipping the pairing
+//! and policy round trips. Resumption is only allowed within the suspend
+//! timeout (5 min, spec/modules/session-orchestrator.md#9.2).
+//!
+//! Tickets are single-use: early data can be replayed, so a consumed ticket
+//! is rejected and `resume` returns the next ticket. Consumed ticket IDs are
+//! recorded in the `SessionStore` (`consume_ticket`), so with a durable store
+//! such as `RedbSessionStore` a ticket cannot be replayed after a restart.
+
+use chrono::{DateTime, Duration, Utc};
+use honeylink_crypto::aead::{ChaCha20Poly1305Cipher, NONCE_SIZE};
+use honeylink_crypto::key_derivation::{DeriveContext, KeyDerivation};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+use crate::persistence::SessionStore;
+use crate::session::Session;
+use crate::state_machine::{SessionState, SessionStateMachine, TransitionEvent};
+
+/// Time a Suspended session may wait for resumption before `SuspendTimeout`
+pub const DEFAULT_SUSPEND_TIMEOUT: Duration = Duration::minutes(5);
+
+/// Length of resumption secrets, client nonces and binders
+pub const RESUMPTION_SECRET_SIZE: usize = 32;
+
+const TICKET_VERSION: u8 = 1;
+const TICKET_AAD: &[u8] = b"HoneyLink-resumption-ticket-v1";
+
+/// Contents of a sealed ticket
+#[derive(Serialize, Deserialize)]
+struct TicketClaims {
+    ticket_id: Uuid,
+    session_id: Uuid,
+    shared_key_id: String,
+    protocol_version: String,
+    expires_at: DateTime<Utc>,
+    resumption_secret: Vec<u8>,
+}
+
+/// Ticket handed to the peer at establishment
+pub struct IssuedTicket {
+    /// Opaque sealed ticket, presented on reconnect
+    pub ticket: Vec<u8>,
+
+    /// Secret shared with the peer over the established session
+    pub resumption_secret: Zeroizing<Vec<u8>>,
+
+    /// Ticket expiry (the session's expiry)
+    pub expires_at: DateTime<Utc>,
+}
+
+impl std::fmt::Debug for IssuedTicket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IssuedTicket")
+            .field("ticket_len", &self.ticket.len())
+            .field("resumption_secret", &"[REDACTED]")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+/// Resumption attempt sent by the reconnecting peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumptionRequest {
+    pub ticket: Vec<u8>,
+    pub client_nonce: [u8; RESUMPTION_SECRET_SIZE],
+
+    /// Proof that the sender holds the ticket's resumption secret
+    pub binder: Vec<u8>,
+}
+
+impl ResumptionRequest {
+    /// Build a request for `ticket` with a fresh nonce
+    pub fn new(ticket: &IssuedTicket) -> Result<Self> {
+        let mut client_nonce = [0u8; RESUMPTION_SECRET_SIZE];
+        rand::thread_rng().fill_bytes(&mut client_nonce);
+
+        let binder = binder(&ticket.resumption_secret, &ticket.ticket, &client_nonce)?;
+        Ok(Self {
+            ticket: ticket.ticket.clone(),
+            client_nonce,
+            binder: binder.to_vec(),
+        })
+    }
+
+    /// Session key for the resumed session, as derived by the peer
+    pub fn session_key(&self, resumption_secret: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        resumed_key(resumption_secret, &self.client_nonce)
+    }
+
+    /// Encode for the wire (e.g. QUIC 0-RTT early data)
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| {
+            Error::Core(honeylink_core::Error::Serialization(format!(
+                "Failed to encode resumption request: {}",
+                e
+            )))
+        })
+    }
+
+    /// Decode a request received from the wire
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| {
+            Error::Core(honeylink_core::Error::Serialization(format!(
+                "Invalid resumption request: {}",
+                e
+            )))
+        })
+    }
+}
+
+/// Outcome of a successful resumption
+pub struct ResumedSession {
+    /// Session, now Active
+    pub session: Session,
+    pub machine: SessionStateMachine,
+
+    /// New session key derived from the resumption secret and client nonce
+    pub session_key: Zeroizing<Vec<u8>>,
+
+    /// Replacement ticket for the next resumption
+    pub next_ticket: IssuedTicket,
+}
+
+/// Issues and redeems resumption tickets
+///
+/// The ticket key must be persisted alongside the session store for tickets
+/// to survive a restart; used tickets are tracked by the store.
+pub struct ResumptionTicketIssuer {
+    cipher: ChaCha20Poly1305Cipher,
+    suspend_timeout: Duration,
+}
+
+impl ResumptionTicketIssuer {
+    /// Create an issuer sealing tickets with a 32-byte `ticket_key`
+    pub fn new(ticket_key: &[u8]) -> Result<Self> {
+        Ok(Self {
+            cipher: ChaCha20Poly1305Cipher::new(ticket_key)?,
+            suspend_timeout: DEFAULT_SUSPEND_TIMEOUT,
+        })
+    }
+
+    /// Create an issuer with a random ticket key
+    pub fn new_random() -> Self {
+        Self {
+            cipher: ChaCha20Poly1305Cipher::new_random(),
+            suspend_timeout: DEFAULT_SUSPEND_TIMEOUT,
+        }
+    }
+
+    /// Override the suspend timeout
+    pub fn with_suspend_timeout(mut self, timeout: Duration) -> Self {
+        self.suspend_timeout = timeout;
+        self
+    }
+
+    /// Issue a ticket for an Active session
+    pub fn issue(&self, session: &Session) -> Result<IssuedTicket> {
+        if session.state != SessionState::Active {
+            return Err(Error::Core(honeylink_core::Error::InvalidState(format!(
+                "Resumption tickets are issued for Active sessions, session {} is {:?}",
+                session.session_id, session.state
+            ))));
+        }
+
+        let mut secret = Zeroizing::new(vec![0u8; RESUMPTION_SECRET_SIZE]);
+        rand::thread_rng().fill_bytes(&mut secret);
+
+        let claims = TicketClaims {
+            ticket_id: Uuid::now_v7(),
+            session_id: session.session_id,
+            shared_key_id: session.shared_key_id.clone(),
+            protocol_version: session.protocol_version.clone(),
+            expires_at: session.expires_at,
+            resumption_secret: secret.to_vec(),
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(&claims).map_err(|e| {
+            Error::Core(honeylink_core::Error::Serialization(format!(
+                "Failed to encode resumption ticket: {}",
+                e
+            )))
+        })?);
+        // Wipe the plaintext copy of the secret
+        drop(Zeroizing::new(claims.resumption_secret));
+
+        let (nonce, ciphertext) = self.cipher.encrypt(&plaintext, TICKET_AAD)?;
+        let mut ticket = Vec::with_capacity(1 + NONCE_SIZE + ciphertext.len());
+        ticket.push(TICKET_VERSION);
+        ticket.extend_from_slice(&nonce);
+        ticket.extend_from_slice(&ciphertext);
+
+        Ok(IssuedTicket {
+            ticket,
+            resumption_secret: secret,
+            expires_at: session.expires_at,
+        })
+    }
+
+    /// Redeem `request` against the session in `store`
+    ///
+    /// # Errors
+    /// - `AuthenticationFailed` if the ticket or binder is invalid, or the
+    ///   ticket was already used
+    /// - `SessionExpired` if the ticket, the session or the suspend window
+    ///   has expired
+    /// - `SessionNotFound` if the session is gone
+    /// - `InvalidStateTransition` if the session is not Suspended
+    pub async fn resume<S>(
+        &self,
+        store: &mut S,
+        request: &ResumptionRequest,
+    ) -> Result<ResumedSession>
+    where
+        S: SessionStore + ?Sized,
+    {
+        let now = Utc::now();
+        let claims = self.open(&request.ticket)?;
+        let secret = Zeroizing::new(claims.resumption_secret);
+
+        let expected = binder(&secret, &request.ticket, &request.client_nonce)?;
+        if !constant_time_eq(&expected, &request.binder) {
+            return Err(Error::AuthenticationFailed(
+                "Resumption binder mismatch".to_string(),
+            ));
+        }
+        if claims.expires_at <= now {
+            return Err(Error::SessionExpired(claims.session_id.to_string()));
+        }
+        let mut session = store
+            .get(claims.session_id)
+            .await?
+            .ok_or_else(|| Error::SessionNotFound(claims.session_id.to_string()))?;
+        if session.shared_key_id != claims.shared_key_id
+            || session.protocol_version != claims.protocol_version
+        {
+            return Err(Error::AuthenticationFailed(format!(
+                "Resumption ticket does not match session {}",
+                session.session_id
+            )));
+        }
+        if session.is_expired() {
+            return Err(Error::SessionExpired(session.session_id.to_string()));
+        }
+        if session.suspend_timed_out(self.suspend_timeout) {
+            return Err(Error::SessionExpired(format!(
+                "{} (suspend timeout exceeded)",
+                session.session_id
+            )));
+        }
+
+        let mut machine = SessionStateMachine::from_state(session.state);
+        machine.transition(TransitionEvent::NetworkRestored)?;
+        if !store
+            .consume_ticket(claims.ticket_id, claims.expires_at)
+            .await?
+        {
+            return Err(Error::AuthenticationFailed(format!(
+                "Resumption ticket for session {} already used",
+                claims.session_id
+            )));
+        }
+
+        session.set_state(machine.state());
+        session.touch();
+        store.update(session.clone()).await?;
+
+        Ok(ResumedSession {
+            session_key: resumed_key(&secret, &request.client_nonce)?,
+            next_ticket: self.issue(&session)?,
+            session,
+            machine,
+        })
+    }
+
+    fn open(&self, ticket: &[u8]) -> Result<TicketClaims> {
+        let invalid = || Error::AuthenticationFailed("Invalid resumption ticket".to_string());
+
+        let (version, rest) = ticket.split_first().ok_or_else(invalid)?;
+        if *version != TICKET_VERSION || rest.len() < NONCE_SIZE {
+            return Err(invalid());
+        }
+        let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+
+        let plaintext = Zeroizing::new(
+            self.cipher
+                .decrypt(nonce, ciphertext, TICKET_AAD)
+                .map_err(|_| invalid())?,
+        );
+        serde_json::from_slice(&plaintext).map_err(|_| invalid())
+    }
+}
+
+fn binder(secret: &[u8], ticket: &[u8], client_nonce: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    let mut info = Vec::with_capacity(ticket.len() + client_nonce.len());
+    info.extend_from_slice(ticket);
+    info.extend_from_slice(client_nonce);
+    Ok(KeyDerivation::derive_with_context(
+        secret,
+        &DeriveContext::custom("resumption-binder", info),
+        RESUMPTION_SECRET_SIZE,
+    )?)
+}
+
+fn resumed_key(secret: &[u8], client_nonce: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    Ok(KeyDerivation::derive_with_context(
+        secret,
+        &DeriveContext::custom("resumption-key", client_nonce),
+        RESUMPTION_SECRET_SIZE,
+    )?)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::InMemorySessionStore;
+
+    async fn active_session(store: &mut InMemorySessionStore) -> Session {
+        let mut session = Session::new(
+            "PHONE".to_string(),
+            "LAPTOP".to_string(),
+            "1.0.0".to_string(),
+            12,
+        );
+        session.set_shared_key_id("key_1".to_string());
+        session.set_state(SessionState::Active);
+        store.create(session.clone()).await.unwrap();
+        session
+    }
+
+    async fn suspend(store: &mut InMemorySessionStore, session: &Session) {
+        let mut session = session.clone();
+        session.set_state(SessionState::Suspended);
+        store.update(session).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_within_suspend_window() {
+        let mut store = InMemorySessionStore::new();
+        let issuer = ResumptionTicketIssuer::new_random();
+        let session = active_session(&mut store).await;
+        let ticket = issuer.issue(&session).unwrap();
+        suspend(&mut store, &session).await;
+
+        let request = ResumptionRequest::from_bytes(
+            &ResumptionRequest::new(&ticket).unwrap().to_bytes().unwrap(),
+        )
+        .unwrap();
+        let resumed = issuer.resume(&mut store, &request).await.unwrap();
+
+        assert_eq!(resumed.session.session_id, session.session_id);
+        assert_eq!(resumed.machine.state(), SessionState::Active);
+        assert_eq!(
+            *resumed.session_key,
+            *request.session_key(&ticket.resumption_secret).unwrap()
+        );
+        assert_eq!(store.count_active().await.unwrap(), 1);
+
+        // Replayed early data is rejected
+        suspend(&mut store, &session).await;
+        assert!(matches!(
+            issuer.resume(&mut store, &request).await,
+            Err(Error::AuthenticationFailed(_))
+        ));
+
+        // The replacement ticket works
+        let next = ResumptionRequest::new(&resumed.next_ticket).unwrap();
+        assert!(issuer.resume(&mut store, &next).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_forged_binder_and_foreign_ticket() {
+        let mut store = InMemorySessionStore::new();
+        let issuer = ResumptionTicketIssuer::new_random();
+        let session = active_session(&mut store).await;
+        let ticket = issuer.issue(&session).unwrap();
+        suspend(&mut store, &session).await;
+
+        let mut forged = ResumptionRequest::new(&ticket).unwrap();
+        forged.binder[0] ^= 0xff;
+        assert!(matches!(
+            issuer.resume(&mut store, &forged).await,
+            Err(Error::AuthenticationFailed(_))
+        ));
+
+        let other = ResumptionTicketIssuer::new_random();
+        let request = ResumptionRequest::new(&ticket).unwrap();
+        assert!(matches!(
+            other.resume(&mut store, &request).await,
+            Err(Error::AuthenticationFailed(_))
+        ));
+
+        // Neither attempt consumed the ticket
+        assert!(issuer.resume(&mut store, &request).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_after_suspend_timeout() {
+        let mut store = InMemorySessionStore::new();
+        let issuer = ResumptionTicketIssuer::new_random().with_suspend_timeout(Duration::zero());
+        let session = active_session(&mut store).await;
+        let ticket = issuer.issue(&session).unwrap();
+        suspend(&mut store, &session).await;
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        let request = ResumptionRequest::new(&ticket).unwrap();
+        assert!(matches!(
+            issuer.resume(&mut store, &request).await,
+            Err(Error::SessionExpired(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_consumed_ticket_rejected_after_restart() {
+        use crate::redb_store::RedbSessionStore;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sessions.redb");
+        let ticket_key = [9u8; 32];
+
+        let mut session = Session::new(
+            "PHONE".to_string(),
+            "LAPTOP".to_string(),
+            "1.0.0".to_string(),
+            12,
+        );
+        session.set_state(SessionState::Active);
+        let request = {
+            let mut store = RedbSessionStore::open(&path).unwrap();
+            store.create(session.clone()).await.unwrap();
+            let issuer = ResumptionTicketIssuer::new(&ticket_key).unwrap();
+            let ticket = issuer.issue(&session).unwrap();
+
+            session.set_state(SessionState::Suspended);
+            store.update(session.clone()).await.unwrap();
+            let request = ResumptionRequest::new(&ticket).unwrap();
+            issuer.resume(&mut store, &request).await.unwrap();
+            request
+        };
+
+        // Process restarts with the same ticket key; the early data is replayed
+        let mut store = RedbSessionStore::open(&path).unwrap();
+        let issuer = ResumptionTicketIssuer::new(&ticket_key).unwrap();
+        let mut resumed = store.get(session.session_id).await.unwrap().unwrap();
+        resumed.set_state(SessionState::Suspended);
+        store.update(resumed).await.unwrap();
+
+        assert!(matches!(
+            issuer.resume(&mut store, &request).await,
+            Err(Error::AuthenticationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_issue_requires_active_session() {
+        let issuer = ResumptionTicketIssuer::new_random();
+        let session = Session::new(
+            "PHONE".to_string(),
+            "LAPTOP".to_string(),
+            "1.0.0".to_string(),
+            12,
+        );
+        assert!(issuer.issue(&session).is_err());
+    }
+}
@@ -41,6 +41,12 @@ pub struct Session {
 
     /// Last activity timestamp (for 30min sliding window)
     pub last_activity_at: DateTime<Utc>,
+
+    /// When the session entered Suspended (`None` in other states)
+    ///
+    /// Measures the suspend timeout independently of later updates.
+    #[serde(default)]
+    pub suspended_at: Option<DateTime<Utc>>,
 }
 
 impl Session {
@@ -72,6 +78,7 @@ impl Session {
             updated_at: now,
             expires_at,
             last_activity_at: now,
+            suspended_at: None,
         }
     }
 
@@ -92,9 +99,28 @@ impl Session {
     }
 
     /// Update session state
+    ///
+    /// Sets `suspended_at` on entering Suspended and clears it on leaving.
     pub fn set_state(&mut self, new_state: SessionState) {
+        let now = Utc::now();
+        if new_state != SessionState::Suspended {
+            self.suspended_at = None;
+        } else if self.state != SessionState::Suspended {
+            self.suspended_at = Some(now);
+        }
         self.state = new_state;
-        self.updated_at = Utc::now();
+        self.updated_at = now;
+    }
+
+    /// Whether the session has been Suspended for longer than `timeout`
+    ///
+    /// A Suspended session without `suspended_at` (stored before the field
+    /// existed) counts as timed out.
+    pub fn suspend_timed_out(&self, timeout: Duration) -> bool {
+        self.state == SessionState::Suspended
+            && self
+                .suspended_at
+                .is_none_or(|suspended_at| Utc::now() - suspended_at > timeout)
     }
 
     /// Set shared key ID after Crypto module generates session key
@@ -167,6 +193,34 @@ mod tests {
         assert!(!session.needs_activity_refresh());
     }
 
+    #[test]
+    fn test_suspended_at_tracks_suspension() {
+        let mut session = Session::new(
+            "DEV-A".to_string(),
+            "DEV-B".to_string(),
+            "1.0.0".to_string(),
+            12,
+        );
+        session.set_state(SessionState::Active);
+        assert_eq!(session.suspended_at, None);
+
+        session.set_state(SessionState::Suspended);
+        let suspended_at = session.suspended_at.unwrap();
+        assert!(!session.suspend_timed_out(Duration::minutes(5)));
+
+        // Later updates do not restart the suspend timeout
+        session.touch();
+        session.set_state(SessionState::Suspended);
+        assert_eq!(session.suspended_at, Some(suspended_at));
+
+        session.suspended_at = Some(Utc::now() - Duration::minutes(6));
+        assert!(session.suspend_timed_out(Duration::minutes(5)));
+
+        session.set_state(SessionState::Active);
+        assert_eq!(session.suspended_at, None);
+        assert!(!session.suspend_timed_out(Duration::zero()));
+    }
+
     #[test]
     fn test_uuidv7_monotonicity() {
         let session1 = Session::new(
@@ -0,0 +1,134 @@
+//! Metrics for events on the shared `honeylink_core::EventBus`
+//!
+//! Telemetry subscribes once to every topic and records:
+//! - `honeylink_events_total{topic, event_type}` for each event
+//! - `honeylink_events_lagged_total` when the recorder falls behind
+
+use crate::collector::TelemetryCollector;
+use crate::transport_events::LinkStateChangeEvent;
+use crate::types::Metric;
+use honeylink_core::event_bus::{EventEnvelope, RecvError, Subscription};
+use honeylink_core::events::{Event, LinkState};
+use std::sync::Arc;
+
+/// Counter metric for one bus event
+pub fn event_metric(envelope: &EventEnvelope) -> Metric {
+    let event = &envelope.event;
+    let mut labels = vec![
+        ("topic".to_string(), event.topic().to_string()),
+        ("event_type".to_string(), event.event_type().to_string()),
+    ];
+    match event {
+        Event::SessionStateChanged { to, .. } => {
+            labels.push(("to_state".to_string(), format!("{:?}", to)));
+        }
+        Event::PolicyUpdated { change, .. } => {
+            labels.push(("change".to_string(), format!("{:?}", change)));
+        }
+        Event::LinkStateChanged { adapter, state } => {
+            labels.push(("adapter".to_string(), adapter.clone()));
+            labels.push(("state".to_string(), format!("{:?}", state)));
+        }
+        Event::StreamCreated { .. } => {}
+    }
+
+    Metric::counter("honeylink_events_total".to_string(), 1.0, labels)
+}
+
+/// Record metrics for every event received on `subscription`
+///
+/// Runs until the bus is dropped.
+pub fn spawn_event_recorder(
+    collector: Arc<TelemetryCollector>,
+    mut subscription: Subscription,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let metric = match subscription.recv().await {
+                Ok(envelope) => event_metric(&envelope),
+                Err(RecvError::Lagged { missed }) => Metric::counter(
+                    "honeylink_events_lagged_total".to_string(),
+                    missed as f64,
+                    vec![],
+                ),
+                Err(RecvError::Closed) => break,
+            };
+
+            if let Err(e) = collector.record_metric(metric).await {
+                log::warn!("Failed to record event metric: {}", e);
+            }
+        }
+    })
+}
+
+impl LinkStateChangeEvent {
+    /// Hot swap as bus events: the previous link down, the new one up
+    pub fn to_core_events(&self) -> [Event; 2] {
+        [
+            Event::LinkStateChanged {
+                adapter: self.from_type.clone(),
+                state: LinkState::Down,
+            },
+            Event::LinkStateChanged {
+                adapter: self.to_type.clone(),
+                state: LinkState::Up,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeylink_core::event_bus::EventBus;
+    use honeylink_core::events::PolicyChangeKind;
+
+    #[test]
+    fn test_event_metric_labels() {
+        let bus = EventBus::new();
+        let mut subscription = bus.subscribe_all();
+        bus.publish(Event::PolicyUpdated {
+            policy_id: "pol_1".to_string(),
+            device_id: None,
+            stream_id: 2,
+            change: PolicyChangeKind::RolledBack,
+        })
+        .unwrap();
+        let envelope = subscription.try_recv().unwrap().unwrap();
+
+        let metric = event_metric(&envelope);
+        assert_eq!(metric.name, "honeylink_events_total");
+        assert!(metric
+            .labels
+            .contains(&("topic".to_string(), "policy".to_string())));
+        assert!(metric
+            .labels
+            .contains(&("change".to_string(), "RolledBack".to_string())));
+    }
+
+    #[test]
+    fn test_hot_swap_to_core_events() {
+        let swap = LinkStateChangeEvent::new(
+            "wifi6e".to_string(),
+            "5g".to_string(),
+            120,
+            "signal loss".to_string(),
+        );
+
+        let [down, up] = swap.to_core_events();
+        assert_eq!(
+            down,
+            Event::LinkStateChanged {
+                adapter: "wifi6e".to_string(),
+                state: LinkState::Down,
+            }
+        );
+        assert!(matches!(
+            up,
+            Event::LinkStateChanged {
+                state: LinkState::Up,
+                ..
+            }
+        ));
+    }
+}
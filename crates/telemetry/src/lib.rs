@@ -9,6 +9,7 @@
 //! - **SLI/SLO**: 5 predefined SLIs with Yellow/Orange/Red alerting
 //! - **Alerting**: PagerDuty and Slack integration
 //! - **Storage**: TimescaleDB buffering with PII detection
+//! - **Events**: Metrics for every event on the shared core event bus
 //!
 //! ## Usage
 //! ```rust,no_run
@@ -29,6 +30,7 @@
 
 pub mod alert;
 pub mod collector;
+pub mod core_events;
 pub mod crypto_metrics;
 pub mod crypto_telemetry_impl;
 pub mod otel;
@@ -40,6 +42,7 @@ pub mod types;
 
 // Re-exports
 pub use collector::{TelemetryCollector, TelemetryConfig};
+pub use core_events::{event_metric, spawn_event_recorder};
 pub use crypto_telemetry_impl::CryptoTelemetryImpl;
 pub use transport_events::{
     FecStrategyChangeEvent, LinkStateChangeEvent, PacketReceiveFailedEvent, PacketSentEvent,
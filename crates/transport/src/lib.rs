@@ -8,8 +8,10 @@
 //! - **FEC**: Forward Error Correction strategies
 //! - **WFQ**: Weighted Fair Queuing scheduling
 //! - **Telemetry**: Link quality monitoring and power management
+//! - **Secure channels**: Noise-based end-to-end encryption over any link
 
 use async_trait::async_trait;
+use honeylink_crypto::packet_protection::{PacketOpener, PacketSealer};
 use std::time::Duration;
 use thiserror::Error;
 
@@ -18,6 +20,8 @@ pub mod quic;
 pub mod webrtc;
 pub mod manager;
 pub mod logging;
+pub mod resolver;
+pub mod secure_channel;
 
 // Phase 4: Transport protocol abstraction (QUIC/WebRTC)
 pub mod protocol;
@@ -33,6 +37,8 @@ pub use protocol::{
     Connection, ConnectionStats, ProtocolStrategy, ProtocolType, Stream, TransportProtocol,
     TransportStats,
 };
+pub use resolver::DeviceResolver;
+pub use secure_channel::{FrameLink, PacketLink, SecureChannel, SecureChannelConfig};
 
 // Existing exports
 pub use fec::{FecEncoder, FecStrategy};
@@ -70,6 +76,14 @@ pub enum TransportError {
     /// Adapter-specific error
     #[error("Adapter error: {0}")]
     AdapterError(String),
+
+    /// Packet protection (encryption or authentication) failed
+    #[error("Packet protection failed: {0}")]
+    ProtectionFailed(String),
+
+    /// Packet sequence number was already received or is outside the replay window
+    #[error("Replayed packet: sequence number {0}")]
+    ReplayDetected(u64),
 }
 
 /// Power consumption modes for physical layers
@@ -143,6 +157,9 @@ impl Default for LinkQualityMetrics {
     }
 }
 
+/// Length of the sequence number prefix added by `Packet::protect`
+pub const PACKET_SEQ_LEN: usize = 8;
+
 /// Packet to be transmitted over physical layer
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Packet {
@@ -171,6 +188,58 @@ impl Packet {
     pub fn size(&self) -> usize {
         self.data.len()
     }
+
+    /// Encrypts the packet data in place
+    ///
+    /// `data` becomes `sequence (u64 BE) | ciphertext | tag`. `header` is
+    /// authenticated but not transmitted; the receiver must supply the same
+    /// bytes (e.g. stream ID or adapter framing).
+    pub fn protect(
+        &mut self,
+        sealer: &mut PacketSealer,
+        header: &[u8],
+    ) -> Result<(), TransportError> {
+        let seq = sealer
+            .seal(header, &mut self.data)
+            .map_err(|e| TransportError::ProtectionFailed(e.to_string()))?;
+        self.data.splice(0..0, seq.to_be_bytes());
+        Ok(())
+    }
+
+    /// Authenticates and decrypts packet data produced by `protect`
+    ///
+    /// On error the packet data is left unchanged.
+    ///
+    /// # Errors
+    /// * `ReplayDetected` if the sequence number was already accepted or is too old
+    /// * `ProtectionFailed` if the packet is truncated or fails authentication
+    pub fn unprotect(
+        &mut self,
+        opener: &mut PacketOpener,
+        header: &[u8],
+    ) -> Result<(), TransportError> {
+        if self.data.len() < PACKET_SEQ_LEN {
+            return Err(TransportError::ProtectionFailed(format!(
+                "Packet too short: {} bytes",
+                self.data.len()
+            )));
+        }
+
+        let mut seq_bytes = [0u8; PACKET_SEQ_LEN];
+        seq_bytes.copy_from_slice(&self.data[..PACKET_SEQ_LEN]);
+        let seq = u64::from_be_bytes(seq_bytes);
+        if !opener.window().check(seq) {
+            return Err(TransportError::ReplayDetected(seq));
+        }
+
+        let mut body = self.data.split_off(PACKET_SEQ_LEN);
+        if let Err(e) = opener.open(seq, header, &mut body) {
+            self.data.append(&mut body);
+            return Err(TransportError::ProtectionFailed(e.to_string()));
+        }
+        self.data = body;
+        Ok(())
+    }
 }
 
 /// Physical layer abstraction trait
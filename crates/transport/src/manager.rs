@@ -18,12 +18,20 @@
 //! - **Protocol abstraction**: TransportProtocol trait enables pluggable backends
 //! - **Connection pooling**: Reuses existing connections for performance
 //! - **Failover logic**: Automatic fallback when primary protocol fails
+//! - **Identity-based dialing**: `connect_device()` resolves a `DeviceId` through a
+//!   `DeviceResolver` (e.g. DiscoveryManager) and pools connections per device
 //! - **Thread-safe**: All state protected by `Arc<RwLock>` and tokio::sync primitives
+//! - **Shared events**: `open_session_stream()` publishes `StreamCreated` to the
+//!   shared `EventBus` attached with `with_event_bus()`
 
 use crate::protocol::{
     Connection, ProtocolStrategy, ProtocolType, Result, StreamPriority, TransportError, TransportProtocol,
     TransportStats, Stream,
 };
+use crate::resolver::DeviceResolver;
+use honeylink_core::events::Event;
+use honeylink_core::types::{DeviceId, SessionId, StreamId};
+use honeylink_core::EventBus;
 use honeylink_qos_scheduler::scheduler::{QoSScheduler, QoSPriority, StreamRequest, StreamMode, AllocationStats};
 use std::collections::HashMap;
 use std::net::SocketAddr;
@@ -52,6 +60,23 @@ pub struct TransportManager {
     /// removed on next access attempt.
     connections: Arc<RwLock<HashMap<SocketAddr, Arc<dyn Connection>>>>,
 
+    /// Connection pool keyed by peer identity (device_id -> connection)
+    ///
+    /// Populated by `connect_device()`. At most one connection per device is
+    /// kept; a new connection after an address change replaces the old one.
+    device_connections: Arc<RwLock<HashMap<DeviceId, Arc<dyn Connection>>>>,
+
+    /// Per-device dial locks
+    ///
+    /// `connect_device()` holds the device's lock from the pool check until
+    /// the new connection is pooled, so concurrent calls for the same device
+    /// share one connection instead of dialing twice. The last caller for a
+    /// device removes its entry, so only in-progress dials are kept.
+    device_dials: Arc<Mutex<HashMap<DeviceId, Arc<Mutex<()>>>>>,
+
+    /// Resolver used by `connect_device()` (typically the DiscoveryManager)
+    resolver: Arc<RwLock<Option<Arc<dyn DeviceResolver>>>>,
+
     /// Protocol selection strategy
     strategy: ProtocolStrategy,
 
@@ -66,6 +91,9 @@ pub struct TransportManager {
     /// Manages bandwidth allocation and stream limits across all connections.
     /// Shared across all protocols.
     qos_scheduler: Arc<Mutex<QoSScheduler>>,
+
+    /// Shared event bus receiving `StreamCreated` events (optional)
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl TransportManager {
@@ -94,13 +122,23 @@ impl TransportManager {
         Self {
             protocols: Arc::new(RwLock::new(HashMap::new())),
             connections: Arc::new(RwLock::new(HashMap::new())),
+            device_connections: Arc::new(RwLock::new(HashMap::new())),
+            device_dials: Arc::new(Mutex::new(HashMap::new())),
+            resolver: Arc::new(RwLock::new(None)),
             strategy,
             stats: Arc::new(RwLock::new(TransportStats::default())),
             default_timeout: Duration::from_secs(5),
             qos_scheduler: Arc::new(Mutex::new(qos_scheduler)),
+            event_bus: None,
         }
     }
 
+    /// Publish `StreamCreated` events for session streams to `event_bus`
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     /// Register a transport protocol
     ///
     /// Adds a new protocol backend to the manager. Protocols can be registered
@@ -185,13 +223,7 @@ impl TransportManager {
         }
 
         // Establish new connection based on strategy
-        let conn = match self.strategy {
-            ProtocolStrategy::PreferQuic => self.connect_prefer_quic(addr).await?,
-            ProtocolStrategy::PreferWebRtc => self.connect_prefer_webrtc(addr).await?,
-            ProtocolStrategy::QuicOnly => self.connect_quic_only(addr).await?,
-            ProtocolStrategy::WebRtcOnly => self.connect_webrtc_only(addr).await?,
-            ProtocolStrategy::All => self.connect_all(addr).await?,
-        };
+        let conn = self.establish(addr).await?;
 
         // Add to pool
         self.add_to_pool(addr, conn.clone()).await;
@@ -204,6 +236,212 @@ impl TransportManager {
         Ok(conn)
     }
 
+    /// Set the resolver used by `connect_device()`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use honeylink_discovery::{protocol::ProtocolStrategy as DiscoveryStrategy, DiscoveryManager};
+    /// use honeylink_transport::manager::TransportManager;
+    /// use honeylink_transport::protocol::ProtocolStrategy;
+    /// use std::sync::Arc;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let discovery = Arc::new(DiscoveryManager::new(DiscoveryStrategy::default(), 100));
+    ///     let mut manager = TransportManager::new(ProtocolStrategy::PreferQuic);
+    ///     manager.set_resolver(discovery).await;
+    /// }
+    /// ```
+    pub async fn set_resolver(&mut self, resolver: Arc<dyn DeviceResolver>) {
+        *self.resolver.write().await = Some(resolver);
+    }
+
+    /// Connect to a peer by device identity
+    ///
+    /// Resolves `device_id` through the configured `DeviceResolver`, tries each
+    /// candidate address with the protocol selection strategy, and accepts the
+    /// first connection whose `peer_device_id()` equals `device_id`. Only
+    /// transports that authenticate the peer report an identity (QUIC built
+    /// with `QuicTransport::with_identity()` and the device's pinned key), so
+    /// unauthenticated peers fail with `IdentityUnverified`.
+    ///
+    /// # Connection Pooling
+    /// - Connections are pooled by device ID, not by address
+    /// - Concurrent calls for the same device are serialized; the later ones
+    ///   reuse the connection the first one established
+    /// - A live pooled connection is reused even if the device changed IP
+    ///   (QUIC connection migration keeps it valid)
+    /// - A stale pooled connection is dropped and replaced by a new one to the
+    ///   device's current address, so no duplicate connections accumulate
+    ///
+    /// # Returns
+    /// - `Ok(Arc<dyn Connection>)`: Identity-verified connection
+    /// - `Err(TransportError::DeviceNotFound)`: No resolver, or device unknown
+    /// - `Err(TransportError::IdentityMismatch)` / `IdentityUnverified`: Peer at the
+    ///   last candidate address was not the requested device
+    /// - `Err(TransportError)`: Connection failed on every candidate address
+    ///
+    /// # Example
+    /// ```no_run
+    /// use honeylink_core::types::DeviceId;
+    /// use honeylink_transport::manager::TransportManager;
+    /// use honeylink_transport::protocol::ProtocolStrategy;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let manager = TransportManager::new(ProtocolStrategy::PreferQuic);
+    ///     let device_id = DeviceId::new("DEV-001".to_string())?;
+    ///     let conn = manager.connect_device(&device_id).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn connect_device(&self, device_id: &DeviceId) -> Result<Arc<dyn Connection>> {
+        let dial_lock = self
+            .device_dials
+            .lock()
+            .await
+            .entry(device_id.clone())
+            .or_default()
+            .clone();
+        let dialing = dial_lock.lock().await;
+        let result = self.dial_device(device_id).await;
+        drop(dialing);
+
+        // Drop the lock entry unless another call is waiting on it
+        let mut dials = self.device_dials.lock().await;
+        drop(dial_lock);
+        if dials
+            .get(device_id)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            dials.remove(device_id);
+        }
+
+        result
+    }
+
+    /// Pool lookup and dial for `connect_device()`, under the device's dial lock
+    async fn dial_device(&self, device_id: &DeviceId) -> Result<Arc<dyn Connection>> {
+        // Check device pool first
+        if let Some(conn) = self.get_pooled_device_connection(device_id).await {
+            debug!("Reusing pooled connection to device {}", device_id);
+            return Ok(conn);
+        }
+
+        let resolver = self.resolver.read().await.clone().ok_or_else(|| {
+            TransportError::DeviceNotFound(format!("{} (no resolver configured)", device_id))
+        })?;
+        let candidates = resolver.resolve(device_id).await?;
+
+        let mut last_error =
+            TransportError::DeviceNotFound(format!("{} (no candidate addresses)", device_id));
+
+        for addr in candidates {
+            let conn = match self.establish(addr).await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    debug!("Candidate {} for device {} failed: {}", addr, device_id, e);
+                    last_error = e;
+                    continue;
+                }
+            };
+
+            // Verify the peer is the device we asked for
+            match conn.peer_device_id() {
+                Some(peer_id) if &peer_id == device_id => {}
+                Some(peer_id) => {
+                    warn!(
+                        "Peer at {} identified as {}, expected {}",
+                        addr, peer_id, device_id
+                    );
+                    let _ = conn.close().await;
+                    last_error = TransportError::IdentityMismatch {
+                        expected: device_id.to_string(),
+                        actual: peer_id.to_string(),
+                    };
+                    self.stats.write().await.connections_failed += 1;
+                    continue;
+                }
+                None => {
+                    warn!("Peer at {} did not present a device identity", addr);
+                    let _ = conn.close().await;
+                    last_error = TransportError::IdentityUnverified(format!(
+                        "peer at {} presented no device identity",
+                        addr
+                    ));
+                    self.stats.write().await.connections_failed += 1;
+                    continue;
+                }
+            }
+
+            info!("Connected to device {} at {}", device_id, addr);
+            self.add_to_device_pool(device_id.clone(), conn.clone()).await;
+
+            let mut stats = self.stats.write().await;
+            stats.connections_established += 1;
+            stats.active_connections += 1;
+
+            return Ok(conn);
+        }
+
+        Err(last_error)
+    }
+
+    /// Establish a new connection using the configured strategy (no pooling)
+    async fn establish(&self, addr: SocketAddr) -> Result<Arc<dyn Connection>> {
+        match self.strategy {
+            ProtocolStrategy::PreferQuic => self.connect_prefer_quic(addr).await,
+            ProtocolStrategy::PreferWebRtc => self.connect_prefer_webrtc(addr).await,
+            ProtocolStrategy::QuicOnly => self.connect_quic_only(addr).await,
+            ProtocolStrategy::WebRtcOnly => self.connect_webrtc_only(addr).await,
+            ProtocolStrategy::All => self.connect_all(addr).await,
+        }
+    }
+
+    /// Get pooled device connection if available and alive
+    async fn get_pooled_device_connection(
+        &self,
+        device_id: &DeviceId,
+    ) -> Option<Arc<dyn Connection>> {
+        let mut connections = self.device_connections.write().await;
+
+        if let Some(conn) = connections.get(device_id) {
+            if conn.is_connected() {
+                return Some(conn.clone());
+            }
+
+            // Remove stale connection
+            debug!("Removing stale connection to device {}", device_id);
+            connections.remove(device_id);
+
+            let mut stats = self.stats.write().await;
+            stats.active_connections = stats.active_connections.saturating_sub(1);
+        }
+
+        None
+    }
+
+    /// Add connection to device pool, closing any connection it replaces
+    async fn add_to_device_pool(&self, device_id: DeviceId, conn: Arc<dyn Connection>) {
+        let replaced = self
+            .device_connections
+            .write()
+            .await
+            .insert(device_id.clone(), conn);
+
+        if let Some(old) = replaced {
+            debug!(
+                "Replacing connection to device {} (was {})",
+                device_id,
+                old.remote_addr()
+            );
+            let _ = old.close().await;
+
+            let mut stats = self.stats.write().await;
+            stats.active_connections = stats.active_connections.saturating_sub(1);
+        }
+    }
+
     /// Get pooled connection if available and alive
     async fn get_pooled_connection(&self, addr: SocketAddr) -> Option<Arc<dyn Connection>> {
         let mut connections = self.connections.write().await;
@@ -460,6 +698,7 @@ impl TransportManager {
     pub async fn clear_pool(&self) {
         let mut connections = self.connections.write().await;
         connections.clear();
+        self.device_connections.write().await.clear();
 
         let mut stats = self.stats.write().await;
         stats.active_connections = 0;
@@ -485,6 +724,25 @@ impl TransportManager {
         Ok(())
     }
 
+    /// Close the connection to a device and remove it from the device pool
+    ///
+    /// # Parameters
+    /// - `device_id`: Device whose connection should be closed
+    pub async fn close_device_connection(&self, device_id: &DeviceId) -> Result<()> {
+        let removed = self.device_connections.write().await.remove(device_id);
+
+        if let Some(conn) = removed {
+            conn.close().await?;
+
+            let mut stats = self.stats.write().await;
+            stats.active_connections = stats.active_connections.saturating_sub(1);
+
+            info!("Closed connection to device {}", device_id);
+        }
+
+        Ok(())
+    }
+
     /// Get list of registered protocol types
     pub async fn registered_protocols(&self) -> Vec<ProtocolType> {
         let protocols = self.protocols.read().await;
@@ -540,6 +798,51 @@ impl TransportManager {
         priority: StreamPriority,
         bandwidth_kbps: u32,
     ) -> Result<Box<dyn Stream>> {
+        let (_, stream) = self
+            .open_allocated_stream(connection, priority, bandwidth_kbps)
+            .await?;
+        Ok(stream)
+    }
+
+    /// Open a prioritized stream for a session and announce it
+    ///
+    /// Same as `open_prioritized_stream()`, but also returns the stream's QoS
+    /// allocation ID (pass it to `release_stream()`) and publishes
+    /// `Event::StreamCreated` to the attached event bus.
+    ///
+    /// # Errors
+    /// Fails like `open_prioritized_stream()`. A failed publish is logged and
+    /// does not fail the call.
+    pub async fn open_session_stream(
+        &self,
+        session_id: SessionId,
+        connection: &Arc<dyn Connection>,
+        priority: StreamPriority,
+        bandwidth_kbps: u32,
+    ) -> Result<(StreamId, Box<dyn Stream>)> {
+        let (stream_id, stream) = self
+            .open_allocated_stream(connection, priority, bandwidth_kbps)
+            .await?;
+
+        if let Some(event_bus) = &self.event_bus {
+            if let Err(e) = event_bus.publish(Event::StreamCreated {
+                session_id,
+                stream_id,
+            }) {
+                warn!("Failed to publish StreamCreated for {:?}: {}", stream_id, e);
+            }
+        }
+
+        Ok((stream_id, stream))
+    }
+
+    /// Allocate QoS bandwidth for a stream and open it on `connection`
+    async fn open_allocated_stream(
+        &self,
+        connection: &Arc<dyn Connection>,
+        priority: StreamPriority,
+        bandwidth_kbps: u32,
+    ) -> Result<(StreamId, Box<dyn Stream>)> {
         // Map StreamPriority to QoSPriority
         let qos_priority = match priority {
             StreamPriority::High => QoSPriority::Burst,
@@ -570,11 +873,12 @@ impl TransportManager {
             .allocate_streams(&[request])
             .map_err(|e| TransportError::ResourceExhausted(e.to_string()))?;
 
-        if allocations.is_empty() {
+        let Some(allocation) = allocations.first() else {
             return Err(TransportError::ResourceExhausted(
                 "QoS scheduler rejected stream allocation".to_string(),
             ));
-        }
+        };
+        let stream_id = allocation.stream_id;
 
         // Stream allocated successfully, open it on the connection
         let stream = connection.open_stream_with_priority(priority).await?;
@@ -587,7 +891,7 @@ impl TransportManager {
             bandwidth_kbps
         );
 
-        Ok(stream)
+        Ok((stream_id, stream))
     }
 
     /// Release a stream from QoS scheduler
@@ -598,7 +902,7 @@ impl TransportManager {
     /// # Parameters
     /// - `stream_id`: Unique stream ID (from honeylink-core types)
     /// - `bandwidth_kbps`: Bandwidth to release
-    pub async fn release_stream(&self, stream_id: honeylink_core::types::StreamId, bandwidth_kbps: u32) {
+    pub async fn release_stream(&self, stream_id: StreamId, bandwidth_kbps: u32) {
         let mut scheduler = self.qos_scheduler.lock().await;
         scheduler.release_stream(stream_id, bandwidth_kbps);
 
@@ -644,9 +948,56 @@ mod tests {
                     self.name
                 )))
             } else {
-                Ok(Arc::new(MockConnection {
-                    addr: "127.0.0.1:8080".parse().unwrap(),
-                }))
+                Ok(Arc::new(MockConnection::new(
+                    "127.0.0.1:8080".parse().unwrap(),
+                    None,
+                )))
+            }
+        }
+
+        async fn listen(&self, _addr: SocketAddr) -> Result<mpsc::Receiver<Arc<dyn Connection>>> {
+            Err(TransportError::ProtocolNotSupported(
+                "Mock listen not implemented".to_string(),
+            ))
+        }
+
+        async fn stop_listening(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn is_listening(&self) -> bool {
+            false
+        }
+
+        async fn stats(&self) -> TransportStats {
+            TransportStats::default()
+        }
+    }
+
+    // Mock transport where each address is served by a specific device
+    struct IdentityTransport {
+        peers: HashMap<SocketAddr, DeviceId>,
+    }
+
+    #[async_trait]
+    impl TransportProtocol for IdentityTransport {
+        fn protocol_name(&self) -> &'static str {
+            "IdentityQUIC"
+        }
+
+        async fn connect(
+            &self,
+            addr: SocketAddr,
+            _timeout: Duration,
+        ) -> Result<Arc<dyn Connection>> {
+            // Let concurrent dials interleave
+            tokio::task::yield_now().await;
+            match self.peers.get(&addr) {
+                Some(peer) => Ok(Arc::new(MockConnection::new(addr, Some(peer.clone())))),
+                None => Err(TransportError::ConnectionFailed(format!(
+                    "nothing listening on {}",
+                    addr
+                ))),
             }
         }
 
@@ -669,9 +1020,41 @@ mod tests {
         }
     }
 
+    // Mock resolver with mutable candidate list (simulates IP changes)
+    struct StaticResolver {
+        addrs: RwLock<Vec<SocketAddr>>,
+    }
+
+    #[async_trait]
+    impl DeviceResolver for StaticResolver {
+        async fn resolve(&self, device_id: &DeviceId) -> Result<Vec<SocketAddr>> {
+            let addrs = self.addrs.read().await.clone();
+            if addrs.is_empty() {
+                return Err(TransportError::DeviceNotFound(device_id.to_string()));
+            }
+            Ok(addrs)
+        }
+    }
+
+    fn device(id: &str) -> DeviceId {
+        DeviceId::new(id.to_string()).unwrap()
+    }
+
     // Mock connection for testing
     struct MockConnection {
         addr: SocketAddr,
+        peer: Option<DeviceId>,
+        connected: std::sync::atomic::AtomicBool,
+    }
+
+    impl MockConnection {
+        fn new(addr: SocketAddr, peer: Option<DeviceId>) -> Self {
+            Self {
+                addr,
+                peer,
+                connected: std::sync::atomic::AtomicBool::new(true),
+            }
+        }
     }
 
     // Mock stream for testing
@@ -719,11 +1102,17 @@ mod tests {
         }
 
         async fn close(&self) -> Result<()> {
+            self.connected
+                .store(false, std::sync::atomic::Ordering::SeqCst);
             Ok(())
         }
 
         fn is_connected(&self) -> bool {
-            true
+            self.connected.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        fn peer_device_id(&self) -> Option<DeviceId> {
+            self.peer.clone()
         }
 
         fn stats(&self) -> ConnectionStats {
@@ -900,6 +1289,202 @@ mod tests {
         assert_eq!(stats.active_connections, 0);
     }
 
+    #[tokio::test]
+    async fn test_connect_device_without_resolver() {
+        let manager = TransportManager::new(ProtocolStrategy::PreferQuic);
+        let result = manager.connect_device(&device("DEV-001")).await;
+        assert!(matches!(result, Err(TransportError::DeviceNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_pooled_by_device_id() {
+        let addr: SocketAddr = "192.168.1.10:7843".parse().unwrap();
+        let mut manager = TransportManager::new(ProtocolStrategy::QuicOnly);
+        manager
+            .register_protocol(
+                ProtocolType::Quic,
+                Arc::new(IdentityTransport {
+                    peers: HashMap::from([(addr, device("DEV-001"))]),
+                }),
+            )
+            .await;
+        manager
+            .set_resolver(Arc::new(StaticResolver {
+                addrs: RwLock::new(vec![addr]),
+            }))
+            .await;
+
+        let conn1 = manager.connect_device(&device("DEV-001")).await.unwrap();
+        let conn2 = manager.connect_device(&device("DEV-001")).await.unwrap();
+
+        assert!(Arc::ptr_eq(&conn1, &conn2));
+        assert_eq!(conn1.peer_device_id(), Some(device("DEV-001")));
+
+        let stats = manager.stats().await;
+        assert_eq!(stats.connections_established, 1);
+        assert_eq!(stats.active_connections, 1);
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_concurrent_calls_share_connection() {
+        let addr: SocketAddr = "192.168.1.10:7843".parse().unwrap();
+        let mut manager = TransportManager::new(ProtocolStrategy::QuicOnly);
+        manager
+            .register_protocol(
+                ProtocolType::Quic,
+                Arc::new(IdentityTransport {
+                    peers: HashMap::from([(addr, device("DEV-001"))]),
+                }),
+            )
+            .await;
+        manager
+            .set_resolver(Arc::new(StaticResolver {
+                addrs: RwLock::new(vec![addr]),
+            }))
+            .await;
+
+        let target = device("DEV-001");
+        let (conn1, conn2) = tokio::join!(
+            manager.connect_device(&target),
+            manager.connect_device(&target)
+        );
+
+        assert!(Arc::ptr_eq(&conn1.unwrap(), &conn2.unwrap()));
+        assert_eq!(manager.stats().await.connections_established, 1);
+
+        // Dial locks are released once no call is in progress
+        assert!(manager.device_dials.lock().await.is_empty());
+        assert!(manager.connect_device(&device("DEV-404")).await.is_err());
+        assert!(manager.device_dials.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_skips_identity_mismatch() {
+        let stale_addr: SocketAddr = "192.168.1.10:7843".parse().unwrap();
+        let real_addr: SocketAddr = "192.168.1.20:7843".parse().unwrap();
+        let mut manager = TransportManager::new(ProtocolStrategy::QuicOnly);
+        manager
+            .register_protocol(
+                ProtocolType::Quic,
+                Arc::new(IdentityTransport {
+                    peers: HashMap::from([
+                        (stale_addr, device("DEV-OTHER")),
+                        (real_addr, device("DEV-001")),
+                    ]),
+                }),
+            )
+            .await;
+        manager
+            .set_resolver(Arc::new(StaticResolver {
+                addrs: RwLock::new(vec![stale_addr, real_addr]),
+            }))
+            .await;
+
+        let conn = manager.connect_device(&device("DEV-001")).await.unwrap();
+        assert_eq!(conn.remote_addr(), real_addr);
+
+        let stats = manager.stats().await;
+        assert_eq!(stats.connections_established, 1);
+        assert_eq!(stats.connections_failed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_identity_mismatch_error() {
+        let addr: SocketAddr = "192.168.1.10:7843".parse().unwrap();
+        let mut manager = TransportManager::new(ProtocolStrategy::QuicOnly);
+        manager
+            .register_protocol(
+                ProtocolType::Quic,
+                Arc::new(IdentityTransport {
+                    peers: HashMap::from([(addr, device("DEV-OTHER"))]),
+                }),
+            )
+            .await;
+        manager
+            .set_resolver(Arc::new(StaticResolver {
+                addrs: RwLock::new(vec![addr]),
+            }))
+            .await;
+
+        let result = manager.connect_device(&device("DEV-001")).await;
+        assert_eq!(
+            result.err(),
+            Some(TransportError::IdentityMismatch {
+                expected: "DEV-001".to_string(),
+                actual: "DEV-OTHER".to_string(),
+            })
+        );
+        assert_eq!(manager.stats().await.active_connections, 0);
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_rejects_unverified_peer() {
+        let mut manager = TransportManager::new(ProtocolStrategy::QuicOnly);
+        manager
+            .register_protocol(
+                ProtocolType::Quic,
+                Arc::new(MockTransport {
+                    name: "QUIC",
+                    should_fail: false,
+                }),
+            )
+            .await;
+        manager
+            .set_resolver(Arc::new(StaticResolver {
+                addrs: RwLock::new(vec!["127.0.0.1:8080".parse().unwrap()]),
+            }))
+            .await;
+
+        let result = manager.connect_device(&device("DEV-001")).await;
+        assert!(matches!(result, Err(TransportError::IdentityUnverified(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_replaces_connection_after_ip_change() {
+        let old_addr: SocketAddr = "192.168.1.10:7843".parse().unwrap();
+        let new_addr: SocketAddr = "10.0.0.5:7843".parse().unwrap();
+        let resolver = Arc::new(StaticResolver {
+            addrs: RwLock::new(vec![old_addr]),
+        });
+
+        let mut manager = TransportManager::new(ProtocolStrategy::QuicOnly);
+        manager
+            .register_protocol(
+                ProtocolType::Quic,
+                Arc::new(IdentityTransport {
+                    peers: HashMap::from([
+                        (old_addr, device("DEV-001")),
+                        (new_addr, device("DEV-001")),
+                    ]),
+                }),
+            )
+            .await;
+        manager.set_resolver(resolver.clone()).await;
+
+        let conn1 = manager.connect_device(&device("DEV-001")).await.unwrap();
+        assert_eq!(conn1.remote_addr(), old_addr);
+
+        // Device moves to a new network; the old connection drops
+        *resolver.addrs.write().await = vec![new_addr];
+        conn1.close().await.unwrap();
+
+        let conn2 = manager.connect_device(&device("DEV-001")).await.unwrap();
+        assert_eq!(conn2.remote_addr(), new_addr);
+
+        // One pooled connection per device, no duplicates
+        assert_eq!(manager.device_connections.read().await.len(), 1);
+        let stats = manager.stats().await;
+        assert_eq!(stats.connections_established, 2);
+        assert_eq!(stats.active_connections, 1);
+
+        manager
+            .close_device_connection(&device("DEV-001"))
+            .await
+            .unwrap();
+        assert!(!conn2.is_connected());
+        assert_eq!(manager.stats().await.active_connections, 0);
+    }
+
     #[tokio::test]
     async fn test_qos_prioritized_stream() {
         let mut manager = TransportManager::new(ProtocolStrategy::PreferQuic);
@@ -1038,4 +1623,37 @@ mod tests {
         let stats = manager.qos_stats().await;
         assert_eq!(stats.total_streams, 3);
     }
+
+    #[tokio::test]
+    async fn test_session_stream_published() {
+        let event_bus = Arc::new(EventBus::new());
+        let mut streams = event_bus.subscribe([honeylink_core::Topic::Stream]);
+        let mut manager = TransportManager::new(ProtocolStrategy::PreferQuic)
+            .with_event_bus(Arc::clone(&event_bus));
+        let mock = Arc::new(MockTransport {
+            name: "QUIC",
+            should_fail: false,
+        });
+        manager.register_protocol(ProtocolType::Quic, mock).await;
+
+        let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let conn = manager.connect(addr).await.unwrap();
+        let session_id = SessionId::new();
+        let (stream_id, _stream) = manager
+            .open_session_stream(session_id, &conn, StreamPriority::Normal, 1000)
+            .await
+            .unwrap();
+
+        let received = streams.recv().await.unwrap();
+        assert_eq!(
+            received.event,
+            Event::StreamCreated {
+                session_id,
+                stream_id
+            }
+        );
+
+        manager.release_stream(stream_id, 1000).await;
+        assert_eq!(manager.qos_stats().await.total_streams, 0);
+    }
 }
@@ -20,6 +20,7 @@
 //! - **Protocol selection**: Manager can choose QUIC vs WebRTC based on network conditions
 
 use async_trait::async_trait;
+use honeylink_core::types::DeviceId;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -71,6 +72,23 @@ pub enum TransportError {
     /// NAT traversal failed
     #[error("NAT traversal failed: {0}")]
     NatTraversalFailed(String),
+
+    /// Device could not be resolved to any address
+    #[error("Device not found: {0}")]
+    DeviceNotFound(String),
+
+    /// Peer presented a different device identity than requested
+    #[error("Peer identity mismatch: expected {expected}, got {actual}")]
+    IdentityMismatch {
+        /// Device ID the caller asked to connect to
+        expected: String,
+        /// Device ID the peer presented
+        actual: String,
+    },
+
+    /// Peer did not present a verifiable device identity
+    #[error("Peer identity could not be verified: {0}")]
+    IdentityUnverified(String),
 }
 
 /// Transport protocol trait
@@ -128,6 +146,14 @@ pub trait TransportProtocol: Send + Sync {
     /// Check if protocol is currently listening
     async fn is_listening(&self) -> bool;
 
+    /// Address the listener is actually bound to
+    ///
+    /// Resolves port 0 to the OS-assigned port, so discovery can advertise the
+    /// real listening port. `None` if not listening.
+    async fn listen_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
     /// Get protocol-specific statistics
     async fn stats(&self) -> TransportStats;
 }
@@ -206,6 +232,32 @@ pub trait Connection: Send + Sync {
     /// Check if connection is still alive
     fn is_connected(&self) -> bool;
 
+    /// Receive the early data the peer sent with the connection
+    ///
+    /// Reads the first unidirectional stream, sent as QUIC 0-RTT data by
+    /// `QuicTransport::connect_with_early_data()` (e.g. a session resumption
+    /// request). Early data can be replayed by an attacker, so it must only
+    /// carry requests the receiver can check for replay.
+    ///
+    /// # Default Implementation
+    /// Returns `ProtocolNotSupported` for protocols without early data.
+    async fn accept_early_data(&self) -> Result<Vec<u8>> {
+        Err(TransportError::ProtocolNotSupported(
+            "Early data is not supported by this protocol".to_string(),
+        ))
+    }
+
+    /// Get the device identity presented by the remote peer
+    ///
+    /// Used by `TransportManager::connect_device()` to make sure the peer at a
+    /// resolved address is the device that was asked for.
+    ///
+    /// # Default Implementation
+    /// Returns `None` for protocols that cannot attest the peer identity.
+    fn peer_device_id(&self) -> Option<DeviceId> {
+        None
+    }
+
     /// Get connection statistics
     fn stats(&self) -> ConnectionStats;
 }
@@ -367,5 +419,14 @@ mod tests {
 
         let err = TransportError::ConnectionClosed;
         assert_eq!(err.to_string(), "Connection closed by peer");
+
+        let err = TransportError::IdentityMismatch {
+            expected: "DEV-001".to_string(),
+            actual: "DEV-002".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Peer identity mismatch: expected DEV-001, got DEV-002"
+        );
     }
 }
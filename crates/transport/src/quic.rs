@@ -17,7 +17,8 @@
 //! # Design Decisions
 //!
 //! - **Pure Rust**: Uses quinn + rustls with ring crypto (no C/C++ dependencies)
-//! - **Self-signed certs**: For development/testing (production should use proper PKI)
+//! - **Self-signed certs**: Device identities use self-signed certificates whose
+//!   key fingerprint is pinned by peers (learned at pairing), not a CA chain
 //! - **Async-first**: All operations are async for non-blocking I/O
 //! - **Error mapping**: Quinn errors are mapped to TransportError for consistency
 //!
@@ -25,18 +26,158 @@
 //!
 //! - TLS 1.3 enforced via rustls
 //! - Certificate validation configurable (skip for testing, enforce for production)
+//! - Peer device identity is only reported when the certificate key matches
+//!   the fingerprint pinned for that device (`DeviceKeyPins`)
+//! - 0-RTT early data is enabled for `connect_with_early_data()` only; it is
+//!   replayable, so receivers must reject replays (resumption tickets are
+//!   single-use)
 //! - No support for insecure protocols
 
 use crate::protocol::{Connection, Result, Stream, StreamPriority, TransportError, TransportProtocol};
 use async_trait::async_trait;
+use honeylink_core::types::DeviceId;
 use quinn::{ClientConfig, Endpoint, RecvStream, SendStream, ServerConfig};
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio::sync::{mpsc, Mutex};
 
+/// DNS SAN suffix used to carry the device identity in the QUIC certificate
+///
+/// A transport created with `QuicTransport::with_identity()` for `DEV-001`
+/// presents a certificate with SAN `DEV-001.device.honeylink`; clients read it
+/// back via `Connection::peer_device_id()` once the key matches its pin.
+pub const DEVICE_ID_SAN_SUFFIX: &str = ".device.honeylink";
+
+/// Largest early data payload accepted by `Connection::accept_early_data()`
+pub const MAX_EARLY_DATA_LEN: usize = 16 * 1024;
+
+/// Device certificate and private key used by a QUIC endpoint
+///
+/// The key fingerprint (`fingerprint()`) is what peers pin, so the identity
+/// must be persisted (`cert_der()`/`key_der()`) and reloaded with
+/// `from_der()`; a regenerated identity is a different key and is rejected.
+pub struct QuicIdentity {
+    device_id: DeviceId,
+    cert: CertificateDer<'static>,
+    key: PrivateKeyDer<'static>,
+}
+
+impl QuicIdentity {
+    /// Generates a new self-signed identity for `device_id`
+    pub fn generate(device_id: &DeviceId) -> Result<Self> {
+        let (cert, key) = QuicTransport::generate_self_signed_cert(vec![
+            "localhost".into(),
+            format!("{}{}", device_id.as_str(), DEVICE_ID_SAN_SUFFIX),
+        ])?;
+
+        Ok(Self {
+            device_id: device_id.clone(),
+            cert,
+            key,
+        })
+    }
+
+    /// Loads a persisted identity
+    ///
+    /// # Errors
+    /// Returns `EncryptionError` if the certificate does not carry `device_id`
+    /// or the key is not valid PKCS#8.
+    pub fn from_der(device_id: &DeviceId, cert_der: &[u8], key_der: &[u8]) -> Result<Self> {
+        let cert = CertificateDer::from(cert_der.to_vec());
+        if device_id_from_cert(&cert).as_ref() != Some(device_id) {
+            return Err(TransportError::EncryptionError(format!(
+                "Certificate does not carry device identity {}",
+                device_id
+            )));
+        }
+        let key = PrivateKeyDer::try_from(key_der.to_vec())
+            .map_err(|e| TransportError::EncryptionError(format!("Invalid private key: {}", e)))?;
+
+        Ok(Self {
+            device_id: device_id.clone(),
+            cert,
+            key,
+        })
+    }
+
+    /// Device this identity belongs to
+    pub fn device_id(&self) -> &DeviceId {
+        &self.device_id
+    }
+
+    /// DER-encoded certificate (for persistence)
+    pub fn cert_der(&self) -> &[u8] {
+        self.cert.as_ref()
+    }
+
+    /// DER-encoded PKCS#8 private key (secret; for persistence)
+    pub fn key_der(&self) -> &[u8] {
+        self.key.secret_der()
+    }
+
+    /// SHA-256 fingerprint of the certificate key, as pinned by peers
+    pub fn fingerprint(&self) -> Result<String> {
+        key_fingerprint(&self.cert)
+    }
+}
+
+/// Pinned key fingerprints of known peer devices
+///
+/// Filled from pairing (or another authenticated source): a peer's QUIC
+/// certificate is accepted only if its key fingerprint equals the pin for the
+/// device ID it claims. Clones share the same pin set, so pins added after
+/// the transport was built take effect on the next connection.
+#[derive(Clone, Default, Debug)]
+pub struct DeviceKeyPins {
+    pins: Arc<RwLock<HashMap<DeviceId, String>>>,
+}
+
+impl DeviceKeyPins {
+    /// Creates an empty pin set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pins the key fingerprint (hex SHA-256, case-insensitive) of `device_id`
+    pub fn pin(&self, device_id: DeviceId, fingerprint: impl Into<String>) {
+        self.pins
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(device_id, fingerprint.into().to_lowercase());
+    }
+
+    /// Removes the pin of `device_id`
+    pub fn unpin(&self, device_id: &DeviceId) {
+        self.pins
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(device_id);
+    }
+
+    /// Pinned fingerprint of `device_id`
+    pub fn fingerprint(&self, device_id: &DeviceId) -> Option<String> {
+        self.pins
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(device_id)
+            .cloned()
+    }
+}
+
+/// SHA-256 fingerprint (lowercase hex) of a certificate's SubjectPublicKeyInfo
+///
+/// Pins the key rather than the whole certificate, so re-issuing the
+/// certificate for the same key keeps the pin valid.
+pub fn key_fingerprint(cert: &CertificateDer<'_>) -> Result<String> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(cert.as_ref())
+        .map_err(|e| TransportError::EncryptionError(format!("Invalid certificate: {}", e)))?;
+    Ok(hex::encode(Sha256::digest(parsed.public_key().raw)))
+}
+
 /// QUIC transport implementation
 ///
 /// # Design Rationale
@@ -50,6 +191,8 @@ pub struct QuicTransport {
     server_config: ServerConfig,
     /// Client configuration (TLS trust anchor)
     client_config: ClientConfig,
+    /// Whether the client configuration verifies peer device identities
+    verifies_identity: bool,
 }
 
 impl QuicTransport {
@@ -60,7 +203,7 @@ impl QuicTransport {
     /// Production deployments should provide proper CA-signed certificates.
     pub fn new() -> Result<Self> {
         // Generate self-signed certificate for testing
-        let (cert, key) = Self::generate_self_signed_cert()?;
+        let (cert, key) = Self::generate_self_signed_cert(vec!["localhost".into()])?;
 
         // Server configuration: accept connections with TLS 1.3
         let server_config = Self::build_server_config(cert.clone(), key)?;
@@ -73,18 +216,57 @@ impl QuicTransport {
             endpoint: Arc::new(Mutex::new(None)),
             server_config,
             client_config,
+            verifies_identity: false,
+        })
+    }
+
+    /// Creates a QUIC transport that presents `identity` and verifies peers
+    ///
+    /// The identity's device ID is embedded as a DNS SAN
+    /// (`<device_id>.device.honeylink`) in the server certificate. As a client,
+    /// the transport only completes a handshake if the server's certificate
+    /// names a device in `pins` and its key matches that device's pinned
+    /// fingerprint; TLS handshake signatures are verified against that key.
+    /// `TransportManager::connect_device()` then compares the verified device
+    /// with the one it asked for.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use honeylink_core::types::DeviceId;
+    /// use honeylink_transport::quic::{DeviceKeyPins, QuicIdentity, QuicTransport};
+    ///
+    /// let device_id = DeviceId::new("DEV-001".to_string()).unwrap();
+    /// let identity = QuicIdentity::generate(&device_id).unwrap();
+    /// let pins = DeviceKeyPins::new();
+    /// // pins.pin(peer_id, peer_fingerprint) for each paired device
+    /// let transport = QuicTransport::with_identity(&identity, pins).unwrap();
+    /// ```
+    pub fn with_identity(identity: &QuicIdentity, pins: DeviceKeyPins) -> Result<Self> {
+        let server_config = Self::build_server_config(identity.cert.clone(), identity.key.clone_key())?;
+        let client_config = Self::build_client_config(Arc::new(DeviceIdentityVerifier::new(pins)));
+
+        Ok(Self {
+            endpoint: Arc::new(Mutex::new(None)),
+            server_config,
+            client_config,
+            verifies_identity: true,
         })
     }
 
     /// Generates a self-signed certificate for development/testing
     ///
+    /// # Arguments
+    /// - `subject_alt_names`: DNS names to include in the certificate
+    ///
     /// # Returns
     /// (certificate_der, private_key_der)
     ///
     /// # Security Warning
     /// This should NOT be used in production. Use proper PKI infrastructure instead.
-    fn generate_self_signed_cert() -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>)> {
-        let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()])
+    fn generate_self_signed_cert(
+        subject_alt_names: Vec<String>,
+    ) -> Result<(CertificateDer<'static>, PrivateKeyDer<'static>)> {
+        let cert = rcgen::generate_simple_self_signed(subject_alt_names)
             .map_err(|e| TransportError::EncryptionError(format!("Failed to generate cert: {}", e)))?;
 
         let cert_der = CertificateDer::from(cert.cert);
@@ -106,6 +288,8 @@ impl QuicTransport {
 
         // Enable ALPN for QUIC
         server_crypto.alpn_protocols = vec![b"hq-29".to_vec()];
+        // Accept 0-RTT data from resuming clients (QUIC requires u32::MAX)
+        server_crypto.max_early_data_size = u32::MAX;
 
         let mut server_config = ServerConfig::with_crypto(Arc::new(
             quinn::crypto::rustls::QuicServerConfig::try_from(server_crypto)
@@ -128,12 +312,18 @@ impl QuicTransport {
     /// # Security Warning
     /// This skips certificate verification. Use only for development/testing.
     fn build_client_config_insecure() -> ClientConfig {
+        Self::build_client_config(Arc::new(SkipServerVerification))
+    }
+
+    /// Builds client configuration around a certificate verifier
+    fn build_client_config(verifier: Arc<dyn rustls::client::danger::ServerCertVerifier>) -> ClientConfig {
         let mut client_crypto = rustls::ClientConfig::builder()
             .dangerous()
-            .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+            .with_custom_certificate_verifier(verifier)
             .with_no_client_auth();
 
         client_crypto.alpn_protocols = vec![b"hq-29".to_vec()];
+        client_crypto.enable_early_data = true;
 
         let mut client_config = ClientConfig::new(Arc::new(
             quinn::crypto::rustls::QuicClientConfig::try_from(client_crypto)
@@ -177,6 +367,7 @@ impl QuicTransport {
             .with_no_client_auth();
 
         client_crypto.alpn_protocols = vec![b"hq-29".to_vec()];
+        client_crypto.enable_early_data = true;
 
         let mut client_config = ClientConfig::new(Arc::new(
             quinn::crypto::rustls::QuicClientConfig::try_from(client_crypto)
@@ -219,7 +410,7 @@ impl QuicTransport {
     /// ```
     pub fn with_cert_pinning(pinned_certs: Vec<String>) -> Result<Self> {
         // Generate self-signed certificate for testing
-        let (cert, key) = Self::generate_self_signed_cert()?;
+        let (cert, key) = Self::generate_self_signed_cert(vec!["localhost".into()])?;
 
         // Server configuration: accept connections with TLS 1.3
         let server_config = Self::build_server_config(cert.clone(), key)?;
@@ -231,9 +422,65 @@ impl QuicTransport {
             endpoint: Arc::new(Mutex::new(None)),
             server_config,
             client_config,
+            verifies_identity: false,
         })
     }
 
+    /// Connect to `addr` and send `early_data` as QUIC 0-RTT data
+    ///
+    /// 0-RTT needs a TLS session ticket from an earlier connection to the
+    /// same server; without one, or if the server rejects 0-RTT, the data is
+    /// sent on a fresh stream once the handshake completes. The server reads
+    /// it with `Connection::accept_early_data()`.
+    ///
+    /// # Returns
+    /// The connection and whether the data was accepted as 0-RTT data
+    pub async fn connect_with_early_data(
+        &self,
+        addr: SocketAddr,
+        timeout: Duration,
+        early_data: &[u8],
+    ) -> Result<(Arc<dyn Connection>, bool)> {
+        let local_addr: SocketAddr = if addr.is_ipv4() {
+            "0.0.0.0:0".parse().unwrap()
+        } else {
+            "[::]:0".parse().unwrap()
+        };
+        let endpoint = self.ensure_endpoint(local_addr).await?;
+
+        let connecting = endpoint.connect(addr, "localhost")
+            .map_err(|e| TransportError::ConnectionFailed(format!("Failed to initiate connection: {}", e)))?;
+
+        let (connection, zero_rtt) = match connecting.into_0rtt() {
+            Ok((connection, accepted)) => {
+                let sent = send_uni(&connection, early_data).await.is_ok();
+                let accepted = tokio::time::timeout(timeout, accepted)
+                    .await
+                    .map_err(|_| TransportError::ConnectionTimeout(timeout))?;
+                // Streams opened in rejected 0-RTT are lost; resend in 1-RTT
+                if !(sent && accepted) {
+                    send_uni(&connection, early_data).await?;
+                }
+                (connection, sent && accepted)
+            }
+            Err(connecting) => {
+                let connection = tokio::time::timeout(timeout, connecting)
+                    .await
+                    .map_err(|_| TransportError::ConnectionTimeout(timeout))?
+                    .map_err(|e| TransportError::ConnectionFailed(format!("Connection failed: {}", e)))?;
+                send_uni(&connection, early_data).await?;
+                (connection, false)
+            }
+        };
+
+        let conn: Arc<dyn Connection> = Arc::new(QuicConnection {
+            connection: Arc::new(connection),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+            identity_verified: self.verifies_identity,
+        });
+        Ok((conn, zero_rtt))
+    }
+
     /// Initializes endpoint if not already initialized
     async fn ensure_endpoint(&self, addr: SocketAddr) -> Result<Endpoint> {
         let mut endpoint_guard = self.endpoint.lock().await;
@@ -287,6 +534,7 @@ impl TransportProtocol for QuicTransport {
         Ok(Arc::new(QuicConnection {
             connection: Arc::new(connection),
             streams: Arc::new(Mutex::new(HashMap::new())),
+            identity_verified: self.verifies_identity,
         }))
     }
 
@@ -299,9 +547,12 @@ impl TransportProtocol for QuicTransport {
             while let Some(incoming) = endpoint.accept().await {
                 match incoming.await {
                     Ok(connection) => {
+                        // Clients present no certificate, so accepted
+                        // connections never carry a verified identity
                         let conn: Arc<dyn Connection> = Arc::new(QuicConnection {
                             connection: Arc::new(connection),
                             streams: Arc::new(Mutex::new(HashMap::new())),
+                            identity_verified: false,
                         });
                         if tx.send(conn).await.is_err() {
                             break;
@@ -329,6 +580,14 @@ impl TransportProtocol for QuicTransport {
         self.endpoint.lock().await.is_some()
     }
 
+    async fn listen_addr(&self) -> Option<SocketAddr> {
+        self.endpoint
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|endpoint| endpoint.local_addr().ok())
+    }
+
     async fn stats(&self) -> crate::protocol::TransportStats {
         // TODO: Implement proper stats collection from quinn
         crate::protocol::TransportStats::default()
@@ -339,6 +598,8 @@ impl TransportProtocol for QuicTransport {
 struct QuicConnection {
     connection: Arc<quinn::Connection>,
     streams: Arc<Mutex<HashMap<u64, (SendStream, RecvStream)>>>,
+    /// Peer certificate was checked by `DeviceIdentityVerifier`
+    identity_verified: bool,
 }
 
 #[async_trait]
@@ -368,6 +629,15 @@ impl Connection for QuicConnection {
         Ok(data)
     }
 
+    async fn accept_early_data(&self) -> Result<Vec<u8>> {
+        let mut recv = self.connection.accept_uni().await
+            .map_err(|e| TransportError::ReceiveFailed(format!("Failed to accept stream: {}", e)))?;
+
+        recv.read_to_end(MAX_EARLY_DATA_LEN)
+            .await
+            .map_err(|e| TransportError::ReceiveFailed(format!("Read failed: {}", e)))
+    }
+
     async fn open_stream(&self) -> Result<Box<dyn Stream>> {
         let (send, recv) = self.connection.open_bi().await
             .map_err(|e| TransportError::SendFailed(format!("Failed to open stream: {}", e)))?;
@@ -413,6 +683,16 @@ impl Connection for QuicConnection {
         !self.connection.close_reason().is_some()
     }
 
+    fn peer_device_id(&self) -> Option<DeviceId> {
+        // Without pin verification the SAN is whatever the peer claims
+        if !self.identity_verified {
+            return None;
+        }
+        let identity = self.connection.peer_identity()?;
+        let certs = identity.downcast_ref::<Vec<CertificateDer<'static>>>()?;
+        certs.first().and_then(|cert| device_id_from_cert(cert))
+    }
+
     fn stats(&self) -> crate::protocol::ConnectionStats {
         let quinn_stats = self.connection.stats();
         crate::protocol::ConnectionStats {
@@ -427,6 +707,33 @@ impl Connection for QuicConnection {
 
 }
 
+/// Send `data` on a new unidirectional stream
+async fn send_uni(connection: &quinn::Connection, data: &[u8]) -> Result<()> {
+    let mut send = connection.open_uni().await
+        .map_err(|e| TransportError::SendFailed(format!("Failed to open stream: {}", e)))?;
+
+    send.write_all(data).await
+        .map_err(|e| TransportError::SendFailed(format!("Write failed: {}", e)))?;
+
+    send.finish()
+        .map_err(|e| TransportError::SendFailed(format!("Finish failed: {}", e)))
+}
+
+/// Extracts the device ID from a certificate's `*.device.honeylink` DNS SAN
+fn device_id_from_cert(cert: &CertificateDer<'_>) -> Option<DeviceId> {
+    use x509_parser::extensions::GeneralName;
+
+    let (_, parsed) = x509_parser::parse_x509_certificate(cert.as_ref()).ok()?;
+    let san = parsed.subject_alternative_name().ok()??;
+
+    san.value.general_names.iter().find_map(|name| match name {
+        GeneralName::DNSName(dns) => dns
+            .strip_suffix(DEVICE_ID_SAN_SUFFIX)
+            .and_then(|id| DeviceId::new(id.to_string()).ok()),
+        _ => None,
+    })
+}
+
 /// QUIC stream wrapper
 struct QuicStream {
     send: SendStream,
@@ -454,6 +761,76 @@ impl Stream for QuicStream {
     }
 }
 
+/// Certificate verifier that binds the peer's device identity to a pinned key
+///
+/// Accepts a server certificate only if it names a device (`*.device.honeylink`
+/// SAN) whose pinned fingerprint equals the certificate's key fingerprint.
+/// Handshake signatures are verified for real, so the peer must hold the
+/// private key of the pinned certificate.
+#[derive(Debug)]
+struct DeviceIdentityVerifier {
+    pins: DeviceKeyPins,
+    algorithms: rustls::crypto::WebPkiSupportedAlgorithms,
+}
+
+impl DeviceIdentityVerifier {
+    fn new(pins: DeviceKeyPins) -> Self {
+        Self {
+            pins,
+            algorithms: rustls::crypto::ring::default_provider().signature_verification_algorithms,
+        }
+    }
+}
+
+impl rustls::client::danger::ServerCertVerifier for DeviceIdentityVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> std::result::Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        let device_id = device_id_from_cert(end_entity).ok_or_else(|| {
+            rustls::Error::General("certificate carries no device identity".to_string())
+        })?;
+        let pinned = self.pins.fingerprint(&device_id).ok_or_else(|| {
+            rustls::Error::General(format!("no pinned key for device {}", device_id))
+        })?;
+        let actual = key_fingerprint(end_entity).map_err(|e| rustls::Error::General(e.to_string()))?;
+
+        if actual != pinned {
+            return Err(rustls::Error::General(format!(
+                "key of device {} does not match its pin",
+                device_id
+            )));
+        }
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> std::result::Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
 /// Certificate verifier that skips all validation (INSECURE - for testing only)
 ///
 /// # Security Warning
@@ -535,6 +912,9 @@ mod tests {
             let endpoint_guard = server.endpoint.lock().await;
             endpoint_guard.as_ref().unwrap().local_addr().unwrap()
         };
+        assert_ne!(server_addr.port(), 0);
+        assert_eq!(server.listen_addr().await, Some(server_addr));
+        assert_eq!(client.listen_addr().await, None);
 
         // Client connects
         let client_conn = client.connect(server_addr, Duration::from_secs(5)).await.unwrap();
@@ -590,6 +970,113 @@ mod tests {
         client_conn.close().await.unwrap();
     }
 
+    async fn listen_local(server: &QuicTransport) -> (SocketAddr, mpsc::Receiver<Arc<dyn Connection>>) {
+        let incoming = server.listen("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let addr = server.listen_addr().await.unwrap();
+        (addr, incoming)
+    }
+
+    #[tokio::test]
+    async fn test_quic_peer_device_id_pinned() {
+        let server_id = DeviceId::new("DEV-QUIC-001".to_string()).unwrap();
+        let identity = QuicIdentity::generate(&server_id).unwrap();
+        let server = QuicTransport::with_identity(&identity, DeviceKeyPins::new()).unwrap();
+
+        let pins = DeviceKeyPins::new();
+        pins.pin(server_id.clone(), identity.fingerprint().unwrap());
+        let client_identity =
+            QuicIdentity::generate(&DeviceId::new("DEV-QUIC-002".to_string()).unwrap()).unwrap();
+        let client = QuicTransport::with_identity(&client_identity, pins).unwrap();
+
+        let (server_addr, mut incoming) = listen_local(&server).await;
+        let client_conn = client.connect(server_addr, Duration::from_secs(5)).await.unwrap();
+        let server_conn = tokio::time::timeout(Duration::from_secs(5), incoming.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Client sees the server's pinned device identity; the server has no
+        // verified identity for the client
+        assert_eq!(client_conn.peer_device_id(), Some(server_id));
+        assert_eq!(server_conn.peer_device_id(), None);
+
+        client_conn.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_quic_rejects_unpinned_or_forged_identity() {
+        let device_id = DeviceId::new("DEV-QUIC-001".to_string()).unwrap();
+        let genuine = QuicIdentity::generate(&device_id).unwrap();
+        // Impostor claims the same device ID with its own key
+        let impostor = QuicIdentity::generate(&device_id).unwrap();
+        let server = QuicTransport::with_identity(&impostor, DeviceKeyPins::new()).unwrap();
+        let (server_addr, _incoming) = listen_local(&server).await;
+
+        let pins = DeviceKeyPins::new();
+        pins.pin(device_id.clone(), genuine.fingerprint().unwrap());
+        let client_identity =
+            QuicIdentity::generate(&DeviceId::new("DEV-QUIC-002".to_string()).unwrap()).unwrap();
+        let client = QuicTransport::with_identity(&client_identity, pins.clone()).unwrap();
+        assert!(client.connect(server_addr, Duration::from_secs(5)).await.is_err());
+
+        pins.unpin(&device_id);
+        assert!(client.connect(server_addr, Duration::from_secs(5)).await.is_err());
+
+        // An unverifying client connects but reports no identity
+        let insecure = QuicTransport::new().unwrap();
+        let conn = insecure.connect(server_addr, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(conn.peer_device_id(), None);
+        conn.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_quic_early_data_on_resumed_connection() {
+        let server = QuicTransport::new().unwrap();
+        let client = QuicTransport::new().unwrap();
+        let (server_addr, mut incoming) = listen_local(&server).await;
+
+        // First connection: full handshake, the client gets a session ticket
+        let (first, zero_rtt) = client
+            .connect_with_early_data(server_addr, Duration::from_secs(5), b"hello")
+            .await
+            .unwrap();
+        assert!(!zero_rtt);
+        let server_conn = tokio::time::timeout(Duration::from_secs(5), incoming.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(server_conn.accept_early_data().await.unwrap(), b"hello");
+        server_conn.send(b"ack").await.unwrap();
+        assert_eq!(first.receive().await.unwrap(), b"ack");
+        first.close().await.unwrap();
+
+        // Reconnect: the request travels as 0-RTT data
+        let (second, zero_rtt) = client
+            .connect_with_early_data(server_addr, Duration::from_secs(5), b"resume")
+            .await
+            .unwrap();
+        let server_conn = tokio::time::timeout(Duration::from_secs(5), incoming.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(server_conn.accept_early_data().await.unwrap(), b"resume");
+        assert!(zero_rtt);
+
+        second.close().await.unwrap();
+    }
+
+    #[test]
+    fn test_quic_identity_roundtrip() {
+        let device_id = DeviceId::new("DEV-QUIC-001".to_string()).unwrap();
+        let identity = QuicIdentity::generate(&device_id).unwrap();
+
+        let loaded = QuicIdentity::from_der(&device_id, identity.cert_der(), identity.key_der()).unwrap();
+        assert_eq!(loaded.fingerprint().unwrap(), identity.fingerprint().unwrap());
+
+        let other = DeviceId::new("DEV-QUIC-002".to_string()).unwrap();
+        assert!(QuicIdentity::from_der(&other, identity.cert_der(), identity.key_der()).is_err());
+    }
+
     #[tokio::test]
     async fn test_quic_send_receive() {
         let server = QuicTransport::new().unwrap();
@@ -0,0 +1,161 @@
+//! Device address resolution
+//!
+//! Bridges Phase 1 discovery and the transport layer: a `DeviceResolver` turns a
+//! `DeviceId` into the candidate socket addresses the peer was last seen at, so
+//! `TransportManager::connect_device()` can dial by identity instead of address.
+//! In the other direction, `advertise_listen_addr()` makes discovery announce
+//! the port the local transport is actually bound to.
+//!
+//! # Design Rationale
+//!
+//! - **Trait-based**: Same pattern as `TransportProtocol`; tests can plug in a
+//!   static resolver without running mDNS/BLE
+//! - **Ordered candidates**: Resolvers return addresses in preference order,
+//!   the manager tries them in turn until an identity-verified connection succeeds
+//! - **No caching**: Every call reflects the current discovery view, so a device
+//!   that changed IP is resolved to its new address
+
+use crate::protocol::{Result, TransportError, TransportProtocol};
+use async_trait::async_trait;
+use honeylink_core::types::DeviceId;
+use honeylink_discovery::{DeviceInfo, DiscoveryManager, DiscoveryService};
+use std::net::{IpAddr, SocketAddr};
+
+/// Resolves a device identity to candidate socket addresses
+#[async_trait]
+pub trait DeviceResolver: Send + Sync {
+    /// Resolve `device_id` to candidate addresses (most preferred first)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SocketAddr>)` - Non-empty list of candidate addresses
+    /// * `Err(TransportError::DeviceNotFound)` - Device is unknown or has no addresses
+    async fn resolve(&self, device_id: &DeviceId) -> Result<Vec<SocketAddr>>;
+}
+
+#[async_trait]
+impl DeviceResolver for DiscoveryManager {
+    async fn resolve(&self, device_id: &DeviceId) -> Result<Vec<SocketAddr>> {
+        let device = self
+            .get_device(device_id.as_str())
+            .await
+            .ok_or_else(|| TransportError::DeviceNotFound(device_id.to_string()))?;
+
+        let candidates = candidate_addrs(&device);
+        if candidates.is_empty() {
+            return Err(TransportError::DeviceNotFound(format!(
+                "{} (no addresses advertised)",
+                device_id
+            )));
+        }
+
+        Ok(candidates)
+    }
+}
+
+/// Advertise the port `transport` is listening on via `discovery`
+///
+/// Call after `TransportProtocol::listen()` and before `DiscoveryService::start()`,
+/// so peers resolve this device to the real (possibly OS-assigned) port
+/// instead of the mDNS default.
+///
+/// # Errors
+/// * `TransportError::InvalidAddress` - `transport` is not listening
+pub async fn advertise_listen_addr(
+    discovery: DiscoveryService,
+    transport: &dyn TransportProtocol,
+) -> Result<DiscoveryService> {
+    let addr = transport.listen_addr().await.ok_or_else(|| {
+        TransportError::InvalidAddress(
+            "transport is not listening; call listen() before advertising".to_string(),
+        )
+    })?;
+    Ok(discovery.with_port(addr.port()))
+}
+
+/// Build candidate socket addresses for a discovered device
+///
+/// Ordering: routable IPv4, routable IPv6, then loopback/link-local addresses.
+/// Link-local IPv6 needs a scope ID that discovery records do not carry, so it
+/// is only tried as a last resort.
+pub fn candidate_addrs(device: &DeviceInfo) -> Vec<SocketAddr> {
+    let mut addrs: Vec<IpAddr> = Vec::with_capacity(device.addresses.len());
+    for ip in &device.addresses {
+        if !addrs.contains(ip) {
+            addrs.push(*ip);
+        }
+    }
+    addrs.sort_by_key(address_rank);
+
+    addrs
+        .into_iter()
+        .map(|ip| SocketAddr::new(ip, device.port))
+        .collect()
+}
+
+/// Preference rank for an address (lower is tried first)
+fn address_rank(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(v4) if v4.is_loopback() || v4.is_link_local() => 2,
+        IpAddr::V4(_) => 0,
+        IpAddr::V6(v6) if v6.is_loopback() || (v6.segments()[0] & 0xffc0) == 0xfe80 => 3,
+        IpAddr::V6(_) => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeylink_discovery::DeviceType;
+
+    #[test]
+    fn test_candidate_addrs_ordering() {
+        let device = DeviceInfo::new("DEV-001", "Test Device", DeviceType::Desktop)
+            .with_addresses(vec![
+                "fe80::1".parse().unwrap(),
+                "127.0.0.1".parse().unwrap(),
+                "2001:db8::1".parse().unwrap(),
+                "192.168.1.100".parse().unwrap(),
+            ])
+            .with_port(7843);
+
+        let addrs = candidate_addrs(&device);
+        assert_eq!(
+            addrs,
+            vec![
+                "192.168.1.100:7843".parse::<SocketAddr>().unwrap(),
+                "[2001:db8::1]:7843".parse().unwrap(),
+                "127.0.0.1:7843".parse().unwrap(),
+                "[fe80::1]:7843".parse().unwrap(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discovery_manager_unknown_device() {
+        use honeylink_discovery::protocol::ProtocolStrategy;
+
+        let manager = DiscoveryManager::new(ProtocolStrategy::All, 10);
+        let device_id = DeviceId::new("DEV-404".to_string()).unwrap();
+
+        let result = manager.resolve(&device_id).await;
+        assert!(matches!(result, Err(TransportError::DeviceNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_advertise_listen_addr_uses_bound_port() {
+        use crate::quic::QuicTransport;
+
+        let quic = QuicTransport::new().unwrap();
+        let service = || DiscoveryService::new("DEV-ADV", "Advertiser", "desktop").unwrap();
+        assert!(matches!(
+            advertise_listen_addr(service(), &quic).await,
+            Err(TransportError::InvalidAddress(_))
+        ));
+
+        let _incoming = quic.listen("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let bound = quic.listen_addr().await.unwrap();
+        let discovery = advertise_listen_addr(service(), &quic).await.unwrap();
+        assert_ne!(bound.port(), 0);
+        assert_eq!(discovery.advertised_port(), bound.port());
+    }
+}
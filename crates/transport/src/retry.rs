@@ -179,6 +179,7 @@ impl Default for RetryExecutor {
 /// - LinkDown (requires Hot Swap)
 /// - FecDecodingFailed (data corruption)
 /// - InvalidPriority (client error)
+/// - ProtectionFailed / ReplayDetected (forged or replayed packet)
 fn is_retryable_error(error: &TransportError) -> bool {
     match error {
         TransportError::Timeout(_) => true,
@@ -188,6 +189,8 @@ fn is_retryable_error(error: &TransportError) -> bool {
         TransportError::LinkDown => false,
         TransportError::FecDecodingFailed(_) => false,
         TransportError::InvalidPriority(_) => false,
+        TransportError::ProtectionFailed(_) => false,
+        TransportError::ReplayDetected(_) => false,
     }
 }
 
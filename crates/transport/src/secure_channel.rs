@@ -0,0 +1,1018 @@
+//! Application-layer end-to-end encrypted channels
+//!
+//! QUIC protects its own links with TLS, but WebRTC data paths and physical
+//! adapters have no encryption of their own. `SecureChannel` gives every link
+//! the same guarantees by running a Noise XX handshake with device identity
+//! keys (see `honeylink_crypto::noise`) and then framing and encrypting each
+//! message with ChaCha20-Poly1305 under a `TrafficRatchet` seeded from the
+//! Noise transport keys.
+//!
+//! # Frame Format
+//! ```text
+//! +------+----------------+-------------+--------------+--------------------------+
+//! | type | sequence (u64) | epoch (u64) | nonce (12 B) | ciphertext + 16-byte tag |
+//! +------+----------------+-------------+--------------+--------------------------+
+//! ```
+//!
+//! - **Keys**: Each direction's traffic key is advanced by the ratchet
+//!   (forward secrecy within the channel); `epoch` names the key used
+//! - **Nonce**: Random per frame, as required by the ratchet's usage limits
+//! - **AAD**: The 17-byte frame header (type, sequence number and epoch)
+//! - **Replay protection**: A 64-frame sliding window accepts reordered
+//!   frames (e.g. separate QUIC streams) and drops duplicates
+//! - **Forgeries**: Frames that fail authentication are dropped and counted
+//!   (`rejected_frames()`), so injected garbage cannot tear the channel down
+//!
+//! # Peer Identity
+//! The device ID in the peer's handshake payload is only a claim unless the
+//! peer's key was pinned with `with_expected_remote()` or its certificate
+//! verified with `with_trust_store()`. `remote_device_id()` returns it only
+//! in those cases; `claimed_remote_device_id()` returns it regardless.
+//!
+//! # Key Updates
+//! Before sending past a `RatchetPolicy` limit (messages, bytes or key age,
+//! see `with_ratchet_policy()`), the channel sends a key update frame and
+//! switches to the next sending key. The receiver applies it and refuses
+//! frames from a peer that keeps using a key beyond the policy, so both
+//! peers must use the same policy.
+//!
+//! # Fleet Enrollment
+//! With `with_certificate_chain()` a device presents its organization-issued
+//! certificate chain during the handshake (its Noise key must come from
+//! `honeylink_crypto::certificate::noise_identity`). With `with_trust_store()`
+//! a device only completes handshakes with peers whose chain verifies against
+//! the store, is bound to the peer's handshake key and grants `CONNECT`.
+//!
+//! # Post-Quantum Handshakes
+//! With the `pq-hybrid` feature, channels prefer a hybrid X25519 + ML-KEM-768
+//! handshake and fall back to X25519 for peers without it. Use
+//! `with_kem_policy(KemPolicy::RequireHybrid)` to refuse the fallback;
+//! `kem_mode()` reports what was negotiated. The negotiation is covered by
+//! the handshake transcript, so it cannot be downgraded by an attacker.
+//!
+//! # Links
+//! Any `FrameLink` can carry a channel. Implementations are provided for
+//! `Arc<dyn Connection>`, `Box<dyn Stream>` and `PhysicalLayer` (through
+//! `PacketLink`). `SecureChannel` itself implements `Stream`.
+//!
+//! # Example
+//! ```no_run
+//! use honeylink_crypto::key_agreement::KeyAgreement;
+//! use honeylink_transport::protocol::{Connection, Stream};
+//! use honeylink_transport::secure_channel::{SecureChannel, SecureChannelConfig};
+//! use std::sync::Arc;
+//!
+//! # async fn example(connection: Arc<dyn Connection>) -> honeylink_transport::protocol::Result<()> {
+//! let (identity, _public) = KeyAgreement::generate_keypair();
+//! let config = SecureChannelConfig::new(identity);
+//!
+//! let mut channel = SecureChannel::connect(connection, &config).await?;
+//! channel.send(b"hello").await?;
+//! let reply = channel.receive().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::protocol::{Connection, Result, Stream, TransportError};
+use crate::{Packet, PhysicalLayer};
+use async_trait::async_trait;
+use honeylink_core::types::DeviceId;
+use honeylink_crypto::aead::{NONCE_SIZE, TAG_SIZE};
+use honeylink_crypto::certificate::{Capabilities, CertificateChain, TrustStore, VerifiedDevice};
+use honeylink_crypto::key_agreement::{PublicKey, SecretKey};
+use honeylink_crypto::noise::{HandshakeRole, KemMode, KemPolicy, NoiseHandshake, TransportKeys};
+use honeylink_crypto::packet_protection::ReplayWindow;
+use honeylink_crypto::ratchet::{RatchetPolicy, SealedRecord, TrafficRatchet, INTEGRITY_LIMIT};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default Noise prologue (binds the handshake to this protocol version)
+pub const DEFAULT_PROLOGUE: &[u8] = b"honeylink-secure-channel-v1";
+
+/// Default time allowed for the handshake (default: 10 seconds)
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Frame header length: type (1 byte) + sequence number (8 bytes) + epoch (8 bytes)
+const HEADER_LEN: usize = 17;
+
+/// Frame carrying application data
+const FRAME_DATA: u8 = 0x01;
+
+/// Frame announcing that the sender closed the channel
+const FRAME_CLOSE: u8 = 0x02;
+
+/// Frame carrying a ratchet key update (sealed under the old sending key)
+const FRAME_KEY_UPDATE: u8 = 0x03;
+
+/// Handshake payload marker for a certificate chain (device IDs never start with 0x00)
+const CERTIFICATE_PAYLOAD: u8 = 0x00;
+
+/// Message-oriented link a `SecureChannel` runs over
+///
+/// Each frame must be delivered whole (or not at all); frames may be lost or
+/// reordered.
+#[async_trait]
+pub trait FrameLink: Send + Sync {
+    /// Send one frame
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()>;
+
+    /// Receive the next frame
+    async fn recv_frame(&mut self) -> Result<Vec<u8>>;
+
+    /// Close the underlying link
+    async fn close(&mut self) -> Result<()>;
+}
+
+#[async_trait]
+impl FrameLink for Arc<dyn Connection> {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.send(frame).await
+    }
+
+    async fn recv_frame(&mut self) -> Result<Vec<u8>> {
+        self.receive().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Connection::close(self.as_ref()).await
+    }
+}
+
+#[async_trait]
+impl FrameLink for Box<dyn Stream> {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.send(frame).await
+    }
+
+    async fn recv_frame(&mut self) -> Result<Vec<u8>> {
+        self.receive().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Stream::close(self.as_mut()).await
+    }
+}
+
+/// `FrameLink` over a physical layer adapter (one frame per packet)
+///
+/// Physical layers are unreliable: a lost handshake packet fails the
+/// handshake, while lost data frames are simply missing on the receiver.
+pub struct PacketLink {
+    layer: Arc<dyn PhysicalLayer>,
+    priority: u8,
+    timeout: Duration,
+}
+
+impl PacketLink {
+    /// Creates a link with normal priority (4) and a 5 second receive timeout
+    pub fn new(layer: Arc<dyn PhysicalLayer>) -> Self {
+        Self {
+            layer,
+            priority: 4,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets the packet priority (0-7)
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the receive timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl FrameLink for PacketLink {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let packet = Packet::new(frame.to_vec(), self.priority)
+            .map_err(|e| TransportError::SendFailed(e.to_string()))?;
+        self.layer
+            .send_packet(&packet)
+            .await
+            .map_err(|e| physical_error(e, TransportError::SendFailed))
+    }
+
+    async fn recv_frame(&mut self) -> Result<Vec<u8>> {
+        self.layer
+            .recv_packet(self.timeout)
+            .await
+            .map(|packet| packet.data)
+            .map_err(|e| physical_error(e, TransportError::ReceiveFailed))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn physical_error(
+    error: crate::TransportError,
+    other: fn(String) -> TransportError,
+) -> TransportError {
+    match error {
+        crate::TransportError::Timeout(timeout) => TransportError::ConnectionTimeout(timeout),
+        crate::TransportError::LinkDown => TransportError::ConnectionClosed,
+        e => other(e.to_string()),
+    }
+}
+
+/// Local identity and peer expectations for a secure channel
+#[derive(Clone)]
+pub struct SecureChannelConfig {
+    identity: SecretKey,
+    device_id: Option<DeviceId>,
+    expected_remote: Option<PublicKey>,
+    certificate_chain: Option<CertificateChain>,
+    trust_store: Option<Arc<TrustStore>>,
+    kem_policy: KemPolicy,
+    ratchet_policy: RatchetPolicy,
+    prologue: Vec<u8>,
+    handshake_timeout: Duration,
+}
+
+impl SecureChannelConfig {
+    /// Creates a config with the local static X25519 device identity key
+    pub fn new(identity: SecretKey) -> Self {
+        Self {
+            identity,
+            device_id: None,
+            expected_remote: None,
+            certificate_chain: None,
+            trust_store: None,
+            kem_policy: KemPolicy::default(),
+            ratchet_policy: RatchetPolicy::default(),
+            prologue: DEFAULT_PROLOGUE.to_vec(),
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+        }
+    }
+
+    /// Announces the local device ID to the peer (sent encrypted)
+    pub fn with_device_id(mut self, device_id: DeviceId) -> Self {
+        self.device_id = Some(device_id);
+        self
+    }
+
+    /// Requires the peer to authenticate with this static key
+    pub fn with_expected_remote(mut self, remote_static: PublicKey) -> Self {
+        self.expected_remote = Some(remote_static);
+        self
+    }
+
+    /// Presents this certificate chain to the peer (replaces `with_device_id()`)
+    ///
+    /// The identity key must be `noise_identity()` of the leaf's device key.
+    pub fn with_certificate_chain(mut self, chain: CertificateChain) -> Self {
+        self.certificate_chain = Some(chain);
+        self
+    }
+
+    /// Only accepts peers enrolled under a root in `trust_store`
+    pub fn with_trust_store(mut self, trust_store: Arc<TrustStore>) -> Self {
+        self.trust_store = Some(trust_store);
+        self
+    }
+
+    /// Sets the key exchange modes to offer and accept (default: hybrid
+    /// preferred with the `pq-hybrid` feature, classic otherwise)
+    pub fn with_kem_policy(mut self, policy: KemPolicy) -> Self {
+        self.kem_policy = policy;
+        self
+    }
+
+    /// Sets when traffic keys are advanced (both peers must match)
+    pub fn with_ratchet_policy(mut self, policy: RatchetPolicy) -> Self {
+        self.ratchet_policy = policy;
+        self
+    }
+
+    /// Sets the Noise prologue (both peers must match)
+    pub fn with_prologue(mut self, prologue: impl Into<Vec<u8>>) -> Self {
+        self.prologue = prologue.into();
+        self
+    }
+
+    /// Sets the handshake timeout
+    pub fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Public static key of the local identity
+    pub fn public_key(&self) -> PublicKey {
+        self.identity.public_key()
+    }
+}
+
+impl std::fmt::Debug for SecureChannelConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecureChannelConfig")
+            .field("identity", &"[REDACTED]")
+            .field("device_id", &self.device_id)
+            .field(
+                "expected_remote",
+                &self.expected_remote.map(|k| hex::encode(k.as_bytes())),
+            )
+            .field(
+                "certificate_serial",
+                &self.certificate_chain.as_ref().map(|c| c.leaf().serial()),
+            )
+            .field("trust_store", &self.trust_store.is_some())
+            .field("kem_policy", &self.kem_policy)
+            .field("ratchet_policy", &self.ratchet_policy)
+            .field("handshake_timeout", &self.handshake_timeout)
+            .finish()
+    }
+}
+
+/// End-to-end encrypted channel over a `FrameLink`
+pub struct SecureChannel<L: FrameLink> {
+    link: L,
+    ratchet: TrafficRatchet,
+    send_seq: u64,
+    replay: ReplayWindow,
+    remote_static: PublicKey,
+    remote_device_id: Option<DeviceId>,
+    remote_identity_verified: bool,
+    remote_certificate: Option<VerifiedDevice>,
+    handshake_hash: [u8; 64],
+    kem_mode: KemMode,
+    rejected_frames: u64,
+    closed: bool,
+}
+
+impl<L: FrameLink> SecureChannel<L> {
+    /// Runs the handshake as initiator and returns the established channel
+    ///
+    /// # Errors
+    /// - `ConnectionTimeout`: Handshake did not complete in time
+    /// - `EncryptionError`: Handshake failed (tampering, wrong prologue)
+    /// - `IdentityMismatch`: Peer key differs from `with_expected_remote()`
+    /// - `IdentityUnverified`: Peer certificate missing or rejected by the trust store
+    pub async fn connect(link: L, config: &SecureChannelConfig) -> Result<Self> {
+        Self::establish(link, config, HandshakeRole::Initiator).await
+    }
+
+    /// Runs the handshake as responder and returns the established channel
+    ///
+    /// # Errors
+    /// Same as `connect()`.
+    pub async fn accept(link: L, config: &SecureChannelConfig) -> Result<Self> {
+        Self::establish(link, config, HandshakeRole::Responder).await
+    }
+
+    /// Authenticated static key of the peer
+    pub fn remote_static(&self) -> &PublicKey {
+        &self.remote_static
+    }
+
+    /// Authenticated device ID of the peer
+    ///
+    /// `None` unless the peer's key was pinned (`with_expected_remote()`) or
+    /// its certificate verified (`with_trust_store()`): without either, any
+    /// peer can announce any device ID.
+    pub fn remote_device_id(&self) -> Option<&DeviceId> {
+        self.remote_device_id
+            .as_ref()
+            .filter(|_| self.remote_identity_verified)
+    }
+
+    /// Device ID announced by the peer during the handshake, unauthenticated
+    ///
+    /// For display and logging only; use `remote_device_id()` for access decisions.
+    pub fn claimed_remote_device_id(&self) -> Option<&DeviceId> {
+        self.remote_device_id.as_ref()
+    }
+
+    /// Number of received frames dropped because they failed authentication
+    pub fn rejected_frames(&self) -> u64 {
+        self.rejected_frames
+    }
+
+    /// Peer identity verified against the trust store (see `with_trust_store()`)
+    pub fn remote_certificate(&self) -> Option<&VerifiedDevice> {
+        self.remote_certificate.as_ref()
+    }
+
+    /// Noise handshake hash (unique per channel, usable for channel binding)
+    pub fn handshake_hash(&self) -> &[u8; 64] {
+        &self.handshake_hash
+    }
+
+    /// Key exchange negotiated for this channel
+    pub fn kem_mode(&self) -> KemMode {
+        self.kem_mode
+    }
+
+    /// Epochs of the current sending and receiving traffic keys
+    pub fn key_epochs(&self) -> (u64, u64) {
+        (self.ratchet.send_epoch(), self.ratchet.recv_epoch())
+    }
+
+    /// Returns the underlying link
+    pub fn into_inner(self) -> L {
+        self.link
+    }
+
+    async fn establish(
+        mut link: L,
+        config: &SecureChannelConfig,
+        role: HandshakeRole,
+    ) -> Result<Self> {
+        let timeout = config.handshake_timeout;
+        let (keys, remote) =
+            tokio::time::timeout(timeout, Self::handshake(&mut link, config, role))
+                .await
+                .map_err(|_| TransportError::ConnectionTimeout(timeout))??;
+
+        let ratchet = TrafficRatchet::from_split_secrets(
+            keys.send_key.as_slice(),
+            keys.recv_key.as_slice(),
+            &hex::encode(keys.handshake_hash),
+        )
+        .map_err(encryption_error)?
+        .with_policy(config.ratchet_policy);
+
+        Ok(Self {
+            link,
+            ratchet,
+            send_seq: 0,
+            replay: ReplayWindow::new(),
+            remote_static: keys.remote_static,
+            remote_device_id: remote.device_id,
+            remote_identity_verified: config.expected_remote.is_some()
+                || remote.certificate.is_some(),
+            remote_certificate: remote.certificate,
+            handshake_hash: keys.handshake_hash,
+            kem_mode: keys.kem_mode,
+            rejected_frames: 0,
+            closed: false,
+        })
+    }
+
+    async fn handshake(
+        link: &mut L,
+        config: &SecureChannelConfig,
+        role: HandshakeRole,
+    ) -> Result<(TransportKeys, RemoteIdentity)> {
+        let mut noise = NoiseHandshake::new(role, config.identity.clone(), &config.prologue)
+            .with_kem_policy(config.kem_policy);
+        let payload = match (&config.certificate_chain, &config.device_id) {
+            (Some(chain), _) => [&[CERTIFICATE_PAYLOAD][..], &chain.to_bytes()].concat(),
+            (None, Some(id)) => id.as_str().as_bytes().to_vec(),
+            (None, None) => Vec::new(),
+        };
+        let mut remote = RemoteIdentity::default();
+        let mut first_message = role == HandshakeRole::Initiator;
+
+        while !noise.is_finished() {
+            if noise.is_my_turn() {
+                // The first message is unencrypted, so it carries no identity
+                let message = if first_message {
+                    noise.write_message(b"")
+                } else {
+                    noise.write_message(&payload)
+                }
+                .map_err(encryption_error)?;
+                first_message = false;
+                link.send_frame(&message).await?;
+            } else {
+                let message = link.recv_frame().await?;
+                let received = noise.read_message(&message).map_err(encryption_error)?;
+
+                // Abort before revealing our identity to an unexpected peer
+                if let (Some(expected), Some(actual)) =
+                    (config.expected_remote, noise.remote_static())
+                {
+                    if expected.as_bytes() != actual.as_bytes() {
+                        return Err(TransportError::IdentityMismatch {
+                            expected: hex::encode(expected.as_bytes()),
+                            actual: hex::encode(actual.as_bytes()),
+                        });
+                    }
+                }
+
+                // The peer's identity payload arrives with its static key
+                if let Some(remote_static) = noise.remote_static() {
+                    remote = RemoteIdentity::from_payload(config, &received, &remote_static)?;
+                }
+            }
+        }
+
+        let keys = noise.into_transport().map_err(encryption_error)?;
+        Ok((keys, remote))
+    }
+
+    /// Next sequence number and frame header for `kind`
+    fn next_header(&mut self, kind: u8) -> Result<Vec<u8>> {
+        let seq = self.send_seq;
+        self.send_seq = seq.checked_add(1).ok_or_else(|| {
+            TransportError::ResourceExhausted("Sequence numbers exhausted".to_string())
+        })?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.push(kind);
+        header.extend_from_slice(&seq.to_be_bytes());
+        header.extend_from_slice(&self.ratchet.send_epoch().to_be_bytes());
+        Ok(header)
+    }
+
+    /// Builds and encrypts the next outgoing frame
+    ///
+    /// Fails once the sending key reached the ratchet policy; `send_sealed()`
+    /// sends a key update first.
+    fn seal_frame(&mut self, kind: u8, data: &[u8]) -> Result<Vec<u8>> {
+        let header = self.next_header(kind)?;
+        let record = self.ratchet.seal(data, &header).map_err(encryption_error)?;
+        Ok(encode_frame(header, record))
+    }
+
+    /// Advances the sending key and builds the key update frame for the peer
+    fn key_update_frame(&mut self) -> Result<Vec<u8>> {
+        let header = self.next_header(FRAME_KEY_UPDATE)?;
+        let record = self
+            .ratchet
+            .initiate_update(&header)
+            .map_err(encryption_error)?;
+        tracing::debug!(
+            epoch = self.ratchet.send_epoch(),
+            "Secure channel key update"
+        );
+        Ok(encode_frame(header, record))
+    }
+
+    /// Seals and sends a frame, updating the sending key first if required
+    async fn send_sealed(&mut self, kind: u8, data: &[u8]) -> Result<()> {
+        if self.ratchet.needs_update() {
+            let update = self.key_update_frame()?;
+            self.link.send_frame(&update).await?;
+        }
+        let frame = self.seal_frame(kind, data)?;
+        self.link.send_frame(&frame).await
+    }
+
+    /// Authenticates and decrypts a frame
+    ///
+    /// Key update frames are applied to the ratchet and returned with an
+    /// empty payload. Returns `None` for replayed or too old frames, which
+    /// are dropped.
+    fn open_frame(&mut self, frame: &[u8]) -> Result<Option<(u8, Vec<u8>)>> {
+        if frame.len() < HEADER_LEN + NONCE_SIZE + TAG_SIZE {
+            return Err(TransportError::EncryptionError(
+                "Frame truncated".to_string(),
+            ));
+        }
+
+        let (header, rest) = frame.split_at(HEADER_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_SIZE);
+        let kind = header[0];
+        let mut seq_bytes = [0u8; 8];
+        seq_bytes.copy_from_slice(&header[1..9]);
+        let seq = u64::from_be_bytes(seq_bytes);
+        let mut epoch_bytes = [0u8; 8];
+        epoch_bytes.copy_from_slice(&header[9..]);
+
+        if !self.replay.check(seq) {
+            tracing::debug!(seq, "Dropping replayed secure channel frame");
+            return Ok(None);
+        }
+
+        let mut record_nonce = [0u8; NONCE_SIZE];
+        record_nonce.copy_from_slice(nonce);
+        let record = SealedRecord {
+            epoch: u64::from_be_bytes(epoch_bytes),
+            nonce: record_nonce,
+            ciphertext: ciphertext.to_vec(),
+        };
+
+        let plaintext = if kind == FRAME_KEY_UPDATE {
+            self.ratchet
+                .apply_update(&record, header)
+                .map_err(encryption_error)?;
+            Vec::new()
+        } else {
+            self.ratchet
+                .open(&record, header)
+                .map_err(encryption_error)?
+        };
+        self.replay.mark(seq);
+
+        Ok(Some((kind, plaintext)))
+    }
+}
+
+#[async_trait]
+impl<L: FrameLink> Stream for SecureChannel<L> {
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        if self.closed {
+            return Err(TransportError::ConnectionClosed);
+        }
+        self.send_sealed(FRAME_DATA, data).await
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>> {
+        loop {
+            if self.closed {
+                return Err(TransportError::ConnectionClosed);
+            }
+
+            let frame = self.link.recv_frame().await?;
+            let opened = match self.open_frame(&frame) {
+                Ok(opened) => opened,
+                Err(TransportError::EncryptionError(reason)) => {
+                    // Forged, corrupted or stale frame: drop it, keep the channel
+                    self.rejected_frames += 1;
+                    tracing::debug!(
+                        %reason,
+                        rejected = self.rejected_frames,
+                        "Dropping unauthenticated secure channel frame"
+                    );
+                    if self.rejected_frames >= INTEGRITY_LIMIT {
+                        return Err(TransportError::EncryptionError(
+                            "Too many unauthenticated frames".to_string(),
+                        ));
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            match opened {
+                Some((FRAME_DATA, data)) => return Ok(data),
+                Some((FRAME_CLOSE, _)) => self.closed = true,
+                Some((FRAME_KEY_UPDATE, _)) => continue,
+                Some((kind, _)) => {
+                    return Err(TransportError::EncryptionError(format!(
+                        "Unknown frame type {}",
+                        kind
+                    )))
+                }
+                None => continue,
+            }
+        }
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        self.send_sealed(FRAME_CLOSE, b"").await?;
+        self.link.close().await
+    }
+}
+
+/// Identity announced by the peer in its handshake payload
+#[derive(Default)]
+struct RemoteIdentity {
+    device_id: Option<DeviceId>,
+    certificate: Option<VerifiedDevice>,
+}
+
+impl RemoteIdentity {
+    /// Parses (and, with a trust store, verifies) the peer's payload
+    fn from_payload(
+        config: &SecureChannelConfig,
+        payload: &[u8],
+        remote_static: &PublicKey,
+    ) -> Result<Self> {
+        let unverified = |reason: String| TransportError::IdentityUnverified(reason);
+
+        let Some((&CERTIFICATE_PAYLOAD, encoded)) = payload.split_first() else {
+            if config.trust_store.is_some() {
+                return Err(unverified("Peer presented no certificate".to_string()));
+            }
+            if payload.is_empty() {
+                return Ok(Self::default());
+            }
+            let id = String::from_utf8(payload.to_vec())
+                .map_err(|_| unverified("Invalid device ID".to_string()))?;
+            return Ok(Self {
+                device_id: Some(DeviceId::new(id).map_err(unverified)?),
+                certificate: None,
+            });
+        };
+
+        let chain = CertificateChain::from_bytes(encoded).map_err(|e| unverified(e.to_string()))?;
+        if chain.leaf().noise_public_key().as_bytes() != remote_static.as_bytes() {
+            return Err(unverified(
+                "Certificate is not bound to the handshake key".to_string(),
+            ));
+        }
+
+        let certificate = match &config.trust_store {
+            Some(trust_store) => {
+                let verified = trust_store
+                    .verify_now(&chain)
+                    .map_err(|e| unverified(e.to_string()))?;
+                if !verified.capabilities.contains(Capabilities::CONNECT) {
+                    return Err(unverified("Certificate does not grant CONNECT".to_string()));
+                }
+                Some(verified)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            device_id: Some(chain.leaf().subject().clone()),
+            certificate,
+        })
+    }
+}
+
+/// Appends a sealed record's nonce and ciphertext to a frame header
+fn encode_frame(mut header: Vec<u8>, record: SealedRecord) -> Vec<u8> {
+    header.reserve(NONCE_SIZE + record.ciphertext.len());
+    header.extend_from_slice(&record.nonce);
+    header.extend(record.ciphertext);
+    header
+}
+
+fn encryption_error(e: honeylink_core::Error) -> TransportError {
+    TransportError::EncryptionError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeylink_crypto::key_agreement::KeyAgreement;
+    use tokio::sync::mpsc;
+
+    /// In-memory duplex link
+    struct ChannelLink {
+        tx: mpsc::UnboundedSender<Vec<u8>>,
+        rx: mpsc::UnboundedReceiver<Vec<u8>>,
+    }
+
+    fn link_pair() -> (ChannelLink, ChannelLink) {
+        let (a_tx, b_rx) = mpsc::unbounded_channel();
+        let (b_tx, a_rx) = mpsc::unbounded_channel();
+        (
+            ChannelLink { tx: a_tx, rx: a_rx },
+            ChannelLink { tx: b_tx, rx: b_rx },
+        )
+    }
+
+    #[async_trait]
+    impl FrameLink for ChannelLink {
+        async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+            self.tx
+                .send(frame.to_vec())
+                .map_err(|_| TransportError::ConnectionClosed)
+        }
+
+        async fn recv_frame(&mut self) -> Result<Vec<u8>> {
+            self.rx.recv().await.ok_or(TransportError::ConnectionClosed)
+        }
+
+        async fn close(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn device(id: &str) -> DeviceId {
+        DeviceId::new(id.to_string()).unwrap()
+    }
+
+    async fn channel_pair(
+        alice: &SecureChannelConfig,
+        bob: &SecureChannelConfig,
+    ) -> (
+        Result<SecureChannel<ChannelLink>>,
+        Result<SecureChannel<ChannelLink>>,
+    ) {
+        let (a_link, b_link) = link_pair();
+        tokio::join!(
+            SecureChannel::connect(a_link, alice),
+            SecureChannel::accept(b_link, bob)
+        )
+    }
+
+    #[tokio::test]
+    async fn test_handshake_and_messages() {
+        let (a_secret, a_public) = KeyAgreement::generate_keypair();
+        let (b_secret, b_public) = KeyAgreement::generate_keypair();
+        let alice = SecureChannelConfig::new(a_secret)
+            .with_device_id(device("DEV-ALICE"))
+            .with_expected_remote(b_public);
+        let bob = SecureChannelConfig::new(b_secret).with_device_id(device("DEV-BOB"));
+
+        let (a, b) = channel_pair(&alice, &bob).await;
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+
+        // Alice pinned Bob's key; Bob only has Alice's claim
+        assert_eq!(a.remote_device_id(), Some(&device("DEV-BOB")));
+        assert_eq!(b.remote_device_id(), None);
+        assert_eq!(b.claimed_remote_device_id(), Some(&device("DEV-ALICE")));
+        assert_eq!(b.remote_static().as_bytes(), a_public.as_bytes());
+        assert_eq!(a.handshake_hash(), b.handshake_hash());
+        assert_eq!(a.kem_mode(), b.kem_mode());
+
+        a.send(b"hello").await.unwrap();
+        assert_eq!(b.receive().await.unwrap(), b"hello");
+        b.send(b"world").await.unwrap();
+        assert_eq!(a.receive().await.unwrap(), b"world");
+
+        a.close().await.unwrap();
+        assert_eq!(b.receive().await, Err(TransportError::ConnectionClosed));
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_peer_rejected() {
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let (_, other_public) = KeyAgreement::generate_keypair();
+        let alice = SecureChannelConfig::new(a_secret)
+            .with_expected_remote(other_public)
+            .with_handshake_timeout(Duration::from_millis(500));
+        let bob =
+            SecureChannelConfig::new(b_secret).with_handshake_timeout(Duration::from_millis(500));
+
+        let (a, b) = channel_pair(&alice, &bob).await;
+        assert!(matches!(a, Err(TransportError::IdentityMismatch { .. })));
+        assert!(b.is_err());
+    }
+
+    #[cfg(feature = "pq-hybrid")]
+    #[tokio::test]
+    async fn test_hybrid_handshake_negotiated() {
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let alice = SecureChannelConfig::new(a_secret);
+        let bob = SecureChannelConfig::new(b_secret).with_kem_policy(KemPolicy::RequireHybrid);
+
+        let (a, b) = channel_pair(&alice, &bob).await;
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+        assert_eq!(a.kem_mode(), KemMode::Hybrid);
+        assert_eq!(b.kem_mode(), KemMode::Hybrid);
+        a.send(b"post-quantum").await.unwrap();
+        assert_eq!(b.receive().await.unwrap(), b"post-quantum");
+
+        // Classic peers still connect unless hybrid is required
+        let classic = alice.clone().with_kem_policy(KemPolicy::Classic);
+        let bob = bob.with_kem_policy(KemPolicy::PreferHybrid);
+        let (a, b) = channel_pair(&classic, &bob).await;
+        assert_eq!(a.unwrap().kem_mode(), KemMode::Classic);
+        assert_eq!(b.unwrap().kem_mode(), KemMode::Classic);
+    }
+
+    #[tokio::test]
+    async fn test_fleet_certificates() {
+        use ed25519_dalek::SigningKey;
+        use honeylink_crypto::certificate::{noise_identity, CertificateIssuer};
+
+        let root = CertificateIssuer::new(SigningKey::from_bytes(&[1u8; 32]));
+        let trust = Arc::new(TrustStore::new().with_root(root.verifying_key()));
+        let enrolled = |seed: u8, id: &str| {
+            let device_key = SigningKey::from_bytes(&[seed; 32]);
+            let certificate = root.issue(
+                &device(id),
+                &device_key.verifying_key(),
+                Capabilities::CONNECT,
+                Duration::from_secs(3600),
+            );
+            SecureChannelConfig::new(noise_identity(&device_key).unwrap())
+                .with_certificate_chain(CertificateChain::new(certificate))
+                .with_trust_store(trust.clone())
+                .with_handshake_timeout(Duration::from_millis(500))
+        };
+
+        let (a, b) = channel_pair(&enrolled(2, "DEV-ALICE"), &enrolled(3, "DEV-BOB")).await;
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert_eq!(a.remote_device_id(), Some(&device("DEV-BOB")));
+        assert_eq!(
+            b.remote_certificate().map(|c| &c.device_id),
+            Some(&device("DEV-ALICE"))
+        );
+
+        // A peer without an enrollment certificate is refused
+        let (stranger, _) = KeyAgreement::generate_keypair();
+        let stranger = SecureChannelConfig::new(stranger)
+            .with_device_id(device("DEV-EVE"))
+            .with_handshake_timeout(Duration::from_millis(500));
+        let (a, _) = channel_pair(&enrolled(2, "DEV-ALICE"), &stranger).await;
+        assert!(matches!(a, Err(TransportError::IdentityUnverified(_))));
+    }
+
+    #[tokio::test]
+    async fn test_replay_and_tamper_rejected() {
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let (a, b) = channel_pair(
+            &SecureChannelConfig::new(a_secret),
+            &SecureChannelConfig::new(b_secret),
+        )
+        .await;
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+
+        let first = a.seal_frame(FRAME_DATA, b"first").unwrap();
+        let second = a.seal_frame(FRAME_DATA, b"second").unwrap();
+
+        // Reordered frames are accepted, duplicates dropped
+        assert_eq!(b.open_frame(&second).unwrap().unwrap().1, b"second");
+        assert_eq!(b.open_frame(&first).unwrap().unwrap().1, b"first");
+        assert!(b.open_frame(&first).unwrap().is_none());
+
+        // Header is authenticated
+        let mut tampered = a.seal_frame(FRAME_DATA, b"third").unwrap();
+        tampered[0] = FRAME_CLOSE;
+        assert!(b.open_frame(&tampered).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_key_update_header_authenticated() {
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let (a, b) = channel_pair(
+            &SecureChannelConfig::new(a_secret),
+            &SecureChannelConfig::new(b_secret),
+        )
+        .await;
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+
+        let first = a.seal_frame(FRAME_DATA, b"first").unwrap();
+        assert!(b.open_frame(&first).unwrap().is_some());
+        let highest = b.replay.highest();
+
+        // A rewritten sequence number must not push the replay window ahead
+        let update = a.key_update_frame().unwrap();
+        let mut tampered = update.clone();
+        tampered[1..9].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(b.open_frame(&tampered).is_err());
+        assert_eq!(b.replay.highest(), highest);
+        assert_eq!(b.key_epochs(), (0, 0));
+
+        assert!(b.open_frame(&update).unwrap().is_some());
+        let next = a.seal_frame(FRAME_DATA, b"next").unwrap();
+        assert_eq!(b.open_frame(&next).unwrap().unwrap().1, b"next");
+        assert_eq!(b.key_epochs(), (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_forged_frames_dropped_and_counted() {
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let (a_link, b_link) = link_pair();
+        let injector = a_link.tx.clone();
+        let (alice, bob) = (
+            SecureChannelConfig::new(a_secret),
+            SecureChannelConfig::new(b_secret),
+        );
+        let (a, b) = tokio::join!(
+            SecureChannel::connect(a_link, &alice),
+            SecureChannel::accept(b_link, &bob)
+        );
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+
+        // An on-path attacker injects garbage and a bit-flipped frame
+        injector.send(vec![0xFF; 3]).unwrap();
+        let mut forged = a.seal_frame(FRAME_DATA, b"forged").unwrap();
+        let last = forged.len() - 1;
+        forged[last] ^= 1;
+        injector.send(forged).unwrap();
+
+        a.send(b"genuine").await.unwrap();
+        assert_eq!(b.receive().await.unwrap(), b"genuine");
+        assert_eq!(b.rejected_frames(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_traffic_keys_ratchet_per_policy() {
+        let policy = RatchetPolicy::default().with_max_messages(2);
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let (a, b) = channel_pair(
+            &SecureChannelConfig::new(a_secret).with_ratchet_policy(policy),
+            &SecureChannelConfig::new(b_secret).with_ratchet_policy(policy),
+        )
+        .await;
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+
+        for i in 0..5u8 {
+            a.send(&[i]).await.unwrap();
+            assert_eq!(b.receive().await.unwrap(), vec![i]);
+        }
+        // Five messages at two per key: epochs 0, 0, 1, 1, 2
+        assert_eq!(a.key_epochs(), (2, 0));
+        assert_eq!(b.key_epochs(), (0, 2));
+
+        // A peer that ignores the policy is refused
+        let (a_secret, _) = KeyAgreement::generate_keypair();
+        let (b_secret, _) = KeyAgreement::generate_keypair();
+        let (a, b) = channel_pair(
+            &SecureChannelConfig::new(a_secret),
+            &SecureChannelConfig::new(b_secret).with_ratchet_policy(policy),
+        )
+        .await;
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+        for _ in 0..2 {
+            let frame = a.seal_frame(FRAME_DATA, b"ok").unwrap();
+            assert!(b.open_frame(&frame).unwrap().is_some());
+        }
+        let frame = a.seal_frame(FRAME_DATA, b"over limit").unwrap();
+        assert!(b.open_frame(&frame).is_err());
+    }
+}
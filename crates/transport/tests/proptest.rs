@@ -2,7 +2,8 @@
 //!
 //! Tests using proptest to verify invariants across a wide range of inputs.
 
-use honeylink_transport::{FecEncoder, FecStrategy, Packet, WeightedFairQueuing};
+use honeylink_crypto::packet_protection::{PacketOpener, PacketProtection, PacketSealer};
+use honeylink_transport::{FecEncoder, FecStrategy, Packet, TransportError, WeightedFairQueuing};
 use proptest::prelude::*;
 
 proptest! {
@@ -158,4 +159,28 @@ proptest! {
         let packet = Packet::new(data.clone(), priority).unwrap();
         prop_assert_eq!(packet.size(), data.len());
     }
+
+    /// Property: Protected packets round-trip once and are rejected on replay
+    #[test]
+    fn prop_packet_protection_roundtrip(
+        data in prop::collection::vec(any::<u8>(), 0..1000),
+        header in prop::collection::vec(any::<u8>(), 0..32)
+    ) {
+        let secret = [0x5Au8; 32];
+        let mut sealer = PacketSealer::new(PacketProtection::derive(&secret, "tx").unwrap());
+        let mut opener = PacketOpener::new(PacketProtection::derive(&secret, "tx").unwrap());
+
+        let mut packet = Packet::new(data.clone(), 3).unwrap();
+        packet.protect(&mut sealer, &header).unwrap();
+        let wire = packet.clone();
+
+        packet.unprotect(&mut opener, &header).unwrap();
+        prop_assert_eq!(&packet.data, &data);
+
+        let mut replayed = wire;
+        prop_assert_eq!(
+            replayed.unprotect(&mut opener, &header),
+            Err(TransportError::ReplayDetected(0))
+        );
+    }
 }